@@ -8,6 +8,7 @@ func _has_gizmo(node) -> bool:
 
 func _init() -> void:
 	create_material("main", Color(0.9,1.0,0.0))
+	create_material("whitebox", Color(0.0,0.8,1.0))
 
 func _redraw(gizmo: EditorNode3DGizmo) -> void:
 	gizmo.clear()
@@ -79,3 +80,7 @@ func _redraw(gizmo: EditorNode3DGizmo) -> void:
 			start_position + Vector3(0.0, -1.0, 1.0) * voxel_size,
 		]
 		gizmo.add_lines(lines, get_material("main", gizmo), false)
+
+	var whitebox := builder.get_whitebox_outline()
+	if !whitebox.is_empty():
+		gizmo.add_lines(whitebox, get_material("whitebox", gizmo), false)
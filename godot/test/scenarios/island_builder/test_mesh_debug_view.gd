@@ -0,0 +1,50 @@
+extends Node3D
+
+func _ready() -> void:
+	# End test after frame
+	StagTest.teardown.call_deferred()
+
+	var arrays: Array = []
+	arrays.resize(Mesh.ARRAY_MAX)
+	arrays[Mesh.ARRAY_VERTEX] = PackedVector3Array([Vector3.ZERO, Vector3.RIGHT, Vector3.FORWARD, Vector3.UP])
+	arrays[Mesh.ARRAY_NORMAL] = PackedVector3Array([Vector3.UP, Vector3.UP, Vector3.UP, Vector3.BACK])
+	arrays[Mesh.ARRAY_COLOR] = PackedColorArray([Color.BLACK, Color.WHITE, Color.RED, Color.GREEN])
+	arrays[Mesh.ARRAY_INDEX] = PackedInt32Array([0, 1, 2, 0, 2, 3])
+	var mesh := ArrayMesh.new()
+	mesh.add_surface_from_arrays(Mesh.PRIMITIVE_TRIANGLES, arrays)
+
+	var view := StagMeshDebugView.new()
+	add_child(view)
+	StagTest.assert_equal(0, view.get_line_segment_count(), "no segments should be drawn without a mesh")
+
+	view.source_mesh = mesh
+	StagTest.assert_equal(4, view.get_line_segment_count(), "one normal segment should be drawn per vertex")
+	StagTest.assert_true(view.mesh is ImmediateMesh, "debug view should draw into an ImmediateMesh")
+	StagTest.assert_equal(2, view.mesh.get_surface_count(), "heatmap and normals should be drawn as separate surfaces")
+
+	view.show_normals = false
+	StagTest.assert_equal(0, view.get_line_segment_count(), "no segments should be drawn when normals are hidden")
+	StagTest.assert_equal(1, view.mesh.get_surface_count(), "only the heatmap should be drawn")
+
+	view.source_mesh = null
+	StagTest.assert_true(view.mesh == null, "clearing the source mesh should clear the debug view")
+
+	# Non-indexed surfaces are read as a triangle list, with one segment per vertex
+	var soup: Array = []
+	soup.resize(Mesh.ARRAY_MAX)
+	soup[Mesh.ARRAY_VERTEX] = PackedVector3Array([Vector3.ZERO, Vector3.RIGHT, Vector3.FORWARD, Vector3.UP, Vector3.LEFT, Vector3.BACK])
+	soup[Mesh.ARRAY_NORMAL] = PackedVector3Array([Vector3.UP, Vector3.UP, Vector3.UP, Vector3.BACK, Vector3.BACK, Vector3.BACK])
+	soup[Mesh.ARRAY_TEX_UV] = PackedVector2Array([Vector2.ZERO, Vector2.RIGHT, Vector2.DOWN, Vector2.ZERO, Vector2.RIGHT, Vector2.DOWN])
+	var soup_mesh := ArrayMesh.new()
+	soup_mesh.add_surface_from_arrays(Mesh.PRIMITIVE_TRIANGLES, soup)
+	view.show_normals = true
+	view.source_mesh = soup_mesh
+	StagTest.assert_equal(6, view.get_line_segment_count(), "non-indexed surfaces should read every vertex")
+
+	# Surfaces without normals are still read, but have no normals to draw
+	soup[Mesh.ARRAY_NORMAL] = null
+	var bare_mesh := ArrayMesh.new()
+	bare_mesh.add_surface_from_arrays(Mesh.PRIMITIVE_TRIANGLES, soup)
+	view.source_mesh = bare_mesh
+	StagTest.assert_equal(0, view.get_line_segment_count(), "surfaces without normals should draw no segments")
+	StagTest.assert_true(view.mesh is ImmediateMesh, "surfaces without normals should still be read")
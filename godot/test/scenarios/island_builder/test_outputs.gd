@@ -0,0 +1,44 @@
+extends Node3D
+
+func _ready() -> void:
+	# End test after frame
+	StagTest.teardown.call_deferred()
+
+	var builder := IslandBuilder.new()
+	builder.name = "IslandBuilder"
+	add_child(builder)
+
+	var body := Node3D.new()
+	body.name = "body"
+	add_child(body)
+	var visual := Node3D.new()
+	visual.name = "visual"
+	add_child(visual)
+	var static_body := StaticBody3D.new()
+	static_body.name = "static_body"
+	add_child(static_body)
+
+	# Nothing configured, everything falls back to the builder
+	builder.output_to = NodePath()
+	StagTest.assert_equal(builder, builder.target(), "empty output should fall back to the builder")
+	StagTest.assert_equal(builder, builder.target_mesh_output(), "mesh output should fall back to the builder")
+	StagTest.assert_equal(builder, builder.target_collision_output(), "collision output should fall back to the builder")
+
+	builder.output_to = NodePath("../missing")
+	StagTest.assert_equal(builder, builder.target(), "missing output should fall back to the builder")
+
+	# General output, specific outputs fall back to it
+	builder.output_to = NodePath("../body")
+	StagTest.assert_equal(body, builder.target(), "should target the configured output")
+	StagTest.assert_equal(body, builder.target_mesh_output(), "mesh output should fall back to output_to")
+	StagTest.assert_equal(body, builder.target_collision_output(), "collision output should fall back to output_to")
+
+	builder.mesh_output_to = NodePath("../missing")
+	StagTest.assert_equal(body, builder.target_mesh_output(), "missing mesh output should fall back to output_to")
+
+	# Routing mesh and collision to separate nodes
+	builder.mesh_output_to = NodePath("../visual")
+	builder.collision_output_to = NodePath("../static_body")
+	StagTest.assert_equal(visual, builder.target_mesh_output(), "mesh should route to its configured node")
+	StagTest.assert_equal(static_body, builder.target_collision_output(), "collision should route to its configured node")
+	StagTest.assert_equal(body, builder.target(), "general output should be unaffected by specific outputs")
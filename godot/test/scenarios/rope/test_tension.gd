@@ -2,10 +2,12 @@ extends Node3D
 
 var loose_unbound: StagTest.SignalExpector
 var taut_snap: StagTest.SignalExpector
+var taut_snap_detailed: StagTest.SignalExpector
 
 func _ready() -> void:
 	loose_unbound = StagTest.signal_expector($loose_left.rope_unbound, "loose rope unbound")
 	taut_snap = StagTest.signal_expector($taut_right.rope_snapped, "taut rope snapped")
+	taut_snap_detailed = StagTest.signal_expector($taut_right.rope_snapped_detailed, "taut rope snapped with details")
 
 func _on_settle_timer_timeout() -> void:
 	# Read tension
@@ -29,6 +31,7 @@ func _on_settle_timer_timeout() -> void:
 	await StagTest.tick_timer_physics_process(3)
 	StagTest.assert_true(not is_instance_valid($taut_right.bind_to), "taut_right should not be attached to anything")
 	taut_snap.assert_emitted("taut rope should have snapped")
+	taut_snap_detailed.assert_emitted("taut rope should have snapped with details")
 
 func _on_test_timeout_timeout() -> void:
 	StagTest.teardown()
@@ -50,6 +50,7 @@ func _ready():
 		"res://test/scenarios/example/test_tick_timers.tscn",
 		"res://test/scenarios/example/test_workflow.tscn",
 		"res://test/scenarios/island_builder/test_island_builder.tscn",
+		"res://test/scenarios/island_builder/test_mesh_debug_view.tscn",
 		"res://test/scenarios/island_builder/test_settings.tscn",
 		"res://test/scenarios/logger/test_logger.tscn",
 #		"res://test/scenarios/physics_server/test_raycast.tscn",
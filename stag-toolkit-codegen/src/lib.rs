@@ -3,12 +3,14 @@
 use proc_macro::TokenStream;
 use quote::{ToTokens, quote};
 use syn::parse::{Parse, ParseStream};
-use syn::{Error, Expr, Ident, LitFloat, LitStr, Token, parse_macro_input};
+use syn::punctuated::Punctuated;
+use syn::{Error, Expr, Ident, Lit, LitInt, LitStr, Token, bracketed, parse_macro_input};
 
 // https://doc.rust-lang.org/reference/procedural-macros.html#derive-macros
 // https://www.freecodecamp.org/news/procedural-macros-in-rust/#heading-the-intostringhashmap-derive-macro
 
 /// Settings management with sensible defaults.
+/// Also implements `crate::utils::SettingBytes` for serializing settings, field by field.
 #[proc_macro_derive(ExposeSettings, attributes(setting))]
 pub fn expose_settings_fn(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as syn::DeriveInput);
@@ -17,11 +19,19 @@ pub fn expose_settings_fn(input: TokenStream) -> TokenStream {
     match &input.data {
         syn::Data::Struct(syn::DataStruct { fields, .. }) => {
             let mut defaults_list = quote! {};
+            let mut write_fields = quote! {};
+            let mut read_fields = quote! {};
 
             for field in fields {
                 let identifier = field.ident.as_ref().unwrap();
                 let mut use_default: bool = true;
 
+                write_fields.extend(
+                    quote! {crate::utils::SettingBytes::write_bytes(&self.#identifier, out);},
+                );
+                read_fields
+                    .extend(quote! {#identifier:crate::utils::SettingBytes::read_bytes(bytes)?,});
+
                 if let Some(attr) = field
                     .attrs
                     .iter()
@@ -52,6 +62,18 @@ pub fn expose_settings_fn(input: TokenStream) -> TokenStream {
                         }
                     }
                 }
+
+                #[automatically_derived]
+                impl crate::utils::SettingBytes for #struct_identifier {
+                    fn write_bytes(&self, out: &mut Vec<u8>) {
+                        #write_fields
+                    }
+                    fn read_bytes(bytes: &mut &[u8]) -> Option<Self> {
+                        Some(#struct_identifier {
+                            #read_fields
+                        })
+                    }
+                }
             }
         }
         _ => unimplemented!(),
@@ -61,15 +83,93 @@ pub fn expose_settings_fn(input: TokenStream) -> TokenStream {
 
 struct Setting {
     default: Option<Expr>,
-    min: Option<LitFloat>,
-    max: Option<LitFloat>,
-    incr: Option<LitFloat>,
+    min: Option<Lit>,
+    max: Option<Lit>,
+    incr: Option<Lit>,
     soft_min: bool,
     soft_max: bool,
     unit: Option<String>,
+    options: Vec<SettingOption>,
     public: bool,
 }
 
+impl Setting {
+    /// Returns the Godot export attribute for this setting.
+    /// Enum options take priority over ranges, and ranges require both a minimum and maximum.
+    fn exporter(&self) -> proc_macro2::TokenStream {
+        if !self.options.is_empty() {
+            let options = self.options.iter();
+            return quote! {#[export(enum=(#(#options),*))]};
+        }
+
+        if let Some(min) = &self.min
+            && let Some(max) = &self.max
+        {
+            let mut range = quote! {#min,#max};
+
+            if let Some(increment) = &self.incr {
+                range.extend(quote! {,#increment});
+            }
+
+            if self.soft_min {
+                range.extend(quote! {,or_lesser});
+            }
+
+            if self.soft_max {
+                range.extend(quote! {,or_greater});
+            }
+
+            if let Some(unit) = &self.unit {
+                range.extend(quote! {,suffix=#unit});
+            }
+
+            // Godot requires both minimum and maximum to be specified
+            return quote! {#[export(range=(#range))]};
+        }
+
+        quote! {#[export]}
+    }
+}
+
+/// A named value of an integer-backed enum setting, with an optional explicit discriminant.
+struct SettingOption {
+    name: Ident,
+    value: Option<LitInt>,
+}
+
+impl Parse for SettingOption {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        let mut value: Option<LitInt> = None;
+        if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            value = Some(input.parse()?);
+        }
+        Ok(SettingOption { name, value })
+    }
+}
+
+impl ToTokens for SettingOption {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let name = &self.name;
+        tokens.extend(match &self.value {
+            Some(value) => quote! {#name=#value},
+            None => quote! {#name},
+        });
+    }
+}
+
+/// Parses an integer or float literal for the given setting argument.
+fn parse_number(input: ParseStream, ident: &Ident) -> syn::Result<Lit> {
+    match input.parse::<Lit>() {
+        Ok(lit @ (Lit::Int(_) | Lit::Float(_))) => Ok(lit),
+        _ => Err(Error::new(
+            ident.span(),
+            format!("Expected an integer or float literal for '{ident}'"),
+        )),
+    }
+}
+
 struct SettingAttr {
     setting: Option<Setting>,
 }
@@ -77,12 +177,13 @@ struct SettingAttr {
 impl Parse for SettingAttr {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let mut default: Option<Expr> = None;
-        let mut min: Option<LitFloat> = None;
-        let mut max: Option<LitFloat> = None;
-        let mut incr: Option<LitFloat> = None;
+        let mut min: Option<Lit> = None;
+        let mut max: Option<Lit> = None;
+        let mut incr: Option<Lit> = None;
         let mut soft_min = false;
         let mut soft_max = false;
         let mut unit: Option<String> = None;
+        let mut options: Vec<SettingOption> = vec![];
         let mut public = false;
 
         while !input.is_empty() {
@@ -94,36 +195,15 @@ impl Parse for SettingAttr {
                 }
                 "min" => {
                     input.parse::<Token![=]>()?;
-                    if let Ok(lit) = input.parse::<LitFloat>() {
-                        min = Some(lit);
-                    } else {
-                        return Err(Error::new(
-                            ident.span(),
-                            "Expected a float literal for 'min'",
-                        ));
-                    }
+                    min = Some(parse_number(input, &ident)?);
                 }
                 "max" => {
                     input.parse::<Token![=]>()?;
-                    if let Ok(lit) = input.parse::<LitFloat>() {
-                        max = Some(lit);
-                    } else {
-                        return Err(Error::new(
-                            ident.span(),
-                            "Expected a float literal for 'max'",
-                        ));
-                    }
+                    max = Some(parse_number(input, &ident)?);
                 }
                 "incr" => {
                     input.parse::<Token![=]>()?;
-                    if let Ok(lit) = input.parse::<LitFloat>() {
-                        incr = Some(lit);
-                    } else {
-                        return Err(Error::new(
-                            ident.span(),
-                            "Expected a float literal for 'incr'",
-                        ));
-                    }
+                    incr = Some(parse_number(input, &ident)?);
                 }
                 "soft_min" => soft_min = true,
                 "soft_max" => soft_max = true,
@@ -138,6 +218,20 @@ impl Parse for SettingAttr {
                         ));
                     }
                 }
+                "options" => {
+                    input.parse::<Token![=]>()?;
+                    let content;
+                    bracketed!(content in input);
+                    options = Punctuated::<SettingOption, Token![,]>::parse_terminated(&content)?
+                        .into_iter()
+                        .collect();
+                    if options.is_empty() {
+                        return Err(Error::new(
+                            ident.span(),
+                            "Expected at least one option for 'options'",
+                        ));
+                    }
+                }
                 "public" => public = true,
                 _ => return Err(syn::Error::new_spanned(ident, "Unknown attribute")),
             }
@@ -148,6 +242,15 @@ impl Parse for SettingAttr {
             }
         }
 
+        if let Some(option) = options.first()
+            && (min.is_some() || max.is_some() || incr.is_some())
+        {
+            return Err(Error::new(
+                option.name.span(),
+                "'options' cannot be combined with 'min', 'max' or 'incr'",
+            ));
+        }
+
         Ok(SettingAttr {
             setting: Some(Setting {
                 default,
@@ -157,6 +260,7 @@ impl Parse for SettingAttr {
                 soft_min,
                 soft_max,
                 unit,
+                options,
                 public,
             }),
         })
@@ -165,6 +269,9 @@ impl Parse for SettingAttr {
 
 /// Generates a separate Godot class from the given struct, with exported properties based on the provided `setting` attributes.
 /// This macro requires a struct name and Godot base class as input.
+///
+/// Ranges accept integer or float literals for `min`, `max` and `incr`.
+/// Integer-backed enum settings can instead list their values with `options = [A, B = 4]`, exported as a Godot enum.
 #[proc_macro_attribute]
 pub fn settings_resource_from(attr: TokenStream, item: TokenStream) -> TokenStream {
     let args = parse_macro_input!(attr as SettingResourceAttr);
@@ -216,32 +323,7 @@ pub fn settings_resource_from(attr: TokenStream, item: TokenStream) -> TokenStre
 
                     // Check if we have a default argument
                     if let Some(settings) = args.setting {
-                        if let Some(min) = settings.min {
-                            let mut range = quote! {#min};
-
-                            if let Some(max) = settings.max {
-                                range.extend(quote! {,#max});
-
-                                if let Some(increment) = settings.incr {
-                                    range.extend(quote! {,#increment});
-                                }
-
-                                if settings.soft_min {
-                                    range.extend(quote! {,or_lesser});
-                                }
-
-                                if settings.soft_max {
-                                    range.extend(quote! {,or_greater});
-                                }
-
-                                if let Some(unit) = settings.unit {
-                                    range.extend(quote! {,suffix=#unit});
-                                }
-
-                                // Godot requires both minimum and maximum to be specified
-                                exporter = quote! {#[export(range=(#range))]};
-                            }
-                        }
+                        exporter = settings.exporter();
 
                         if let Some(default) = settings.default {
                             initializer = quote! {#[init(val=#default #type_conversion)]};
@@ -393,3 +475,80 @@ pub fn camera_process_toggles(_attr: TokenStream, item: TokenStream) -> TokenStr
     }
     .into()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SettingAttr;
+    use quote::quote;
+
+    /// Parses the given `setting` attribute arguments, and returns the generated Godot export attribute as a string.
+    fn exporter(args: proc_macro2::TokenStream) -> String {
+        let attr: SettingAttr = syn::parse2(args).expect("setting should parse");
+        attr.setting
+            .expect("setting should be present")
+            .exporter()
+            .to_string()
+    }
+
+    #[test]
+    fn test_export_range_float() {
+        assert_eq!(
+            quote! {#[export(range=(0.05,1.0,0.001,or_greater,suffix="m"))]}.to_string(),
+            exporter(
+                quote! {default=Vec3::splat(0.275), min=0.05, max=1.0, incr=0.001, soft_max, unit="m"}
+            ),
+        );
+        assert_eq!(
+            quote! {#[export(range=(-1.0,2.0,or_lesser))]}.to_string(),
+            exporter(quote! {default = -1.0, min = -1.0, max = 2.0, soft_min}),
+        );
+    }
+
+    #[test]
+    fn test_export_range_int() {
+        assert_eq!(
+            quote! {#[export(range=(0,6,or_greater))]}.to_string(),
+            exporter(quote! {default = 3, min = 0, max = 6, soft_max}),
+        );
+        assert_eq!(
+            quote! {#[export(range=(1,512,1))]}.to_string(),
+            exporter(quote! {default = 32, min = 1, max = 512, incr = 1}),
+        );
+        assert_eq!(
+            quote! {#[export]}.to_string(),
+            exporter(quote! {default = 4, min = 1}),
+            "ranges without a maximum should use a plain export"
+        );
+    }
+
+    #[test]
+    fn test_export_enum() {
+        assert_eq!(
+            quote! {#[export(enum=(Box,Gaussian,Bilateral))]}.to_string(),
+            exporter(quote! {default = 0, options = [Box, Gaussian, Bilateral]}),
+        );
+        assert_eq!(
+            quote! {#[export(enum=(Off=0,Fast=2,Full=4))]}.to_string(),
+            exporter(quote! {default = 2, options = [Off = 0, Fast = 2, Full = 4,], public}),
+        );
+    }
+
+    #[test]
+    fn test_invalid_settings() {
+        for args in [
+            quote! {min = "0"},
+            quote! {max = Vec3::ONE},
+            quote! {incr = true},
+            quote! {unit = 5},
+            quote! {options = []},
+            quote! {options = [A, B], min = 0, max = 1},
+            quote! {options = [A = 1.0]},
+            quote! {unknown = 1},
+        ] {
+            assert!(
+                syn::parse2::<SettingAttr>(args.clone()).is_err(),
+                "setting arguments `{args}` should fail to parse"
+            );
+        }
+    }
+}
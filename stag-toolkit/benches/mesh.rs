@@ -49,6 +49,9 @@ fn mesh_baking(c: &mut Criterion) {
     });
 
     group.bench_function("optimize", |b| b.iter(|| preview.clone().optimize(1e-6)));
+    group.bench_function("quantize_weld", |b| {
+        b.iter(|| preview.clone().quantize_weld(1e-3))
+    });
 
     // Optimize mesh before performing bakes
     preview.optimize(1e-6);
@@ -64,6 +67,7 @@ fn mesh_baking(c: &mut Criterion) {
             preview.get_ambient_occlusion(
                 8,
                 10.0,
+                std::f32::consts::FRAC_PI_2,
                 321,
                 NonZero::new(16).expect("This should never fail"),
             )
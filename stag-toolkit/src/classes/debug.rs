@@ -0,0 +1,153 @@
+use godot::classes::base_material_3d::{Flags, ShadingMode};
+use godot::classes::mesh::PrimitiveType;
+use godot::classes::{IMeshInstance3D, ImmediateMesh, Mesh, MeshInstance3D, StandardMaterial3D};
+use godot::prelude::*;
+
+use crate::math::types::ToVector3;
+use crate::math::types::gdmath::ToColor;
+use crate::mesh::godot::GodotSurfaceArrays;
+use crate::mesh::island::heatmap_color;
+use crate::mesh::trimesh::TriangleMesh;
+
+/// Draws debug information for a mesh, such as one baked by the [IslandBuilder].
+/// Per-vertex normals are drawn as lines, and a vertex color channel can be drawn as a heatmap.
+///
+/// Only the first surface of the [member source_mesh] is visualized.
+#[derive(GodotClass)]
+#[class(init,base=MeshInstance3D,tool,rename=StagMeshDebugView)]
+pub struct MeshDebugView {
+    /// Mesh to visualize.
+    #[var(set = set_source_mesh)]
+    #[export]
+    #[init(val = None)]
+    source_mesh: Option<Gd<Mesh>>,
+
+    /// Whether to draw a line along each vertex normal.
+    #[var(set = set_show_normals)]
+    #[export]
+    #[init(val = true)]
+    show_normals: bool,
+
+    /// Length of each drawn vertex normal.
+    #[var(set = set_normal_length)]
+    #[export(range = (0.0, 2.0, 0.001, or_greater, suffix="m"))]
+    #[init(val = 0.25)]
+    normal_length: f32,
+
+    /// Vertex color channel to draw as a heatmap over the mesh, from blue at 0 to red at 1.
+    /// On baked islands, Red is Ambient Occlusion, Green is dirt, Blue is sand, and Alpha is the noise mask.
+    #[var(set = set_heatmap_channel)]
+    #[export(enum = (Disabled = 0, Red = 1, Green = 2, Blue = 3, Alpha = 4))]
+    #[init(val = 1)]
+    heatmap_channel: i32,
+
+    /// Number of line segments drawn on the last rebuild.
+    line_segments: usize,
+
+    base: Base<MeshInstance3D>,
+}
+
+#[godot_api]
+impl IMeshInstance3D for MeshDebugView {
+    fn ready(&mut self) {
+        self.rebuild();
+    }
+}
+
+#[godot_api]
+impl MeshDebugView {
+    #[func]
+    fn set_source_mesh(&mut self, source_mesh: Option<Gd<Mesh>>) {
+        self.source_mesh = source_mesh;
+        self.rebuild();
+    }
+
+    #[func]
+    fn set_show_normals(&mut self, show_normals: bool) {
+        self.show_normals = show_normals;
+        self.rebuild();
+    }
+
+    #[func]
+    fn set_normal_length(&mut self, normal_length: f32) {
+        self.normal_length = normal_length;
+        self.rebuild();
+    }
+
+    #[func]
+    fn set_heatmap_channel(&mut self, heatmap_channel: i32) {
+        self.heatmap_channel = heatmap_channel;
+        self.rebuild();
+    }
+
+    /// Returns the number of normal line segments drawn on the last rebuild.
+    #[func]
+    pub fn get_line_segment_count(&self) -> i64 {
+        self.line_segments as i64
+    }
+
+    /// Redraws the debug visualization from the [member source_mesh].
+    #[func]
+    pub fn rebuild(&mut self) {
+        self.line_segments = 0;
+
+        let trimesh = self
+            .source_mesh
+            .as_ref()
+            .and_then(|mesh| GodotSurfaceArrays::from_mesh_surface(mesh, 0))
+            .map(|surface| surface.to_trimesh())
+            .filter(|trimesh| !trimesh.positions.is_empty());
+        let Some(trimesh) = trimesh else {
+            self.base_mut().set_mesh(Gd::<Mesh>::null_arg());
+            return;
+        };
+
+        let mut material = StandardMaterial3D::new_gd();
+        material.set_shading_mode(ShadingMode::UNSHADED);
+        material.set_flag(Flags::ALBEDO_FROM_VERTEX_COLOR, true);
+
+        let mut immediate = ImmediateMesh::new_gd();
+        if self.heatmap_channel > 0 && !trimesh.colors.is_empty() {
+            self.draw_heatmap(&mut immediate, &trimesh, &material);
+        }
+        if self.show_normals && !trimesh.normals.is_empty() {
+            let segments = trimesh.normal_segments(self.normal_length);
+            immediate
+                .surface_begin_ex(PrimitiveType::LINES)
+                .material(&material)
+                .done();
+            immediate.surface_set_color(Color::CYAN);
+            for (start, end) in segments.iter() {
+                immediate.surface_add_vertex(start.to_vector3());
+                immediate.surface_add_vertex(end.to_vector3());
+            }
+            immediate.surface_end();
+            self.line_segments = segments.len();
+        }
+
+        self.base_mut().set_mesh(&immediate);
+    }
+
+    /// Draws the selected vertex color channel of the mesh as a heatmap.
+    fn draw_heatmap(
+        &self,
+        immediate: &mut Gd<ImmediateMesh>,
+        trimesh: &TriangleMesh,
+        material: &Gd<StandardMaterial3D>,
+    ) {
+        let channel = (self.heatmap_channel - 1).clamp(0, 3) as usize;
+
+        immediate
+            .surface_begin_ex(PrimitiveType::TRIANGLES)
+            .material(material)
+            .done();
+        for tri in trimesh.triangles.iter() {
+            for idx in tri {
+                immediate
+                    .surface_set_color(heatmap_color(trimesh.colors[*idx][channel]).to_color());
+                immediate.surface_add_vertex(trimesh.positions[*idx].to_vector3());
+            }
+        }
+        immediate.surface_end();
+    }
+}
@@ -1,10 +1,15 @@
 use crate::classes::island_settings::IslandBuilderSettings;
 use crate::math::bounding_box::BoundingBox;
-use crate::mesh::island::{Data, IslandBuilderSettingsTweaks, SettingsTweaks};
+use crate::mesh::island::{
+    CollisionLod, Data, IslandBuilderSettingsTweaks, SettingsTweaks, hull_debug_color,
+};
 use crate::mesh::trimesh::TriangleMesh;
 use crate::{
     classes::utils::editor_lock,
-    math::types::ToVector3,
+    math::types::{
+        ToVector3,
+        gdmath::{ToColor, ToTransform3D},
+    },
     mesh::godot::{GodotSurfaceArrays, GodotWhitebox},
 };
 use core::f32;
@@ -14,10 +19,14 @@ use godot::signal::ConnectHandle;
 use godot::{
     classes::{
         ArrayMesh, CollisionShape3D, ConvexPolygonShape3D, MeshInstance3D, ProjectSettings,
-        RigidBody3D, mesh::PrimitiveType, physics_server_3d::BodyAxis,
+        RigidBody3D,
+        mesh::{ArrayFormat, PrimitiveType},
+        physics_server_3d::BodyAxis,
     },
     prelude::*,
 };
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
 use std::thread::JoinHandle;
 
@@ -60,6 +69,16 @@ pub struct IslandBuilder {
     #[export]
     #[init(val=NodePath::from("."))]
     output_to: NodePath,
+    /// Node to target for storing the visual mesh.
+    /// If empty or target is not found, falls back to `output_to`.
+    #[export]
+    #[init(val=NodePath::default())]
+    mesh_output_to: NodePath,
+    /// Node to target for storing collision shapes and physics properties.
+    /// If empty or target is not found, falls back to `output_to`.
+    #[export]
+    #[init(val=NodePath::default())]
+    collision_output_to: NodePath,
 
     /// If true, the node will watch for changes in its settings, and regenerate when needed.
     /// Only during editor.
@@ -70,6 +89,9 @@ pub struct IslandBuilder {
     /// Thread handle for real-time preview.
     #[init(val=None)]
     realtime_preview_thread: Option<JoinHandle<Option<TriangleMesh>>>,
+    /// Cancel flag of the running real-time preview thread.
+    #[init(val=None)]
+    realtime_preview_cancel: Option<Arc<AtomicBool>>,
     /// Swap buffer for real-time preview.
     #[init(val=None)]
     realtime_preview_mesh_buffer: Option<Gd<ArrayMesh>>,
@@ -83,6 +105,21 @@ pub struct IslandBuilder {
     #[init(val=None)]
     settings: Option<Gd<IslandBuilderSettings>>,
 
+    /// If true, the noise seed is offset by a stable hash of the node's position,
+    /// so islands sharing the same tweaks still vary when placed in different locations.
+    #[var(set=set_auto_seed)]
+    #[export]
+    #[init(val = false)]
+    auto_seed: bool,
+
+    /// Transform from the IslandBuilder's local space into the space noise and UVs are sampled in.
+    /// Islands sharing a sampling space, such as their common parent's space, have continuous noise and UVs across each other.
+    /// Shapes are unaffected.
+    #[var(set=set_sampling_space)]
+    #[export]
+    #[init(val = Transform3D::IDENTITY)]
+    sampling_space: Transform3D,
+
     #[init(val=None)]
     handle_tweaks: Option<ConnectHandle>,
     #[init(val=None)]
@@ -111,7 +148,7 @@ impl INode3D for IslandBuilder {
     }
 
     fn exit_tree(&mut self) {
-        self.wait_for_preview_finish(); // wait for preview to finish
+        self.cancel_preview(); // don't wait on a preview that will never be shown
     }
 
     fn process(&mut self, _delta: f64) {
@@ -138,23 +175,34 @@ impl IslandBuilder {
 
         self.tweaks = tweaks;
 
-        let changed = self.data.set_tweaks(match &self.tweaks {
-            Some(tweaks) => {
-                // Connect to change events
-                let builder = self.to_gd();
-                self.handle_tweaks = Some(
-                    tweaks
-                        .signals()
-                        .changed()
-                        .builder()
-                        .connect_other_mut(&builder, Self::apply_tweaks),
-                );
-
-                tweaks.bind().to_struct()
-            }
-            _ => SettingsTweaks::default(),
-        });
-        if changed {
+        // Connect to change events
+        if let Some(tweaks) = &self.tweaks {
+            let builder = self.to_gd();
+            self.handle_tweaks = Some(
+                tweaks
+                    .signals()
+                    .changed()
+                    .builder()
+                    .connect_other_mut(&builder, Self::apply_tweaks),
+            );
+        }
+
+        self.apply_tweaks();
+    }
+
+    #[func]
+    fn set_auto_seed(&mut self, auto_seed: bool) {
+        self.auto_seed = auto_seed;
+        self.apply_tweaks();
+    }
+
+    #[func]
+    fn set_sampling_space(&mut self, sampling_space: Transform3D) {
+        self.sampling_space = sampling_space;
+        if self
+            .data
+            .set_sampling_space(sampling_space.to_transform3d())
+        {
             self.update_preview();
         }
     }
@@ -230,7 +278,19 @@ impl IslandBuilder {
         }
     }
 
+    /// Cancels any running real-time preview, discarding its result.
+    fn cancel_preview(&mut self) {
+        if let Some(cancel) = self.realtime_preview_cancel.take() {
+            cancel.store(true, Ordering::Relaxed);
+        }
+        if let Some(handle) = self.realtime_preview_thread.take() {
+            // Canceled bakes stop early, so this should not block for long
+            handle.join().expect("realtime preview thread panicked");
+        }
+    }
+
     fn wait_for_preview_finish(&mut self) {
+        self.realtime_preview_cancel = None;
         if let Some(handle) = self.realtime_preview_thread.take() {
             let data = handle.join().expect("realtime preview thread panicked");
             if let Some(trimesh) = data {
@@ -267,12 +327,28 @@ impl IslandBuilder {
     /// Copies the tweak settings into the builder data.
     #[func]
     fn apply_tweaks(&mut self) {
-        if self.data.set_tweaks(match &self.tweaks {
+        if self.data.set_tweaks(self.internal_tweaks()) {
+            self.update_preview();
+        }
+    }
+
+    /// Returns the tweak settings to use for building, with the seed offset by position if `auto_seed` is enabled.
+    fn internal_tweaks(&self) -> SettingsTweaks {
+        let tweaks = match &self.tweaks {
             Some(tweaks) => tweaks.bind().to_struct(),
             _ => SettingsTweaks::default(),
-        }) {
-            self.update_preview();
+        };
+
+        if !self.auto_seed {
+            return tweaks;
         }
+
+        let position = if self.base().is_inside_tree() {
+            self.base().get_global_position()
+        } else {
+            self.base().get_position()
+        };
+        tweaks.with_position_seed(position.to_vector3())
     }
 
     /// Applies Godot settings to corresponding whitebox and mesh data.
@@ -320,12 +396,69 @@ impl IslandBuilder {
         self.data.get_volume()
     }
 
+    /// Returns the number of bytes used by cached bake data.
+    /// Call `clear_cache` to free it.
+    #[func]
+    pub fn get_memory_footprint(&self) -> i64 {
+        self.data.total_memory_footprint() as i64
+    }
+
+    /// Returns a 2D slice of the raw voxel field perpendicular to the given axis (0 = X, 1 = Y, 2 = Z),
+    /// for visualizing the Signed Distance Field. Bakes voxels if necessary.
+    /// Values are ordered along the first remaining axis, then the second, i.e. a Z slice is laid out along X, then Y.
+    /// Out-of-range slice indices are clamped.
+    ///
+    /// Returns an empty array if no shapes are serialized, or the axis is invalid.
+    #[func]
+    pub fn debug_voxel_slice(&mut self, axis: i32, slice: i32) -> PackedFloat32Array {
+        if self.data.get_shapes().is_empty() {
+            godot_warn!("IslandBuilder: no shapes serialized, cannot slice voxels.");
+            return PackedFloat32Array::new();
+        }
+        if !(0..3).contains(&axis) {
+            godot_warn!("IslandBuilder: invalid voxel slice axis {axis}, expected 0, 1 or 2.");
+            return PackedFloat32Array::new();
+        }
+
+        Self::warn_over_budget(&self.data);
+        self.data.bake_voxels();
+
+        match self.data.voxel_slice(axis as usize, slice.max(0) as usize) {
+            Some(values) => PackedFloat32Array::from(values.as_slice()),
+            None => PackedFloat32Array::new(),
+        }
+    }
+
+    /// Returns the dimensions of the raw voxel field, for use with `debug_voxel_slice`.
+    /// Bakes voxels if necessary. Returns zero if no shapes are serialized.
+    #[func]
+    pub fn debug_voxel_dimensions(&mut self) -> Vector3i {
+        if !self.data.get_shapes().is_empty() {
+            Self::warn_over_budget(&self.data);
+            self.data.bake_voxels();
+        }
+
+        let [x, y, z] = self.data.get_voxel_dimensions();
+        Vector3i::new(x as i32, y as i32, z as i32)
+    }
+
     /// Returns the number of currently serialized shapes.
     #[func]
     pub fn get_shape_count(&self) -> i32 {
         self.data.get_shapes().len() as i32
     }
 
+    /// Returns line segments outlining the serialized whitebox shapes, in local space.
+    /// Every two points describe a segment, for use with `EditorNode3DGizmo.add_lines`.
+    #[func]
+    pub fn get_whitebox_outline(&self) -> PackedVector3Array {
+        self.data
+            .get_shape_outlines()
+            .iter()
+            .flat_map(|(a, b)| [a.to_vector3(), b.to_vector3()])
+            .collect()
+    }
+
     // Build Steps //
 
     /// Clears the build cache. Frees up system memory,
@@ -343,7 +476,12 @@ impl IslandBuilder {
     pub fn serialize(&mut self) {
         let mut whitebox = GodotWhitebox::new();
         whitebox.serialize_from(self.base().to_godot());
-        let changed = self.data.set_shapes(whitebox.get_shapes().clone());
+        let mut changed = self.data.set_shapes(whitebox.get_shapes().clone());
+
+        // Pick up position changes for automatic seeding
+        if self.auto_seed {
+            changed = self.data.set_tweaks(self.internal_tweaks()) || changed;
+        }
 
         if changed {
             self.base_mut().update_gizmos(); // Force redraw of IslandBuilder gizmo
@@ -362,22 +500,41 @@ impl IslandBuilder {
             if preview_thread.is_finished() {
                 self.wait_for_preview_finish(); // collect task resources if necessary
             } else {
-                // TODO: dirty preview so we update it again if not changing
-                return; // don't spawn another thread
+                // Preview is now stale, so cancel it and start over with the latest input
+                self.cancel_preview();
             }
         }
 
         self.serialize();
 
+        let cancel = Arc::new(AtomicBool::new(false));
         let mut preview_data = self.data.clone_for_preview();
+        preview_data.set_cancel_flag(Some(cancel.clone()));
+        preview_data.bake_bounding_box();
+        if Self::warn_over_budget(&preview_data) {
+            return;
+        }
         let handle = thread::spawn(move || {
-            preview_data.bake_bounding_box();
             preview_data.bake_voxels();
             preview_data.bake_preview();
             preview_data.take_mesh_preview()
         });
 
         self.realtime_preview_thread = Some(handle);
+        self.realtime_preview_cancel = Some(cancel);
+    }
+
+    /// Pushes a warning if a bake of the given data would exceed the triangle budget, and returns true if so.
+    fn warn_over_budget(data: &Data) -> bool {
+        if !data.exceeds_triangle_budget() {
+            return false;
+        }
+        godot_warn!(
+            "IslandBuilder: bake aborted, approximately {} triangles exceeds the budget of {}. Increase the voxel size or the triangle budget.",
+            data.projected_triangle_count(),
+            data.get_voxel_settings().max_triangle_budget
+        );
+        true
     }
 
     /// Returns an unoptimized triangle mesh for previewing with no extra information baked-in.
@@ -385,6 +542,7 @@ impl IslandBuilder {
     /// Returns an empty mesh if there is no data to bake.
     #[func]
     pub fn generate_preview_mesh(&mut self, recycle_mesh: Option<Gd<ArrayMesh>>) -> Gd<ArrayMesh> {
+        Self::warn_over_budget(&self.data);
         self.data.bake_voxels();
         self.data.bake_preview();
 
@@ -415,10 +573,13 @@ impl IslandBuilder {
 
     fn trimesh_to_preview(&self, trimesh: &TriangleMesh, mut array_mesh: Gd<ArrayMesh>) {
         let surface_arrays = GodotSurfaceArrays::from_trimesh(trimesh);
-        array_mesh.add_surface_from_arrays(
-            PrimitiveType::TRIANGLES,
-            surface_arrays.get_surface_arrays(),
-        );
+        array_mesh
+            .add_surface_from_arrays_ex(
+                PrimitiveType::TRIANGLES,
+                surface_arrays.get_surface_arrays(),
+            )
+            .flags(ArrayFormat::from_ord(surface_arrays.get_format_flags()))
+            .done();
         array_mesh.surface_set_name(0, "island");
         // Add a material, if valid
         if let Some(material) = &self.settings_internal.bind().get_material_preview() {
@@ -431,25 +592,42 @@ impl IslandBuilder {
     /// Returns an empty mesh if there is no data to bake.
     #[func]
     pub fn generate_baked_mesh(&mut self) -> Gd<ArrayMesh> {
+        Self::warn_over_budget(&self.data);
         self.data.bake_voxels();
         self.data.bake_preview();
         self.data.bake_mesh();
 
         match self.data.get_mesh_baked() {
             Some(trimesh) => {
-                let surface_arrays = GodotSurfaceArrays::from_trimesh(trimesh);
+                // Split into a surface per material index, if shapes were assigned materials
+                let surfaces = GodotSurfaceArrays::from_trimesh_split(trimesh);
+                let surface_name = |material: u32| -> GString {
+                    if surfaces.len() > 1 {
+                        format!("island_{material}").into()
+                    } else {
+                        "island".into()
+                    }
+                };
+
                 let mut importer = ImporterMesh::new_gd();
-                importer.add_surface(
-                    PrimitiveType::TRIANGLES,
-                    surface_arrays.get_surface_arrays(),
-                );
+                for (_, surface_arrays) in surfaces.iter() {
+                    importer
+                        .add_surface_ex(
+                            PrimitiveType::TRIANGLES,
+                            surface_arrays.get_surface_arrays(),
+                        )
+                        .flags(surface_arrays.get_format_flags())
+                        .done();
+                }
                 importer.generate_lods(25.0, 60.0, &varray![]);
-                importer.set_surface_name(0, "island");
 
-                // If we have a material, assign it!
+                // If we have a material, assign it to every surface!
                 let material = &self.settings_internal.bind().get_material_baked();
-                if let Some(material) = material {
-                    importer.set_surface_material(0, material);
+                for (idx, (material_index, _)) in surfaces.iter().enumerate() {
+                    importer.set_surface_name(idx as i32, &surface_name(*material_index));
+                    if let Some(material) = material {
+                        importer.set_surface_material(idx as i32, material);
+                    }
                 }
 
                 // If we were able to successfully generate a mesh, return it
@@ -461,14 +639,18 @@ impl IslandBuilder {
                 godot_warn!("IslandBuilder: LOD generation failed. Returning island with no LODs.");
 
                 let mut mesh = ArrayMesh::new_gd();
-                mesh.add_surface_from_arrays(
-                    PrimitiveType::TRIANGLES,
-                    surface_arrays.get_surface_arrays(),
-                );
-                mesh.surface_set_name(0, "island");
-
-                if let Some(material) = material {
-                    mesh.surface_set_material(0, material);
+                for (idx, (material_index, surface_arrays)) in surfaces.iter().enumerate() {
+                    mesh.add_surface_from_arrays_ex(
+                        PrimitiveType::TRIANGLES,
+                        surface_arrays.get_surface_arrays(),
+                    )
+                    .flags(ArrayFormat::from_ord(surface_arrays.get_format_flags()))
+                    .done();
+                    mesh.surface_set_name(idx as i32, &surface_name(*material_index));
+
+                    if let Some(material) = material {
+                        mesh.surface_set_material(idx as i32, material);
+                    }
                 }
 
                 mesh
@@ -481,18 +663,63 @@ impl IslandBuilder {
     /// Returns an empty array if there is no data to bake.
     #[func]
     pub fn generate_collision_hulls(&mut self) -> Array<Gd<ConvexPolygonShape3D>> {
+        self.generate_collision_hulls_lod(CollisionLod::Full)
+    }
+    /// Computes and returns a list of coarse collision hulls, for distant islands.
+    /// Uses fewer hulls and stronger decimation than `generate_collision_hulls`.
+    /// Bakes underlying voxel and mesh data if necessary.
+    /// Returns an empty array if there is no data to bake.
+    #[func]
+    pub fn generate_collision_hulls_coarse(&mut self) -> Array<Gd<ConvexPolygonShape3D>> {
+        self.generate_collision_hulls_lod(CollisionLod::Coarse)
+    }
+    fn generate_collision_hulls_lod(
+        &mut self,
+        lod: CollisionLod,
+    ) -> Array<Gd<ConvexPolygonShape3D>> {
+        Self::warn_over_budget(&self.data);
         self.data.bake_voxels();
         self.data.bake_preview();
-        self.data.bake_collision();
+        self.data.bake_collision_lod(lod);
 
-        let hull_pts = self.data.get_hulls();
+        Self::hulls_to_shapes(self.data.get_hulls_lod(lod))
+    }
+    /// Computes and returns a list of collision hulls, by approximate convex decomposition of the whole island mesh.
+    /// Better suited to heavily overlapping shapes, or concave shapes carved by subtractions, than `generate_collision_hulls`.
+    /// Pieces are split until within `concavity` distance of being convex, or until there are `max_hulls` pieces.
+    /// Bakes underlying voxel and mesh data if necessary.
+    /// Returns an empty array if there is no data to bake.
+    #[func]
+    pub fn generate_collision_hulls_decomposed(
+        &mut self,
+        max_hulls: i64,
+        concavity: f32,
+    ) -> Array<Gd<ConvexPolygonShape3D>> {
+        Self::warn_over_budget(&self.data);
+        self.data.bake_voxels();
+        self.data.bake_preview();
+        self.data
+            .bake_collision_vhacd(max_hulls.max(1) as usize, concavity.max(0.0));
 
-        Array::<Gd<ConvexPolygonShape3D>>::from_iter(hull_pts.iter().map(|pts| {
+        Self::hulls_to_shapes(self.data.get_hulls())
+    }
+    fn hulls_to_shapes(hulls: &[TriangleMesh]) -> Array<Gd<ConvexPolygonShape3D>> {
+        Array::<Gd<ConvexPolygonShape3D>>::from_iter(hulls.iter().map(|pts| {
             let mut shape = ConvexPolygonShape3D::new_gd();
             shape.set_points(&pts.positions.to_vector3()); // Fetch remaining positions from the hull
             shape
         }))
     }
+    /// Returns the index of the collision hull from [method generate_collision_hulls] that contains the given point,
+    /// in the IslandBuilder's local space. Useful for localized destruction.
+    /// Returns -1 if collision hulls have not been generated.
+    #[func]
+    pub fn get_hull_index_at(&self, position: Vector3) -> i64 {
+        self.data
+            .hull_index_at(position.to_vector3())
+            .map_or(-1, |idx| idx as i64)
+    }
+
     /// Computes and returns the navigation properties of the island.
     /// Properties will be zero'd if not pre-computed.
     #[func]
@@ -523,7 +750,7 @@ impl IslandBuilder {
     /// Sets up physics properties on RigidBodies when possible.
     #[func]
     fn apply_collision_hulls(&mut self, hulls: Array<Gd<ConvexPolygonShape3D>>, volume: f32) {
-        let mut target = self.target();
+        let mut target = self.target_collision_output();
 
         // Remove all current collider children
         for child in target.get_children().iter_shared() {
@@ -539,13 +766,19 @@ impl IslandBuilder {
 
         // Fetch color for debug drawing
         let debug_color: Color = self.settings_internal.bind().get_collision_color();
+        let debug_color_per_hull = self.settings_internal.bind().get_debug_color_per_hull();
 
         // Get collision hulls
         for (idx, hull) in hulls.iter_shared().enumerate() {
             let mut shape = CollisionShape3D::new_alloc();
             shape.set_shape(&hull);
             shape.set_name(&format!("collis{idx}"));
-            shape.set_debug_color(debug_color); // Apply debug draw color
+            // Apply debug draw color
+            if debug_color_per_hull {
+                shape.set_debug_color(hull_debug_color(idx, debug_color.a).to_color());
+            } else {
+                shape.set_debug_color(debug_color);
+            }
             editor_lock(shape.clone().upcast(), true); // Lock editing
 
             target.add_child(&shape); // Add shape to scene
@@ -607,17 +840,38 @@ impl IslandBuilder {
     #[func]
     fn target(&mut self) -> Gd<Node> {
         let target = self.base().get_node_or_null(&self.output_to);
-        match target {
-            Some(node) => node,
-            None => self.base_mut().clone().upcast::<Node>(),
+        target.unwrap_or(self.base_mut().clone().upcast::<Node>())
+    }
+
+    /// Fetches the output [Node] for storing the visual mesh.
+    /// If no mesh output is specified, uses `target()` instead.
+    #[func]
+    fn target_mesh_output(&mut self) -> Gd<Node> {
+        let specific = self.find_output(&self.mesh_output_to.clone());
+        specific.unwrap_or_else(|| self.target())
+    }
+
+    /// Fetches the output [Node] for storing collision shapes.
+    /// If no collision output is specified, uses `target()` instead.
+    #[func]
+    fn target_collision_output(&mut self) -> Gd<Node> {
+        let specific = self.find_output(&self.collision_output_to.clone());
+        specific.unwrap_or_else(|| self.target())
+    }
+
+    /// Returns the node at the given path, if the path is non-empty and the node exists.
+    fn find_output(&self, path: &NodePath) -> Option<Gd<Node>> {
+        if path.is_empty() {
+            return None;
         }
+        self.base().get_node_or_null(path)
     }
 
     /// Fetches the output [MeshInstance3D] for this IslandBuilder.
     /// Creates one if none was found.
     #[func]
     fn target_mesh(&mut self) -> Gd<MeshInstance3D> {
-        let mut target = self.target();
+        let mut target = self.target_mesh_output();
 
         // Find a mesh
         for child in target.get_children().iter_shared() {
@@ -647,7 +901,7 @@ impl IslandBuilder {
         mesh
     }
 
-    /// Destroys all MeshInstance3D and CollisionShape3D nodes directly under the output node.
+    /// Destroys all MeshInstance3D and CollisionShape3D nodes directly under the mesh and collision output nodes.
     /// Clears all working data: The IslandBuilder will have to be re-serialized and netted.
     /// Removes PackedScene references on the IslandBuilder's target node.
     #[func]
@@ -657,25 +911,19 @@ impl IslandBuilder {
         let mut out = self.target();
         out.set_scene_file_path(""); // Clear scene file path
 
-        // Iterate over all children.
-        for child in out.get_children().iter_shared() {
-            // match_class! {}
+        // Clear meshes from all MeshInstance3D nodes under the mesh output
+        for child in self.target_mesh_output().get_children().iter_shared() {
+            if let Ok(mut mesh) = child.try_cast::<MeshInstance3D>() {
+                mesh.set_mesh(Gd::null_arg());
+            }
+        }
 
-            // If this is a MeshInstance3D, destroy it
-            match child.try_cast::<MeshInstance3D>() {
-                Ok(mut mesh) => {
-                    mesh.set_mesh(Gd::null_arg());
-                }
-                Err(as_node) => {
-                    // OR, if this is a CollisionShape3D, destroy it
-                    match as_node.try_cast::<CollisionShape3D>() {
-                        Ok(mut collision) => {
-                            out.remove_child(&collision);
-                            collision.queue_free();
-                        }
-                        Err(_as_node_again) => {}
-                    }
-                }
+        // Destroy all CollisionShape3D nodes under the collision output
+        let mut collision_out = self.target_collision_output();
+        for child in collision_out.get_children().iter_shared() {
+            if let Ok(mut collision) = child.try_cast::<CollisionShape3D>() {
+                collision_out.remove_child(&collision);
+                collision.queue_free();
             }
         }
     }
@@ -61,6 +61,12 @@ pub struct IslandBuilderSettings {
     #[export]
     #[init(val=Color::from_rgba(1.0, 0.0, 0.667, 1.0))]
     collision_color: Color,
+    /// If true, each collision hull is debug drawn in a distinct color, cycling a palette by hull index,
+    /// so the hull partition is visible in the editor. Otherwise, all hulls use the collision color.
+    #[var(pub, set = set_debug_color_per_hull)]
+    #[export]
+    #[init(val = false)]
+    debug_color_per_hull: bool,
 
     #[var(pub, set = set_render_layers,hint=LAYERS_3D_RENDER)]
     #[export]
@@ -229,6 +235,12 @@ impl IslandBuilderSettings {
         self.base_mut().emit_changed();
     }
 
+    #[func]
+    fn set_debug_color_per_hull(&mut self, debug_color_per_hull: bool) {
+        self.debug_color_per_hull = debug_color_per_hull;
+        self.base_mut().emit_changed();
+    }
+
     #[func]
     fn set_render_layers(&mut self, render_layers: u32) {
         self.render_layers = render_layers;
@@ -1,5 +1,7 @@
-use crate::math::primqueue::FloatQueue;
-use crate::math::types::gdmath::{ToVector2, Vec2Godot, packed_float32_array};
+use crate::math::primqueue::{FloatQueue, PrimQueue};
+use crate::math::types::ToVector3;
+use crate::math::types::gdmath::{ToVector2, Vec2Godot, Vec3Godot, packed_float32_array};
+use glam::Vec3;
 use godot::{prelude::GodotClass, prelude::*};
 
 // GODOT IMPLEMENTATION //
@@ -91,3 +93,85 @@ impl QueueFloat {
         self.queue.standard_deviation(self.queue.mean())
     }
 }
+
+/// A queue of positions, such as for streaming recent positions of a trail.
+/// Unbounded by default. When given a capacity, pushing onto a full queue drops the oldest position.
+#[derive(GodotClass)]
+#[class(base=RefCounted)]
+pub struct QueueVector3 {
+    queue: PrimQueue<Vec3>,
+    base: Base<RefCounted>,
+}
+
+#[godot_api]
+impl IRefCounted for QueueVector3 {
+    fn init(base: Base<RefCounted>) -> Self {
+        Self {
+            queue: PrimQueue::new(),
+            base,
+        }
+    }
+}
+
+#[godot_api]
+impl QueueVector3 {
+    /// Sets the maximum number of positions the queue can hold, or a negative value for unbounded.
+    /// If the queue holds more positions than the new capacity, the oldest positions are dropped.
+    /// When `overwrite` is set, pushing onto a full queue drops the oldest position, otherwise the new position is rejected.
+    #[func]
+    pub fn set_capacity(&mut self, capacity: i64, overwrite: bool) {
+        self.queue
+            .set_capacity((capacity >= 0).then_some(capacity as usize));
+        self.queue.set_overwrite(overwrite);
+    }
+
+    /// Returns the maximum number of positions the queue can hold, or -1 if unbounded.
+    #[func]
+    pub fn get_capacity(&self) -> i64 {
+        self.queue.capacity().map_or(-1, |capacity| capacity as i64)
+    }
+
+    /// Returns the number of positions in the queue.
+    #[func]
+    pub fn size(&self) -> i64 {
+        self.queue.len() as i64
+    }
+
+    /// Returns true if the queue is bounded and holds as many positions as it can.
+    #[func]
+    pub fn is_full(&self) -> bool {
+        self.queue.is_full()
+    }
+
+    /// Pushes a position onto the queue.
+    /// Returns false if the queue is full and does not overwrite.
+    #[func]
+    pub fn push(&mut self, position: Vec3Godot) -> bool {
+        let accepted = !self.queue.is_full() || (self.queue.overwrite() && !self.queue.is_empty());
+        self.queue.push(position.to_vector3());
+        accepted
+    }
+
+    /// Removes all positions from the queue.
+    #[func]
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
+
+    /// Returns the queue's positions, from oldest to newest.
+    /// Does not modify the queue.
+    #[func]
+    pub fn get_positions(&self) -> PackedVector3Array {
+        self.queue
+            .iter()
+            .copied()
+            .collect::<Vec<Vec3>>()
+            .to_vector3()
+    }
+
+    /// Removes and returns all of the queue's positions, from oldest to newest.
+    #[func]
+    pub fn drain(&mut self) -> PackedVector3Array {
+        self.queue.drain().collect::<Vec<Vec3>>().to_vector3()
+    }
+}
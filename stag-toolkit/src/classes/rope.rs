@@ -1,12 +1,19 @@
 use crate::math::types::gdmath::ToTransform3D;
-use crate::{math::types::ToVector3, simulation::rope::RopeData};
+use crate::{
+    math::types::ToVector3,
+    simulation::rope::{
+        FixedTimestep, ROPE_GRAVITY, RopeCollider, RopeColliderShape, RopeData, RopeState,
+        bindings_transformed, resolve_collision, smooth_force,
+    },
+};
 use glam::{Mat4, Vec3, Vec4, vec4};
 use godot::classes::PhysicsRayQueryParameters3D;
 use godot::classes::object::ConnectFlags;
 use godot::signal::ConnectHandle;
 use godot::{
     classes::{
-        Engine, Mesh, MeshInstance3D, ProjectSettings, ResourceLoader, RigidBody3D, ShaderMaterial,
+        CapsuleShape3D, Engine, Mesh, MeshInstance3D, ProjectSettings, ResourceLoader, RigidBody3D,
+        ShaderMaterial, Shape3D, SphereShape3D,
     },
     init::is_main_thread,
     prelude::*,
@@ -45,6 +52,46 @@ pub struct SimulatedRopeSettings {
     #[init(val = 150)]
     simulation_constraint_iterations: u32,
 
+    /// Number of sub-ticks each simulation tick is split into.
+    /// More substeps keep heavy or stiff ropes stable when yanked, at the cost of performance,
+    /// as each substep performs all `simulation_constraint_iterations`.
+    #[export(range = (1.0, 16.0, 1.0, or_greater))]
+    #[init(val = 1)]
+    simulation_substeps: u32,
+
+    /// Ratio of actual to ideal length, of any section of rope between binds, at which the rope is considered taut.
+    #[export(range = (0.0, 1.0, 0.001, or_greater))]
+    #[init(val = 0.98)]
+    simulation_taut_threshold: f32,
+
+    /// Ratio of actual to ideal length, of any section of rope between binds, at which the rope is considered overstretched.
+    #[export(range = (1.0, 2.0, 0.001, or_greater))]
+    #[init(val = 1.05)]
+    simulation_overstretch_threshold: f32,
+
+    /// Gravitational acceleration applied to the rope, in the [SimulatedRope]'s local space.
+    /// Can be changed at runtime on individual ropes using `set_gravity`.
+    #[export]
+    #[init(val = Vector3::new(0.0, -ROPE_GRAVITY, 0.0))]
+    simulation_gravity: Vector3,
+
+    /// Constant wind acceleration applied to the rope, in the [SimulatedRope]'s local space.
+    /// Can be changed at runtime on individual ropes using `set_wind`.
+    #[export]
+    #[init(val = Vector3::ZERO)]
+    simulation_wind: Vector3,
+
+    /// Strength of turbulent wind acceleration, varying along the rope and over time.
+    #[export(range = (0.0, 20.0, 0.01, or_greater, suffix="m/s²"))]
+    #[init(val = 0.0)]
+    simulation_wind_turbulence: f32,
+
+    /// Spatial and temporal frequency of wind turbulence.
+    /// Higher values result in more rapid gusts, over shorter sections of rope.
+    #[export(range = (0.0, 5.0, 0.01, or_greater))]
+    #[init(val = 0.5)]
+    simulation_wind_frequency: f32,
+
     /// Whether to automatically call `tick_simulation` on the physics process tick.
     /// If this is `false`, **the simulation is not ticked at all**, and is expected to be ticked manually by the user.
     ///
@@ -54,6 +101,15 @@ pub struct SimulatedRopeSettings {
     #[init(val = true)]
     simulation_tick_on_physics: bool,
 
+    /// Fixed time step to simulate the rope in when ticking on physics, in seconds.
+    /// Frame time is accumulated, and the simulation is stepped in fixed increments, carrying the remainder.
+    /// This makes rope behavior reproducible regardless of frame rate.
+    ///
+    /// When zero, the simulation is stepped once per physics tick, using the physics tick's time step.
+    #[export(range = (0.0, 0.1, 0.0001, or_greater, suffix="s"))]
+    #[init(val = 0.0)]
+    simulation_fixed_timestep: f64,
+
     #[export_subgroup(name = "Render", prefix = "render_")]
     /// Whether to generate a corresponding [MeshInstance3D] for visualizing the rope.
     #[export]
@@ -189,6 +245,13 @@ pub struct SimulatedRope {
     #[init(val = 25.0)]
     ideal_length: f32,
 
+    /// How rope factors passed to and returned from this rope are interpreted.
+    /// Index-based factors map evenly across rope points,
+    /// while arc-length factors are a fraction of the rope's actual length, which shifts as the rope stretches unevenly.
+    #[export(enum = (IndexBased = 0, ArcLength = 1))]
+    #[init(val = 0)]
+    factor_mode: i32,
+
     /// Settings for the rope.
     #[var(set = set_settings)]
     #[export]
@@ -207,6 +270,9 @@ pub struct SimulatedRope {
     /// Whether to automatically perform simulation ticks.
     #[init(val = true)]
     do_simulation_tick: bool,
+    /// Accumulator for stepping the simulation in fixed increments.
+    #[init(val = FixedTimestep::default())]
+    timestep: FixedTimestep,
 
     /// Internal, simulated rope data.
     data: RopeData,
@@ -224,6 +290,13 @@ pub struct SimulatedRope {
     #[init(val = 0.0)]
     aabb_timer: f64,
 
+    /// Rope state as of the last simulation tick.
+    #[init(val = RopeState::Slack)]
+    state: RopeState,
+    /// Last rope state that a transition signal was emitted for.
+    #[init(val = RopeState::Slack)]
+    state_emitted: RopeState,
+
     base: Base<Node3D>,
 }
 
@@ -257,14 +330,43 @@ impl INode3D for SimulatedRope {
         self.tick_collision();
 
         if self.do_simulation_tick {
-            self.tick_simulation(delta);
+            if self.timestep.is_fixed() {
+                for _ in 0..self.timestep.advance(delta) {
+                    self.tick_simulation(self.timestep.step);
+                }
+            } else {
+                self.tick_simulation(delta);
+            }
             // godot_print!("rope simulation tick: {0}\t{1}", delta, self.data.points.len());
         }
+
+        // Simulation may be ticked on other threads, so emit state transitions here instead
+        if self.state != self.state_emitted {
+            let (from, to) = (self.state_emitted, self.state);
+            self.state_emitted = to;
+            self.signals()
+                .rope_state_changed()
+                .emit(from as i32, to as i32);
+        }
     }
 }
 
 #[godot_api]
 impl SimulatedRope {
+    /// Rope state where no section of the rope is near its ideal length.
+    #[constant]
+    pub const STATE_SLACK: i32 = RopeState::Slack as i32;
+    /// Rope state where at least one section of the rope is pulled to its ideal length.
+    #[constant]
+    pub const STATE_TAUT: i32 = RopeState::Taut as i32;
+    /// Rope state where at least one section of the rope is stretched well past its ideal length.
+    #[constant]
+    pub const STATE_OVERSTRETCHED: i32 = RopeState::Overstretched as i32;
+
+    /// Emitted on the physics tick after the rope transitions between states, such as `STATE_SLACK` to `STATE_TAUT`.
+    #[signal]
+    pub fn rope_state_changed(from: i32, to: i32);
+
     #[func]
     fn set_settings(&mut self, new_settings: Option<Gd<SimulatedRopeSettings>>) {
         let init_sim_callable = &self.base_mut().callable("initialize_simulation");
@@ -300,12 +402,28 @@ impl SimulatedRope {
         // self.initialize_simulation();
     }
 
+    /// Changes the number of points in the rope without restarting the simulation, such as for a winch paying out or reeling in rope.
+    /// The distance between points is kept, so `ideal_length` is updated to match.
+    /// New points are added before the end of the rope, and points are removed from the end of the rope.
+    /// The rope always keeps at least two points.
+    ///
+    /// Binds keep their rope factor, while collision contacts past the new end of the rope are dropped.
+    #[func]
+    pub fn set_point_count(&mut self, count: i64) {
+        self.data.resize(count.max(0) as usize);
+
+        let point_count = self.data.point_count;
+        self.collision_bindings.retain(|idx, _| *idx < point_count);
+        self.ideal_length = self.data.distance_between_points * point_count as f32;
+    }
+
     /// Sets or replaces a bind on the rope with the corresponding `bind_id`.
     /// `position` is where the bind is placed in the [SimulatedRope]'s local space.
     /// `rope_factor` is what part of the rope should be constrained to the bind, in a range between 0 and 1.
     /// `rope_factor` is automatically scaled and rounded to a corresponding point index during simulation.
     #[func]
     fn bind_set(&mut self, bind_id: i64, position: Vector3, rope_factor: f32) {
+        let rope_factor = self.to_index_factor(rope_factor);
         self.bindings.insert(
             bind_id,
             vec4(position.x, position.y, position.z, rope_factor),
@@ -325,6 +443,62 @@ impl SimulatedRope {
         self.bindings.clear();
     }
 
+    /// Sets the gravitational acceleration of the rope, in local space, without regenerating the simulation.
+    /// Resets to `simulation_gravity` upon calling `initialize_simulation`.
+    #[func]
+    pub fn set_gravity(&mut self, gravity: Vector3) {
+        self.data.gravity = gravity.to_vector3();
+    }
+
+    /// Sets the constant wind acceleration of the rope, in local space, without regenerating the simulation.
+    /// Resets to `simulation_wind` upon calling `initialize_simulation`.
+    #[func]
+    pub fn set_wind(&mut self, wind: Vector3) {
+        self.data.wind = wind.to_vector3();
+    }
+
+    /// Registers an analytic collider that rope points are pushed out of during `tick_simulation`.
+    /// `transform` is the collider's transform in the [SimulatedRope]'s local space, and should not be scaled.
+    /// Supports [SphereShape3D] and [CapsuleShape3D]. Returns false if the shape is unsupported.
+    ///
+    /// Rope points are kept `collision_offset` away from the collider surface.
+    /// This is more stable than `collision_raycasts` when draping ropes over shapes.
+    #[func]
+    pub fn add_collider(&mut self, shape: Gd<Shape3D>, transform: Transform3D) -> bool {
+        let shape = match shape.try_cast::<SphereShape3D>() {
+            Ok(sphere) => RopeColliderShape::Sphere {
+                radius: sphere.get_radius(),
+            },
+            Err(shape) => match shape.try_cast::<CapsuleShape3D>() {
+                Ok(capsule) => RopeColliderShape::Capsule {
+                    radius: capsule.get_radius(),
+                    height: capsule.get_height(),
+                },
+                Err(_) => {
+                    godot_warn!("SimulatedRope: only sphere and capsule colliders are supported");
+                    return false;
+                }
+            },
+        };
+
+        self.data
+            .colliders
+            .push(RopeCollider::new(shape, transform.to_transform3d()));
+        true
+    }
+
+    /// Removes all analytic colliders from the rope.
+    #[func]
+    pub fn clear_colliders(&mut self) {
+        self.data.colliders.clear();
+    }
+
+    /// Returns the number of analytic colliders registered on the rope.
+    #[func]
+    pub fn get_collider_count(&self) -> i64 {
+        self.data.colliders.len() as i64
+    }
+
     /// Regenerates internal rope data based on its given simulation settings.
     #[func]
     pub fn initialize_simulation(&mut self) {
@@ -332,12 +506,24 @@ impl SimulatedRope {
         let settings = settings_resource.bind();
 
         self.do_simulation_tick = settings.simulation_tick_on_physics;
+        self.timestep = FixedTimestep::new(settings.simulation_fixed_timestep);
 
         // Generate new rope data and apply settings
         let mut data = RopeData::new(self.ideal_length, settings.simulation_point_distance);
 
         data.spring_constant = settings.simulation_spring_constant;
         data.constraint_iterations = settings.simulation_constraint_iterations;
+        data.substeps = settings.simulation_substeps.max(1);
+        data.taut_threshold = settings.simulation_taut_threshold;
+        data.overstretch_threshold = settings.simulation_overstretch_threshold;
+        data.gravity = settings.simulation_gravity.to_vector3();
+        data.wind = settings.simulation_wind.to_vector3();
+        data.wind_turbulence = settings.simulation_wind_turbulence;
+        data.wind_frequency = settings.simulation_wind_frequency;
+        data.collider_margin = settings.collision_offset;
+
+        // Keep any registered colliders across regenerations
+        data.colliders = std::mem::take(&mut self.data.colliders);
 
         self.data = data;
     }
@@ -475,7 +661,7 @@ impl SimulatedRope {
     }
 
     /// Ticks the rope simulation forward by `delta` seconds.
-    /// Uses the last `tick_collision` state (if any).
+    /// Uses the last `tick_collision` state (if any), and pushes points out of any colliders registered with `add_collider`.
     ///
     /// This method can be run on any thread, as long as no other thread reads or modifies the Rope data while simulating.
     #[func]
@@ -486,14 +672,10 @@ impl SimulatedRope {
         // Combine collision state into bind map, to treat each collision point like it's static
         bind_map.extend(self.collision_bindings.iter());
 
-        // Compute tension data
-        self.data.tension(&bind_map);
-
-        // First, step simulation
-        self.data.step(delta);
+        // Compute tension, step simulation, and apply constraints, for each substep
+        self.data.tick(delta, &bind_map);
 
-        // Apply constraints
-        self.data.constrain(&bind_map);
+        self.state = self.data.state();
     }
 
     /// Ticks the rope render, updating shader parameters and corresponding [AABB].
@@ -543,6 +725,7 @@ impl SimulatedRope {
     }
 
     /// Ticks the rope collision, attempting to collide with terrain.
+    /// Each point's motion since the previous step is cast against the physics space as a finite segment.
     /// **Must** be run on physics tick.
     ///
     /// @experimental: Collision for simulations is still a work in progress. A bit more slow and buggy than helpful at the moment.
@@ -567,7 +750,6 @@ impl SimulatedRope {
             // TODO: we probably need less than every point?
             for (idx, simulated) in self.data.points.iter_mut().enumerate() {
                 let prev = self.data.points_simulated_previous[idx];
-                let motion = *simulated - prev;
 
                 self.rayquery
                     .set_from(transform.project_point3(prev).to_vector3());
@@ -586,15 +768,14 @@ impl SimulatedRope {
                         .to();
                     let hit_normal: Vec3 = hit_normal.to_vector3();
 
-                    // Get our actual position, and slide it along the surface plane of our hit normal
-                    let position = hit_position
-                        + (motion - hit_normal * hit_normal.dot(motion))
-                        + hit_normal * offset;
-
-                    // Deproject the point back into local space
-                    let combined = transform
-                        .inverse()
-                        .project_point3(position + hit_normal * offset);
+                    let combined = resolve_collision(
+                        prev,
+                        *simulated,
+                        hit_position,
+                        hit_normal,
+                        offset,
+                        transform,
+                    );
 
                     // Update simulation position
                     *simulated = combined;
@@ -605,6 +786,17 @@ impl SimulatedRope {
         }
     }
 
+    /// Returns the positions of all rope points currently held in place by collisions, in global space,
+    /// ordered from a rope factor of 0 to 1.
+    /// Useful for visualizing contacts.
+    ///
+    /// @experimental: Collision for simulations is still a work in progress.
+    #[func]
+    pub fn get_collision_points(&self) -> PackedVector3Array {
+        let transform: Mat4 = self.base().get_global_transform().to_transform3d();
+        bindings_transformed(&self.collision_bindings, transform).to_vector3()
+    }
+
     /// Computes and returns an enclosing [AABB] for the rope.
     #[func]
     pub fn get_aabb(&self) -> Aabb {
@@ -617,17 +809,102 @@ impl SimulatedRope {
         aabb
     }
 
+    /// Returns all current rope points, in global space, ordered from a rope factor of 0 to 1.
+    #[func]
+    pub fn get_points_global(&self) -> PackedVector3Array {
+        let transform: Mat4 = self.base().get_global_transform().to_transform3d();
+        self.data.points_transformed(transform).to_vector3()
+    }
+
+    /// Returns all current rope points, in local space, ordered from a rope factor of 0 to 1.
+    #[func]
+    pub fn get_points_local(&self) -> PackedVector3Array {
+        self.data.points.to_vector3()
+    }
+
+    /// Returns the number of points in the rope.
+    #[func]
+    pub fn get_point_count(&self) -> i64 {
+        self.data.points.len() as i64
+    }
+
+    /// Returns the position of the rope point at the given index, in global space.
+    /// Returns [Vector3.ZERO] and pushes a warning if the index is out of range.
+    #[func]
+    pub fn get_point_position(&self, index: i64) -> Vector3 {
+        let Some(point) = usize::try_from(index)
+            .ok()
+            .and_then(|idx| self.data.points.get(idx))
+        else {
+            godot_warn!(
+                "SimulatedRope: point index {index} is out of range of {} points",
+                self.data.points.len()
+            );
+            return Vector3::ZERO;
+        };
+
+        self.base().to_global(point.to_vector3())
+    }
+
+    /// Returns all rope points from the previous simulation step, in local space, ordered from a rope factor of 0 to 1.
+    /// Useful for visualizing rope velocity.
+    #[func]
+    pub fn get_previous_points_local(&self) -> PackedVector3Array {
+        self.data.points_simulated_previous.to_vector3()
+    }
+
     /// Returns the tension force, in global space, at the given point on the rope within the range [0,1].
     #[func]
     pub fn get_tension_force_at(&self, factor: f32) -> Vector3 {
         let basis = self.base().get_global_basis();
 
-        let idx = self.data.bind_index(factor);
+        let idx = self.data.bind_index(self.to_index_factor(factor));
         let force_local: Vector3 = self.data.force(idx).to_vector3();
 
         basis.inverse() * force_local
     }
 
+    /// Converts a rope factor, as interpreted by the `factor_mode`, into an index-based rope factor.
+    #[func]
+    pub fn to_index_factor(&self, factor: f32) -> f32 {
+        match self.factor_mode {
+            1 => self.data.arclength_to_factor(factor),
+            _ => factor,
+        }
+    }
+
+    /// Converts an index-based rope factor into a rope factor, as interpreted by the `factor_mode`.
+    #[func]
+    pub fn from_index_factor(&self, index_factor: f32) -> f32 {
+        match self.factor_mode {
+            1 => self.data.factor_to_arclength(index_factor),
+            _ => index_factor,
+        }
+    }
+
+    /// Returns the overall state of the rope as of the last simulation tick,
+    /// as one of `STATE_SLACK`, `STATE_TAUT`, or `STATE_OVERSTRETCHED`.
+    #[func]
+    pub fn get_rope_state(&self) -> i32 {
+        self.state as i32
+    }
+
+    /// Returns the largest ratio of actual to ideal length of any section of rope between binds,
+    /// as of the last simulation tick.
+    #[func]
+    pub fn get_stretch_ratio(&self) -> f32 {
+        self.data.stretch_ratio()
+    }
+
+    /// Returns the number of bindings sharing the nearest rope point at the given rope factor.
+    /// Positions of bindings sharing a point are averaged together.
+    #[func]
+    pub fn get_bind_count_at(&self, factor: f32) -> i64 {
+        let idx = self.data.bind_index(self.to_index_factor(factor));
+        let counts = self.data.bind_share_counts(&self.bindings);
+        counts.get(&idx).copied().unwrap_or(0) as i64
+    }
+
     /// Returns the rope factor of the nearest rope point at the given global space position.
     #[func]
     pub fn get_rope_factor(&self, position: Vector3) -> f32 {
@@ -643,13 +920,13 @@ impl SimulatedRope {
             }
         }
 
-        self.data.bind_factor(closest_idx)
+        self.from_index_factor(self.data.bind_factor(closest_idx))
     }
 
     /// Returns the global space rope position at the given rope factor.
     #[func]
     pub fn get_rope_position(&self, factor: f64) -> Vector3 {
-        let idx = self.data.bind_index(factor as f32);
+        let idx = self.data.bind_index(self.to_index_factor(factor as f32));
         self.base().to_global(self.data.points[idx].to_vector3())
     }
 
@@ -672,7 +949,8 @@ impl SimulatedRope {
     /// Returns the amount of slack in the rope, in a range of 0 to 1, at the given rope factor.
     #[func]
     pub fn get_rope_slack(&self, factor: f32) -> f32 {
-        self.data.slack(self.data.bind_index(factor))
+        self.data
+            .slack(self.data.bind_index(self.to_index_factor(factor)))
     }
 
     /// Returns the AVERAGED forward direction ("forward" meaning the direction FROM a factor of 0 TOWARD a factor of 1),
@@ -680,10 +958,13 @@ impl SimulatedRope {
     /// If desired, factor width can be determined via a sample distance and the rope's length: `sample_distance / rope.ideal_length`.
     #[func]
     pub fn get_rope_slide_direction(&self, factor: f32, factor_width: f32) -> Vector3 {
-        let bind_min: usize = self.data.bind_index(factor.clamp(0.0, 1.0)).max(1);
+        let bind_min: usize = self
+            .data
+            .bind_index(self.to_index_factor(factor.clamp(0.0, 1.0)))
+            .max(1);
         let bind_max: usize = self
             .data
-            .bind_index((factor + factor_width).clamp(0.0, 1.0));
+            .bind_index(self.to_index_factor((factor + factor_width).clamp(0.0, 1.0)));
 
         let mut dir: Vec3 = Vec3::ZERO;
 
@@ -723,6 +1004,16 @@ pub struct SimulatedRopeBinding {
     #[init(val = 1.0)]
     spring_constant_multiplier: f32,
 
+    /// Time constant for exponentially smoothing the tension force applied to the parent [RigidBody3D].
+    /// Higher values reduce jitter from noisy rope tension, but respond slower to changes. Zero disables smoothing.
+    #[export(range = (0.0, 1.0, 0.001, or_greater, suffix="s"))]
+    #[init(val = 0.0)]
+    force_smoothing: f32,
+
+    /// Smoothed tension force, as of the last physics tick.
+    #[init(val = Vector3::ZERO)]
+    force_smoothed: Vector3,
+
     /// What tick to update the [SimulatedRope]'s bound position on.
     #[var(set = set_update_tick)]
     #[export(enum = (Disabled = 0, Process = 1, PhysicsProcess = 2))]
@@ -787,12 +1078,20 @@ impl INode3D for SimulatedRopeBinding {
         self.update_bind();
     }
 
-    fn physics_process(&mut self, _delta: f64) {
+    fn physics_process(&mut self, delta: f64) {
         if !Engine::singleton().is_editor_hint()
             && let Some(rope) = self.get_bind_to()
         {
-            let force: Vector3 =
+            let tension: Vector3 =
                 rope.bind().get_tension_force_at(self.bind_at) * self.spring_constant_multiplier;
+            let force = smooth_force(
+                self.force_smoothed.to_vector3(),
+                tension.to_vector3(),
+                self.force_smoothing,
+                delta as f32,
+            )
+            .to_vector3();
+            self.force_smoothed = force;
 
             // Apply tension force to RigidBody
             if let Some(mut rigid) = self.get_rigid_body() {
@@ -803,8 +1102,13 @@ impl INode3D for SimulatedRopeBinding {
 
             // Snap binding if too much tension is applied
             if self.snap_enabled && force.length() > self.snap_tension_threshold {
+                let position = rope.bind().get_rope_position(self.bind_at as f64);
+                let factor = self.bind_at;
                 self.set_bind_to(None);
                 self.signals().rope_snapped().emit(force);
+                self.signals()
+                    .rope_snapped_detailed()
+                    .emit(&rope, force, position, factor);
             }
         }
 
@@ -828,6 +1132,16 @@ impl SimulatedRopeBinding {
     #[signal]
     pub fn rope_snapped(tension_force: Vector3);
 
+    /// Emitted alongside [signal rope_snapped], with the snapped rope,
+    /// the global position on the rope where it snapped, and the rope factor it was bound at.
+    #[signal]
+    pub fn rope_snapped_detailed(
+        rope: Gd<SimulatedRope>,
+        tension_force: Vector3,
+        global_position: Vector3,
+        factor: f32,
+    );
+
     #[func]
     fn set_bind_to(&mut self, new_bind_to: Option<Gd<SimulatedRope>>) {
         let id = self.base().instance_id().to_i64();
@@ -849,6 +1163,7 @@ impl SimulatedRopeBinding {
 
         // Save the reference
         self.bind_to = new_bind_to.clone();
+        self.force_smoothed = Vector3::ZERO;
 
         // Pass new bind position to the rope simulation
         if self.base().is_inside_tree() {
@@ -912,6 +1227,13 @@ impl SimulatedRopeBinding {
         Vector3::ZERO
     }
 
+    /// Returns the smoothed tension force applied to the parent [RigidBody3D] on the last physics tick.
+    /// Matches [get_tension_force] when [force_smoothing] is zero.
+    #[func]
+    fn get_applied_force(&self) -> Vector3 {
+        self.force_smoothed
+    }
+
     /// Updates the bind settings on this [SimulatedRopeBinding]'s corresponding rope.
     #[func]
     fn update_bind(&mut self) {
@@ -948,7 +1270,7 @@ impl SimulatedRopeBinding {
         if let Some(rope) = self.bind_to.clone() {
             let sim = rope.bind();
 
-            let idx_current = sim.data.bind_index(self.bind_at);
+            let idx_current = sim.data.bind_index(sim.to_index_factor(self.bind_at));
 
             // Get bounds for binding
             let bind_map = sim.data.unique_bind_map(&sim.bindings);
@@ -958,7 +1280,7 @@ impl SimulatedRopeBinding {
 
             // Construct new bind index
             new_factor = self.bind_at + factor_amount;
-            let mut new_bind_index = sim.data.bind_index(new_factor);
+            let mut new_bind_index = sim.data.bind_index(sim.to_index_factor(new_factor));
 
             // Clamp bind index to the given bounds, if present
             if has_smallest {
@@ -969,7 +1291,7 @@ impl SimulatedRopeBinding {
             }
 
             // Finally, update bind
-            new_factor = sim.data.bind_factor(new_bind_index);
+            new_factor = sim.from_index_factor(sim.data.bind_factor(new_bind_index));
 
             // godot_print!("Smallest: {0} {1}\tLargest: {2} {3}\tFinal: {4} {5}", smallest, has_smallest, largest, has_largest, new_bind_index, new_factor);
         } else {
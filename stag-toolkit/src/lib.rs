@@ -50,6 +50,10 @@ pub mod math {
 pub mod mesh {
     // Convex Hull algorithms like Quick Hull and related functions.
     // pub mod hull;
+    /// Bounding Volume Hierarchy for accelerating raycasts against a TriangleMesh.
+    pub mod bvh;
+    /// Marching Cubes for meshing voxel data with sharper features than Surface Nets.
+    pub mod marching;
     /// Net algorithms like Naive Surface Nets.
     pub mod nets;
     /// PointCloud trait for managing large sets of point data.
@@ -118,6 +122,9 @@ pub mod classes {
     /// Simple nodes for building complex camera rigs.
     pub mod camera;
 
+    /// Debug visualization nodes for inspecting meshes.
+    pub mod debug;
+
     /// Island Builder data handling.
     pub mod island_settings;
 
@@ -1,4 +1,5 @@
-use glam::{Mat4, Vec3, Vec4Swizzles};
+use crate::math::projection::Plane;
+use glam::{Mat4, Vec3, Vec4, Vec4Swizzles};
 use std::ops::Mul;
 
 /// An axis-aligned Bounding Box.
@@ -99,6 +100,50 @@ impl BoundingBox {
     pub fn zero(&self) -> bool {
         self.minimum.eq(&self.maximum)
     }
+
+    /// Returns the distances along the ray at which it enters and exits the bounding box, using the slab method.
+    /// The enter distance is negative if the ray starts inside the box.
+    /// Distances are in multiples of the direction, which does not need to be normalized.
+    ///
+    /// Returns [None] if the ray misses the box, or the box is entirely behind the ray.
+    pub fn ray_intersection(&self, origin: Vec3, direction: Vec3) -> Option<(f32, f32)> {
+        let mut enter = f32::NEG_INFINITY;
+        let mut exit = f32::INFINITY;
+
+        for axis in 0..3 {
+            if direction[axis] == 0.0 {
+                // Parallel to this slab, so the ray must already be between its planes
+                if origin[axis] < self.minimum[axis] || origin[axis] > self.maximum[axis] {
+                    return None;
+                }
+                continue;
+            }
+
+            let inv = 1.0 / direction[axis];
+            let a = (self.minimum[axis] - origin[axis]) * inv;
+            let b = (self.maximum[axis] - origin[axis]) * inv;
+            enter = enter.max(a.min(b));
+            exit = exit.min(a.max(b));
+        }
+
+        if enter > exit || exit < 0.0 {
+            return None;
+        }
+        Some((enter, exit))
+    }
+
+    /// Returns true if the bounding box is at least partially on the positive side of every given plane,
+    /// such as the inward-facing planes of a camera frustum. The negative side of each plane is treated as outside.
+    ///
+    /// Only tests the corner furthest along each plane normal, so this is conservative:
+    /// boxes near frustum corners may be kept despite being outside, but a visible box is never rejected.
+    pub fn intersects_planes(&self, planes: &[Vec4]) -> bool {
+        planes.iter().all(|plane| {
+            // Find the corner furthest along the plane normal
+            let positive = Vec3::select(plane.xyz().cmpge(Vec3::ZERO), self.maximum, self.minimum);
+            plane.signed_distance(positive) >= 0.0
+        })
+    }
 }
 
 impl Mul<BoundingBox> for Mat4 {
@@ -128,6 +173,7 @@ impl Mul<BoundingBox> for Mat4 {
 #[cfg(test)]
 mod tests {
     use crate::math::bounding_box::BoundingBox;
+    use crate::math::projection::plane;
     use glam::{Mat4, Vec3};
 
     #[test]
@@ -204,6 +250,118 @@ mod tests {
         assert_eq!(joined.volume(), 8.0, "volume increased");
     }
 
+    #[test]
+    fn test_ray_intersection() {
+        let aabb = BoundingBox::new(Vec3::NEG_ONE, Vec3::ONE);
+
+        assert_eq!(
+            Some((2.0, 4.0)),
+            aabb.ray_intersection(Vec3::new(-3.0, 0.0, 0.0), Vec3::X),
+            "ray should enter and exit along X"
+        );
+        assert_eq!(
+            Some((1.0, 2.0)),
+            aabb.ray_intersection(Vec3::new(-3.0, 0.0, 0.0), Vec3::X * 2.0),
+            "distances should be in multiples of the direction"
+        );
+        assert_eq!(
+            None,
+            aabb.ray_intersection(Vec3::new(-3.0, 2.0, 0.0), Vec3::X),
+            "ray passing beside the box should miss"
+        );
+        assert_eq!(
+            None,
+            aabb.ray_intersection(Vec3::new(3.0, 0.0, 0.0), Vec3::X),
+            "box behind the ray should miss"
+        );
+        assert_eq!(
+            Some((-1.0, 1.0)),
+            aabb.ray_intersection(Vec3::ZERO, Vec3::X),
+            "ray starting inside should have a negative enter distance"
+        );
+
+        // Grazing an edge and a corner
+        assert_eq!(
+            Some((2.0, 4.0)),
+            aabb.ray_intersection(Vec3::new(-3.0, 1.0, 1.0), Vec3::X),
+            "ray grazing an edge should hit"
+        );
+        let corner = aabb.ray_intersection(Vec3::new(-3.0, -1.0, 3.0), Vec3::new(1.0, 1.0, -1.0));
+        assert_eq!(
+            Some((2.0, 2.0)),
+            corner,
+            "ray grazing a corner should enter and exit at once"
+        );
+
+        // Parallel to slabs
+        assert_eq!(
+            Some((f32::NEG_INFINITY, f32::INFINITY)),
+            aabb.ray_intersection(Vec3::ZERO, Vec3::ZERO),
+            "motionless ray inside the box should always be inside"
+        );
+        assert_eq!(
+            None,
+            aabb.ray_intersection(Vec3::new(0.0, 0.0, 1.5), Vec3::Y),
+            "ray parallel to and outside a slab should miss"
+        );
+        assert_eq!(
+            Some((0.0, 2.0)),
+            aabb.ray_intersection(Vec3::new(1.0, -1.0, 0.0), Vec3::Y),
+            "ray parallel to and on a slab face should hit"
+        );
+    }
+
+    #[test]
+    fn test_intersects_planes() {
+        let aabb = BoundingBox::new(Vec3::NEG_ONE, Vec3::ONE);
+
+        assert!(
+            aabb.intersects_planes(&[]),
+            "box should be kept without any planes"
+        );
+        assert!(
+            !aabb.intersects_planes(&[plane(Vec3::X * 2.0, Vec3::X)]),
+            "box fully behind a plane should be rejected"
+        );
+        assert!(
+            aabb.intersects_planes(&[plane(Vec3::X * 0.5, Vec3::X)]),
+            "box straddling a plane should be kept"
+        );
+        assert!(
+            aabb.intersects_planes(&[plane(Vec3::X, Vec3::X)]),
+            "box touching a plane should be kept"
+        );
+        assert!(
+            aabb.intersects_planes(&[plane(Vec3::NEG_X * 2.0, Vec3::X)]),
+            "box fully in front of a plane should be kept"
+        );
+
+        // Diagonal plane, just past the furthest corner
+        let normal = Vec3::ONE.normalize();
+        assert!(
+            !aabb.intersects_planes(&[plane(normal * 1.8, normal)]),
+            "box behind a diagonal plane should be rejected"
+        );
+        assert!(
+            aabb.intersects_planes(&[plane(normal * 1.7, normal)]),
+            "box corner past a diagonal plane should be kept"
+        );
+
+        // Every plane must be passed
+        let slab = [
+            plane(Vec3::NEG_X * 5.0, Vec3::X),
+            plane(Vec3::NEG_X * 3.0, Vec3::NEG_X),
+        ];
+        assert!(
+            !aabb.intersects_planes(&slab),
+            "box outside a pair of planes should be rejected"
+        );
+        assert!(
+            aabb.translate(Vec3::NEG_X * 4.0).intersects_planes(&slab),
+            "box between a pair of planes should be kept"
+        );
+    }
+
     #[test]
     fn test_transform() {
         let aabb = BoundingBox::new(Vec3::NEG_ONE, Vec3::ONE);
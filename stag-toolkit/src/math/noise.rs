@@ -1,11 +1,44 @@
 use glam::{Vec3, Vec4};
 use noise::{NoiseFn, Perlin, Seedable};
 
+/// A scalar noise function sampled from a 4D position.
+/// Allows custom noise, such as tileable generators, to be plugged into the island pipeline.
+pub trait NoiseSource: Send + Sync {
+    /// Returns a noise value at the given position.
+    fn sample(&self, p: Vec4) -> f32;
+    /// Reseeds the noise function.
+    fn set_seed(&mut self, seed: u32);
+    /// Returns a boxed copy of this noise function.
+    fn clone_box(&self) -> Box<dyn NoiseSource>;
+}
+
+/// Frequency multiplier applied between each octave of fractal noise.
+pub const FBM_LACUNARITY: f32 = 2.0;
+/// Amplitude multiplier applied between each octave of fractal noise.
+pub const FBM_GAIN: f32 = 0.5;
+
+/// Returns the octave weights of fractal noise, normalized to sum to one,
+/// so the output amplitude stays comparable regardless of octave count.
+fn fbm_weights(octaves: u32, gain: f32) -> impl Iterator<Item = f64> {
+    // Weights are accumulated by multiplication rather than powers, so they are identical on every platform
+    let weights: Vec<f64> = (0..octaves.max(1))
+        .scan(1.0, |weight: &mut f64, _| {
+            let current = *weight;
+            *weight *= gain as f64;
+            Some(current)
+        })
+        .collect();
+    let total: f64 = weights.iter().sum();
+    weights.into_iter().map(move |weight| weight / total)
+}
+
 /// A one-dimensional perlin noise function.
 #[derive(Clone)]
 pub struct Perlin1D {
     pub frequency: [f64; 4],
     pub amplitude: f64,
+    /// Number of fractal octaves used when sampled as a [NoiseSource].
+    pub octaves: u32,
     perlin: Perlin,
 }
 
@@ -15,6 +48,7 @@ impl Perlin1D {
         Self {
             frequency,
             amplitude,
+            octaves: 1,
             perlin: Perlin::new(seed),
         }
     }
@@ -48,6 +82,31 @@ impl Perlin1D {
             position.w as f64 * self.frequency[3],
         ]) * self.amplitude
     }
+
+    /// Returns fractal Brownian motion noise at the sampling position, layering octaves of `sample`.
+    /// Each octave multiplies the frequency by `lacunarity`, and the weight by `gain`.
+    /// Weights are normalized, so the output never exceeds the amplitude of a single octave.
+    /// A single octave is identical to `sample`.
+    pub fn fbm(&self, position: Vec4, octaves: u32, lacunarity: f32, gain: f32) -> f64 {
+        fbm_weights(octaves, gain)
+            .enumerate()
+            .map(|(octave, weight)| self.sample(position * lacunarity.powi(octave as i32)) * weight)
+            .sum()
+    }
+}
+
+impl NoiseSource for Perlin1D {
+    fn sample(&self, p: Vec4) -> f32 {
+        self.fbm(p, self.octaves, FBM_LACUNARITY, FBM_GAIN) as f32
+    }
+
+    fn set_seed(&mut self, seed: u32) {
+        Self::set_seed(self, seed);
+    }
+
+    fn clone_box(&self) -> Box<dyn NoiseSource> {
+        Box::new(self.clone())
+    }
 }
 
 impl Default for Perlin1D {
@@ -61,6 +120,8 @@ impl Default for Perlin1D {
 pub struct Perlin3D {
     pub frequency: [f64; 4],
     pub amplitude: [f64; 3],
+    /// Number of fractal octaves used by `sample_octaves`.
+    pub octaves: u32,
     x: Perlin,
     y: Perlin,
     z: Perlin,
@@ -72,6 +133,7 @@ impl Perlin3D {
         Self {
             frequency,
             amplitude,
+            octaves: 1,
             x: Perlin::new(seed),
             y: Perlin::new(seed + 1),
             z: Perlin::new(seed + 2),
@@ -116,6 +178,24 @@ impl Perlin3D {
         ]);
         Vec3::new(res[0] as f32, res[1] as f32, res[2] as f32)
     }
+
+    /// Returns fractal Brownian motion noise at the sampling position, layering octaves of `sample`.
+    /// Each octave multiplies the frequency by `lacunarity`, and the weight by `gain`.
+    /// Weights are normalized, so the output never exceeds the amplitude of a single octave.
+    /// A single octave is identical to `sample`.
+    pub fn fbm(&self, position: Vec4, octaves: u32, lacunarity: f32, gain: f32) -> Vec3 {
+        fbm_weights(octaves, gain)
+            .enumerate()
+            .map(|(octave, weight)| {
+                self.sample(position * lacunarity.powi(octave as i32)) * weight as f32
+            })
+            .sum()
+    }
+
+    /// Returns fractal noise at the sampling position, using the configured number of `octaves`.
+    pub fn sample_octaves(&self, position: Vec4) -> Vec3 {
+        self.fbm(position, self.octaves, FBM_LACUNARITY, FBM_GAIN)
+    }
 }
 
 impl Default for Perlin3D {
@@ -123,3 +203,297 @@ impl Default for Perlin3D {
         Self::new(0, [1.0; 4], [1.0, 1.0, 1.0])
     }
 }
+
+/// Returns a hash of the given integer lattice coordinates, using a SplitMix64 style mixer.
+/// Only uses integer operations, so results are identical on every platform.
+fn lattice_hash(x: i64, y: i64, z: i64, seed: u32) -> u32 {
+    let mut hash = (seed as u64) ^ 0x9E37_79B9_7F4A_7C15;
+    for value in [x, y, z] {
+        hash = (hash ^ value as u64).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        hash ^= hash >> 31;
+    }
+    hash ^= hash >> 30;
+    hash = hash.wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    hash ^= hash >> 27;
+    hash = hash.wrapping_mul(0x94D0_49BB_1331_11EB);
+    hash ^= hash >> 31;
+    (hash >> 32) as u32
+}
+
+/// Returns trilinearly interpolated value noise in the range \[-1, 1\] at the given position.
+fn value_noise(position: [f64; 3], seed: u32) -> f64 {
+    let cell = position.map(|axis| axis.floor());
+    // Smoothstep the interpolation weights, using only basic arithmetic for determinism
+    let weight = [0, 1, 2].map(|axis| {
+        let t = position[axis] - cell[axis];
+        t * t * (3.0 - 2.0 * t)
+    });
+    let cell = cell.map(|axis| axis as i64);
+
+    let corner = |dx: i64, dy: i64, dz: i64| -> f64 {
+        let hash = lattice_hash(cell[0] + dx, cell[1] + dy, cell[2] + dz, seed);
+        hash as f64 / u32::MAX as f64 * 2.0 - 1.0
+    };
+    let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+
+    let x0 = lerp(
+        lerp(corner(0, 0, 0), corner(1, 0, 0), weight[0]),
+        lerp(corner(0, 1, 0), corner(1, 1, 0), weight[0]),
+        weight[1],
+    );
+    let x1 = lerp(
+        lerp(corner(0, 0, 1), corner(1, 0, 1), weight[0]),
+        lerp(corner(0, 1, 1), corner(1, 1, 1), weight[0]),
+        weight[1],
+    );
+    lerp(x0, x1, weight[2])
+}
+
+/// A three-dimensional value noise function, built from an integer lattice hash and trilinear interpolation.
+/// Unlike [Perlin3D], results are bit-identical across every platform and target.
+/// The fourth sampling axis shifts the lattice diagonally, rather than adding a fourth dimension.
+#[derive(Clone)]
+pub struct ValueNoise3D {
+    pub frequency: [f64; 4],
+    pub amplitude: [f64; 3],
+    /// Number of fractal octaves used by `sample_octaves`.
+    pub octaves: u32,
+    seed: u32,
+}
+
+/// Generates a 3D noise value from a 4D input.
+impl ValueNoise3D {
+    pub fn new(seed: u32, frequency: [f64; 4], amplitude: [f64; 3]) -> Self {
+        Self {
+            frequency,
+            amplitude,
+            octaves: 1,
+            seed,
+        }
+    }
+
+    pub fn set_seed(&mut self, seed: u32) {
+        self.seed = seed;
+    }
+
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    /// Returns a value based on the sampling position,
+    /// applying frequency and amplitude automatically.
+    pub fn sample_precise(&self, position: [f64; 4]) -> [f64; 3] {
+        let w = position[3] * self.frequency[3];
+        let pos = [
+            position[0] * self.frequency[0] + w,
+            position[1] * self.frequency[1] + w,
+            position[2] * self.frequency[2] + w,
+        ];
+
+        [
+            value_noise(pos, self.seed) * self.amplitude[0],
+            value_noise(pos, self.seed.wrapping_add(1)) * self.amplitude[1],
+            value_noise(pos, self.seed.wrapping_add(2)) * self.amplitude[2],
+        ]
+    }
+
+    /// Returns a value based on the sampling position,
+    /// applying frequency and amplitude automatically.
+    pub fn sample(&self, position: Vec4) -> Vec3 {
+        let res = self.sample_precise([
+            position.x as f64,
+            position.y as f64,
+            position.z as f64,
+            position.w as f64,
+        ]);
+        Vec3::new(res[0] as f32, res[1] as f32, res[2] as f32)
+    }
+
+    /// Returns fractal Brownian motion noise at the sampling position, layering octaves of `sample`.
+    /// Each octave multiplies the frequency by `lacunarity`, and the weight by `gain`.
+    /// Weights are normalized, so the output never exceeds the amplitude of a single octave.
+    /// A single octave is identical to `sample`.
+    pub fn fbm(&self, position: Vec4, octaves: u32, lacunarity: f32, gain: f32) -> Vec3 {
+        let mut scale = 1.0;
+        fbm_weights(octaves, gain)
+            .map(|weight| {
+                let value = self.sample(position * scale) * weight as f32;
+                scale *= lacunarity;
+                value
+            })
+            .sum()
+    }
+
+    /// Returns fractal noise at the sampling position, using the configured number of `octaves`.
+    pub fn sample_octaves(&self, position: Vec4) -> Vec3 {
+        self.fbm(position, self.octaves, FBM_LACUNARITY, FBM_GAIN)
+    }
+}
+
+/// Samples the first noise axis, so value noise can be used as custom density or striation noise.
+impl NoiseSource for ValueNoise3D {
+    fn sample(&self, p: Vec4) -> f32 {
+        self.sample_octaves(p).x
+    }
+
+    fn set_seed(&mut self, seed: u32) {
+        Self::set_seed(self, seed);
+    }
+
+    fn clone_box(&self) -> Box<dyn NoiseSource> {
+        Box::new(self.clone())
+    }
+}
+
+impl Default for ValueNoise3D {
+    fn default() -> Self {
+        Self::new(0, [1.0; 4], [1.0, 1.0, 1.0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FBM_GAIN, FBM_LACUNARITY, NoiseSource, Perlin1D, Perlin3D, ValueNoise3D};
+    use glam::{Vec4, vec4};
+
+    #[test]
+    fn test_fbm() {
+        let noise = Perlin1D::new(4, [0.7, 1.3, 0.9, 0.5], 2.0);
+        let noise_3d = Perlin3D::new(4, [0.7, 1.3, 0.9, 0.5], [2.0, 1.0, 0.5]);
+        let positions: Vec<Vec4> = (0..400)
+            .map(|i| {
+                let t = i as f32 * 0.137;
+                vec4(t.sin() * 5.0, t * 0.3, t.cos() * 4.0, t * 0.1)
+            })
+            .collect();
+
+        for position in positions.iter() {
+            assert_eq!(
+                noise.sample(*position),
+                noise.fbm(*position, 1, FBM_LACUNARITY, FBM_GAIN),
+                "single octave should equal sample at {position}"
+            );
+            assert_eq!(
+                noise_3d.sample(*position),
+                noise_3d.fbm(*position, 1, FBM_LACUNARITY, FBM_GAIN),
+                "single octave should equal sample at {position}"
+            );
+            assert_eq!(
+                noise.sample(*position) as f32,
+                NoiseSource::sample(&noise, *position),
+                "default octave count should equal sample at {position}"
+            );
+        }
+
+        // Extra octaves add detail between nearby samples, without exceeding the amplitude bound
+        let roughness = |octaves: u32| -> f64 {
+            let values: Vec<f64> = (0..2000)
+                .map(|i| {
+                    let t = i as f32 * 0.005;
+                    noise.fbm(
+                        vec4(t, t * 0.5, 0.3, 0.1),
+                        octaves,
+                        FBM_LACUNARITY,
+                        FBM_GAIN,
+                    )
+                })
+                .collect();
+            let variation: f64 = values
+                .windows(2)
+                .map(|pair| (pair[1] - pair[0]).abs())
+                .sum();
+            let range = values.iter().copied().fold(f64::MIN, f64::max)
+                - values.iter().copied().fold(f64::MAX, f64::min);
+            variation / range
+        };
+        assert!(
+            roughness(5) > roughness(1) * 1.5,
+            "more octaves should add higher-frequency detail"
+        );
+
+        for position in positions.iter() {
+            for octaves in [2, 4, 8] {
+                let value = noise.fbm(*position, octaves, FBM_LACUNARITY, FBM_GAIN);
+                assert!(
+                    value.abs() <= noise.amplitude,
+                    "fbm {value} with {octaves} octaves should not exceed the amplitude"
+                );
+                let value = noise_3d.fbm(*position, octaves, FBM_LACUNARITY, FBM_GAIN);
+                assert!(
+                    value.abs().cmple(glam::vec3(2.0, 1.0, 0.5)).all(),
+                    "fbm {value} with {octaves} octaves should not exceed the amplitude"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_value_noise() {
+        let noise = ValueNoise3D::new(7, [1.0, 0.5, 2.0, 1.0], [1.0, 2.0, 0.5]);
+
+        // Values are locked, as value noise must be bit-identical on every platform
+        let expected: [([f64; 4], [f64; 3]); 4] = [
+            (
+                [0.0, 0.0, 0.0, 0.0],
+                [-0.5342279899712252, -1.628043915989819, 0.2795883479480604],
+            ),
+            (
+                [0.25, 1.5, -3.75, 0.0],
+                [
+                    -0.5620087043508868,
+                    -0.07942340923212443,
+                    -0.16540082381410923,
+                ],
+            ),
+            (
+                [-12.3, 4.56, 7.89, 0.5],
+                [0.5341129624977965, 0.8508863004623672, -0.12452692747170546],
+            ),
+            (
+                [1000.1, -2000.2, 3000.3, 2.0],
+                [-0.42276725234240065, -0.2704482898863591, 0.172013816676521],
+            ),
+        ];
+        for (position, value) in expected {
+            assert_eq!(
+                value,
+                noise.sample_precise(position),
+                "value noise at {position:?} should match the locked value"
+            );
+        }
+
+        // Noise is continuous across lattice cells, and bounded by the amplitude
+        let mut previous = noise.sample(vec4(-2.0, 0.3, 0.7, 0.0));
+        for i in 1..=400 {
+            let current = noise.sample(vec4(-2.0 + i as f32 * 0.01, 0.3, 0.7, 0.0));
+            assert!(
+                current.distance(previous) < 0.1,
+                "value noise should be continuous, jumped from {previous} to {current}"
+            );
+            assert!(
+                current.abs().cmple(glam::vec3(1.0, 2.0, 0.5)).all(),
+                "value noise {current} should not exceed the amplitude"
+            );
+            previous = current;
+        }
+
+        let mut reseeded = noise.clone();
+        reseeded.set_seed(8);
+        assert_ne!(
+            noise.sample(vec4(0.5, 0.5, 0.5, 0.0)),
+            reseeded.sample(vec4(0.5, 0.5, 0.5, 0.0)),
+            "different seeds should give different noise"
+        );
+        let position = vec4(0.3, 1.7, -0.4, 0.2);
+        assert_eq!(
+            noise.sample(position),
+            noise.fbm(position, 1, FBM_LACUNARITY, FBM_GAIN),
+            "single octave should equal sample"
+        );
+        assert_eq!(
+            noise.sample(position).x,
+            NoiseSource::sample(&noise, position),
+            "scalar value noise should sample the first axis"
+        );
+    }
+}
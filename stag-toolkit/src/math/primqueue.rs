@@ -1,5 +1,6 @@
 use glam::vec2;
 use std::cmp::Ordering;
+use std::collections::VecDeque;
 
 /// A queue of floats, used for quickly storing and iterating through a set of data.
 /// Can also perform analysis on the data set.
@@ -128,11 +129,146 @@ impl FloatQueue {
     }
 }
 
+/// A first-in, first-out queue of primitive values, such as recent positions for a trail.
+///
+/// Unbounded by default. When given a capacity, the queue either overwrites its oldest
+/// values when full, or rejects new values until some are popped.
+#[derive(Clone, Debug)]
+pub struct PrimQueue<T> {
+    /// Values in the queue, from oldest to newest.
+    vals: VecDeque<T>,
+    /// Maximum number of values the queue can hold, if bounded.
+    capacity: Option<usize>,
+    /// Whether pushing onto a full queue drops the oldest value, instead of rejecting the new value.
+    overwrite: bool,
+}
+
+impl<T> Default for PrimQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> PrimQueue<T> {
+    /// Returns a new, unbounded queue.
+    pub fn new() -> Self {
+        Self {
+            vals: VecDeque::new(),
+            capacity: None,
+            overwrite: false,
+        }
+    }
+
+    /// Returns a new queue that holds at most `capacity` values.
+    /// If `overwrite` is set, pushing onto a full queue drops the oldest value,
+    /// otherwise the new value is rejected.
+    pub fn bounded(capacity: usize, overwrite: bool) -> Self {
+        Self {
+            vals: VecDeque::with_capacity(capacity),
+            capacity: Some(capacity),
+            overwrite,
+        }
+    }
+
+    /// Returns the maximum number of values the queue can hold, or [None] if unbounded.
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    /// Sets the maximum number of values the queue can hold, or [None] for unbounded.
+    /// If the queue holds more values than the new capacity, the oldest values are dropped.
+    pub fn set_capacity(&mut self, capacity: Option<usize>) {
+        self.capacity = capacity;
+        if let Some(capacity) = capacity
+            && self.vals.len() > capacity
+        {
+            self.vals.drain(..self.vals.len() - capacity);
+        }
+    }
+
+    /// Returns true if pushing onto a full queue drops the oldest value.
+    pub fn overwrite(&self) -> bool {
+        self.overwrite
+    }
+
+    /// Sets whether pushing onto a full queue drops the oldest value, instead of rejecting the new value.
+    pub fn set_overwrite(&mut self, overwrite: bool) {
+        self.overwrite = overwrite;
+    }
+
+    /// Returns the number of values in the queue.
+    pub fn len(&self) -> usize {
+        self.vals.len()
+    }
+
+    /// Returns true if the queue holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.vals.is_empty()
+    }
+
+    /// Returns true if the queue is bounded and holds as many values as it can.
+    /// Unbounded queues are never full.
+    pub fn is_full(&self) -> bool {
+        self.capacity
+            .is_some_and(|capacity| self.vals.len() >= capacity)
+    }
+
+    /// Pushes a value onto the back of the queue.
+    ///
+    /// If the queue is full, returns the value that did not fit:
+    /// the dropped oldest value when overwriting, otherwise the rejected new value.
+    pub fn push(&mut self, value: T) -> Option<T> {
+        if !self.is_full() {
+            self.vals.push_back(value);
+            return None;
+        }
+
+        // A zero-capacity queue can never hold the value
+        if !self.overwrite || self.vals.is_empty() {
+            return Some(value);
+        }
+
+        let oldest = self.vals.pop_front();
+        self.vals.push_back(value);
+        oldest
+    }
+
+    /// Removes and returns the oldest value of the queue.
+    pub fn pop(&mut self) -> Option<T> {
+        self.vals.pop_front()
+    }
+
+    /// Returns the oldest value of the queue, without removing it.
+    pub fn front(&self) -> Option<&T> {
+        self.vals.front()
+    }
+
+    /// Returns the newest value of the queue, without removing it.
+    pub fn back(&self) -> Option<&T> {
+        self.vals.back()
+    }
+
+    /// Iterates over the queue, from oldest to newest.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.vals.iter()
+    }
+
+    /// Removes and iterates over all values of the queue, from oldest to newest.
+    pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
+        self.vals.drain(..)
+    }
+
+    /// Removes all values from the queue, keeping its configuration.
+    pub fn clear(&mut self) {
+        self.vals.clear();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use glam::vec2;
 
-    use super::FloatQueue;
+    use super::{FloatQueue, PrimQueue};
 
     #[test]
     fn test_floatqueue() {
@@ -193,4 +329,62 @@ mod tests {
         assert_eq!(vec2(3.0, 5.0), queue.range());
         assert_eq!(0.816_496_6, queue.standard_deviation(queue.mean()));
     }
+
+    #[test]
+    fn test_primqueue() {
+        // Unbounded by default
+        let mut queue: PrimQueue<i32> = PrimQueue::default();
+        assert_eq!(None, queue.capacity());
+        for i in 0..100 {
+            assert_eq!(None, queue.push(i), "unbounded queue should accept {i}");
+        }
+        assert_eq!(100, queue.len());
+        assert!(!queue.is_full(), "unbounded queue should never be full");
+
+        // Overwriting wraps around, dropping the oldest values
+        let mut queue = PrimQueue::bounded(4, true);
+        for i in 0..4 {
+            assert_eq!(None, queue.push(i));
+        }
+        assert!(queue.is_full());
+        for i in 4..10 {
+            assert_eq!(Some(i - 4), queue.push(i), "pushing {i} should drop oldest");
+        }
+        assert_eq!(4, queue.len(), "queue should not grow past capacity");
+        assert_eq!(Some(&6), queue.front());
+        assert_eq!(Some(&9), queue.back());
+        assert_eq!(vec![6, 7, 8, 9], queue.drain().collect::<Vec<i32>>());
+        assert!(queue.is_empty());
+        assert!(!queue.is_full());
+
+        // Popping from a wrapped queue frees space in order
+        for i in 0..6 {
+            queue.push(i);
+        }
+        assert_eq!(Some(2), queue.pop());
+        assert!(!queue.is_full());
+        assert_eq!(None, queue.push(6));
+        assert_eq!(vec![&3, &4, &5, &6], queue.iter().collect::<Vec<&i32>>());
+
+        // Without overwriting, new values are rejected when full
+        let mut queue = PrimQueue::bounded(2, false);
+        assert_eq!(None, queue.push(1.0));
+        assert_eq!(None, queue.push(2.0));
+        assert_eq!(Some(3.0), queue.push(3.0), "full queue should reject value");
+        assert_eq!(vec![1.0, 2.0], queue.drain().collect::<Vec<f32>>());
+
+        // Shrinking capacity drops the oldest values
+        let mut queue = PrimQueue::new();
+        for i in 0..5 {
+            queue.push(i);
+        }
+        queue.set_capacity(Some(3));
+        assert!(queue.is_full());
+        assert_eq!(vec![&2, &3, &4], queue.iter().collect::<Vec<&i32>>());
+
+        // Zero-capacity queues hold nothing
+        let mut queue = PrimQueue::bounded(0, true);
+        assert_eq!(Some(1), queue.push(1));
+        assert!(queue.is_empty());
+    }
 }
@@ -1,4 +1,8 @@
 use glam::{Mat3, Quat, Vec3, Vec4, Vec4Swizzles};
+use std::f32::consts::FRAC_PI_2;
+
+/// Golden angle in radians, used for evenly distributing samples on a disk.
+pub const GOLDEN_ANGLE: f32 = 2.399_963;
 
 #[derive(Copy, Clone, Default, Debug)]
 pub struct RayIntersectionResult {
@@ -35,6 +39,12 @@ pub trait Plane {
         ray_direction: Vec3,
         signed_distance: f32,
     ) -> RayIntersectionResult;
+    /// Intersects the given plane with the finite line segment from `a` to `b`,
+    /// returning the intersection point only if it lies within the segment.
+    ///
+    /// Endpoints lying exactly on the plane count as hits.
+    /// If the segment is parallel with the plane, including lying within it, returns `None`.
+    fn segment_intersection(self, a: Vec3, b: Vec3) -> Option<Vec3>;
 }
 
 impl Plane for Vec4 {
@@ -77,6 +87,26 @@ impl Plane for Vec4 {
             reversed: !dt.is_sign_negative(),
         }
     }
+
+    fn segment_intersection(self, a: Vec3, b: Vec3) -> Option<Vec3> {
+        let da = self.signed_distance(a);
+        let db = self.signed_distance(b);
+
+        // Segment is parallel with the plane, or both endpoints are on the same side
+        if da == db || (da > 0.0 && db > 0.0) || (da < 0.0 && db < 0.0) {
+            return None;
+        }
+
+        // Return endpoints exactly, to avoid rounding error
+        if da == 0.0 {
+            return Some(a);
+        }
+        if db == 0.0 {
+            return Some(b);
+        }
+
+        Some(a.lerp(b, da / (da - db)))
+    }
 }
 
 /// Finds the index of the point furthest in a given direction from a set of points.
@@ -135,6 +165,92 @@ pub fn direction_to_quaternion(vector: Vec3) -> Quat {
     Quat::look_to_rh(-vector, Vec3::Y).conjugate()
 }
 
+/// Returns evenly spread, cosine-weighted directions over the hemisphere around the given normal,
+/// using a Fibonacci spiral.
+///
+/// Directions are denser towards the normal, matching Lambertian falloff,
+/// so each sample can be weighted equally when integrating lighting or occlusion.
+pub fn fibonacci_hemisphere(count: usize, normal: Vec3) -> Vec<Vec3> {
+    fibonacci_cone(count, normal, FRAC_PI_2)
+}
+
+/// Returns evenly spread, cosine-weighted directions within the given angle (in radians) of an axis,
+/// using a Fibonacci spiral. See [fibonacci_hemisphere].
+pub fn fibonacci_cone(count: usize, axis: Vec3, angle: f32) -> Vec<Vec3> {
+    let orientation = Quat::from_rotation_arc(Vec3::Z, axis.normalize_or(Vec3::Z));
+    let spread = angle.clamp(0.0, FRAC_PI_2).sin();
+
+    (0..count)
+        .map(|i| {
+            // Uniform disk sample, projected up onto the hemisphere (Malley's method)
+            let u = (i as f32 + 0.5) / count as f32;
+            let radius = u.sqrt() * spread;
+            let theta = i as f32 * GOLDEN_ANGLE;
+            let local = Vec3::new(
+                radius * theta.cos(),
+                radius * theta.sin(),
+                (1.0 - radius * radius).sqrt(),
+            );
+
+            orientation * local
+        })
+        .collect()
+}
+
+/// Resamples a polyline into the given number of points, evenly spaced along its arc length.
+///
+/// Open polylines keep their first and last points.
+/// Closed polylines include the segment from the last point back to the first,
+/// and the first point is not repeated at the end of the output.
+pub fn resample_polyline(points: &[Vec3], count: usize, closed: bool) -> Vec<Vec3> {
+    let Some(first) = points.first() else {
+        return vec![];
+    };
+
+    let mut path = points.to_vec();
+    if closed {
+        path.push(*first);
+    }
+
+    let total: f32 = path.windows(2).map(|pair| pair[0].distance(pair[1])).sum();
+    if count < 2 || total <= 0.0 {
+        return vec![*first; count];
+    }
+
+    let spacing = if closed {
+        total / count as f32
+    } else {
+        total / (count - 1) as f32
+    };
+
+    let mut resampled = Vec::with_capacity(count);
+    let mut segment = 0;
+    let mut segment_start = 0.0;
+    for i in 0..count {
+        let target = (i as f32 * spacing).min(total);
+
+        // Advance to the segment containing the target distance
+        while segment < path.len() - 2 {
+            let length = path[segment].distance(path[segment + 1]);
+            if segment_start + length >= target {
+                break;
+            }
+            segment_start += length;
+            segment += 1;
+        }
+
+        let length = path[segment].distance(path[segment + 1]);
+        let t = if length > 0.0 {
+            ((target - segment_start) / length).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        resampled.push(path[segment].lerp(path[segment + 1], t));
+    }
+
+    resampled
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -352,6 +468,219 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_segment_intersection() {
+        struct TestPlaneSegment {
+            a: Vec3,
+            b: Vec3,
+            result: Option<Vec3>,
+        }
+        let test_cases: Vec<TestPlaneSegment> = vec![
+            // Segment crosses plane
+            TestPlaneSegment {
+                a: Vec3::new(0.5, 0.0, 2.0),
+                b: Vec3::new(0.5, 0.0, -2.0),
+                result: Some(Vec3::new(0.5, 0.0, 1.0)),
+            },
+            // Segment crosses plane from behind
+            TestPlaneSegment {
+                a: Vec3::new(1.0, 0.0, 0.0),
+                b: Vec3::new(-1.0, 0.0, 4.0),
+                result: Some(Vec3::new(0.5, 0.0, 1.0)),
+            },
+            // Segment stops short of plane
+            TestPlaneSegment {
+                a: Vec3::Z * 3.0,
+                b: Vec3::Z * 1.5,
+                result: None,
+            },
+            // Segment starts past plane
+            TestPlaneSegment {
+                a: Vec3::Z * 0.5,
+                b: Vec3::NEG_Z,
+                result: None,
+            },
+            // Start point is exactly on plane
+            TestPlaneSegment {
+                a: Vec3::new(2.0, 3.0, 1.0),
+                b: Vec3::NEG_Z,
+                result: Some(Vec3::new(2.0, 3.0, 1.0)),
+            },
+            // End point is exactly on plane
+            TestPlaneSegment {
+                a: Vec3::Z * 4.0,
+                b: Vec3::new(-1.0, 2.0, 1.0),
+                result: Some(Vec3::new(-1.0, 2.0, 1.0)),
+            },
+            // Segment is parallel to plane
+            TestPlaneSegment {
+                a: Vec3::new(-1.0, 0.0, 2.0),
+                b: Vec3::new(1.0, 0.0, 2.0),
+                result: None,
+            },
+            // Segment lies within plane
+            TestPlaneSegment {
+                a: Vec3::new(-1.0, 0.0, 1.0),
+                b: Vec3::new(1.0, 0.0, 1.0),
+                result: None,
+            },
+            // Segment is a single point on the plane
+            TestPlaneSegment {
+                a: Vec3::Z,
+                b: Vec3::Z,
+                result: None,
+            },
+        ];
+
+        let pl = plane(Vec3::Z, Vec3::Z);
+        for (idx, case) in test_cases.iter().enumerate() {
+            let result = pl.segment_intersection(case.a, case.b);
+            match (result, case.result) {
+                (Some(result), Some(expected)) => assert_in_delta_vector(
+                    expected,
+                    result,
+                    1e-6,
+                    &format!("case {idx}: segment [{0} -> {1}]", case.a, case.b),
+                ),
+                _ => assert_eq!(
+                    case.result, result,
+                    "case {idx}: segment [{0} -> {1}] hit state should match expected",
+                    case.a, case.b
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_fibonacci_cone() {
+        let axis = Vec3::new(-0.2, 0.4, 0.9).normalize();
+        let angle = 20f32.to_radians();
+        let directions = fibonacci_cone(64, axis, angle);
+        assert_eq!(64, directions.len(), "should return requested count");
+        for dir in directions.iter() {
+            assert!(dir.is_normalized(), "direction {dir} should be normalized");
+            assert!(
+                dir.angle_between(axis) <= angle + 1e-4,
+                "direction {dir} should be within the cone"
+            );
+        }
+        assert!(
+            directions
+                .iter()
+                .any(|dir| dir.angle_between(axis) > angle * 0.9),
+            "directions should spread out to the edge of the cone"
+        );
+
+        let single = fibonacci_cone(1, axis, 0.0);
+        assert_in_delta_vector(axis, single[0], 1e-6, "zero-width cone follows the axis");
+    }
+
+    #[test]
+    fn test_fibonacci_hemisphere() {
+        let normal = Vec3::new(0.3, -0.8, 0.5).normalize();
+        let count = 512;
+        let directions = fibonacci_hemisphere(count, normal);
+        assert_eq!(count, directions.len(), "should return requested count");
+
+        for dir in directions.iter() {
+            assert!(dir.is_normalized(), "direction {dir} should be normalized");
+            assert!(
+                dir.dot(normal) > 0.0,
+                "direction {dir} should be within the hemisphere"
+            );
+        }
+
+        // Cosine-weighted hemisphere has an average cosine of 2/3
+        let mean = directions.iter().sum::<Vec3>() / count as f32;
+        assert_in_delta_vector(
+            normal * (2.0 / 3.0),
+            mean,
+            0.01,
+            "mean direction should lean towards the normal without bias",
+        );
+
+        // Each quadrant around the normal should receive an even share of samples
+        let orientation = Quat::from_rotation_arc(Vec3::Z, normal).inverse();
+        let mut quadrants = [0usize; 4];
+        for dir in directions.iter() {
+            let local = orientation * *dir;
+            let idx = (local.x >= 0.0) as usize + 2 * (local.y >= 0.0) as usize;
+            quadrants[idx] += 1;
+        }
+        for (idx, quadrant) in quadrants.iter().enumerate() {
+            assert!(
+                quadrant.abs_diff(count / 4) < count / 32,
+                "quadrant {idx} should have roughly a quarter of samples, got {quadrant}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_resample_polyline() {
+        // L-shaped polyline with uneven point spacing, 4 meters long
+        let points = [
+            Vec3::ZERO,
+            Vec3::new(0.5, 0.0, 0.0),
+            Vec3::new(3.0, 0.0, 0.0),
+            Vec3::new(3.0, 1.0, 0.0),
+        ];
+
+        let resampled = resample_polyline(&points, 9, false);
+        assert_eq!(9, resampled.len(), "should return requested count");
+        assert_in_delta_vector(points[0], resampled[0], 1e-6, "should keep first point");
+        assert_in_delta_vector(points[3], resampled[8], 1e-6, "should keep last point");
+        assert_in_delta_vector(
+            Vec3::new(3.0, 0.5, 0.0),
+            resampled[7],
+            1e-5,
+            "points should follow the polyline around corners",
+        );
+
+        // Every point lies on the polyline, so arc length between points is measurable by axis
+        let arc_length = |p: Vec3| p.x + p.y;
+        for (i, pair) in resampled.windows(2).enumerate() {
+            let spacing = arc_length(pair[1]) - arc_length(pair[0]);
+            assert!(
+                (spacing - 0.5).abs() < 1e-5,
+                "points {i} and {} should be 0.5 apart along the arc, got {spacing}",
+                i + 1
+            );
+        }
+
+        // Closed unit square, 4 meters long
+        let square = [
+            Vec3::ZERO,
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 1.0),
+            Vec3::new(0.0, 0.0, 1.0),
+        ];
+        let resampled = resample_polyline(&square, 8, true);
+        assert_eq!(8, resampled.len(), "should return requested count");
+        assert_in_delta_vector(
+            Vec3::new(0.0, 0.0, 0.5),
+            resampled[7],
+            1e-5,
+            "closed loop should resample the closing segment",
+        );
+        for i in 0..resampled.len() {
+            let next = resampled[(i + 1) % resampled.len()];
+            assert!(
+                (resampled[i].distance(next) - 0.5).abs() < 1e-5,
+                "closed loop points {i} should be evenly spaced"
+            );
+        }
+
+        assert!(
+            resample_polyline(&[], 4, false).is_empty(),
+            "empty polyline should resample to nothing"
+        );
+        assert_eq!(
+            vec![Vec3::ONE; 3],
+            resample_polyline(&[Vec3::ONE], 3, true),
+            "single point should be repeated"
+        );
+    }
+
     #[test]
     fn test_vector_in_cone() {
         // https://www.desmos.com/3d/vtqnlijzr8
@@ -11,6 +11,15 @@ pub trait Raycast {
     /// The raycast result for the shallowest collision point is returned.
     /// Returns [None] if the ray did not hit.
     fn raycast(&self, parameters: RaycastParameters) -> Option<RaycastResult>;
+
+    /// Performs a raycast for every set of parameters.
+    /// Results are returned in the same order as the given parameters.
+    fn raycast_many(&self, parameters: &[RaycastParameters]) -> Vec<Option<RaycastResult>> {
+        parameters
+            .iter()
+            .map(|params| self.raycast(*params))
+            .collect()
+    }
 }
 
 /// Settings for preparing a raycast.
@@ -24,6 +33,11 @@ pub struct RaycastParameters {
     pub max_depth: f32,
     /// If true, the direction of the face is ignored.
     pub hit_backfaces: bool,
+    /// If true, rays starting inside of a closed surface report a collision at the ray origin,
+    /// with a depth of zero and a zero normal.
+    pub hit_from_inside: bool,
+    /// If true, the result normal is interpolated from vertex normals at the hit point, when available.
+    pub interpolate_normals: bool,
 }
 
 impl RaycastParameters {
@@ -33,21 +47,65 @@ impl RaycastParameters {
             direction,
             max_depth,
             hit_backfaces,
+            ..Default::default()
+        }
+    }
+
+    /// Returns a [RaycastParametersBuilder] for the given ray, with all other parameters set to their defaults.
+    pub fn builder(origin: Vec3, direction: Vec3) -> RaycastParametersBuilder {
+        RaycastParametersBuilder {
+            parameters: Self {
+                origin,
+                direction,
+                ..Default::default()
+            },
         }
     }
 }
 
+/// Builder for [RaycastParameters], allowing optional parameters to be set by name.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RaycastParametersBuilder {
+    parameters: RaycastParameters,
+}
+
+impl RaycastParametersBuilder {
+    /// Sets the maximum depth a collision can occur at.
+    pub fn max_depth(mut self, max_depth: f32) -> Self {
+        self.parameters.max_depth = max_depth;
+        self
+    }
+    /// Sets whether the direction of the face is ignored.
+    pub fn hit_backfaces(mut self, hit_backfaces: bool) -> Self {
+        self.parameters.hit_backfaces = hit_backfaces;
+        self
+    }
+    /// Sets whether rays starting inside of a closed surface report a collision at the ray origin.
+    pub fn hit_from_inside(mut self, hit_from_inside: bool) -> Self {
+        self.parameters.hit_from_inside = hit_from_inside;
+        self
+    }
+    /// Sets whether the result normal is interpolated from vertex normals at the hit point.
+    pub fn interpolate_normals(mut self, interpolate_normals: bool) -> Self {
+        self.parameters.interpolate_normals = interpolate_normals;
+        self
+    }
+    /// Returns the finished [RaycastParameters].
+    pub fn build(self) -> RaycastParameters {
+        self.parameters
+    }
+}
+
 impl Mul<RaycastParameters> for Mat4 {
     type Output = RaycastParameters;
 
     /// Returns a new set of raycast parameters transformed by the given matrix.
     fn mul(self, rhs: RaycastParameters) -> Self::Output {
-        RaycastParameters::new(
-            self.transform_point3(rhs.origin),
-            self.transform_vector3(rhs.direction),
-            rhs.max_depth,
-            rhs.hit_backfaces,
-        )
+        RaycastParameters {
+            origin: self.transform_point3(rhs.origin),
+            direction: self.transform_vector3(rhs.direction),
+            ..rhs
+        }
     }
 }
 
@@ -58,6 +116,8 @@ impl Default for RaycastParameters {
             direction: Vec3::Z,
             max_depth: f32::INFINITY,
             hit_backfaces: false,
+            hit_from_inside: false,
+            interpolate_normals: false,
         }
     }
 }
@@ -66,8 +126,13 @@ impl Display for RaycastParameters {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{{ origin: {0}, direction: {1}, max_depth: {2}, hit_backfaces: {3} }}",
-            self.origin, self.direction, self.max_depth, self.hit_backfaces
+            "{{ origin: {0}, direction: {1}, max_depth: {2}, hit_backfaces: {3}, hit_from_inside: {4}, interpolate_normals: {5} }}",
+            self.origin,
+            self.direction,
+            self.max_depth,
+            self.hit_backfaces,
+            self.hit_from_inside,
+            self.interpolate_normals
         )
     }
 }
@@ -194,3 +259,45 @@ impl RaycastResultReducer for Vec<RaycastResult> {
         self.len()
     }
 }
+
+// UNIT TESTS //
+#[cfg(test)]
+mod tests {
+    use super::RaycastParameters;
+    use glam::Vec3;
+
+    #[test]
+    fn test_parameters_builder() {
+        let positional = RaycastParameters::new(Vec3::ONE, Vec3::NEG_Y, 5.0, true);
+        let built = RaycastParameters::builder(Vec3::ONE, Vec3::NEG_Y)
+            .max_depth(5.0)
+            .hit_backfaces(true)
+            .build();
+        assert_eq!(
+            positional, built,
+            "builder should match positional constructor"
+        );
+
+        let defaults = RaycastParameters::builder(Vec3::ZERO, Vec3::Z).build();
+        assert_eq!(
+            RaycastParameters::default(),
+            defaults,
+            "builder should use default parameters"
+        );
+
+        let inside = RaycastParameters::builder(Vec3::ZERO, Vec3::X)
+            .hit_from_inside(true)
+            .build();
+        assert!(inside.hit_from_inside, "hit_from_inside should be set");
+        assert!(!inside.hit_backfaces, "hit_backfaces should be unset");
+        assert!(
+            !inside.interpolate_normals,
+            "interpolate_normals should be unset"
+        );
+        assert_eq!(
+            f32::INFINITY,
+            inside.max_depth,
+            "max depth should default to infinity"
+        );
+    }
+}
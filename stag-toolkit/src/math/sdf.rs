@@ -1,5 +1,5 @@
 use crate::math::bounding_box::BoundingBox;
-use glam::{Mat4, Vec2, Vec3, Vec3Swizzles, Vec4, Vec4Swizzles, vec2, vec3};
+use glam::{Mat4, Quat, Vec2, Vec3, Vec3Swizzles, Vec4, Vec4Swizzles, vec2, vec3};
 
 /// Joins two distance functions, using a logarithm for smoothing values.
 /// `k = 32.0` was the original suggestion for smoothing value.
@@ -8,6 +8,24 @@ pub fn smooth_union(a: f32, b: f32, k: f32) -> f32 {
     -res.max(0.0001).log10() / k
 }
 
+/// Polynomial smooth minimum of two distance functions, blending within `k` distance of where they meet.
+/// A `k` of zero or less is identical to [union].
+///
+/// <https://iquilezles.org/articles/smin/>
+pub fn smooth_min(a: f32, b: f32, k: f32) -> f32 {
+    if k <= 0.0 {
+        return union(a, b);
+    }
+    let h = (k - (a - b).abs()).max(0.0) / k;
+    a.min(b) - h * h * k * 0.25
+}
+
+/// Polynomial smooth maximum of two distance functions, the counterpart of [smooth_min].
+/// A `k` of zero or less is identical to [intersection].
+pub fn smooth_max(a: f32, b: f32, k: f32) -> f32 {
+    -smooth_min(-a, -b, k)
+}
+
 /// Returns the union of two distance functions: A + B.
 pub fn union(a: f32, b: f32) -> f32 {
     a.min(b)
@@ -63,21 +81,58 @@ pub fn sample_torus(sample_position: Vec3, ring_thickness: f32, radius: f32) ->
     q.length() - ring_thickness
 }
 
+/// Distance function for a capsule, with its segment running along the Y axis.
+///
+/// <https://iquilezles.org/articles/distfunctions/>
+pub fn sample_capsule(sample_position: Vec3, shape_radius: f32, shape_height: f32) -> f32 {
+    let half = shape_height * 0.5;
+    let mut p = sample_position;
+    p.y -= p.y.clamp(-half, half);
+    p.length() - shape_radius
+}
+
+/// Distance function for a cone, with its apex pointing up the Y axis
+/// and its base centered at half the height below the origin.
+///
+/// <https://iquilezles.org/articles/distfunctions/>
+pub fn sample_cone(sample_position: Vec3, shape_radius: f32, shape_height: f32) -> f32 {
+    let half = shape_height * 0.5;
+    let q = vec2(sample_position.xz().length(), sample_position.y);
+    let k1 = vec2(0.0, half);
+    let k2 = vec2(-shape_radius, shape_height);
+
+    // Distance to the base cap
+    let ca = vec2(
+        q.x - q.x.min(if q.y < 0.0 { shape_radius } else { 0.0 }),
+        q.y.abs() - half,
+    );
+    // Distance to the slanted side, guarded against degenerate cones
+    let cb = q - k1 + k2 * ((k1 - q).dot(k2) / k2.length_squared().max(1e-12)).clamp(0.0, 1.0);
+
+    let sign = if cb.x < 0.0 && ca.y < 0.0 { -1.0 } else { 1.0 };
+    sign * ca.length_squared().min(cb.length_squared()).sqrt()
+}
+
 /// Describes an SDF primitive shape.
 #[derive(Copy, Clone, PartialEq)]
+#[repr(u32)]
 pub enum ShapeType {
     /// A sphere primitive.
-    Sphere,
+    Sphere = 0,
     /// A rounded box primitive.
-    RoundedBox,
+    RoundedBox = 1,
     /// A rounded cylinder primitive.
-    RoundedCylinder,
+    RoundedCylinder = 2,
     /// A torus primitive.
-    Torus,
+    Torus = 3,
+    /// A capsule primitive.
+    Capsule = 4,
+    /// A cone primitive.
+    Cone = 5,
 }
 
 /// Describes an SDF primitive operation.
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum ShapeOperation {
     /// A joining between two shapes.
     Union,
@@ -85,6 +140,74 @@ pub enum ShapeOperation {
     Intersection,
     /// A subtraction between two shapes.
     Subtraction,
+    /// A joining between two shapes, blended within `k` distance of where they meet.
+    SmoothUnion { k: f32 },
+    /// An intersection between two shapes, blended within `k` distance of where they meet.
+    SmoothIntersection { k: f32 },
+    /// A subtraction between two shapes, blended within `k` distance of where they meet.
+    SmoothSubtraction { k: f32 },
+}
+
+impl ShapeOperation {
+    /// Parses an operation from its name, case-insensitive, such as `"union"`, `"intersect"`, or `"subtract"`.
+    /// Returns [None] if the name is not recognized.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "union" => Some(Self::Union),
+            "intersect" | "intersection" => Some(Self::Intersection),
+            "subtract" | "subtraction" => Some(Self::Subtraction),
+            _ => None,
+        }
+    }
+
+    /// Returns the operation without any blending, such as [Self::Union] for [Self::SmoothUnion].
+    pub fn hard(&self) -> Self {
+        match self {
+            Self::Union | Self::SmoothUnion { .. } => Self::Union,
+            Self::Intersection | Self::SmoothIntersection { .. } => Self::Intersection,
+            Self::Subtraction | Self::SmoothSubtraction { .. } => Self::Subtraction,
+        }
+    }
+
+    /// Returns the smooth counterpart of this operation, blending within `k` distance.
+    /// Returns the hard operation if `k` is zero or less.
+    pub fn smoothed(&self, k: f32) -> Self {
+        if k <= 0.0 {
+            return self.hard();
+        }
+        match self.hard() {
+            Self::Intersection => Self::SmoothIntersection { k },
+            Self::Subtraction => Self::SmoothSubtraction { k },
+            _ => Self::SmoothUnion { k },
+        }
+    }
+
+    /// Returns the blending distance of the operation, or zero if it is a hard operation.
+    pub fn blend(&self) -> f32 {
+        match self {
+            Self::SmoothUnion { k }
+            | Self::SmoothIntersection { k }
+            | Self::SmoothSubtraction { k } => k.max(0.0),
+            _ => 0.0,
+        }
+    }
+
+    /// Whether the operation joins shapes together, smoothly or not.
+    pub fn is_union(&self) -> bool {
+        self.hard() == Self::Union
+    }
+
+    /// Combines the accumulated distance `a` with a shape's distance `b`.
+    pub fn apply(&self, a: f32, b: f32) -> f32 {
+        match self {
+            Self::Union => union(a, b),
+            Self::Intersection => intersection(a, b),
+            Self::Subtraction => subtraction(a, b),
+            Self::SmoothUnion { k } => smooth_min(a, b, *k),
+            Self::SmoothIntersection { k } => smooth_max(a, b, *k),
+            Self::SmoothSubtraction { k } => smooth_max(a, -b, *k),
+        }
+    }
 }
 
 /// Collection of data describing a Signed Distance Field primitive.
@@ -94,16 +217,29 @@ pub struct Shape {
     shape: ShapeType,
     /// Informs which operation to use when combining SDFs.
     pub operation: ShapeOperation,
-    /// Describes a sphere or cylinder's radius.
+    /// Describes a sphere, cylinder, capsule or cone's radius.
     radius: f32,
     /// Describes the edge rounding on the given shape, if available.
     pub radius_ring: f32,
-    /// Describes the dimensions of a box or cylinder.
+    /// Describes the dimensions of a box, cylinder, capsule or cone.
     dimensions: Vec3,
     /// Transform of the shape. Applied to position before sampling.
     transform: Mat4,
     /// Inverse transform of the shape. Used for point projection.
     transform_inv: Mat4,
+    /// Smallest axis scale of the transform, for converting local distances back into world space.
+    distance_scale: f32,
+    /// How strongly added noise affects the surface near this shape, from 0 (smooth) to 1 (fully noisy).
+    pub noise_weight: f32,
+    /// Whether to preserve sharp features near this shape, by reducing the strength of SDF smoothing near its surface.
+    pub preserve_sharp: bool,
+    /// Operation to use for this shape when generating collision, if it differs from the visual operation.
+    pub collision_operation: Option<ShapeOperation>,
+    /// Edge rounding radius for boxes and cylinders, overriding the edge radius of the whole shape list if set.
+    /// Allows crisp and heavily rounded shapes within the same build.
+    pub edge_radius: Option<f32>,
+    /// Material index for surfaces nearest to this shape, used for splitting baked meshes into surfaces.
+    pub material_index: u32,
 }
 
 impl Shape {
@@ -114,6 +250,12 @@ impl Shape {
             operation,
             transform,
             transform_inv: transform.inverse(),
+            distance_scale: distance_scale(&transform.inverse()),
+            noise_weight: 1.0,
+            preserve_sharp: false,
+            collision_operation: None,
+            edge_radius: None,
+            material_index: 0,
             radius,
             radius_ring: 0.0,
             dimensions: Vec3::ZERO,
@@ -131,6 +273,12 @@ impl Shape {
             operation,
             transform,
             transform_inv: transform.inverse(),
+            distance_scale: distance_scale(&transform.inverse()),
+            noise_weight: 1.0,
+            preserve_sharp: false,
+            collision_operation: None,
+            edge_radius: None,
+            material_index: 0,
             radius: 0.0,
             radius_ring: radius_edge,
             dimensions,
@@ -149,6 +297,12 @@ impl Shape {
             operation,
             transform,
             transform_inv: transform.inverse(),
+            distance_scale: distance_scale(&transform.inverse()),
+            noise_weight: 1.0,
+            preserve_sharp: false,
+            collision_operation: None,
+            edge_radius: None,
+            material_index: 0,
             radius,
             radius_ring: radius_edge,
             dimensions: vec3(1.0, height, 1.0),
@@ -167,20 +321,88 @@ impl Shape {
             operation,
             transform,
             transform_inv: transform.inverse(),
+            distance_scale: distance_scale(&transform.inverse()),
+            noise_weight: 1.0,
+            preserve_sharp: false,
+            collision_operation: None,
+            edge_radius: None,
+            material_index: 0,
             radius,
             radius_ring: ring_thickness,
             dimensions: Vec3::ONE,
         }
     }
+    /// Creates a capsule primitive between the two given endpoints, relative to the given transform.
+    pub fn capsule(
+        transform: Mat4,
+        a: Vec3,
+        b: Vec3,
+        radius: f32,
+        operation: ShapeOperation,
+    ) -> Self {
+        // Align the local Y axis with the capsule segment
+        let length = a.distance(b);
+        let direction = if length > f32::EPSILON {
+            (b - a) / length
+        } else {
+            Vec3::Y
+        };
+        let transform = transform
+            * Mat4::from_rotation_translation(
+                Quat::from_rotation_arc(Vec3::Y, direction),
+                (a + b) * 0.5,
+            );
+
+        Self {
+            shape: ShapeType::Capsule,
+            operation,
+            transform,
+            transform_inv: transform.inverse(),
+            distance_scale: distance_scale(&transform.inverse()),
+            noise_weight: 1.0,
+            preserve_sharp: false,
+            collision_operation: None,
+            edge_radius: None,
+            material_index: 0,
+            radius,
+            radius_ring: 0.0,
+            dimensions: vec3(1.0, length, 1.0),
+        }
+    }
+    /// Creates a cone primitive with the given parameters.
+    /// The apex points up the Y axis, with the base centered half the height below the origin.
+    pub fn cone(transform: Mat4, height: f32, radius: f32, operation: ShapeOperation) -> Self {
+        Self {
+            shape: ShapeType::Cone,
+            operation,
+            transform,
+            transform_inv: transform.inverse(),
+            distance_scale: distance_scale(&transform.inverse()),
+            noise_weight: 1.0,
+            preserve_sharp: false,
+            collision_operation: None,
+            edge_radius: None,
+            material_index: 0,
+            radius,
+            radius_ring: 0.0,
+            dimensions: vec3(1.0, height, 1.0),
+        }
+    }
     /// Samples the SDF shape at the given point.
     /// Returned value is the point's distance to the surface of the shape,
     /// with negative being inside the shape, positive being outside.
+    ///
+    /// The point is sampled in the shape's local space, then scaled back by the smallest axis of the transform.
+    /// Under non-uniform scale, this underestimates the true distance, but never overshoots the surface.
+    ///
+    /// The given edge radius rounds boxes and cylinders, unless the shape has its own [Self::edge_radius].
     pub fn sample(&self, at: Vec3, edge_radius: f32) -> f32 {
         let position_local = self
             .transform_inv
             .mul_vec4(Vec4::new(at.x, at.y, at.z, 1.0))
             .xyz();
-        match self.shape {
+        let edge_radius = self.edge_radius.unwrap_or(edge_radius);
+        let d = match self.shape {
             ShapeType::Sphere => sample_sphere(position_local, self.radius),
             ShapeType::RoundedBox => {
                 sample_box_rounded(position_local, self.dimensions, edge_radius)
@@ -189,7 +411,10 @@ impl Shape {
                 sample_cylinder_rounded(position_local, self.radius, self.dimensions.y, edge_radius)
             }
             ShapeType::Torus => sample_torus(position_local, self.radius_ring, self.radius),
-        }
+            ShapeType::Capsule => sample_capsule(position_local, self.radius, self.dimensions.y),
+            ShapeType::Cone => sample_cone(position_local, self.radius, self.dimensions.y),
+        };
+        d * self.distance_scale
     }
     /// Returns the minimum and maximum boundary points of the shape, NOT transformed
     pub fn relative_bounds(&self) -> BoundingBox {
@@ -212,9 +437,139 @@ impl Shape {
                     vec3(width, self.radius_ring, width),
                 )
             }
+            ShapeType::Capsule => {
+                let half = self.dimensions.y * 0.5 + self.radius;
+                BoundingBox::new(
+                    vec3(-self.radius, -half, -self.radius),
+                    vec3(self.radius, half, self.radius),
+                )
+            }
+            ShapeType::Cone => BoundingBox::new(
+                vec3(-self.radius, -self.dimensions.y * 0.5, -self.radius),
+                vec3(self.radius, self.dimensions.y * 0.5, self.radius),
+            ),
         }
     }
 
+    /// Returns line segments outlining the shape, in the same coordinate space as the shape's transform.
+    /// Circles are approximated using the given number of segments.
+    pub fn outline(&self, circle_segments: usize) -> Vec<(Vec3, Vec3)> {
+        let lines = match self.shape {
+            ShapeType::Sphere => {
+                // Great circles on all three axes
+                let mut lines =
+                    outline_circle(Vec3::ZERO, Vec3::X, Vec3::Y, self.radius, circle_segments);
+                lines.append(&mut outline_circle(
+                    Vec3::ZERO,
+                    Vec3::Y,
+                    Vec3::Z,
+                    self.radius,
+                    circle_segments,
+                ));
+                lines.append(&mut outline_circle(
+                    Vec3::ZERO,
+                    Vec3::Z,
+                    Vec3::X,
+                    self.radius,
+                    circle_segments,
+                ));
+                lines
+            }
+            ShapeType::RoundedBox => {
+                let half = self.dimensions * 0.5;
+                let corner = |x: f32, y: f32, z: f32| half * vec3(x, y, z);
+
+                let mut lines = Vec::with_capacity(12);
+                for (a, b) in [(-1.0, -1.0), (-1.0, 1.0), (1.0, -1.0), (1.0, 1.0)] {
+                    lines.push((corner(-1.0, a, b), corner(1.0, a, b)));
+                    lines.push((corner(a, -1.0, b), corner(a, 1.0, b)));
+                    lines.push((corner(a, b, -1.0), corner(a, b, 1.0)));
+                }
+                lines
+            }
+            ShapeType::RoundedCylinder => {
+                // Top and bottom rings, joined by vertical lines
+                let top = Vec3::Y * self.dimensions.y * 0.5;
+                let mut lines = outline_circle(top, Vec3::X, Vec3::Z, self.radius, circle_segments);
+                lines.append(&mut outline_circle(
+                    -top,
+                    Vec3::X,
+                    Vec3::Z,
+                    self.radius,
+                    circle_segments,
+                ));
+                for side in [Vec3::X, Vec3::NEG_X, Vec3::Z, Vec3::NEG_Z] {
+                    lines.push((side * self.radius + top, side * self.radius - top));
+                }
+                lines
+            }
+            ShapeType::Torus => {
+                // Inner, outer, top and bottom rings
+                let top = Vec3::Y * self.radius_ring;
+                let mut lines = Vec::with_capacity(circle_segments * 4);
+                for (center, radius) in [
+                    (Vec3::ZERO, self.radius + self.radius_ring),
+                    (Vec3::ZERO, self.radius - self.radius_ring),
+                    (top, self.radius),
+                    (-top, self.radius),
+                ] {
+                    lines.append(&mut outline_circle(
+                        center,
+                        Vec3::X,
+                        Vec3::Z,
+                        radius,
+                        circle_segments,
+                    ));
+                }
+                lines
+            }
+            ShapeType::Capsule => {
+                // Rings around both ends, joined by vertical lines, with arcs over the caps
+                let top = Vec3::Y * self.dimensions.y * 0.5;
+                let mut lines = outline_circle(top, Vec3::X, Vec3::Z, self.radius, circle_segments);
+                lines.append(&mut outline_circle(
+                    -top,
+                    Vec3::X,
+                    Vec3::Z,
+                    self.radius,
+                    circle_segments,
+                ));
+                for side in [Vec3::X, Vec3::NEG_X, Vec3::Z, Vec3::NEG_Z] {
+                    lines.push((side * self.radius + top, side * self.radius - top));
+                }
+                for (center, up) in [(top, Vec3::Y), (-top, Vec3::NEG_Y)] {
+                    for side in [Vec3::X, Vec3::Z] {
+                        let mut arc =
+                            outline_circle(center, side, up, self.radius, circle_segments);
+                        arc.truncate(circle_segments.max(3) / 2);
+                        lines.append(&mut arc);
+                    }
+                }
+                lines
+            }
+            ShapeType::Cone => {
+                // Base ring, joined to the apex
+                let top = Vec3::Y * self.dimensions.y * 0.5;
+                let mut lines =
+                    outline_circle(-top, Vec3::X, Vec3::Z, self.radius, circle_segments);
+                for side in [Vec3::X, Vec3::NEG_X, Vec3::Z, Vec3::NEG_Z] {
+                    lines.push((side * self.radius - top, top));
+                }
+                lines
+            }
+        };
+
+        lines
+            .into_iter()
+            .map(|(a, b)| {
+                (
+                    self.transform.transform_point3(a),
+                    self.transform.transform_point3(b),
+                )
+            })
+            .collect()
+    }
+
     /// Returns the transform of the given shape.
     pub fn transform(&self) -> Mat4 {
         self.transform
@@ -223,32 +578,166 @@ impl Shape {
     /// Sets the transform of the given shape.
     pub fn set_transform(&mut self, transform: Mat4) {
         self.transform_inv = transform.inverse();
+        self.distance_scale = distance_scale(&self.transform_inv);
         self.transform = transform;
     }
+
+    /// Returns the operation to use for this shape when generating collision.
+    pub fn operation_for_collision(&self) -> ShapeOperation {
+        self.collision_operation.unwrap_or(self.operation)
+    }
+
+    /// Returns a copy of the shape, with the given parent transform applied on top of its own transform.
+    pub fn transformed(&self, transform: Mat4) -> Self {
+        let mut shape = *self;
+        shape.set_transform(transform * self.transform);
+        shape
+    }
+}
+
+/// Edge rounding radii to apply to shapes, chosen by each shape's CSG operation.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct EdgeRadius {
+    /// Rounding radius for shapes that are unioned.
+    pub union: f32,
+    /// Rounding radius for shapes that are intersected.
+    pub intersection: f32,
+    /// Rounding radius for shapes that are subtracted.
+    pub subtraction: f32,
+}
+
+impl EdgeRadius {
+    /// Uses the same rounding radius for every operation.
+    pub fn uniform(radius: f32) -> Self {
+        Self {
+            union: radius,
+            intersection: radius,
+            subtraction: radius,
+        }
+    }
+
+    /// Returns the rounding radius to use for the given operation.
+    pub fn for_operation(&self, operation: ShapeOperation) -> f32 {
+        match operation.hard() {
+            ShapeOperation::Intersection => self.intersection,
+            ShapeOperation::Subtraction => self.subtraction,
+            _ => self.union,
+        }
+    }
+}
+
+impl From<f32> for EdgeRadius {
+    fn from(radius: f32) -> Self {
+        Self::uniform(radius)
+    }
 }
 
 /// Iterates through a shape list, sampling each shape at the given point
-/// and smooth unioning the shapes together, returning a distance.
-pub fn sample_shape_list(list: &[Shape], point: Vec3, radius_edge: f32) -> f32 {
-    let mut d: f32 = 1.0;
+/// and combining the shapes by their operations, returning a distance.
+/// Distances are capped at 1, which is also returned for an empty list.
+///
+/// Edge rounding is picked per shape based on its operation.
+/// A single `f32` can be passed to round all shapes equally.
+pub fn sample_shape_list(list: &[Shape], point: Vec3, radius_edge: impl Into<EdgeRadius>) -> f32 {
+    let radius_edge = radius_edge.into();
+    // Start from empty space, so smooth operations do not blend against a placeholder surface
+    let mut d = f32::INFINITY;
 
     for shape in list.iter() {
-        let j = shape.sample(point, radius_edge);
+        let j = shape.sample(point, radius_edge.for_operation(shape.operation));
+        d = shape.operation.apply(d, j);
+    }
 
-        match shape.operation {
-            ShapeOperation::Union => {
-                d = union(d, j);
-            }
-            ShapeOperation::Intersection => {
-                d = intersection(d, j);
-            }
-            ShapeOperation::Subtraction => {
-                d = subtraction(d, j);
-            }
+    // Cap the field, so shapes only influence cells within a distance of 1 of their surface.
+    // This also clamps lists with no union shapes back from infinity.
+    // `Data::update_shape` relies on this reach to only resample cells near a changed shape.
+    d.min(1.0)
+}
+
+/// Returns the noise weight at the given point, blending the noise weights of union shapes by proximity.
+/// Shapes containing the point are weighted fully, while the influence of other shapes falls off over the blend distance.
+/// Returns 1 if no union shapes influence the point.
+pub fn sample_noise_weight(list: &[Shape], point: Vec3, blend: f32) -> f32 {
+    sample_shape_weight(list, point, blend, |shape| shape.noise_weight)
+}
+
+/// Returns the SDF smoothing weight at the given point, from 0 (unsmoothed) to 1 (fully smoothed).
+/// Union shapes that preserve sharp features weigh 0, while all other union shapes weigh 1,
+/// blending by proximity like [sample_noise_weight].
+/// Returns 1 if no union shapes influence the point.
+pub fn sample_smooth_weight(list: &[Shape], point: Vec3, blend: f32) -> f32 {
+    sample_shape_weight(list, point, blend, |shape| {
+        if shape.preserve_sharp { 0.0 } else { 1.0 }
+    })
+}
+
+/// Blends a per-shape weight between union shapes by proximity to the given point.
+/// Shapes containing the point are weighted fully, while the influence of other shapes falls off over the blend distance.
+fn sample_shape_weight(
+    list: &[Shape],
+    point: Vec3,
+    blend: f32,
+    weight: impl Fn(&Shape) -> f32,
+) -> f32 {
+    let blend = blend.max(1e-3);
+    let mut weighted: f32 = 0.0;
+    let mut total: f32 = 0.0;
+
+    for shape in list.iter() {
+        if !shape.operation.is_union() {
+            continue;
         }
+
+        let influence = (-shape.sample(point, 0.0).max(0.0) / blend).exp();
+        weighted += weight(shape) * influence;
+        total += influence;
+    }
+
+    if total > 0.0 { weighted / total } else { 1.0 }
+}
+
+/// Returns the smallest axis scale of a transform, given its inverse.
+/// Each row of the inverse basis has a length of one over the scale of the matching axis.
+fn distance_scale(transform_inv: &Mat4) -> f32 {
+    let basis = transform_inv.transpose();
+    let largest = basis
+        .x_axis
+        .xyz()
+        .length()
+        .max(basis.y_axis.xyz().length())
+        .max(basis.z_axis.xyz().length());
+
+    if largest > 0.0 && largest.is_finite() {
+        1.0 / largest
+    } else {
+        1.0
     }
+}
+
+/// Returns line segments approximating a circle around the given center,
+/// on the plane described by the two given axes.
+fn outline_circle(
+    center: Vec3,
+    axis_u: Vec3,
+    axis_v: Vec3,
+    radius: f32,
+    segments: usize,
+) -> Vec<(Vec3, Vec3)> {
+    let segments = segments.max(3);
+    let point = |i: usize| {
+        let theta = i as f32 / segments as f32 * std::f32::consts::TAU;
+        center + (axis_u * theta.cos() + axis_v * theta.sin()) * radius
+    };
+
+    (0..segments).map(|i| (point(i), point(i + 1))).collect()
+}
 
-    d
+/// Returns line segments outlining every shape in the list, for drawing whitebox geometry.
+/// Circles are approximated using the given number of segments.
+pub fn shape_list_outline(list: &[Shape], circle_segments: usize) -> Vec<(Vec3, Vec3)> {
+    list.iter()
+        .flat_map(|shape| shape.outline(circle_segments))
+        .collect()
 }
 
 /// Creates an axis-aligned bounding box that encloses all provided Union shapes.
@@ -258,9 +747,10 @@ pub fn shape_list_bounds(list: &[Shape]) -> BoundingBox {
     let mut aabb: Option<BoundingBox> = None;
 
     for shape in list.iter() {
-        if shape.operation == ShapeOperation::Union {
-            // Get transformed bounding box of shape
-            let shape_aabb = shape.transform() * shape.relative_bounds();
+        if shape.operation.is_union() {
+            // Get transformed bounding box of shape, including any bulging from smooth blending
+            let shape_aabb = (shape.transform() * shape.relative_bounds())
+                .expand_margin(shape.operation.blend() * 0.25);
 
             // If we already set a bounding box, update it to include the shape
             if let Some(unwrapped_aabb) = aabb {
@@ -275,13 +765,124 @@ pub fn shape_list_bounds(list: &[Shape]) -> BoundingBox {
     aabb.unwrap_or_default()
 }
 
+const TAG_SPHERE: u32 = ShapeType::Sphere as u32;
+const TAG_ROUNDED_BOX: u32 = ShapeType::RoundedBox as u32;
+const TAG_ROUNDED_CYLINDER: u32 = ShapeType::RoundedCylinder as u32;
+const TAG_TORUS: u32 = ShapeType::Torus as u32;
+const TAG_CAPSULE: u32 = ShapeType::Capsule as u32;
+const TAG_CONE: u32 = ShapeType::Cone as u32;
+const TAG_UNION: u32 = 0;
+const TAG_INTERSECTION: u32 = 1;
+const TAG_SUBTRACTION: u32 = 2;
+
+/// Flattened, plain-old-data representation of a [Shape], for uploading to the GPU or serializing.
+///
+/// Laid out in 16-byte aligned blocks, matching std430 buffer layouts.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(C)]
+pub struct PackedShape {
+    /// Inverse transform of the shape, in column-major order.
+    pub transform_inv: [f32; 16],
+    /// Dimensions of the shape (XYZ) and the shape radius (W).
+    pub params: [f32; 4],
+    /// [ShapeType] tag of the shape.
+    pub shape: u32,
+    /// [ShapeOperation] tag of the shape, ignoring blending: 0 for union, 1 for intersection, 2 for subtraction.
+    pub operation: u32,
+    /// Ring radius of the shape.
+    pub radius_ring: f32,
+    /// Edge rounding radius of the shape, or negative to use the edge radius of the whole shape list.
+    pub edge_radius: f32,
+    /// Blending distance of the shape's operation, or zero for a hard operation.
+    pub blend: f32,
+    /// Unused, keeps the struct 16-byte aligned.
+    pub padding: [f32; 3],
+}
+
+impl From<&Shape> for PackedShape {
+    fn from(shape: &Shape) -> Self {
+        Self {
+            transform_inv: shape.transform_inv.to_cols_array(),
+            params: [
+                shape.dimensions.x,
+                shape.dimensions.y,
+                shape.dimensions.z,
+                shape.radius,
+            ],
+            shape: shape.shape as u32,
+            operation: match shape.operation.hard() {
+                ShapeOperation::Intersection => TAG_INTERSECTION,
+                ShapeOperation::Subtraction => TAG_SUBTRACTION,
+                _ => TAG_UNION,
+            },
+            radius_ring: shape.radius_ring,
+            edge_radius: shape.edge_radius.map_or(-1.0, |radius| radius.max(0.0)),
+            blend: shape.operation.blend(),
+            padding: [0.0; 3],
+        }
+    }
+}
+
+/// Flattens a shape list into a buffer of [PackedShape]s, preserving order.
+pub fn pack_shapes(list: &[Shape]) -> Vec<PackedShape> {
+    list.iter().map(PackedShape::from).collect()
+}
+
+/// Iterates through a packed shape list, sampling each shape at the given point
+/// and combining the shapes together, returning a distance.
+///
+/// CPU reference implementation of [sample_shape_list] for packed shape buffers.
+/// Shapes with an unknown shape tag are skipped.
+pub fn sample_packed(list: &[PackedShape], point: Vec3, radius_edge: impl Into<EdgeRadius>) -> f32 {
+    let radius_edge = radius_edge.into();
+    let mut d = f32::INFINITY;
+
+    for shape in list.iter() {
+        let transform_inv = Mat4::from_cols_array(&shape.transform_inv);
+        let position_local = transform_inv.transform_point3(point);
+        let dimensions = vec3(shape.params[0], shape.params[1], shape.params[2]);
+        let radius = shape.params[3];
+        let radius_edge = if shape.edge_radius >= 0.0 {
+            shape.edge_radius
+        } else {
+            match shape.operation {
+                TAG_INTERSECTION => radius_edge.intersection,
+                TAG_SUBTRACTION => radius_edge.subtraction,
+                _ => radius_edge.union,
+            }
+        };
+
+        let j = match shape.shape {
+            TAG_SPHERE => sample_sphere(position_local, radius),
+            TAG_ROUNDED_BOX => sample_box_rounded(position_local, dimensions, radius_edge),
+            TAG_ROUNDED_CYLINDER => {
+                sample_cylinder_rounded(position_local, radius, dimensions.y, radius_edge)
+            }
+            TAG_TORUS => sample_torus(position_local, shape.radius_ring, radius),
+            TAG_CAPSULE => sample_capsule(position_local, radius, dimensions.y),
+            TAG_CONE => sample_cone(position_local, radius, dimensions.y),
+            // Packed buffers may come from anywhere, so skip shapes that can't be sampled
+            _ => continue,
+        } * distance_scale(&transform_inv);
+
+        d = match shape.operation {
+            TAG_INTERSECTION => smooth_max(d, j, shape.blend),
+            TAG_SUBTRACTION => smooth_max(d, -j, shape.blend),
+            _ => smooth_min(d, j, shape.blend),
+        };
+    }
+
+    d.min(1.0)
+}
+
 // UNIT TESTS //
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::math::delta::assert_in_delta;
+    use crate::math::delta::{assert_in_delta, assert_in_delta_vector};
 
     use glam::Quat;
+    use std::f32::consts::FRAC_PI_4;
 
     #[test]
     fn test_smooth_union() {
@@ -474,7 +1075,7 @@ mod tests {
             // Scale
             TestCaseTransform {
                 note: String::from(
-                    "Coordinate space of sphere is half that of the point's, so point position should appear doubled to a regular sphere",
+                    "Sphere is scaled down by half, so point is half a unit above its surface",
                 ),
                 sample: Vec3::X,
                 transform: Mat4::from_scale_rotation_translation(
@@ -483,11 +1084,11 @@ mod tests {
                     Vec3::ZERO,
                 ),
                 radius: 1.0,
-                expect: 1.0,
+                expect: 0.5,
             },
             TestCaseTransform {
                 note: String::from(
-                    "Coordinate space of sphere is double that of the point's, so point position should appear half to a regular sphere",
+                    "Sphere is scaled up by double, so point is one unit below its surface",
                 ),
                 sample: Vec3::X,
                 transform: Mat4::from_scale_rotation_translation(
@@ -496,7 +1097,7 @@ mod tests {
                     Vec3::ZERO,
                 ),
                 radius: 1.0,
-                expect: -0.5,
+                expect: -1.0,
             },
         ];
 
@@ -542,4 +1143,741 @@ mod tests {
         let bounds = shape_list_bounds(&shapes);
         assert_eq!(bounds, BoundingBox::new(Vec3::splat(0.5), Vec3::splat(1.5)));
     }
+
+    #[test]
+    fn test_shape_transformed() {
+        let shape = Shape::sphere(Mat4::from_translation(Vec3::X), 1.0, ShapeOperation::Union);
+        let parent = Mat4::from_scale_rotation_translation(
+            Vec3::splat(2.0),
+            Quat::IDENTITY,
+            Vec3::new(0.0, 5.0, 0.0),
+        );
+        let transformed = shape.transformed(parent);
+
+        assert_eq!(
+            parent * shape.transform(),
+            transformed.transform(),
+            "parent transform should be applied on top of the shape transform"
+        );
+        assert!(
+            transformed.sample(Vec3::new(2.0, 5.0, 0.0), 0.0) < 0.0,
+            "center of the transformed sphere should be inside"
+        );
+        assert!(
+            transformed.sample(Vec3::new(4.5, 5.0, 0.0), 0.0) > 0.0,
+            "point beyond the scaled radius should be outside"
+        );
+        assert_eq!(
+            BoundingBox::new(Vec3::new(0.0, 3.0, -2.0), Vec3::new(4.0, 7.0, 2.0)),
+            shape_list_bounds(&[transformed]),
+            "bounds should follow the transformed shape"
+        );
+    }
+
+    #[test]
+    fn test_operation_overrides() {
+        assert_eq!(
+            Some(ShapeOperation::Union),
+            ShapeOperation::from_name("union")
+        );
+        assert_eq!(
+            Some(ShapeOperation::Subtraction),
+            ShapeOperation::from_name(" Subtract ")
+        );
+        assert_eq!(
+            Some(ShapeOperation::Intersection),
+            ShapeOperation::from_name("INTERSECTION")
+        );
+        assert_eq!(
+            None,
+            ShapeOperation::from_name("merge"),
+            "unknown names should not map to an operation"
+        );
+
+        // Visually a union, but subtracts from collision
+        let mut shape = Shape::sphere(Mat4::IDENTITY, 1.0, ShapeOperation::Union);
+        assert_eq!(
+            ShapeOperation::Union,
+            shape.operation_for_collision(),
+            "collision should default to the visual operation"
+        );
+        shape.collision_operation = ShapeOperation::from_name("subtract");
+        assert_eq!(
+            ShapeOperation::Union,
+            shape.operation,
+            "collision override should not change the visual operation"
+        );
+        assert_eq!(
+            ShapeOperation::Subtraction,
+            shape.operation_for_collision(),
+            "collision override should change the collision operation"
+        );
+    }
+
+    #[test]
+    fn test_sample_packed() {
+        let shapes = vec![
+            Shape::sphere(
+                Mat4::from_scale_rotation_translation(Vec3::splat(0.5), Quat::IDENTITY, Vec3::ONE),
+                1.5,
+                ShapeOperation::Union,
+            ),
+            Shape::rounded_box(
+                Mat4::from_rotation_y(0.7),
+                vec3(3.0, 1.0, 2.0),
+                0.2,
+                ShapeOperation::Union,
+            ),
+            Shape::rounded_cylinder(
+                Mat4::from_translation(vec3(-1.0, 0.5, 0.0)),
+                2.0,
+                0.75,
+                0.1,
+                ShapeOperation::Union,
+            ),
+            Shape::torus(
+                Mat4::from_rotation_x(0.3),
+                0.25,
+                1.0,
+                ShapeOperation::Intersection,
+            ),
+            Shape::sphere(
+                Mat4::from_translation(vec3(0.0, 1.0, 0.5)),
+                0.5,
+                ShapeOperation::Subtraction,
+            ),
+            Shape::capsule(
+                Mat4::from_translation(vec3(1.0, 0.0, -1.0)),
+                vec3(-0.5, -1.0, 0.0),
+                vec3(0.5, 1.0, 0.5),
+                0.4,
+                ShapeOperation::Union,
+            ),
+            Shape::cone(
+                Mat4::from_rotation_z(0.4),
+                2.0,
+                1.0,
+                ShapeOperation::Subtraction,
+            ),
+        ];
+        let packed = pack_shapes(&shapes);
+        assert_eq!(
+            shapes.len(),
+            packed.len(),
+            "packed list should match length"
+        );
+        assert_eq!(
+            0,
+            size_of::<PackedShape>() % 16,
+            "packed shape should be 16-byte aligned"
+        );
+
+        // Deterministic pseudo-random sample points
+        let mut state: u32 = 12345;
+        let mut random = || {
+            state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+            (state >> 8) as f32 / (1 << 24) as f32 * 6.0 - 3.0
+        };
+
+        for _ in 0..256 {
+            let point = vec3(random(), random(), random());
+            for radius_edge in [
+                EdgeRadius::uniform(0.0),
+                EdgeRadius::uniform(0.15),
+                EdgeRadius {
+                    union: 0.2,
+                    intersection: 0.05,
+                    subtraction: 0.0,
+                },
+            ] {
+                let expected = sample_shape_list(&shapes, point, radius_edge);
+                let actual = sample_packed(&packed, point, radius_edge);
+                assert_in_delta(
+                    expected,
+                    actual,
+                    1e-5,
+                    format!("packed sample should match shape list sample at {point}"),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_edge_radius_per_operation() {
+        // A box subtracted from the corner of a larger box
+        let base = Shape::rounded_box(Mat4::IDENTITY, Vec3::splat(4.0), 0.0, ShapeOperation::Union);
+        let cut = Shape::rounded_box(
+            Mat4::from_translation(Vec3::splat(2.0)),
+            Vec3::splat(2.0),
+            0.0,
+            ShapeOperation::Subtraction,
+        );
+        let shapes = vec![base, cut];
+
+        let radii = EdgeRadius {
+            union: 0.5,
+            intersection: 0.5,
+            subtraction: 0.0,
+        };
+
+        // Outer corner of the union box is rounded
+        let outer = Vec3::splat(-2.0);
+        assert!(
+            sample_shape_list(&shapes, outer, radii) > 0.0,
+            "union corner should be rounded away"
+        );
+        assert_in_delta(
+            sample_shape_list(&shapes, outer, 0.0),
+            0.0,
+            1e-5,
+            "union corner should be sharp without rounding".to_string(),
+        );
+
+        // The corner of the cut is carved by the subtracted box's corner, which stays sharp
+        let carved = Vec3::splat(1.0);
+        assert_in_delta(
+            sample_shape_list(&shapes, carved, radii),
+            0.0,
+            1e-5,
+            "subtraction corner should be sharp with no subtraction rounding".to_string(),
+        );
+        assert!(
+            sample_shape_list(&shapes, carved, 0.5) < 0.0,
+            "subtraction corner should be rounded with uniform rounding",
+        );
+    }
+
+    #[test]
+    fn test_edge_radius_per_shape() {
+        // 2x2x2 box, with a 0.5 edge radius of its own
+        let mut shape = Shape::rounded_box(
+            Mat4::from_translation(Vec3::X * 10.0),
+            Vec3::splat(2.0),
+            0.0,
+            ShapeOperation::Union,
+        );
+        shape.edge_radius = Some(0.5);
+        let offset = Vec3::X * 10.0;
+
+        // Faces are unaffected by rounding
+        assert_in_delta(
+            0.5,
+            shape.sample(offset + vec3(1.5, 0.0, 0.0), 0.0),
+            1e-5,
+            "distance from face".to_string(),
+        );
+        assert_in_delta(
+            -1.0,
+            shape.sample(offset, 0.0),
+            1e-5,
+            "distance from center".to_string(),
+        );
+
+        // Corners are rounded around a sphere centered 0.5 inside of each axis
+        let corner = Vec3::splat(1.0);
+        let rounded = (corner - Vec3::splat(0.5)).length() - 0.5;
+        assert_in_delta(
+            rounded,
+            shape.sample(offset + corner, 0.0),
+            1e-5,
+            "distance from rounded corner".to_string(),
+        );
+        assert_in_delta(
+            Vec3::splat(1.0).length() - 0.5,
+            shape.sample(offset + Vec3::splat(1.5), 0.0),
+            1e-5,
+            "distance outside rounded corner".to_string(),
+        );
+
+        // The shape's own radius takes priority over the shape list's radius
+        assert_in_delta(
+            rounded,
+            shape.sample(offset + corner, 0.9),
+            1e-5,
+            "per-shape radius should override list radius".to_string(),
+        );
+        let sharp = Shape {
+            edge_radius: Some(0.0),
+            ..shape
+        };
+        let list = vec![
+            shape,
+            sharp.transformed(Mat4::from_translation(Vec3::X * 10.0)),
+        ];
+        assert_in_delta(
+            0.0,
+            sample_shape_list(&list, offset * 2.0 + corner, 0.5),
+            1e-5,
+            "crisp shape should keep a sharp corner alongside rounded shapes".to_string(),
+        );
+        assert!(
+            sample_shape_list(&list, offset + corner, 0.0) > 0.0,
+            "rounded shape should keep a rounded corner alongside crisp shapes"
+        );
+    }
+
+    #[test]
+    fn test_shape_list_leading_non_union() {
+        let sphere = Shape::sphere(Mat4::from_translation(Vec3::X), 1.0, ShapeOperation::Union);
+        let points = [Vec3::ZERO, Vec3::X, Vec3::X * 1.5, Vec3::NEG_X * 3.0];
+
+        for operation in [
+            ShapeOperation::Subtraction,
+            ShapeOperation::Intersection,
+            ShapeOperation::SmoothSubtraction { k: 0.5 },
+            ShapeOperation::SmoothIntersection { k: 0.5 },
+        ] {
+            let leading = Shape::sphere(Mat4::IDENTITY, 2.0, operation);
+
+            // Nothing precedes the shape to carve from, so the list stays empty space
+            for point in points {
+                assert_eq!(
+                    1.0,
+                    sample_shape_list(&[leading], point, 0.0),
+                    "{operation:?} alone should sample as empty space at {point}"
+                );
+            }
+
+            // Unions after the leading shape are unaffected by it
+            for point in points {
+                assert_eq!(
+                    sample_shape_list(&[sphere], point, 0.0),
+                    sample_shape_list(&[leading, sphere], point, 0.0),
+                    "{operation:?} before any union should have no effect at {point}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_sample_packed_unknown_tag() {
+        let shapes = vec![
+            Shape::sphere(Mat4::IDENTITY, 1.0, ShapeOperation::Union),
+            Shape::sphere(Mat4::from_translation(Vec3::X), 1.0, ShapeOperation::Union),
+        ];
+        let mut packed = pack_shapes(&shapes);
+        packed[1].shape = u32::MAX;
+
+        for point in [Vec3::ZERO, Vec3::X, Vec3::X * 2.0, Vec3::NEG_Y * 0.5] {
+            assert_eq!(
+                sample_shape_list(&shapes[..1], point, 0.0),
+                sample_packed(&packed, point, 0.0),
+                "shape with an unknown tag should be skipped at {point}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_sample_packed_edge_radius() {
+        // Boxes and cylinders with and without their own edge radius, under each operation
+        let shapes = vec![
+            Shape {
+                edge_radius: Some(0.3),
+                ..Shape::rounded_box(
+                    Mat4::from_rotation_y(0.7),
+                    vec3(3.0, 1.0, 2.0),
+                    0.0,
+                    ShapeOperation::Union,
+                )
+            },
+            Shape {
+                edge_radius: Some(0.0),
+                ..Shape::rounded_cylinder(
+                    Mat4::from_translation(vec3(-1.0, 0.5, 0.0)),
+                    2.0,
+                    0.75,
+                    0.0,
+                    ShapeOperation::Union,
+                )
+            },
+            Shape::rounded_box(
+                Mat4::from_translation(vec3(1.0, 0.5, 0.0)),
+                Vec3::splat(1.5),
+                0.0,
+                ShapeOperation::Intersection,
+            ),
+            Shape {
+                edge_radius: Some(0.2),
+                ..Shape::rounded_box(
+                    Mat4::from_translation(vec3(0.5, 1.0, 0.5)),
+                    Vec3::ONE,
+                    0.0,
+                    ShapeOperation::Subtraction,
+                )
+            },
+        ];
+        let packed = pack_shapes(&shapes);
+
+        // Deterministic pseudo-random sample points
+        let mut state: u32 = 54321;
+        let mut random = || {
+            state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+            (state >> 8) as f32 / (1 << 24) as f32 * 6.0 - 3.0
+        };
+
+        for _ in 0..256 {
+            let point = vec3(random(), random(), random());
+            for radius_edge in [
+                EdgeRadius::uniform(0.0),
+                EdgeRadius::uniform(0.4),
+                EdgeRadius {
+                    union: 0.1,
+                    intersection: 0.25,
+                    subtraction: 0.05,
+                },
+            ] {
+                let expected = sample_shape_list(&shapes, point, radius_edge);
+                let actual = sample_packed(&packed, point, radius_edge);
+                assert_in_delta(
+                    expected,
+                    actual,
+                    1e-5,
+                    format!("packed sample should honor per-shape edge radius at {point}"),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_smooth_operations() {
+        // Hard operations are matched exactly without blending
+        for (a, b) in [(0.5, 0.25), (-1.0, 2.0), (0.0, 0.0), (3.0, -0.1)] {
+            assert_eq!(union(a, b), smooth_min(a, b, 0.0), "smin({a}, {b}) at k=0");
+            assert_eq!(
+                intersection(a, b),
+                smooth_max(a, b, 0.0),
+                "smax({a}, {b}) at k=0"
+            );
+            assert_in_delta(
+                union(a, b),
+                smooth_min(a, b, 1e-6),
+                1e-6,
+                format!("smin({a}, {b}) as k approaches 0"),
+            );
+            for op in [
+                ShapeOperation::Union,
+                ShapeOperation::Intersection,
+                ShapeOperation::Subtraction,
+            ] {
+                assert_eq!(
+                    op.apply(a, b),
+                    op.smoothed(0.0).apply(a, b),
+                    "{op:?} should be unchanged at k=0"
+                );
+                assert_eq!(
+                    op.apply(a, b),
+                    op.smoothed(0.5).hard().apply(a, b),
+                    "{op:?} should be recovered from its smooth counterpart"
+                );
+            }
+        }
+
+        // Two spheres, slightly apart
+        let spheres = |operation: ShapeOperation| {
+            vec![
+                Shape::sphere(
+                    Mat4::from_translation(Vec3::NEG_X * 1.1),
+                    1.0,
+                    ShapeOperation::Union,
+                ),
+                Shape::sphere(Mat4::from_translation(Vec3::X * 1.1), 1.0, operation),
+            ]
+        };
+        let hard = spheres(ShapeOperation::Union);
+        let smooth = spheres(ShapeOperation::SmoothUnion { k: 0.5 });
+        // The blended surface should bulge outward into the gap between spheres
+        let gap = Vec3::ZERO;
+        assert!(
+            sample_shape_list(&hard, gap, 0.0) > 0.0,
+            "gap should be empty with a hard union"
+        );
+        assert!(
+            sample_shape_list(&smooth, gap, 0.0) < 0.0,
+            "gap should be filled with a smooth union"
+        );
+        for offset in [0.2, 0.5, 0.8] {
+            let point = Vec3::Y * offset;
+            assert!(
+                sample_shape_list(&smooth, point, 0.0) < sample_shape_list(&hard, point, 0.0),
+                "smooth union should bulge outward at {point}"
+            );
+        }
+
+        // Far from the seam, the spheres are unaffected
+        let far = Vec3::X * 3.1;
+        assert_eq!(
+            sample_shape_list(&hard, far, 0.0),
+            sample_shape_list(&smooth, far, 0.0),
+            "smooth union should not affect surfaces away from the seam"
+        );
+        assert!(
+            shape_list_bounds(&smooth).maximum.y > shape_list_bounds(&hard).maximum.y,
+            "bounds should enclose bulging from blending"
+        );
+
+        // Smooth subtraction and intersection should stay between the hard result and the blend distance
+        for (hard_op, smooth_op) in [
+            (
+                ShapeOperation::Subtraction,
+                ShapeOperation::SmoothSubtraction { k: 0.5 },
+            ),
+            (
+                ShapeOperation::Intersection,
+                ShapeOperation::SmoothIntersection { k: 0.5 },
+            ),
+        ] {
+            for point in [Vec3::ZERO, Vec3::NEG_X * 0.2, vec3(-0.3, 0.4, 0.0)] {
+                let hard = sample_shape_list(&spheres(hard_op), point, 0.0);
+                let smooth = sample_shape_list(&spheres(smooth_op), point, 0.0);
+                assert!(
+                    smooth >= hard && smooth <= hard + 0.125,
+                    "{smooth_op:?} should shrink the surface by at most k/4 at {point}, got {smooth} vs {hard}"
+                );
+            }
+        }
+
+        // Packed shapes should blend identically
+        for operation in [
+            ShapeOperation::SmoothUnion { k: 0.5 },
+            ShapeOperation::SmoothIntersection { k: 0.3 },
+            ShapeOperation::SmoothSubtraction { k: 0.7 },
+        ] {
+            let list = spheres(operation);
+            let packed = pack_shapes(&list);
+            for point in [Vec3::ZERO, vec3(0.3, 0.5, 0.0), vec3(1.0, 0.0, 0.2)] {
+                assert_in_delta(
+                    sample_shape_list(&list, point, 0.0),
+                    sample_packed(&packed, point, 0.0),
+                    1e-5,
+                    format!("packed {operation:?} sample at {point}"),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotated_box() {
+        // 2x2x2 box, rotated 45 degrees around Y and moved away from the origin
+        let offset = vec3(5.0, 1.0, -2.0);
+        let shape = Shape::rounded_box(
+            Mat4::from_rotation_translation(Quat::from_rotation_y(FRAC_PI_4), offset),
+            Vec3::splat(2.0),
+            0.0,
+            ShapeOperation::Union,
+        );
+
+        let diagonal = 2.0_f32.sqrt();
+        for (point, expected, label) in [
+            (Vec3::ZERO, -1.0, "center"),
+            (Vec3::X * diagonal, 0.0, "rotated corner on X axis"),
+            (
+                Vec3::Z * 3.0,
+                3.0 - diagonal,
+                "beyond rotated corner on Z axis",
+            ),
+            (vec3(1.0, 0.0, 1.0).normalize(), 0.0, "rotated face"),
+            (
+                vec3(2.0, 0.0, 2.0).normalize() * 2.0,
+                1.0,
+                "beyond rotated face",
+            ),
+            (Vec3::Y * 1.5, 0.5, "above top face"),
+            (
+                vec3(1.0, 0.0, 0.0),
+                -(diagonal - 1.0) / diagonal,
+                "inside, along X axis",
+            ),
+        ] {
+            assert_in_delta(
+                expected,
+                shape.sample(offset + point, 0.0),
+                1e-5,
+                format!("rotated box distance at {label}"),
+            );
+        }
+
+        // Packed shapes should sample identically
+        let packed = pack_shapes(&[shape]);
+        for point in [Vec3::ZERO, Vec3::X * 2.0, vec3(1.0, 2.0, -1.0)] {
+            assert_in_delta(
+                sample_shape_list(&[shape], offset + point, 0.0),
+                sample_packed(&packed, offset + point, 0.0),
+                1e-5,
+                format!("packed rotated box distance at {point}"),
+            );
+        }
+    }
+
+    #[test]
+    fn test_non_uniform_scale() {
+        // Unit sphere squashed into an ellipsoid with radii of 3, 1 and 2
+        let shape = Shape::sphere(
+            Mat4::from_scale(vec3(3.0, 1.0, 2.0)),
+            1.0,
+            ShapeOperation::Union,
+        );
+
+        // Distances are conservative, never overshooting the true surface
+        for (point, exact) in [
+            (Vec3::X * 5.0, 2.0),
+            (Vec3::Y * 5.0, 4.0),
+            (Vec3::Z * 5.0, 3.0),
+        ] {
+            let d = shape.sample(point, 0.0);
+            assert!(d > 0.0, "{point} should be outside of ellipsoid, got {d}");
+            assert!(
+                d <= exact + 1e-5,
+                "distance at {point} should not exceed {exact}, got {d}"
+            );
+        }
+        assert_in_delta(
+            4.0,
+            shape.sample(Vec3::Y * 5.0, 0.0),
+            1e-5,
+            "distance along smallest axis should be exact".to_string(),
+        );
+        assert_in_delta(
+            0.0,
+            shape.sample(Vec3::X * 3.0, 0.0),
+            1e-5,
+            "surface should stay in place".to_string(),
+        );
+        assert_in_delta(
+            -1.0,
+            shape.sample(Vec3::ZERO, 0.0),
+            1e-5,
+            "depth at center should be limited by smallest axis".to_string(),
+        );
+    }
+
+    #[test]
+    fn test_capsule_and_cone() {
+        // Capsule lying along the X axis, from -1 to 1, offset upwards
+        let offset = Vec3::Y * 2.0;
+        let capsule = Shape::capsule(
+            Mat4::from_translation(offset),
+            Vec3::NEG_X,
+            Vec3::X,
+            0.5,
+            ShapeOperation::Union,
+        );
+        for (point, expected, label) in [
+            (Vec3::ZERO, -0.5, "center"),
+            (Vec3::X, -0.5, "endpoint"),
+            (Vec3::X * 1.5, 0.0, "tip of cap"),
+            (Vec3::X * 3.0, 1.5, "past cap along axis"),
+            (Vec3::Y * 0.5, 0.0, "side surface"),
+            (vec3(0.5, 0.0, 2.0), 1.5, "beside segment"),
+            (
+                vec3(2.0, 1.0, 0.0),
+                2.0_f32.sqrt() - 0.5,
+                "diagonal from endpoint",
+            ),
+        ] {
+            assert_in_delta(
+                expected,
+                capsule.sample(offset + point, 0.0),
+                1e-5,
+                format!("capsule distance at {label}"),
+            );
+        }
+        let bounds = capsule.transform() * capsule.relative_bounds();
+        assert_in_delta_vector(
+            vec3(-1.5, 1.5, -0.5),
+            bounds.minimum,
+            1e-5,
+            "capsule bounds minimum should enclose caps",
+        );
+        assert_in_delta_vector(
+            vec3(1.5, 2.5, 0.5),
+            bounds.maximum,
+            1e-5,
+            "capsule bounds maximum should enclose caps",
+        );
+
+        // Degenerate capsule is a sphere
+        let sphere = Shape::capsule(
+            Mat4::IDENTITY,
+            Vec3::ONE,
+            Vec3::ONE,
+            1.0,
+            ShapeOperation::Union,
+        );
+        assert_in_delta(
+            1.0,
+            sphere.sample(Vec3::ONE + Vec3::Z * 2.0, 0.0),
+            1e-5,
+            "degenerate capsule should act as sphere".to_string(),
+        );
+
+        // Cone with a height of 2, base radius of 1, apex at (0, 1, 0)
+        let cone = Shape::cone(Mat4::IDENTITY, 2.0, 1.0, ShapeOperation::Union);
+        let slant = 1.0 / 5.0_f32.sqrt();
+        for (point, expected, label) in [
+            (Vec3::Y, 0.0, "apex"),
+            (Vec3::Y * 3.0, 2.0, "above apex"),
+            (Vec3::NEG_Y, 0.0, "base center"),
+            (Vec3::NEG_Y * 2.0, 1.0, "below base"),
+            (Vec3::ZERO, -slant, "center"),
+            (Vec3::NEG_Y * 0.9, -0.1, "just above base"),
+            (vec3(0.5, 0.0, 0.0), 0.0, "side surface"),
+            (vec3(-1.0, -1.0, 0.0), 0.0, "base rim"),
+            (vec3(2.0, -1.0, 0.0), 1.0, "beside base rim"),
+        ] {
+            let actual = cone.sample(point, 0.0);
+            assert!(
+                actual.is_finite(),
+                "cone distance at {label} should be finite"
+            );
+            assert_in_delta(expected, actual, 1e-5, format!("cone distance at {label}"));
+        }
+
+        // Cones that collapse to a point or line should stay well-defined
+        for (height, radius) in [(0.0, 0.0), (0.0, 1.0), (1.0, 0.0)] {
+            let cone = Shape::cone(Mat4::IDENTITY, height, radius, ShapeOperation::Union);
+            for point in [Vec3::ZERO, Vec3::Y, Vec3::X] {
+                assert!(
+                    cone.sample(point, 0.0).is_finite(),
+                    "degenerate cone ({height}, {radius}) should be finite at {point}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_box_outline() {
+        let shape = Shape::rounded_box(
+            Mat4::from_translation(Vec3::ONE),
+            vec3(2.0, 4.0, 6.0),
+            0.1,
+            ShapeOperation::Union,
+        );
+        let lines = shape.outline(16);
+        assert_eq!(12, lines.len(), "box should have 12 edges");
+
+        for length in [2.0, 4.0, 6.0] {
+            let count = lines
+                .iter()
+                .filter(|(a, b)| (a.distance(*b) - length).abs() < 1e-5)
+                .count();
+            assert_eq!(4, count, "box should have 4 edges of length {length}");
+        }
+
+        let bounds = BoundingBox::new(Vec3::new(0.0, -1.0, -2.0), Vec3::new(2.0, 3.0, 4.0));
+        for (a, b) in lines.iter() {
+            assert_eq!(
+                bounds,
+                bounds.enclose(*a),
+                "edge start {a} should be on box"
+            );
+            assert_eq!(bounds, bounds.enclose(*b), "edge end {b} should be on box");
+        }
+
+        let sphere = Shape::sphere(Mat4::IDENTITY, 1.0, ShapeOperation::Union);
+        assert_eq!(
+            3 * 16,
+            sphere.outline(16).len(),
+            "sphere should have three great circles"
+        );
+    }
 }
@@ -30,6 +30,7 @@ pub mod gdmath {
     pub use godot::builtin::Vector3 as Vec3Godot;
     use godot::builtin::{Basis, Color, Transform3D};
     pub use godot::builtin::{PackedInt32Array, PackedVector3Array};
+    use godot::classes::Camera3D;
     use godot::prelude::*;
 
     // From Glam, to Godot
@@ -94,6 +95,11 @@ pub mod gdmath {
             PackedVector2Array::from_iter(self.iter().map(|val| -> Vec2Godot { val.to_vector2() }))
         }
     }
+    impl ToVector2<Vec<Vec2>> for PackedVector2Array {
+        fn to_vector2(&self) -> Vec<Vec2> {
+            self.as_slice().iter().map(|val| val.to_vector2()).collect()
+        }
+    }
 
     // COLORS //
     /// Implements conversion between Vector4s and Colors.
@@ -182,6 +188,19 @@ pub mod gdmath {
         }
     }
 
+    /// Returns the frustum planes of the given camera, in global space, for use with [BoundingBox::intersects_planes].
+    /// Godot frustum planes face outward, so they are flipped to face inward.
+    pub fn frustum_planes(camera: &Gd<Camera3D>) -> Vec<Vec4> {
+        camera
+            .get_frustum()
+            .iter_shared()
+            .map(|plane| {
+                let normal = plane.normal.to_vector3();
+                Vec4::new(-normal.x, -normal.y, -normal.z, plane.d)
+            })
+            .collect()
+    }
+
     #[cfg(test)]
     mod tests {
         use std::f32::consts::PI;
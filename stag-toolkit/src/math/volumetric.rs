@@ -1,6 +1,10 @@
-use crate::math::noise::Perlin1D;
+use crate::math::noise::NoiseSource;
+use crate::utils::SettingBytes;
+use core::fmt;
 use glam::{FloatExt, Mat4, Vec3, Vec4};
-use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
+use rayon::iter::{IntoParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
+use rayon::slice::ParallelSlice;
+use std::fmt::{Display, Formatter};
 use std::mem::swap;
 
 /// A container for storing and managing volumetric data.
@@ -109,6 +113,57 @@ impl<T: Clone + Copy + Default> VolumeData<T> {
         }
     }
 
+    /// Sets the bordering cell margin of a larger volume to the given value,
+    /// where this volume is a sub-range of it starting at the given cell offset.
+    pub fn set_padding_within(
+        &mut self,
+        offset: [usize; 3],
+        outer_dim: [usize; 3],
+        cell_padding: usize,
+        to: T,
+    ) {
+        for i in 0usize..self.size {
+            let [x, y, z] = self.delinearize(i);
+            let [x, y, z] = [x + offset[0], y + offset[1], z + offset[2]];
+
+            if (x < cell_padding || x + cell_padding >= outer_dim[0])
+                || (y < cell_padding || y + cell_padding >= outer_dim[1])
+                || (z < cell_padding || z + cell_padding >= outer_dim[2])
+            {
+                self.set_linear(i, to);
+            }
+        }
+    }
+
+    /// Returns a copy of the sub-range of this volume starting at the given cell offset,
+    /// with the given dimensions. Out-of-bounds cells are clamped to the nearest edge.
+    pub fn crop(&self, offset: [usize; 3], dim: [usize; 3]) -> Self {
+        let mut cropped = Self::new(T::default(), dim);
+        for i in 0..cropped.size {
+            let [x, y, z] = cropped.delinearize(i);
+            cropped.data[i] =
+                self.get_linear(self.linearize(x + offset[0], y + offset[1], z + offset[2]));
+        }
+        cropped
+    }
+
+    /// Returns a copy of the 2D slice of cells perpendicular to the given axis (0 = X, 1 = Y, 2 = Z),
+    /// with the slice index clamped within the volume.
+    /// Cells are ordered along the first remaining axis, then the second, i.e. a Z slice is laid out along X, then Y.
+    ///
+    /// Returns [None] if the axis is invalid or the volume is empty.
+    pub fn slice(&self, axis: usize, index: usize) -> Option<Vec<T>> {
+        if axis > 2 || self.size == 0 {
+            return None;
+        }
+
+        let mut offset = [0; 3];
+        offset[axis] = index.min(self.dim[axis] - 1);
+        let mut dim = self.dim;
+        dim[axis] = 1;
+        Some(self.crop(offset, dim).data)
+    }
+
     /// Returns the dimensions of this Volume.
     pub fn get_dimensions(&self) -> [usize; 3] {
         self.dim
@@ -118,6 +173,11 @@ impl<T: Clone + Copy + Default> VolumeData<T> {
         self.size
     }
 
+    /// Returns the number of bytes allocated for the voxel buffer, including any spare capacity.
+    pub fn memory_footprint(&self) -> usize {
+        self.data.capacity() * size_of::<T>()
+    }
+
     /// Returns the number of workers needed for processing.
     fn worker_count(&self, group_size: usize) -> usize {
         (self.size as f64 / group_size as f64).ceil() as usize
@@ -160,6 +220,38 @@ impl<T: Clone + Copy + Default> VolumeData<T> {
         workers
     }
 
+    /// Returns true if the given workers fully and contiguously cover the volume buffer, in order.
+    pub fn workers_cover(&self, workers: &[VolumeWorker<T>]) -> bool {
+        let mut next: usize = 0;
+        for worker in workers.iter() {
+            if worker.range_min != next
+                || worker.range_max < worker.range_min
+                || worker.range_width != worker.range_max - worker.range_min
+                || worker.data.len() != worker.range_width
+            {
+                return false;
+            }
+            next = worker.range_max;
+        }
+        next == self.size
+    }
+
+    /// Copies worker data back into the volume, placing each worker's data by its `range_min`
+    /// rather than relying on iteration order.
+    ///
+    /// Panics if the workers do not fully and contiguously cover the volume buffer.
+    pub fn gather_workers(&mut self, workers: &[VolumeWorker<T>]) {
+        assert!(
+            self.workers_cover(workers),
+            "workers do not fully and contiguously cover the volume buffer"
+        );
+
+        self.data.resize(self.size, T::default());
+        for worker in workers.iter() {
+            self.data[worker.range_min..worker.range_max].copy_from_slice(&worker.data);
+        }
+    }
+
     /// Splits the volume into separate cubic chunks for parallel operations.
     /// This is slower than `to_workers`, but useful if the data must be spatially related.
     ///
@@ -248,10 +340,40 @@ impl VolumeData<f32> {
     /// The buffer is expected to be the same dimensions as this volume.
     /// The [VolumeWorker] data will be overwritten each iteration.
     pub fn blur(
+        &mut self,
+        settings: BlurSettings<f32>,
+        buffer: Self,
+        workers: Vec<VolumeWorker<f32>>,
+    ) {
+        self.blur_internal(settings, buffer, workers, None);
+    }
+
+    /// Performs an in-place box-blur like [Self::blur],
+    /// scaling the blur weight in each cell by the corresponding weight.
+    /// Cells with a weight of zero are left unsmoothed.
+    pub fn blur_weighted(
+        &mut self,
+        settings: BlurSettings<f32>,
+        buffer: Self,
+        workers: Vec<VolumeWorker<f32>>,
+        weights: &[f32],
+    ) {
+        #[cfg(debug_assertions)]
+        assert_eq!(
+            self.size,
+            weights.len(),
+            "weight buffer size does not match volume size"
+        );
+
+        self.blur_internal(settings, buffer, workers, Some(weights));
+    }
+
+    fn blur_internal(
         &mut self,
         settings: BlurSettings<f32>,
         mut buffer: Self,
         mut workers: Vec<VolumeWorker<f32>>,
+        weights: Option<&[f32]>,
     ) {
         #[cfg(debug_assertions)]
         assert_eq!(
@@ -272,34 +394,30 @@ impl VolumeData<f32> {
                 worker.copy_from(self);
             }
 
-            buffer.data = workers
-                .par_iter_mut()
-                .flat_map(|worker| -> Vec<f32> {
-                    for i in 0..worker.range_width {
-                        let idx = i + worker.range_min;
-                        let [x, y, z] = self.delinearize(idx);
+            workers.par_iter_mut().for_each(|worker| {
+                for i in 0..worker.range_width {
+                    let idx = i + worker.range_min;
+                    let [x, y, z] = self.delinearize(idx);
 
-                        let mut avg: f32 = 0.0;
-                        for tx in
-                            x.saturating_sub(settings.radius)..=(x + settings.radius).min(max_x)
+                    let mut avg: f32 = 0.0;
+                    for tx in x.saturating_sub(settings.radius)..=(x + settings.radius).min(max_x) {
+                        for ty in
+                            y.saturating_sub(settings.radius)..=(y + settings.radius).min(max_y)
                         {
-                            for ty in
-                                y.saturating_sub(settings.radius)..=(y + settings.radius).min(max_y)
+                            for tz in
+                                z.saturating_sub(settings.radius)..=(z + settings.radius).min(max_z)
                             {
-                                for tz in z.saturating_sub(settings.radius)
-                                    ..=(z + settings.radius).min(max_z)
-                                {
-                                    avg += self.data[self.linearize_fast(tx, ty, tz)];
-                                }
+                                avg += self.data[self.linearize_fast(tx, ty, tz)];
                             }
                         }
-
-                        worker.data[i] = self.data[idx].lerp(avg * inv_cvg_cubed, settings.weight);
                     }
 
-                    worker.data.clone()
-                })
-                .collect();
+                    let weight =
+                        weights.map_or(settings.weight, |weights| settings.weight * weights[idx]);
+                    worker.data[i] = self.data[idx].lerp(avg * inv_cvg_cubed, weight);
+                }
+            });
+            buffer.gather_workers(&workers);
 
             // Avoid bleeding over edges
             buffer.set_padding(settings.cell_padding, settings.padding_value);
@@ -309,21 +427,532 @@ impl VolumeData<f32> {
         }
     }
 
+    /// Performs an in-place separable gaussian blur with the given standard deviation, in cells,
+    /// applying additional padding each iteration.
+    /// Each iteration performs three passes, one per axis, sampling cells within the settings radius.
+    /// Cells beyond the volume edges are clamped to the nearest edge cell.
+    ///
+    /// This gives a smoother falloff than [Self::blur], at a similar cost.
+    /// The buffer is expected to be the same dimensions as this volume.
+    /// The [VolumeWorker] data will be overwritten each pass.
+    pub fn blur_gaussian(
+        &mut self,
+        settings: BlurSettings<f32>,
+        sigma: f32,
+        buffer: Self,
+        workers: Vec<VolumeWorker<f32>>,
+    ) {
+        self.blur_gaussian_internal(settings, sigma, buffer, workers, None);
+    }
+
+    /// Performs an in-place gaussian blur like [Self::blur_gaussian],
+    /// scaling the blur weight in each cell by the corresponding weight.
+    /// Cells with a weight of zero are left unsmoothed.
+    pub fn blur_gaussian_weighted(
+        &mut self,
+        settings: BlurSettings<f32>,
+        sigma: f32,
+        buffer: Self,
+        workers: Vec<VolumeWorker<f32>>,
+        weights: &[f32],
+    ) {
+        #[cfg(debug_assertions)]
+        assert_eq!(
+            self.size,
+            weights.len(),
+            "weight buffer size does not match volume size"
+        );
+
+        self.blur_gaussian_internal(settings, sigma, buffer, workers, Some(weights));
+    }
+
+    fn blur_gaussian_internal(
+        &mut self,
+        settings: BlurSettings<f32>,
+        sigma: f32,
+        mut buffer: Self,
+        mut workers: Vec<VolumeWorker<f32>>,
+        weights: Option<&[f32]>,
+    ) {
+        #[cfg(debug_assertions)]
+        assert_eq!(
+            buffer.dim, self.dim,
+            "buffer dimensions should match original volume"
+        );
+
+        // Build a normalized 1D kernel, falling back to an identity kernel for non-positive deviations
+        let radius = settings.radius as isize;
+        let mut kernel: Vec<f32> = (-radius..=radius)
+            .map(|k| {
+                if sigma > 0.0 {
+                    (-((k * k) as f32) / (2.0 * sigma * sigma)).exp()
+                } else if k == 0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        let total: f32 = kernel.iter().sum();
+        kernel.iter_mut().for_each(|w| *w /= total);
+
+        // Only keep a copy of the unblurred data if it needs to be blended back in
+        let blend = settings.weight < 1.0 || weights.is_some();
+
+        for _ in 0..settings.iterations {
+            let original = blend.then(|| self.data.clone());
+
+            for axis in 0..3 {
+                let max = self.dim[axis] as isize - 1;
+
+                workers.par_iter_mut().for_each(|worker| {
+                    for i in 0..worker.range_width {
+                        let mut coord = self.delinearize(i + worker.range_min);
+                        let center = coord[axis] as isize;
+
+                        let mut sum: f32 = 0.0;
+                        for (k, w) in (-radius..=radius).zip(kernel.iter()) {
+                            coord[axis] = (center + k).clamp(0, max) as usize;
+                            sum += self.data[self.linearize_fast(coord[0], coord[1], coord[2])] * w;
+                        }
+                        worker.data[i] = sum;
+                    }
+                });
+                buffer.gather_workers(&workers);
+
+                // Swap buffers so we can continue operating in-place
+                swap(&mut buffer.data, &mut self.data);
+            }
+
+            // Blend the blurred result with the unblurred data
+            if let Some(original) = original {
+                for (idx, value) in self.data.iter_mut().enumerate() {
+                    let weight =
+                        weights.map_or(settings.weight, |weights| settings.weight * weights[idx]);
+                    *value = original[idx].lerp(*value, weight);
+                }
+            }
+
+            // Avoid bleeding over edges
+            self.set_padding(settings.cell_padding, settings.padding_value);
+        }
+    }
+
+    /// Performs an in-place morphological dilation, growing negative (solid) regions outward.
+    /// Each cell takes the minimum value within a box of the given radius, in cells.
+    pub fn dilate(&mut self, radius: usize) {
+        if radius == 0 {
+            return;
+        }
+
+        // A box minimum is separable, so filter each axis in turn
+        for axis in 0..3 {
+            let source = self.data.clone();
+            let max = self.dim[axis] - 1;
+
+            for i in 0..self.size {
+                let mut coord = self.delinearize(i);
+                let center = coord[axis];
+
+                let mut min = f32::INFINITY;
+                for c in center.saturating_sub(radius)..=(center + radius).min(max) {
+                    coord[axis] = c;
+                    min = min.min(source[self.linearize_fast(coord[0], coord[1], coord[2])]);
+                }
+                self.data[i] = min;
+            }
+        }
+    }
+
+    /// Returns the minimum and maximum values within the volume, computed in parallel over chunks.
+    /// Returns `(f32::INFINITY, f32::NEG_INFINITY)` if the volume is empty.
+    pub fn min_max(&self) -> (f32, f32) {
+        self.data
+            .par_chunks(VOLUME_REDUCTION_CHUNK)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), v| {
+                        (min.min(*v), max.max(*v))
+                    })
+            })
+            .reduce(
+                || (f32::INFINITY, f32::NEG_INFINITY),
+                |(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)),
+            )
+    }
+
+    /// Returns the minimum and maximum values within the inclusive cell range.
+    /// The range is clamped to the volume dimensions.
+    fn min_max_within(&self, first: [usize; 3], last: [usize; 3]) -> (f32, f32) {
+        let last = [0, 1, 2].map(|axis| last[axis].min(self.dim[axis] - 1));
+        let mut range = (f32::INFINITY, f32::NEG_INFINITY);
+        for z in first[2]..=last[2] {
+            for y in first[1]..=last[1] {
+                for x in first[0]..=last[0] {
+                    let v = self.data[self.linearize_fast(x, y, z)];
+                    range = (range.0.min(v), range.1.max(v));
+                }
+            }
+        }
+        range
+    }
+
+    /// Returns the coordinates of all bricks of `brick_size` cells per axis that contain a zero crossing,
+    /// ordered along X first, then Y, then Z.
+    /// Each brick also considers the first cell of its next neighbors, so crossings along brick borders are not missed.
+    /// Values of exactly zero are considered crossings.
+    ///
+    /// Useful for skipping empty regions of large volumes entirely.
+    pub fn occupied_bricks(&self, brick_size: usize) -> impl Iterator<Item = [usize; 3]> + '_ {
+        let brick_size = brick_size.max(1);
+        let bricks_dim = if self.size == 0 {
+            [0; 3]
+        } else {
+            self.dim.map(|d| d.div_ceil(brick_size))
+        };
+
+        (0..bricks_dim[2])
+            .flat_map(move |z| (0..bricks_dim[1]).map(move |y| (y, z)))
+            .flat_map(move |(y, z)| (0..bricks_dim[0]).map(move |x| [x, y, z]))
+            .filter(move |brick| {
+                let first = brick.map(|b| b * brick_size);
+                let last = first.map(|f| f + brick_size);
+                let (min, max) = self.min_max_within(first, last);
+                min <= 0.0 && max >= 0.0
+            })
+    }
+
+    /// Returns the number of voxels that straddle the zero isovalue,
+    /// where a voxel straddles zero if it differs in sign from its next neighbor along any axis.
+    /// Negative values are considered inside. Useful as a proxy for surface complexity.
+    pub fn isovalue_crossing_count(&self) -> usize {
+        (0..self.size)
+            .into_par_iter()
+            .filter(|i| {
+                let [x, y, z] = self.delinearize(*i);
+                let inside = self.data[*i] < 0.0;
+                (x + 1 < self.dim[0] && (self.data[i + self.strides[0]] < 0.0) != inside)
+                    || (y + 1 < self.dim[1] && (self.data[i + self.strides[1]] < 0.0) != inside)
+                    || (z + 1 < self.dim[2] && (self.data[i + self.strides[2]] < 0.0) != inside)
+            })
+            .count()
+    }
+
     /// In-place adds noise to the volumetric.
-    pub fn noise_add(&mut self, noise: &Perlin1D, transform: Mat4, w: f32) {
+    pub fn noise_add(&mut self, noise: &(impl NoiseSource + ?Sized), transform: Mat4, w: f32) {
         for i in 0usize..self.size {
             let [x, y, z] = self.delinearize(i);
 
             let sample_pos = transform.transform_point3(Vec3::new(x as f32, y as f32, z as f32));
 
-            self.data[i] += noise.sample(Vec4::from((sample_pos, w))) as f32;
+            self.data[i] += noise.sample(Vec4::from((sample_pos, w)));
+        }
+    }
+
+    /// In-place adds noise to the volumetric, scaling the noise in each cell by the corresponding weight.
+    /// Cells with a weight of zero are skipped.
+    pub fn noise_add_weighted(
+        &mut self,
+        noise: &(impl NoiseSource + ?Sized),
+        transform: Mat4,
+        w: f32,
+        weights: &[f32],
+    ) {
+        #[cfg(debug_assertions)]
+        assert_eq!(
+            self.size,
+            weights.len(),
+            "weight buffer size does not match volume size"
+        );
+
+        for (i, weight) in weights.iter().enumerate().take(self.size) {
+            if *weight == 0.0 {
+                continue;
+            }
+
+            let [x, y, z] = self.delinearize(i);
+
+            let sample_pos = transform.transform_point3(Vec3::new(x as f32, y as f32, z as f32));
+
+            self.data[i] += noise.sample(Vec4::from((sample_pos, w))) * weight;
+        }
+    }
+
+    /// Serializes the volume into a flat binary blob, for dumping intermediate grids to disk.
+    /// Starts with a header of the magic bytes, data type and dimensions, followed by every cell.
+    /// All values are written little-endian.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out: Vec<u8> =
+            Vec::with_capacity(VOLUME_BYTES_HEADER + self.size * size_of::<f32>());
+        out.extend_from_slice(&VOLUME_BYTES_MAGIC);
+        out.push(VOLUME_BYTES_DTYPE_F32);
+        for d in self.dim {
+            (d as u32).write_bytes(&mut out);
+        }
+        for value in self.data.iter() {
+            value.write_bytes(&mut out);
+        }
+        out
+    }
+
+    /// Deserializes a volume written by [Self::to_bytes].
+    ///
+    /// Fails if the header is malformed, the dimensions do not match the given dimensions,
+    /// or the buffer length does not match the dimensions.
+    pub fn from_bytes(bytes: &[u8], dimensions: [usize; 3]) -> Result<Self, VolumeBytesError> {
+        let (magic, rest) = bytes
+            .split_first_chunk::<4>()
+            .ok_or(VolumeBytesError::Truncated)?;
+        if *magic != VOLUME_BYTES_MAGIC {
+            return Err(VolumeBytesError::BadMagic(*magic));
+        }
+        let (dtype, mut rest) = rest.split_first().ok_or(VolumeBytesError::Truncated)?;
+        if *dtype != VOLUME_BYTES_DTYPE_F32 {
+            return Err(VolumeBytesError::UnsupportedDataType(*dtype));
+        }
+
+        let mut read_dim = || u32::read_bytes(&mut rest).ok_or(VolumeBytesError::Truncated);
+        let dim = [
+            read_dim()? as usize,
+            read_dim()? as usize,
+            read_dim()? as usize,
+        ];
+        if dim != dimensions {
+            return Err(VolumeBytesError::DimensionMismatch {
+                expected: dimensions,
+                found: dim,
+            });
+        }
+
+        let expected = dim.iter().product::<usize>() * size_of::<f32>();
+        match rest.len() {
+            len if len < expected => return Err(VolumeBytesError::Truncated),
+            len if len > expected => return Err(VolumeBytesError::TrailingBytes(len - expected)),
+            _ => {}
+        }
+
+        let data = rest
+            .chunks_exact(size_of::<f32>())
+            .map(|mut chunk| f32::read_bytes(&mut chunk))
+            .collect::<Option<Vec<f32>>>()
+            .ok_or(VolumeBytesError::Truncated)?;
+        Ok(Self::with_data(data, dim))
+    }
+
+    /// Converts the volume into a sparse representation,
+    /// only storing bricks of cells that contain a value differing from the background value.
+    pub fn to_sparse(&self, background: f32) -> SparseVolume {
+        let bricks_dim = self.dim.map(|d| d.div_ceil(SPARSE_BRICK_SIZE));
+        let mut bricks: Vec<(usize, Vec<f32>)> = vec![];
+
+        for brick in 0..(bricks_dim[0] * bricks_dim[1] * bricks_dim[2]) {
+            let offset = SparseVolume::brick_offset(brick, bricks_dim);
+            let values: Vec<f32> = (0..SPARSE_BRICK_CELLS)
+                .map(|cell| {
+                    let [x, y, z] = SparseVolume::cell_offset(cell, offset);
+                    if x < self.dim[0] && y < self.dim[1] && z < self.dim[2] {
+                        self.data[self.linearize_fast(x, y, z)]
+                    } else {
+                        background
+                    }
+                })
+                .collect();
+
+            // Compare bits, so the volume is reconstructed exactly
+            if values.iter().any(|v| v.to_bits() != background.to_bits()) {
+                bricks.push((brick, values));
+            }
+        }
+
+        SparseVolume {
+            dim: self.dim,
+            background,
+            bricks,
+        }
+    }
+
+    /// Reconstructs a dense volume from a sparse representation.
+    pub fn from_sparse(sparse: &SparseVolume) -> Self {
+        let mut volume = Self::new(sparse.background, sparse.dim);
+        let bricks_dim = sparse.dim.map(|d| d.div_ceil(SPARSE_BRICK_SIZE));
+
+        for (brick, values) in sparse.bricks.iter() {
+            let offset = SparseVolume::brick_offset(*brick, bricks_dim);
+            for (cell, value) in values.iter().enumerate() {
+                let [x, y, z] = SparseVolume::cell_offset(cell, offset);
+                if x < volume.dim[0] && y < volume.dim[1] && z < volume.dim[2] {
+                    let i = volume.linearize_fast(x, y, z);
+                    volume.data[i] = *value;
+                }
+            }
+        }
+
+        volume
+    }
+}
+
+/// Reasons a serialized [VolumeData] can fail to deserialize.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VolumeBytesError {
+    /// The buffer ended before the header or cell data was complete.
+    Truncated,
+    /// The buffer did not start with the expected magic bytes.
+    BadMagic([u8; 4]),
+    /// The data type tag is not one this version can read.
+    UnsupportedDataType(u8),
+    /// The dimensions in the header differ from the requested dimensions.
+    DimensionMismatch {
+        /// Dimensions the caller asked for.
+        expected: [usize; 3],
+        /// Dimensions written in the header.
+        found: [usize; 3],
+    },
+    /// The buffer holds this many bytes beyond the cell data.
+    TrailingBytes(usize),
+}
+
+impl Display for VolumeBytesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "volume data is truncated"),
+            Self::BadMagic(magic) => write!(f, "volume data has bad magic bytes {magic:?}"),
+            Self::UnsupportedDataType(dtype) => {
+                write!(f, "volume data has unsupported data type {dtype}")
+            }
+            Self::DimensionMismatch { expected, found } => write!(
+                f,
+                "volume data has dimensions {found:?}, expected {expected:?}"
+            ),
+            Self::TrailingBytes(count) => {
+                write!(f, "volume data has {count} trailing bytes")
+            }
+        }
+    }
+}
+
+impl std::error::Error for VolumeBytesError {}
+
+/// Magic bytes at the start of a serialized [VolumeData].
+const VOLUME_BYTES_MAGIC: [u8; 4] = *b"STVX";
+/// Data type tag for serialized [VolumeData] of 32-bit floats.
+const VOLUME_BYTES_DTYPE_F32: u8 = 1;
+/// Number of header bytes in a serialized [VolumeData].
+const VOLUME_BYTES_HEADER: usize = VOLUME_BYTES_MAGIC.len() + 1 + 3 * size_of::<u32>();
+
+/// Number of cells each parallel task handles when reducing over a [VolumeData].
+const VOLUME_REDUCTION_CHUNK: usize = 4096;
+
+/// Width of a brick of cells in a [SparseVolume], along each axis.
+const SPARSE_BRICK_SIZE: usize = 4;
+/// Number of cells in a brick of a [SparseVolume].
+const SPARSE_BRICK_CELLS: usize = SPARSE_BRICK_SIZE * SPARSE_BRICK_SIZE * SPARSE_BRICK_SIZE;
+
+/// A sparse representation of a [VolumeData], for compact caching of mostly-empty volumes.
+/// The volume is split into small bricks of cells, and only bricks that differ from a background value are stored.
+#[derive(Clone, PartialEq)]
+pub struct SparseVolume {
+    dim: [usize; 3],
+    background: f32,
+    /// Stored bricks, as a linear brick index and the values of all cells within the brick.
+    bricks: Vec<(usize, Vec<f32>)>,
+}
+
+impl SparseVolume {
+    /// Returns the dimensions of the represented volume.
+    pub fn get_dimensions(&self) -> [usize; 3] {
+        self.dim
+    }
+
+    /// Returns the value of all cells not stored in a brick.
+    pub fn get_background(&self) -> f32 {
+        self.background
+    }
+
+    /// Returns the number of stored bricks.
+    pub fn brick_count(&self) -> usize {
+        self.bricks.len()
+    }
+
+    /// Returns the cell offset of the given linear brick index.
+    fn brick_offset(brick: usize, bricks_dim: [usize; 3]) -> [usize; 3] {
+        [
+            (brick % bricks_dim[0]) * SPARSE_BRICK_SIZE,
+            ((brick / bricks_dim[0]) % bricks_dim[1]) * SPARSE_BRICK_SIZE,
+            (brick / (bricks_dim[0] * bricks_dim[1])) * SPARSE_BRICK_SIZE,
+        ]
+    }
+
+    /// Returns the cell coordinates of the given linear cell index within a brick at the given offset.
+    fn cell_offset(cell: usize, offset: [usize; 3]) -> [usize; 3] {
+        [
+            offset[0] + cell % SPARSE_BRICK_SIZE,
+            offset[1] + (cell / SPARSE_BRICK_SIZE) % SPARSE_BRICK_SIZE,
+            offset[2] + cell / (SPARSE_BRICK_SIZE * SPARSE_BRICK_SIZE),
+        ]
+    }
+}
+
+impl SettingBytes for SparseVolume {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        for d in self.dim {
+            (d as u32).write_bytes(out);
+        }
+        self.background.write_bytes(out);
+        (self.bricks.len() as u32).write_bytes(out);
+        for (brick, values) in self.bricks.iter() {
+            (*brick as u32).write_bytes(out);
+            for value in values.iter() {
+                value.write_bytes(out);
+            }
         }
     }
+
+    fn read_bytes(bytes: &mut &[u8]) -> Option<Self> {
+        let dim = [
+            u32::read_bytes(bytes)? as usize,
+            u32::read_bytes(bytes)? as usize,
+            u32::read_bytes(bytes)? as usize,
+        ];
+        let background = f32::read_bytes(bytes)?;
+        let count = u32::read_bytes(bytes)? as usize;
+
+        // Bricks must lie within the volume, so a volume with an empty axis can't have any
+        let brick_total = dim
+            .map(|d| d.div_ceil(SPARSE_BRICK_SIZE))
+            .into_iter()
+            .try_fold(1usize, |total, d| total.checked_mul(d))?;
+
+        // Don't trust the count for allocations beyond what the remaining bytes could hold
+        let mut bricks = Vec::with_capacity(count.min(bytes.len() / (4 + 4 * SPARSE_BRICK_CELLS)));
+        for _ in 0..count {
+            let brick = u32::read_bytes(bytes)? as usize;
+            if brick >= brick_total {
+                return None;
+            }
+            let values = (0..SPARSE_BRICK_CELLS)
+                .map(|_| f32::read_bytes(bytes))
+                .collect::<Option<Vec<f32>>>()?;
+            bricks.push((brick, values));
+        }
+
+        Some(Self {
+            dim,
+            background,
+            bricks,
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::VolumeData;
+    use super::{BlurSettings, SparseVolume, VolumeBytesError, VolumeData};
+    use crate::math::delta::assert_in_delta;
+    use crate::utils::SettingBytes;
+    use glam::Vec3;
+    use noise::{NoiseFn, Perlin};
+    use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     #[test]
     fn test_volume_data_indexing() {
@@ -348,6 +977,235 @@ mod tests {
         assert_eq!(vol.linearize(4, 4, 4), idx_max, "Linearize at 4,4,4");
     }
 
+    #[test]
+    fn test_volume_min_max_crossings() {
+        // Laid out along X first, then Y:
+        //   y=0: -1.0  2.0  3.0
+        //   y=1: -4.0 -0.5  5.0
+        let volume = VolumeData::<f32>::with_data(vec![-1.0, 2.0, 3.0, -4.0, -0.5, 5.0], [3, 2, 1]);
+
+        assert_eq!((-4.0, 5.0), volume.min_max(), "min and max should match");
+        // (0,0) crosses to (1,0), (1,0) crosses to (1,1), and (1,1) crosses to (2,1)
+        assert_eq!(
+            3,
+            volume.isovalue_crossing_count(),
+            "crossing count should match"
+        );
+
+        // Span multiple reduction chunks
+        let mut volume = VolumeData::new(1.0f32, [20, 20, 20]);
+        volume.set_linear(4210, -3.0); // Cell 10,10,10
+        volume.set_linear(123, 9.0);
+        assert_eq!(
+            (-3.0, 9.0),
+            volume.min_max(),
+            "min and max should match across chunks"
+        );
+        // The solid voxel crosses to each of its next neighbors, and each previous neighbor crosses to it
+        assert_eq!(
+            4,
+            volume.isovalue_crossing_count(),
+            "a single interior solid voxel should have four straddling voxels"
+        );
+
+        let empty = VolumeData::<f32>::with_data(vec![], [0, 0, 0]);
+        assert_eq!(
+            (f32::INFINITY, f32::NEG_INFINITY),
+            empty.min_max(),
+            "empty volume should have an inverted range"
+        );
+        assert_eq!(
+            0,
+            empty.isovalue_crossing_count(),
+            "empty volume should have no crossings"
+        );
+    }
+
+    #[test]
+    fn test_volume_occupied_bricks() {
+        let dim = [24, 24, 24];
+        let center = Vec3::splat(11.5);
+        let radius = 8.0;
+        let mut volume = VolumeData::new(0.0f32, dim);
+        for i in 0..volume.get_buffer_size() {
+            let [x, y, z] = volume.delinearize(i);
+            let pos = Vec3::new(x as f32, y as f32, z as f32);
+            volume.set_linear(i, pos.distance(center) - radius);
+        }
+
+        let brick_size = 4;
+        let bricks: Vec<[usize; 3]> = volume.occupied_bricks(brick_size).collect();
+        assert!(!bricks.is_empty(), "sphere surface should occupy bricks");
+        assert!(
+            bricks.len() < 6 * 6 * 6,
+            "not all bricks should be occupied, got {}",
+            bricks.len()
+        );
+
+        for bx in 0..6 {
+            for by in 0..6 {
+                for bz in 0..6 {
+                    let brick = [bx, by, bz];
+                    let first = brick.map(|b| b * brick_size);
+                    let last = first.map(|f| (f + brick_size).min(dim[0] - 1));
+                    let (min, max) = volume.min_max_within(first, last);
+                    let straddles = min <= 0.0 && max >= 0.0;
+                    assert_eq!(
+                        straddles,
+                        bricks.contains(&brick),
+                        "brick {brick:?} should only be yielded if it straddles the surface"
+                    );
+                }
+            }
+        }
+
+        // Center brick is fully inside, corner brick fully outside
+        assert!(
+            !bricks.contains(&[2, 2, 2]),
+            "interior brick should not be yielded"
+        );
+        assert!(
+            !bricks.contains(&[0, 0, 0]),
+            "exterior brick should not be yielded"
+        );
+        assert!(
+            bricks.contains(&[1, 2, 2]),
+            "brick on the sphere surface should be yielded"
+        );
+
+        let empty = VolumeData::<f32>::with_data(vec![], [0, 0, 0]);
+        assert_eq!(
+            0,
+            empty.occupied_bricks(brick_size).count(),
+            "empty volume should have no bricks"
+        );
+    }
+
+    #[test]
+    fn test_volume_blur_weighted() {
+        let dim = [6, 6, 6];
+        let mut original = VolumeData::new(1.0f32, dim);
+        for i in 0..original.get_buffer_size() {
+            let [x, _, _] = original.delinearize(i);
+            original.set_linear(i, if x < 3 { -1.0 } else { 1.0 });
+        }
+        let settings = || BlurSettings {
+            iterations: 2,
+            radius: 1,
+            weight: 1.0,
+            cell_padding: 0,
+            padding_value: 1.0,
+        };
+        let size = original.get_buffer_size();
+
+        let mut blurred = original.clone();
+        let workers = blurred.to_workers(size, false);
+        blurred.blur(settings(), VolumeData::new(1.0, dim), workers);
+
+        let mut full = original.clone();
+        let workers = full.to_workers(size, false);
+        full.blur_weighted(
+            settings(),
+            VolumeData::new(1.0, dim),
+            workers,
+            &vec![1.0; size],
+        );
+        assert_eq!(
+            blurred.data, full.data,
+            "full weights should match an unweighted blur"
+        );
+
+        let mut none = original.clone();
+        let workers = none.to_workers(size, false);
+        none.blur_weighted(
+            settings(),
+            VolumeData::new(1.0, dim),
+            workers,
+            &vec![0.0; size],
+        );
+        assert_eq!(
+            original.data, none.data,
+            "zero weights should leave the volume unsmoothed"
+        );
+    }
+
+    #[test]
+    fn test_volume_blur_gaussian() {
+        let dim = [9, 9, 9];
+        let center = [4, 4, 4];
+        let mut impulse = VolumeData::new(0.0f32, dim);
+        let center_idx = impulse.linearize(center[0], center[1], center[2]);
+        impulse.set_linear(center_idx, 1.0);
+        let settings = || BlurSettings {
+            iterations: 1,
+            radius: 2,
+            weight: 1.0,
+            cell_padding: 0,
+            padding_value: 0.0,
+        };
+        let size = impulse.get_buffer_size();
+
+        let mut blurred = impulse.clone();
+        let workers = blurred.to_workers(7, false);
+        blurred.blur_gaussian(settings(), 1.0, VolumeData::new(0.0, dim), workers);
+
+        let mass: f32 = blurred.data.iter().sum();
+        assert_in_delta(1.0, mass, 1e-5, "blur should conserve mass".to_string());
+        assert!(
+            blurred.get_linear(center_idx) < 1.0,
+            "impulse should spread out"
+        );
+
+        // Every cell should match its mirror along each axis, and its transpose
+        for i in 0..size {
+            let [x, y, z] = blurred.delinearize(i);
+            let value = blurred.get_linear(i);
+            let mirrors = [
+                [dim[0] - 1 - x, y, z],
+                [x, dim[1] - 1 - y, z],
+                [x, y, dim[2] - 1 - z],
+                [y, z, x],
+            ];
+            for [mx, my, mz] in mirrors {
+                assert_in_delta(
+                    value,
+                    blurred.get_linear(blurred.linearize(mx, my, mz)),
+                    1e-6,
+                    format!("impulse should spread symmetrically at {x},{y},{z}"),
+                );
+            }
+        }
+
+        // Falloff should decrease away from the center
+        let along_x: Vec<f32> = (4..7)
+            .map(|x| blurred.get_linear(blurred.linearize(x, 4, 4)))
+            .collect();
+        assert!(
+            along_x[0] > along_x[1] && along_x[1] > along_x[2],
+            "falloff should decrease with distance, got {along_x:?}"
+        );
+        assert_eq!(
+            0.0,
+            blurred.get_linear(blurred.linearize(7, 4, 4)),
+            "blur should not spread beyond its radius"
+        );
+
+        // Zero weights should leave the volume unsmoothed
+        let mut none = impulse.clone();
+        let workers = none.to_workers(7, false);
+        none.blur_gaussian_weighted(
+            settings(),
+            1.0,
+            VolumeData::new(0.0, dim),
+            workers,
+            &vec![0.0; size],
+        );
+        assert_eq!(
+            impulse.data, none.data,
+            "zero weights should leave the volume unsmoothed"
+        );
+    }
+
     #[test]
     fn test_volume_workers() {
         let volume = VolumeData::<f32>::with_data((1u8..=27).map(f32::from).collect(), [3, 3, 3]);
@@ -360,13 +1218,9 @@ mod tests {
 
         let workers = volume.to_workers(6, true);
 
-        for i in 0..3 {
+        for worker in workers.iter().take(3) {
             // First 4 workers have 2 points of data
-            assert_eq!(
-                6,
-                workers[i].data.len(),
-                "worker should be of expected size"
-            );
+            assert_eq!(6, worker.data.len(), "worker should be of expected size");
         }
 
         // Last worker has 1
@@ -377,6 +1231,297 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_volume_gather_workers() {
+        // 105 cells, which is not divisible by the group size
+        let dim = [5, 3, 7];
+        let group_size = 8;
+        let mut volume = VolumeData::new(-1.0f32, dim);
+        let mut workers = volume.to_workers(group_size, false);
+        assert!(
+            volume.workers_cover(&workers),
+            "workers should fully cover the buffer"
+        );
+
+        // Sample every voxel in parallel, tracking how many times each was sampled
+        let samples: Vec<AtomicUsize> = (0..volume.get_buffer_size())
+            .map(|_| AtomicUsize::new(0))
+            .collect();
+        workers.par_iter_mut().for_each(|worker| {
+            for i in 0..worker.range_width {
+                let idx = i + worker.range_min;
+                samples[idx].fetch_add(1, Ordering::Relaxed);
+                worker.data[i] = idx as f32;
+            }
+        });
+
+        // Reassemble in reverse to ensure placement does not depend on order
+        workers.reverse();
+        assert!(
+            !volume.workers_cover(&workers),
+            "out-of-order workers should not be considered contiguous"
+        );
+        workers.reverse();
+        volume.gather_workers(&workers);
+
+        for (idx, count) in samples.iter().enumerate() {
+            assert_eq!(
+                1,
+                count.load(Ordering::Relaxed),
+                "voxel {idx} should be sampled exactly once"
+            );
+            assert_eq!(
+                idx as f32,
+                volume.get_linear(idx),
+                "voxel {idx} should be placed correctly"
+            );
+        }
+
+        // Gaps and missing workers should not be considered coverage
+        let mut gapped = volume.to_workers(group_size, false);
+        gapped.remove(3);
+        assert!(
+            !volume.workers_cover(&gapped),
+            "workers with a gap should not cover the buffer"
+        );
+        let mut truncated = volume.to_workers(group_size, false);
+        truncated.pop();
+        assert!(
+            !volume.workers_cover(&truncated),
+            "workers missing the remainder should not cover the buffer"
+        );
+    }
+
+    #[test]
+    fn test_volume_crop() {
+        let volume = VolumeData::<f32>::with_data((1u8..=27).map(f32::from).collect(), [3, 3, 3]);
+
+        let cropped = volume.crop([1, 1, 1], [2, 2, 2]);
+        assert_eq!(
+            vec![14.0, 15.0, 17.0, 18.0, 23.0, 24.0, 26.0, 27.0],
+            cropped.data,
+            "cropped data should match corner of original volume"
+        );
+
+        let mut padded = volume.clone();
+        padded.set_padding(1, 0.0);
+        let mut cropped = volume.crop([1, 0, 0], [2, 3, 3]);
+        cropped.set_padding_within([1, 0, 0], [3, 3, 3], 1, 0.0);
+        assert_eq!(
+            padded.crop([1, 0, 0], [2, 3, 3]).data,
+            cropped.data,
+            "sub-range padding should match padding of the full volume"
+        );
+
+        assert_eq!(
+            Some(vec![2.0, 5.0, 8.0, 11.0, 14.0, 17.0, 20.0, 23.0, 26.0]),
+            volume.slice(0, 1),
+            "X slice should be laid out along Y, then Z"
+        );
+        assert_eq!(
+            Some(vec![7.0, 8.0, 9.0, 16.0, 17.0, 18.0, 25.0, 26.0, 27.0]),
+            volume.slice(1, 5),
+            "Y slice should be clamped within the volume"
+        );
+        assert_eq!(
+            Some((1u8..=9).map(f32::from).collect()),
+            volume.slice(2, 0),
+            "Z slice should be laid out along X, then Y"
+        );
+        assert_eq!(
+            None,
+            volume.slice(3, 0),
+            "invalid axis should have no slice"
+        );
+    }
+
+    #[test]
+    fn test_volume_dilate() {
+        let mut volume = VolumeData::new(1.0f32, [7, 7, 7]);
+        let center = volume.linearize(3, 3, 3);
+        volume.set_linear(center, -1.0);
+
+        volume.dilate(2);
+        for i in 0..volume.get_buffer_size() {
+            let [x, y, z] = volume.delinearize(i);
+            let inside = [x, y, z].iter().all(|c| c.abs_diff(3) <= 2);
+            assert_eq!(
+                if inside { -1.0 } else { 1.0 },
+                volume.get_linear(i),
+                "cell ({x}, {y}, {z}) should be solid only within the dilation radius"
+            );
+        }
+    }
+
+    #[test]
+    fn test_volume_bytes() {
+        let dim = [7, 5, 3];
+        let noise = Perlin::new(42);
+        let mut volume = VolumeData::new(0.0f32, dim);
+        for i in 0..volume.get_buffer_size() {
+            let [x, y, z] = volume.delinearize(i);
+            let value = noise.get([x as f64 * 0.37, y as f64 * 0.53, z as f64 * 0.71]) as f32;
+            volume.set_linear(i, value * 10.0);
+        }
+        // Include special values, which should round-trip exactly
+        volume.set_linear(0, -0.0);
+        volume.set_linear(1, f32::MAX);
+        volume.set_linear(2, f32::NAN);
+
+        let bytes = volume.to_bytes();
+        assert_eq!(
+            17 + volume.get_buffer_size() * 4,
+            bytes.len(),
+            "blob should be the header followed by every cell"
+        );
+        assert_eq!(b"STVX", &bytes[0..4], "blob should start with magic bytes");
+        assert_eq!(
+            7u32.to_le_bytes(),
+            bytes[5..9],
+            "dimensions should be written little-endian"
+        );
+
+        let read = VolumeData::from_bytes(&bytes, dim).expect("volume should read");
+        assert_eq!(dim, read.get_dimensions(), "dimensions should match");
+        let bits = |volume: &VolumeData<f32>| -> Vec<u32> {
+            volume.data.iter().map(|v| v.to_bits()).collect()
+        };
+        assert_eq!(
+            bits(&volume),
+            bits(&read),
+            "volume should round-trip exactly"
+        );
+
+        assert_eq!(
+            Err(VolumeBytesError::DimensionMismatch {
+                expected: [7, 5, 4],
+                found: dim
+            }),
+            VolumeData::from_bytes(&bytes, [7, 5, 4]).map(|_| ()),
+            "mismatched dimensions should be rejected"
+        );
+        assert_eq!(
+            Err(VolumeBytesError::Truncated),
+            VolumeData::from_bytes(&bytes[..bytes.len() - 1], dim).map(|_| ()),
+            "truncated buffers should be rejected"
+        );
+        assert_eq!(
+            Err(VolumeBytesError::Truncated),
+            VolumeData::from_bytes(&bytes[..10], dim).map(|_| ()),
+            "truncated headers should be rejected"
+        );
+        let mut extended = bytes.clone();
+        extended.extend_from_slice(&[0; 4]);
+        assert_eq!(
+            Err(VolumeBytesError::TrailingBytes(4)),
+            VolumeData::from_bytes(&extended, dim).map(|_| ()),
+            "oversized buffers should be rejected"
+        );
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        assert_eq!(
+            Err(VolumeBytesError::BadMagic(*b"XTVX")),
+            VolumeData::from_bytes(&bad_magic, dim).map(|_| ()),
+            "bad magic bytes should be rejected"
+        );
+        let mut bad_dtype = bytes.clone();
+        bad_dtype[4] = 9;
+        assert_eq!(
+            Err(VolumeBytesError::UnsupportedDataType(9)),
+            VolumeData::from_bytes(&bad_dtype, dim).map(|_| ()),
+            "unknown data types should be rejected"
+        );
+        assert_eq!(
+            Err(VolumeBytesError::Truncated),
+            VolumeData::from_bytes(&[], dim).map(|_| ()),
+            "empty buffers should be rejected"
+        );
+    }
+
+    #[test]
+    fn test_volume_sparse() {
+        // Mostly empty volume, with a small solid blob and an odd size to test partial bricks
+        let mut volume = VolumeData::new(1.0f32, [30, 25, 33]);
+        for z in 10..14 {
+            for y in 8..12 {
+                for x in 12..15 {
+                    let i = volume.linearize(x, y, z);
+                    volume.set_linear(i, (x + y + z) as f32 * -0.1);
+                }
+            }
+        }
+        let corner = volume.linearize(29, 24, 32);
+        volume.set_linear(corner, -0.5);
+
+        let sparse = volume.to_sparse(1.0);
+        assert_eq!([30, 25, 33], sparse.get_dimensions(), "dimensions");
+        assert!(
+            sparse.brick_count() < 16,
+            "only bricks near the blob and corner should be stored, got {}",
+            sparse.brick_count()
+        );
+
+        let mut bytes: Vec<u8> = vec![];
+        sparse.write_bytes(&mut bytes);
+        let dense_bytes = volume.get_buffer_size() * size_of::<f32>();
+        assert!(
+            bytes.len() * 20 < dense_bytes,
+            "sparse volume ({} bytes) should be much smaller than the dense volume ({dense_bytes} bytes)",
+            bytes.len()
+        );
+
+        let read =
+            SparseVolume::read_bytes(&mut bytes.as_slice()).expect("sparse volume should read");
+        assert!(
+            read == sparse,
+            "sparse volume should round-trip through bytes"
+        );
+
+        let reconstructed = VolumeData::from_sparse(&read);
+        assert_eq!(
+            volume.get_dimensions(),
+            reconstructed.get_dimensions(),
+            "reconstructed dimensions"
+        );
+        assert_eq!(
+            volume.data, reconstructed.data,
+            "sparse volume should reconstruct exactly"
+        );
+
+        // Corrupt blobs should fail to read, rather than allocating or panicking
+        let corrupt = |dim: [u32; 3], count: u32, bricks: &[u32]| -> Option<SparseVolume> {
+            let mut bytes: Vec<u8> = vec![];
+            for d in dim {
+                d.write_bytes(&mut bytes);
+            }
+            1.0f32.write_bytes(&mut bytes);
+            count.write_bytes(&mut bytes);
+            for brick in bricks {
+                brick.write_bytes(&mut bytes);
+                for _ in 0..64 {
+                    0.0f32.write_bytes(&mut bytes);
+                }
+            }
+            SparseVolume::read_bytes(&mut bytes.as_slice())
+        };
+        assert!(
+            corrupt([4, 4, 4], 1, &[0]).is_some(),
+            "valid brick should read"
+        );
+        assert!(
+            corrupt([4, 4, 4], u32::MAX, &[0]).is_none(),
+            "brick count beyond the data should not read"
+        );
+        assert!(
+            corrupt([0, 4, 4], 1, &[0]).is_none(),
+            "brick in a volume with an empty axis should not read"
+        );
+        assert!(
+            corrupt([4, 4, 4], 1, &[1]).is_none(),
+            "brick outside the volume should not read"
+        );
+    }
+
     #[test]
     fn test_volume_chunks() {
         let volume =
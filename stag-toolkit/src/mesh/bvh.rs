@@ -0,0 +1,231 @@
+use crate::math::bounding_box::BoundingBox;
+use crate::math::raycast::{Raycast, RaycastParameters, RaycastResult};
+use crate::mesh::trimesh::{TriangleMesh, TriangleOperations, ray_may_hit_bounds};
+use glam::Vec3;
+use rayon::prelude::*;
+
+/// Maximum number of triangles stored in a single leaf of a [TriangleMeshBVH].
+const BVH_LEAF_TRIANGLES: usize = 4;
+
+/// A node of a [TriangleMeshBVH].
+struct BvhNode {
+    /// Bounds enclosing every triangle within this node.
+    bounds: BoundingBox,
+    /// Index of the first child node, followed by the second child node.
+    /// Zero if this node is a leaf, as the root can never be a child.
+    children: usize,
+    /// Range of the faces within this node, as indices into the face list.
+    first: usize,
+    count: usize,
+}
+
+/// A Bounding Volume Hierarchy over the triangles of a [TriangleMesh], for fast repeated raycasting.
+/// Nodes are split at the median triangle centroid along their longest axis.
+///
+/// Raycasts return identical results to raycasting the [TriangleMesh] directly.
+/// The hierarchy is *not* updated when modifying the mesh, and must be rebuilt.
+pub struct TriangleMeshBVH<'a> {
+    mesh: &'a TriangleMesh,
+    nodes: Vec<BvhNode>,
+    /// Face indices, ordered so that each node covers a contiguous range.
+    faces: Vec<usize>,
+}
+
+impl<'a> TriangleMeshBVH<'a> {
+    /// Builds a hierarchy over all triangles of the given mesh.
+    pub fn build(mesh: &'a TriangleMesh) -> Self {
+        let centroids: Vec<Vec3> = mesh
+            .triangles
+            .iter()
+            .map(|tri| tri.centerpoint(&mesh.positions))
+            .collect();
+        let face_bounds = |faces: &[usize]| {
+            let points: Vec<Vec3> = faces
+                .iter()
+                .flat_map(|face| mesh.triangles[*face].map(|i| mesh.positions[i]))
+                .collect();
+            BoundingBox::from(&points)
+        };
+
+        let mut faces: Vec<usize> = (0..mesh.triangles.len()).collect();
+        let mut nodes: Vec<BvhNode> = vec![BvhNode {
+            bounds: face_bounds(&faces),
+            children: 0,
+            first: 0,
+            count: faces.len(),
+        }];
+
+        // Split nodes until they are small enough to be leaves
+        let mut stack: Vec<usize> = vec![0];
+        while let Some(node) = stack.pop() {
+            let (first, count) = (nodes[node].first, nodes[node].count);
+            if count <= BVH_LEAF_TRIANGLES {
+                continue;
+            }
+
+            let range = &mut faces[first..first + count];
+            let centroid_bounds = BoundingBox::from(
+                &range
+                    .iter()
+                    .map(|face| centroids[*face])
+                    .collect::<Vec<Vec3>>(),
+            );
+            let size = centroid_bounds.size();
+            let axis = if size.x >= size.y && size.x >= size.z {
+                0
+            } else if size.y >= size.z {
+                1
+            } else {
+                2
+            };
+
+            // Partition faces around the median centroid, breaking ties by face index for determinism
+            let half = count / 2;
+            range.select_nth_unstable_by(half, |a, b| {
+                centroids[*a][axis]
+                    .total_cmp(&centroids[*b][axis])
+                    .then(a.cmp(b))
+            });
+
+            let children = nodes.len();
+            for (child_first, child_count) in [(first, half), (first + half, count - half)] {
+                nodes.push(BvhNode {
+                    bounds: face_bounds(&faces[child_first..child_first + child_count]),
+                    children: 0,
+                    first: child_first,
+                    count: child_count,
+                });
+            }
+            nodes[node].children = children;
+            stack.push(children);
+            stack.push(children + 1);
+        }
+
+        Self { mesh, nodes, faces }
+    }
+
+    /// Returns the number of nodes in the hierarchy.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+}
+
+impl Raycast for TriangleMeshBVH<'_> {
+    fn raycast(&self, params: RaycastParameters) -> Option<RaycastResult> {
+        if self.faces.is_empty() {
+            return None;
+        }
+
+        // Gather all faces within leaves the ray passes through
+        let mut candidates: Vec<usize> = vec![];
+        let mut stack: Vec<usize> = vec![0];
+        while let Some(idx) = stack.pop() {
+            let node = &self.nodes[idx];
+            if !ray_may_hit_bounds(node.bounds, &params) {
+                continue;
+            }
+
+            if node.children == 0 {
+                candidates.extend_from_slice(&self.faces[node.first..node.first + node.count]);
+            } else {
+                stack.push(node.children);
+                stack.push(node.children + 1);
+            }
+        }
+
+        // Test faces in the same order as a full raycast, so ties resolve identically
+        candidates.sort_unstable();
+        self.mesh.raycast_faces(params, candidates)
+    }
+
+    /// Performs many raycasts in parallel. Results are returned in the same order as the given parameters.
+    fn raycast_many(&self, parameters: &[RaycastParameters]) -> Vec<Option<RaycastResult>> {
+        parameters
+            .par_iter()
+            .map(|params| self.raycast(*params))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TriangleMeshBVH;
+    use crate::math::raycast::{Raycast, RaycastParameters};
+    use crate::mesh::trimesh::{Triangle, TriangleMesh};
+    use glam::{Vec3, vec3};
+    use noise::{NoiseFn, Perlin};
+
+    #[test]
+    fn test_bvh_matches_brute_force() {
+        // Deterministic pseudo-random values between -1 and 1
+        let perlin = Perlin::new(7);
+        let random = |i: usize| -> f32 { (perlin.get([i as f64 * 0.731, 0.5]) * 2.5) as f32 };
+        let random_vec = |i: usize| vec3(random(i * 3), random(i * 3 + 1), random(i * 3 + 2));
+
+        // A soup of overlapping random triangles, and a closed box for rays starting inside
+        let mut positions: Vec<Vec3> = (0..600).map(|i| random_vec(i) * 4.0).collect();
+        let mut triangles: Vec<Triangle> =
+            (0..200).map(|i| [i * 3, i * 3 + 1, i * 3 + 2]).collect();
+        let offset = positions.len();
+        positions.extend((0..8).map(|i| {
+            vec3((i & 1) as f32, ((i >> 1) & 1) as f32, ((i >> 2) & 1) as f32) * 2.0 - 1.0
+        }));
+        for q in [
+            [0, 2, 6, 4],
+            [1, 5, 7, 3],
+            [0, 4, 5, 1],
+            [2, 3, 7, 6],
+            [0, 1, 3, 2],
+            [4, 6, 7, 5],
+        ] {
+            triangles.push([q[0] + offset, q[1] + offset, q[2] + offset]);
+            triangles.push([q[0] + offset, q[2] + offset, q[3] + offset]);
+        }
+
+        let mut mesh = TriangleMesh::new(triangles, positions, None, None);
+        mesh.bake_normals_smooth();
+        mesh.bake_raycast_planes();
+        let bvh = TriangleMeshBVH::build(&mesh);
+        assert!(bvh.node_count() > 1, "hierarchy should be split into nodes");
+
+        let mut hits = 0;
+        for i in 0..500 {
+            let origin = random_vec(1000 + i) * 6.0;
+            let direction = random_vec(3000 + i).normalize_or(Vec3::Y);
+
+            for (hit_backfaces, hit_from_inside, interpolate_normals) in [
+                (false, false, false),
+                (true, false, false),
+                (false, true, true),
+                (true, true, false),
+            ] {
+                let params = RaycastParameters::builder(origin, direction)
+                    .max_depth(if i % 2 == 0 { f32::INFINITY } else { 3.0 })
+                    .hit_backfaces(hit_backfaces)
+                    .hit_from_inside(hit_from_inside)
+                    .interpolate_normals(interpolate_normals)
+                    .build();
+
+                let expected = mesh.raycast(params);
+                assert_eq!(
+                    expected,
+                    bvh.raycast(params),
+                    "hierarchy should match brute force for ray {i} with {params:?}"
+                );
+                hits += expected.is_some() as usize;
+            }
+        }
+        assert!(
+            hits > 200,
+            "many rays should hit for a meaningful test, got {hits}"
+        );
+
+        let empty = TriangleMesh::default();
+        assert_eq!(
+            None,
+            TriangleMeshBVH::build(&empty)
+                .raycast(RaycastParameters::builder(Vec3::ZERO, Vec3::X).build()),
+            "empty hierarchy should never hit"
+        );
+    }
+}
@@ -1,19 +1,40 @@
 use super::trimesh::TriangleMesh;
 use crate::math::sdf;
 use crate::math::sdf::{ShapeOperation, shape_list_bounds};
-use crate::math::types::ToVector3;
 use crate::math::types::gdmath::*;
+use crate::math::types::{ToVector3, Vec2, Vec3, Vec4};
 use godot::builtin::Array;
 use godot::classes::csg_shape_3d::Operation;
-use godot::classes::mesh::ArrayType;
-use godot::classes::{CsgBox3D, CsgCylinder3D, CsgShape3D, CsgSphere3D, CsgTorus3D};
+use godot::classes::mesh::{ArrayCustomFormat, ArrayFormat, ArrayType, PrimitiveType};
+use godot::classes::{
+    ArrayMesh, CsgBox3D, CsgCylinder3D, CsgShape3D, CsgSphere3D, CsgTorus3D, Mesh,
+};
 use godot::obj::IndexEnum;
 use godot::prelude::*;
 
+/// Metadata key on CSG nodes for setting how strongly added noise affects the shape, from 0 to 1.
+pub const META_NOISE_WEIGHT: &str = "noise_weight";
+/// Metadata key on CSG nodes for preserving sharp features near the shape, by reducing SDF smoothing.
+pub const META_PRESERVE_SHARP: &str = "preserve_sharp";
+/// Metadata key on CSG boxes and cylinders for rounding their edges by the given radius, instead of the island's edge radius.
+pub const META_EDGE_RADIUS: &str = "edge_radius";
+/// Metadata key on CSG nodes for overriding the CSG operation, by name, such as `"union"` or `"subtract"`.
+pub const META_OPERATION: &str = "stag_operation";
+/// Metadata key on CSG nodes for overriding the CSG operation used only when generating collision, by name.
+pub const META_COLLISION_OPERATION: &str = "stag_collision_operation";
+/// Metadata key on CSG nodes for smoothly blending the CSG operation within the given distance, instead of a hard seam.
+pub const META_BLEND: &str = "stag_blend";
+/// Metadata key on CSG cylinders for building them as another primitive, by name, such as `"capsule"` or `"cone"`.
+pub const META_SHAPE: &str = "stag_shape";
+/// Metadata key on CSG nodes for the material index of surfaces nearest to the shape, when splitting baked meshes into surfaces.
+pub const META_MATERIAL: &str = "stag_material";
+
 // MESH DATA HANDLING //
 /// A helper class for batch-handling mesh surface data within Godot Engine.
 pub struct GodotSurfaceArrays {
     surface_arrays: Array<Variant>,
+    /// Format flags for the surface, describing custom channel formats.
+    format_flags: u64,
 }
 impl Default for GodotSurfaceArrays {
     fn default() -> Self {
@@ -58,7 +79,10 @@ impl GodotSurfaceArrays {
         // FINALLY, bind indices (actually don't bother since we'll be overriding them anyway)
         // sa.set(ArrayType::INDEX.to_index(), &Variant::nil()); // Overridden anyway
 
-        Self { surface_arrays: sa }
+        Self {
+            surface_arrays: sa,
+            format_flags: 0,
+        }
     }
 
     /// Creates a corresponding GodotSurfaceArrays set from a TriangleMesh.
@@ -80,10 +104,66 @@ impl GodotSurfaceArrays {
         if let Some(uv2) = &mesh.uv2 {
             surface.set_uv2(uv2.to_vector2());
         }
+        if let Some(tangents) = &mesh.tangents {
+            surface.set_tangents(packed_float32_array(
+                tangents
+                    .iter()
+                    .flat_map(|tangent| tangent.to_array())
+                    .collect(),
+            ));
+        }
+        if let Some(custom0) = &mesh.custom0 {
+            surface.set_custom0(packed_float32_array(custom0.clone()));
+        }
+        if let Some(custom1) = &mesh.custom1 {
+            surface.set_custom1(packed_float32_array(custom1.clone()));
+        }
+        if let Some(custom2) = &mesh.custom2 {
+            surface.set_custom2(packed_float32_array(custom2.clone()));
+        }
 
         surface
     }
 
+    /// Creates a GodotSurfaceArrays set for each material of a TriangleMesh, sorted by material index.
+    /// See [TriangleMesh::split_by_material].
+    pub fn from_trimesh_split(mesh: &TriangleMesh) -> Vec<(u32, Self)> {
+        if mesh.material_index.is_none() {
+            return vec![(0, Self::from_trimesh(mesh))];
+        }
+
+        mesh.split_by_material()
+            .iter()
+            .map(|(material, surface)| (*material, Self::from_trimesh(surface)))
+            .collect()
+    }
+
+    /// Creates a GodotSurfaceArrays set from the given surface of a Godot mesh.
+    /// Returns `None` if the surface does not exist.
+    pub fn from_mesh_surface(mesh: &Gd<Mesh>, surface: i32) -> Option<Self> {
+        if surface < 0 || surface >= mesh.get_surface_count() {
+            return None;
+        }
+
+        // Only array meshes expose their surface format
+        let format_flags = mesh
+            .clone()
+            .try_cast::<ArrayMesh>()
+            .map_or(0, |mesh| mesh.surface_get_format(surface).ord());
+
+        Some(Self {
+            surface_arrays: mesh.surface_get_arrays(surface),
+            format_flags,
+        })
+    }
+
+    /// Reads the surface arrays back into a TriangleMesh.
+    /// See [trimesh_from_surface_arrays] for how missing data is handled.
+    /// Returns an empty mesh if the surface has no vertices.
+    pub fn to_trimesh(&self) -> TriangleMesh {
+        trimesh_from_surface_arrays(&self.surface_arrays).unwrap_or_default()
+    }
+
     /// Internally sets a SurfaceArray value to the given variant.
     fn set_internal(&mut self, arrtype: ArrayType, value: Variant) {
         self.surface_arrays.set(arrtype.to_index(), &value);
@@ -101,8 +181,8 @@ impl GodotSurfaceArrays {
     pub fn set_normals(&mut self, value: PackedVector3Array) {
         self.set_internal(ArrayType::NORMAL, value.to_variant());
     }
-    /// Sets the vertex tangent buffer
-    pub fn set_tangents(&mut self, value: PackedVector3Array) {
+    /// Sets the vertex tangent buffer, as four floats per vertex: the tangent direction, then the binormal handedness.
+    pub fn set_tangents(&mut self, value: PackedFloat32Array) {
         self.set_internal(ArrayType::TANGENT, value.to_variant());
     }
     /// Sets the vertex color buffer
@@ -118,12 +198,110 @@ impl GodotSurfaceArrays {
         self.set_internal(ArrayType::TEX_UV2, value.to_variant());
     }
 
+    /// Sets the vertex CUSTOM0 buffer, as a single float per vertex.
+    /// Also updates the format flags to match.
+    pub fn set_custom0(&mut self, value: PackedFloat32Array) {
+        self.set_internal(ArrayType::CUSTOM0, value.to_variant());
+        self.format_flags |=
+            (ArrayCustomFormat::R_FLOAT.ord() as u64) << ArrayFormat::CUSTOM0_SHIFT.ord();
+    }
+    /// Sets the vertex CUSTOM1 buffer, as a single float per vertex.
+    /// Also updates the format flags to match.
+    pub fn set_custom1(&mut self, value: PackedFloat32Array) {
+        self.set_internal(ArrayType::CUSTOM1, value.to_variant());
+        self.format_flags |=
+            (ArrayCustomFormat::R_FLOAT.ord() as u64) << ArrayFormat::CUSTOM1_SHIFT.ord();
+    }
+    /// Sets the vertex CUSTOM2 buffer, as a single float per vertex.
+    /// Also updates the format flags to match.
+    pub fn set_custom2(&mut self, value: PackedFloat32Array) {
+        self.set_internal(ArrayType::CUSTOM2, value.to_variant());
+        self.format_flags |=
+            (ArrayCustomFormat::R_FLOAT.ord() as u64) << ArrayFormat::CUSTOM2_SHIFT.ord();
+    }
+
+    /// Returns the format flags to pass alongside the surface arrays.
+    pub fn get_format_flags(&self) -> u64 {
+        self.format_flags
+    }
+
     /// Returns a copy of the surface arrays, for passing to Godot.
     pub fn get_surface_arrays(&self) -> &Array<Variant> {
         &self.surface_arrays
     }
 }
 
+/// Reads a TriangleMesh from a set of Godot mesh surface arrays, such as from `Mesh.surface_get_arrays`.
+/// Reads indices, vertices, normals, colors and UVs. Non-indexed surfaces are treated as a triangle list.
+/// Channels that are missing, or do not have one value per vertex, are left empty.
+/// Returns `None` if the surface has no vertices.
+pub fn trimesh_from_surface_arrays(arrays: &VarArray) -> Option<TriangleMesh> {
+    let channel = |arrtype: ArrayType| arrays.get(arrtype.to_index());
+
+    let positions: Vec<Vec3> = channel(ArrayType::VERTEX)?
+        .try_to::<PackedVector3Array>()
+        .ok()?
+        .to_vector3();
+    if positions.is_empty() {
+        return None;
+    }
+
+    let indices: Vec<usize> = match channel(ArrayType::INDEX)
+        .and_then(|indices| indices.try_to::<PackedInt32Array>().ok())
+    {
+        Some(indices) if !indices.is_empty() => {
+            indices.as_slice().iter().map(|idx| *idx as usize).collect()
+        }
+        _ => (0..positions.len()).collect(),
+    };
+    let normals: Option<Vec<Vec3>> = channel(ArrayType::NORMAL)
+        .and_then(|normals| normals.try_to::<PackedVector3Array>().ok())
+        .map(|normals| normals.to_vector3())
+        .filter(|normals| normals.len() == positions.len());
+    let colors: Option<Vec<Vec4>> = channel(ArrayType::COLOR)
+        .and_then(|colors| colors.try_to::<PackedColorArray>().ok())
+        .map(|colors| {
+            colors
+                .as_slice()
+                .iter()
+                .map(|c| Vec4::new(c.r, c.g, c.b, c.a))
+                .collect()
+        })
+        .filter(|colors: &Vec<Vec4>| colors.len() == positions.len());
+
+    let uv = |arrtype: ArrayType| -> Option<Vec<Vec2>> {
+        channel(arrtype)
+            .and_then(|uvs| uvs.try_to::<PackedVector2Array>().ok())
+            .map(|uvs| uvs.to_vector2())
+            .filter(|uvs| uvs.len() == positions.len())
+    };
+    let (uv1, uv2) = (uv(ArrayType::TEX_UV), uv(ArrayType::TEX_UV2));
+
+    let mut mesh = TriangleMesh::from_indices(indices, positions, normals);
+    if let Some(colors) = colors {
+        mesh.colors = colors;
+    }
+    mesh.uv1 = uv1;
+    mesh.uv2 = uv2;
+    Some(mesh)
+}
+
+impl TriangleMesh {
+    /// Reads the given surface of a Godot [ArrayMesh] into a TriangleMesh, such as for optimizing imported meshes.
+    /// See [trimesh_from_surface_arrays] for how missing data is handled.
+    /// Returns `None` if the surface does not exist, or is not made of triangles.
+    pub fn from_godot_mesh(mesh: Gd<ArrayMesh>, surface: i32) -> Option<Self> {
+        if surface < 0
+            || surface >= mesh.get_surface_count()
+            || mesh.surface_get_primitive_type(surface) != PrimitiveType::TRIANGLES
+        {
+            return None;
+        }
+
+        Some(GodotSurfaceArrays::from_mesh_surface(&mesh.upcast(), surface)?.to_trimesh())
+    }
+}
+
 /// A collection of Signed Distance Field shapes for sampling.
 #[derive(Clone)]
 pub struct GodotWhitebox {
@@ -198,7 +376,7 @@ impl GodotWhitebox {
             }
         }
 
-        let op: ShapeOperation; // CSG operation of node
+        let mut op: ShapeOperation; // CSG operation of node
 
         // First, do generic cast to get basic Node3D properties
         if let Ok(shape) = node.clone().try_cast::<CsgShape3D>() {
@@ -214,6 +392,20 @@ impl GodotWhitebox {
             return;
         }
 
+        // Allow node metadata to override the CSG operation
+        if let Some(operation) = meta_operation(node, META_OPERATION) {
+            op = operation;
+        }
+
+        // Allow node metadata to smoothly blend the CSG operation
+        if node.has_meta(META_BLEND)
+            && let Ok(blend) = node.get_meta(META_BLEND).try_to::<f32>()
+        {
+            op = op.smoothed(blend);
+        }
+
+        let shape_count = self.shapes.len();
+
         // Then, cast to each type of CSG class
         match_class! {node.clone(),
             csg @ CsgBox3D => {
@@ -244,13 +436,36 @@ impl GodotWhitebox {
                 transform = transform.scaled_local(Vec3Godot::ONE / Vec3Godot::new(1.0, scale.y, 1.0));
                 scale.y *= csg.get_height();
 
-                self.shapes.push(sdf::Shape::rounded_cylinder(
-                    transform.to_transform3d(),
-                    scale.y,
-                    csg.get_radius(),
-                    0.0,
-                    op,
-                ));
+                // Cylinders can be tagged to be built as a capsule or cone instead
+                let shape_name = meta_shape(node, META_SHAPE).unwrap_or_default();
+
+                let radius = csg.get_radius();
+                if shape_name == "capsule" {
+                    // Capsule caps fit within the cylinder height
+                    let half = (scale.y * 0.5 - radius).max(0.0);
+                    self.shapes.push(sdf::Shape::capsule(
+                        transform.to_transform3d(),
+                        Vec3::NEG_Y * half,
+                        Vec3::Y * half,
+                        radius,
+                        op,
+                    ));
+                } else if shape_name == "cone" || csg.is_cone() {
+                    self.shapes.push(sdf::Shape::cone(
+                        transform.to_transform3d(),
+                        scale.y,
+                        radius,
+                        op,
+                    ));
+                } else {
+                    self.shapes.push(sdf::Shape::rounded_cylinder(
+                        transform.to_transform3d(),
+                        scale.y,
+                        radius,
+                        0.0,
+                        op,
+                    ));
+                }
             },
             csg @ CsgTorus3D => {
                 let thickness = (csg.get_outer_radius() - csg.get_inner_radius()).abs() * 0.5;
@@ -263,11 +478,67 @@ impl GodotWhitebox {
             },
             _ => {}
         }
+
+        // Apply optional properties stored as node metadata, if this node produced a shape
+        if self.shapes.len() > shape_count
+            && let Some(shape) = self.shapes.last_mut()
+        {
+            // Noise weighting
+            if let Some(noise_weight) = meta_value::<f32>(node, META_NOISE_WEIGHT) {
+                shape.noise_weight = noise_weight.clamp(0.0, 1.0);
+            }
+
+            // Sharp feature preservation
+            if let Some(preserve_sharp) = meta_value::<bool>(node, META_PRESERVE_SHARP) {
+                shape.preserve_sharp = preserve_sharp;
+            }
+
+            // Edge radius override
+            if let Some(edge_radius) = meta_value::<f32>(node, META_EDGE_RADIUS) {
+                shape.edge_radius = Some(edge_radius.max(0.0));
+            }
+
+            // Collision operation override, keeping the same blending as the visual operation
+            if let Some(operation) = meta_operation(node, META_COLLISION_OPERATION) {
+                shape.collision_operation = Some(operation.smoothed(shape.operation.blend()));
+            }
+
+            // Material index
+            if let Some(material_index) = meta_value::<i64>(node, META_MATERIAL) {
+                shape.material_index = material_index.max(0) as u32;
+            }
+        }
     }
 }
 
 // HELPER FUNCTIONS
 
+/// Returns the value of the given metadata key on the node, if present and of the given type.
+fn meta_value<T: FromGodot>(node: &Gd<Node>, key: &str) -> Option<T> {
+    if !node.has_meta(key) {
+        return None;
+    }
+    node.get_meta(key).try_to::<T>().ok()
+}
+
+/// Returns the CSG operation named in the given metadata key on the node, if any.
+fn meta_operation(node: &Gd<Node>, key: &str) -> Option<ShapeOperation> {
+    if !node.has_meta(key) {
+        return None;
+    }
+    let name = node.get_meta(key).try_to::<GString>().ok()?;
+    ShapeOperation::from_name(&name.to_string())
+}
+
+/// Reads a shape name from node metadata, lowercased and trimmed, if present.
+fn meta_shape(node: &Gd<Node>, key: &str) -> Option<String> {
+    if !node.has_meta(key) {
+        return None;
+    }
+    let name = node.get_meta(key).try_to::<GString>().ok()?;
+    Some(name.to_string().trim().to_ascii_lowercase())
+}
+
 fn csg_operation(gd_op: Operation) -> ShapeOperation {
     match gd_op {
         Operation::INTERSECTION => ShapeOperation::Intersection,
@@ -1,21 +1,111 @@
 use crate::math::bounding_box::BoundingBox;
-use crate::math::noise::{Perlin1D, Perlin3D};
-use crate::math::sdf::{Shape, ShapeOperation, sample_shape_list, shape_list_bounds};
+use crate::math::noise::{NoiseSource, Perlin1D, Perlin3D, ValueNoise3D};
+use crate::math::sdf::{
+    EdgeRadius, Shape, ShapeOperation, sample_noise_weight, sample_shape_list,
+    sample_smooth_weight, shape_list_bounds, shape_list_outline,
+};
 use crate::math::volumetric::{BlurSettings, VolumeData};
+use crate::mesh::marching::mesh_from_marching_cubes;
 use crate::mesh::nets::mesh_from_nets;
-use crate::mesh::trimesh::{TriangleMesh, TriangleOperations};
+use crate::mesh::trimesh::{Triangle, TriangleMesh, TriangleOperations, triplanar_projection};
 use crate::utils;
+use crate::utils::SettingBytes;
 use fast_surface_nets::{SurfaceNetsBuffer, ndshape::ConstShape, surface_nets};
 use glam::{FloatExt, Mat4, Quat, Vec2, Vec3, Vec4};
 use ndshape::ConstShape3u32;
 use rayon::prelude::*;
 use stag_toolkit_codegen::{ExposeSettings, settings_resource_from};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 #[cfg(feature = "godot")]
 use {crate::math::types::ToVector3, godot::prelude::*};
 
 const VOLUME_MAX_CELLS: usize = 48;
 const VOLUME_MAX_CELLS_TRIM: usize = 44;
+/// Width of voxel bricks checked for surfaces before meshing, along each axis.
+const MESH_BRICK_SIZE: usize = 8;
+/// Standard deviation of gaussian SDF smoothing, relative to the smoothing radius.
+const GAUSSIAN_SIGMA_PER_RADIUS: f32 = 0.5;
 type IslandChunkSize = ConstShape3u32<48, 48, 48>; // Same size as VolumeMaxCells
+/// Number of line segments used for approximating circles in whitebox outlines.
+const OUTLINE_CIRCLE_SEGMENTS: usize = 24;
+
+/// Noise function used for offsetting SDF sampling positions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "godot", derive(GodotConvert, Var, Export))]
+#[cfg_attr(feature = "godot", godot(via = u32))]
+pub enum SamplingNoise {
+    /// Perlin noise, which may differ slightly between platforms.
+    Perlin = 0,
+    /// Value noise, which is bit-identical on every platform.
+    Value = 1,
+}
+
+impl SettingBytes for SamplingNoise {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        (*self as u32).write_bytes(out);
+    }
+    fn read_bytes(bytes: &mut &[u8]) -> Option<Self> {
+        match u32::read_bytes(bytes)? {
+            0 => Some(Self::Perlin),
+            1 => Some(Self::Value),
+            _ => None,
+        }
+    }
+}
+
+/// Algorithm used for converting voxels into a mesh.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "godot", derive(GodotConvert, Var, Export))]
+#[cfg_attr(feature = "godot", godot(via = u32))]
+pub enum MeshingAlgorithm {
+    /// Surface Nets, which gives smooth, blobby surfaces.
+    SurfaceNets = 0,
+    /// Marching Cubes, which keeps sharper features for stylized terrain.
+    MarchingCubes = 1,
+}
+
+impl SettingBytes for MeshingAlgorithm {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        (*self as u32).write_bytes(out);
+    }
+    fn read_bytes(bytes: &mut &[u8]) -> Option<Self> {
+        match u32::read_bytes(bytes)? {
+            0 => Some(Self::SurfaceNets),
+            1 => Some(Self::MarchingCubes),
+            _ => None,
+        }
+    }
+}
+
+/// Projection used for baking UV1 and UV2 onto island meshes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "godot", derive(GodotConvert, Var, Export))]
+#[cfg_attr(feature = "godot", godot(via = u32))]
+pub enum UvProjection {
+    /// Wraps UV1 around the island, and projects UV2 top-down.
+    Wrapped = 0,
+    /// Projects UV1 onto the YZ plane and UV2 onto the XZ plane, so shaders can blend between all three planes.
+    /// The XY projection is made of the X component of UV2 and the Y component of UV1.
+    Triplanar = 1,
+    /// Projects UV1 onto whichever of the YZ, XZ or XY planes each face faces most directly, and UV2 top-down.
+    /// Vertices are split between faces projected onto different planes.
+    TriplanarSplit = 2,
+}
+
+impl SettingBytes for UvProjection {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        (*self as u32).write_bytes(out);
+    }
+    fn read_bytes(bytes: &mut &[u8]) -> Option<Self> {
+        match u32::read_bytes(bytes)? {
+            0 => Some(Self::Wrapped),
+            1 => Some(Self::Triplanar),
+            2 => Some(Self::TriplanarSplit),
+            _ => None,
+        }
+    }
+}
 
 /// Settings for voxel generation.
 #[derive(Copy, Clone, PartialEq, ExposeSettings)]
@@ -23,7 +113,7 @@ type IslandChunkSize = ConstShape3u32<48, 48, 48>; // Same size as VolumeMaxCell
 pub struct SettingsVoxels {
     /// Number of voxels to pad on each side of the island volume.
     /// This helps reduce cases where large amounts of noise or smoothing result in oddly flat (or cut off) surfaces.
-    #[setting(default = 3, min = 0.0, max = 6.0, soft_max)]
+    #[setting(default = 3, min = 0, max = 6, soft_max)]
     pub voxel_padding: u32,
     /// Width/height/depth of a voxel. This is the approximate resolution of the resulting island mesh.
     #[setting(default=Vec3::splat(0.275), min=0.05, max=1.0, incr=0.001, soft_max, unit="m")]
@@ -49,19 +139,39 @@ pub struct SettingsVoxels {
     #[setting(default=Vec3::splat(0.2),min=0.0,max=1.0,incr=0.001,soft_max,unit="m")]
     /// Amplitude of noise directly added to the SDF sampling position.
     pub sampling_offset_noise_amplitude: Vec3,
+    /// Number of fractal octaves of noise added to the SDF sampling position.
+    /// Additional octaves add finer detail, without increasing the overall amplitude.
+    #[setting(default = 1, min = 1, max = 8)]
+    pub sampling_offset_noise_octaves: u32,
+    /// Noise function used for offsetting SDF sampling positions.
+    /// Value noise is bit-identical across platforms, such as web and desktop, at the cost of a blockier look.
+    #[setting(default = SamplingNoise::Perlin)]
+    pub sampling_offset_noise_source: SamplingNoise,
 
     /// Rounding distance to apply to edges of Signed Distance Field primitives.
     #[setting(default = 1.6, min = 0.0, max = 2.0, soft_max, unit = "m")]
     pub sdf_edge_radius: f32,
+    /// Rounding distance to apply to edges of unioned Signed Distance Field primitives.
+    /// Negative values fall back to the SDF edge radius.
+    #[setting(default = -1.0, min = -1.0, max = 2.0, soft_max, unit = "m")]
+    pub edge_radius_union: f32,
+    /// Rounding distance to apply to edges of subtracted Signed Distance Field primitives.
+    /// Negative values fall back to the SDF edge radius.
+    #[setting(default = -1.0, min = -1.0, max = 2.0, soft_max, unit = "m")]
+    pub edge_radius_subtract: f32,
     /// Number of smoothing iterations to apply to voxels immediately after sampling Signed Distance Fields.
-    #[setting(default = 4, min = 0.0, max = 20.0, soft_max)]
+    #[setting(default = 4, min = 0, max = 20, soft_max)]
     pub sdf_smooth_iterations: u32,
     /// Radius of voxels to include in each smoothing pass applied immediately after sampling Signed Distance Fields.
-    #[setting(default = 3, min = 0.0, max = 5.0, soft_max)]
+    #[setting(default = 3, min = 0, max = 5, soft_max)]
     pub sdf_smooth_radius_voxels: u32,
     /// Weighting of each smoothing pass applied immediately after sampling Signed Distance Fields.
     #[setting(default = 0.95, min = 0.0, max = 1.0)]
     pub sdf_smooth_weight: f32,
+    /// If true, smoothing passes use a gaussian blur instead of a box blur,
+    /// giving a smoother falloff on high-contrast regions of the Signed Distance Field.
+    #[setting(default = false)]
+    pub sdf_smooth_gaussian: bool,
 
     /// Frequency scale for striation noise on each local axis.
     #[setting(default = Vec3::new(0.2,1.0,0.2), min = 0.0, max = 10.0, incr = 0.001, soft_max)]
@@ -76,13 +186,66 @@ pub struct SettingsVoxels {
         unit = "m"
     )]
     pub striation_amplitude: f64,
+    /// Number of fractal octaves of striation noise.
+    /// Additional octaves add finer detail, without increasing the overall amplitude.
+    #[setting(default = 1, min = 1, max = 8)]
+    pub striation_octaves: u32,
+    /// Distance over which the noise weights of neighboring shapes blend together,
+    /// for shapes that opt out of noise.
+    #[setting(
+        default = 1.0,
+        min = 0.01,
+        max = 4.0,
+        incr = 0.001,
+        soft_max,
+        unit = "m"
+    )]
+    pub noise_weight_blend: f32,
+    /// Distance over which SDF smoothing fades back in around shapes that preserve sharp features.
+    #[setting(
+        default = 0.5,
+        min = 0.01,
+        max = 4.0,
+        incr = 0.001,
+        soft_max,
+        unit = "m"
+    )]
+    pub preserve_sharp_blend: f32,
+    /// Algorithm used for meshing the voxels.
+    /// Marching Cubes keeps sharper features than Surface Nets, at the cost of more triangles.
+    #[setting(default = MeshingAlgorithm::SurfaceNets)]
+    pub meshing_algorithm: MeshingAlgorithm,
+    /// Maximum approximate number of triangles a bake may generate before it is aborted.
+    /// Guards against tiny voxel sizes on large islands exhausting memory. Zero disables the limit.
+    #[setting(default = 4000000, min = 0, max = 4294967295)]
+    pub max_triangle_budget: u32,
 
     /// Number of voxels per worker group.
     /// This is a performance setting and will not affect the output result.
-    #[setting(default=IslandChunkSize::USIZE as u32,min=1.0)]
+    #[setting(default=IslandChunkSize::USIZE as u32,min=1)]
     pub worker_group_size: u32,
 }
 
+impl SettingsVoxels {
+    /// Returns the edge rounding radii to use for each CSG operation,
+    /// falling back to the SDF edge radius for any negative overrides.
+    pub fn edge_radius(&self) -> EdgeRadius {
+        let or_fallback = |radius: f32| {
+            if radius < 0.0 {
+                self.sdf_edge_radius
+            } else {
+                radius
+            }
+        };
+
+        EdgeRadius {
+            union: or_fallback(self.edge_radius_union),
+            intersection: self.sdf_edge_radius,
+            subtraction: or_fallback(self.edge_radius_subtract),
+        }
+    }
+}
+
 /// Settings for mesh generation.
 #[derive(Copy, Clone, PartialEq, ExposeSettings)]
 #[settings_resource_from(IslandBuilderSettingsMesh, Resource)]
@@ -127,8 +290,35 @@ pub struct SettingsMesh {
     pub ao_strength: f32,
     /// Number of ambient occlusion samples to perform.
     /// More samples take significantly longer to bake, but reduces noise in the result.
-    #[setting(default = 32, min = 1.0, max = 512.0, incr = 1.0)]
+    #[setting(default = 32, min = 1, max = 512, incr = 1)]
     pub ao_samples: u32,
+    /// Half-angle of the cone around each vertex normal that Ambient Occlusion samples are taken within, in degrees.
+    /// At 90 degrees, samples cover the full hemisphere.
+    /// Narrower cones only darken deeper crevices.
+    #[setting(default = 90.0, min = 0.1, max = 90.0, incr = 0.1, unit = "degrees")]
+    pub ao_cone_angle: f32,
+
+    /// Whether to bake directional shadows from a fixed sun direction into the CUSTOM0 channel when baking meshes.
+    /// Shadow values range from 0.0 (fully shadowed) to 1.0 (fully lit), and are only baked when enabled.
+    ///
+    /// Like Ambient Occlusion, shadow baking is raycast-based and slows down the bake step.
+    #[setting(default = false)]
+    pub shadow_enabled: bool,
+    /// Direction towards the sun, in local space, used for baking directional shadows.
+    #[setting(default=Vec3::new(0.0,1.0,0.0),min=-1.0,max=1.0,incr=0.001)]
+    pub shadow_direction: Vec3,
+    /// Number of directional shadow samples to perform per vertex.
+    /// Additional samples are jittered within a narrow cone, softening shadow edges.
+    #[setting(default = 4, min = 1, max = 64, incr = 1)]
+    pub shadow_samples: u32,
+
+    /// How UV1 and UV2 are projected onto the mesh.
+    /// Wrapping UV1 around the island stretches textures on vertical cliffs, which triplanar projections avoid.
+    #[setting(default = UvProjection::Wrapped)]
+    pub uv_projection: UvProjection,
+    /// Scale of the triplanar UV projections, in texture repeats per meter.
+    #[setting(default = 1.0, min = 0.001, max = 10.0, incr = 0.001, soft_max)]
+    pub uv_triplanar_scale: f32,
 
     /// Minimum dot value for adding dirt gradation into the Green channel.
     /// The dot value is computed from a dot product of the triangle's normal to the local-space up vector.
@@ -154,6 +344,27 @@ pub struct SettingsMesh {
     #[setting(default=2.6,min=-5.0,max=5.0,incr=0.001,soft_max)]
     pub mask_sand_exponent: f32,
 
+    /// Mean curvature at which weathering is strongest, baked into the CUSTOM1 channel.
+    /// Negative curvature is concave, such as crevices, while positive curvature is convex, such as ridges.
+    #[setting(default=-1.0,min=-10.0,max=10.0,incr=0.001,soft_max)]
+    pub mask_curvature_minimum: f32,
+    /// Mean curvature at which weathering fades out entirely, baked into the CUSTOM1 channel.
+    /// Negative curvature is concave, such as crevices, while positive curvature is convex, such as ridges.
+    #[setting(default=1.0,min=-10.0,max=10.0,incr=0.001,soft_max)]
+    pub mask_curvature_maximum: f32,
+    /// Arbitrary exponent to apply to the weathering gradient.
+    #[setting(default = 1.0, min = -5.0, max = 5.0, incr = 0.001, soft_max)]
+    pub mask_curvature_exponent: f32,
+
+    /// Normalized height within the island's bounding box, from 0.0 at the bottom to 1.0 at the top,
+    /// above which snow is baked into the CUSTOM2 channel.
+    #[setting(default = 0.8, min = 0.0, max = 1.0, incr = 0.001)]
+    pub mask_snow_threshold: f32,
+    /// Normalized height range over which snow fades in above the threshold.
+    /// When zero, snow begins abruptly at the threshold.
+    #[setting(default = 0.1, min = 0.0, max = 1.0, incr = 0.001)]
+    pub mask_snow_falloff: f32,
+
     /// XYZ frequency scale when sampling perlin noise for baking into the Alpha channel.
     #[setting(default=Vec3::new(0.75,0.33,0.75),min=0.0,max=2.0,incr=0.001,soft_max)]
     pub mask_perlin_frequency: Vec3,
@@ -186,7 +397,7 @@ pub struct SettingsCollision {
     pub decimation_angle: f32,
     /// Maximum number of iterations for performing collision mesh decimation.
     /// The mesh will automatically stop decimating if nothing changes after an iteration.
-    #[setting(default = 100, min = 0.0, max = 500.0, incr = 1.0, soft_max)]
+    #[setting(default = 100, min = 0, max = 500, incr = 1, soft_max)]
     pub decimation_iterations: u32,
 
     /// Stops the decimation if this many triangles or less were removed during the previous decimation step.
@@ -194,8 +405,76 @@ pub struct SettingsCollision {
     /// at the cost of some determinism and slightly less optimized collision.
     ///
     /// Example: scanning a 5000-triangle mesh only to remove 1 edge is a lot of computation time that is not totally necessary.
-    #[setting(default = 8, min = 0.0, max = 24.0, incr = 1.0, soft_max)]
+    #[setting(default = 8, min = 0, max = 24, incr = 1, soft_max)]
     pub decimation_dropout: u32,
+    /// Edges longer than this are never collapsed during decimation.
+    /// This preserves the boundaries of large, flat surfaces, such as island tops.
+    /// A value of zero disables the limit.
+    #[setting(
+        default = 0.0,
+        min = 0.0,
+        max = 100.0,
+        incr = 0.01,
+        soft_max,
+        unit = "m"
+    )]
+    pub decimation_max_edge_length: f32,
+
+    /// Angle threshold for decimating coarse, distant collision, in place of the decimation angle.
+    #[setting(
+        default = 12.0,
+        min = 0.0,
+        max = 179.9,
+        incr = 0.001,
+        soft_max,
+        unit = "degrees"
+    )]
+    pub lod_decimation_angle: f32,
+    /// Maximum number of hulls to generate for coarse, distant collision.
+    /// Only the largest union shapes receive hulls.
+    #[setting(default = 4, min = 1, max = 32, incr = 1, soft_max)]
+    pub lod_max_hulls: u32,
+    /// Grows the collision surface outward by this many voxels before generating hulls,
+    /// giving thin features a minimum thickness. Only affects collision, not the visual mesh.
+    /// When zero, collision matches the preview mesh.
+    #[setting(default = 0, min = 0, max = 4, incr = 1, soft_max)]
+    pub skin_voxels: u32,
+}
+
+/// Level of detail for generated collision hulls.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CollisionLod {
+    /// One hull per union shape, decimated with the standard collision settings.
+    Full,
+    /// Fewer hulls with stronger decimation, for distant islands.
+    Coarse,
+}
+
+/// Number of distinct colors used when debug drawing collision hulls individually.
+pub const HULL_DEBUG_PALETTE_SIZE: usize = 8;
+
+/// Returns a debug draw color for the collision hull at the given index, with the given alpha.
+/// Hues step around the color wheel so neighboring hulls contrast,
+/// only repeating after [HULL_DEBUG_PALETTE_SIZE] hulls.
+pub fn hull_debug_color(index: usize, alpha: f32) -> Vec4 {
+    // Step by a count coprime with the palette size, so every hue is visited before wrapping
+    let hue = ((index * 3) % HULL_DEBUG_PALETTE_SIZE) as f32 / HULL_DEBUG_PALETTE_SIZE as f32;
+    hue_color(hue, alpha)
+}
+
+/// Returns a debug heatmap color for the given value, from blue at 0, through green, to red at 1.
+/// Values outside the range \[0, 1\] are clamped.
+pub fn heatmap_color(value: f32) -> Vec4 {
+    hue_color((1.0 - value.clamp(0.0, 1.0)) * (2.0 / 3.0), 1.0)
+}
+
+/// Converts a hue in the range \[0, 1) to an RGB color, with full saturation and value.
+fn hue_color(hue: f32, alpha: f32) -> Vec4 {
+    let channel = |offset: f32| -> f32 {
+        let k = (offset + hue * 6.0) % 6.0;
+        1.0 - k.min(4.0 - k).clamp(0.0, 1.0)
+    };
+    Vec4::new(channel(5.0), channel(3.0), channel(1.0), alpha)
 }
 
 /// Tweakable noise seeds for a given island builder.
@@ -203,7 +482,7 @@ pub struct SettingsCollision {
 #[settings_resource_from(IslandBuilderSettingsTweaks, Resource)]
 pub struct SettingsTweaks {
     /// Seed for noise parameters.
-    #[setting(default = 0, min = 0.0, max = 4294967295.0)]
+    #[setting(default = 0, min = 0, max = 4294967295)]
     pub seed: u32,
 
     pub w_sampling_density: f64,
@@ -212,6 +491,88 @@ pub struct SettingsTweaks {
     pub w_mask: f64,
 }
 
+impl SettingsTweaks {
+    /// Returns a copy of the tweaks with the seed offset by a stable hash of the given position,
+    /// so islands sharing tweaks still vary when placed in different locations.
+    /// Positions are quantized to the centimeter, so tiny floating-point drift does not change the seed.
+    pub fn with_position_seed(&self, position: Vec3) -> Self {
+        // FNV-1a hash of the quantized position
+        let mut hash: u32 = 0x811c_9dc5;
+        for component in (position * 100.0).round().to_array() {
+            for byte in (component as i32).to_le_bytes() {
+                hash = (hash ^ byte as u32).wrapping_mul(0x0100_0193);
+            }
+        }
+
+        Self {
+            seed: self.seed.wrapping_add(hash),
+            ..*self
+        }
+    }
+}
+
+/// A snapshot of all settings used for building an island, for saving and sharing presets.
+#[derive(Copy, Clone, PartialEq, Default)]
+pub struct IslandSettingsSnapshot {
+    pub voxels: SettingsVoxels,
+    pub mesh: SettingsMesh,
+    pub collision: SettingsCollision,
+    pub tweaks: SettingsTweaks,
+}
+
+impl IslandSettingsSnapshot {
+    /// Identifying header for serialized snapshots.
+    const MAGIC: [u8; 4] = *b"STIS";
+    /// Serialization format version. Must be incremented whenever a settings struct changes.
+    const VERSION: u32 = 18;
+
+    /// Serializes the snapshot into a little-endian byte blob.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out: Vec<u8> = Vec::with_capacity(256);
+        out.extend_from_slice(&Self::MAGIC);
+        Self::VERSION.write_bytes(&mut out);
+        self.voxels.write_bytes(&mut out);
+        self.mesh.write_bytes(&mut out);
+        self.collision.write_bytes(&mut out);
+        self.tweaks.write_bytes(&mut out);
+        out
+    }
+
+    /// Deserializes a snapshot from a byte blob.
+    /// Returns [None] if the blob is malformed, or was written by a different format version.
+    pub fn from_bytes(mut bytes: &[u8]) -> Option<Self> {
+        let (magic, rest) = bytes.split_first_chunk::<4>()?;
+        if *magic != Self::MAGIC {
+            return None;
+        }
+        bytes = rest;
+        if u32::read_bytes(&mut bytes)? != Self::VERSION {
+            return None;
+        }
+
+        let snapshot = Self {
+            voxels: SettingBytes::read_bytes(&mut bytes)?,
+            mesh: SettingBytes::read_bytes(&mut bytes)?,
+            collision: SettingBytes::read_bytes(&mut bytes)?,
+            tweaks: SettingBytes::read_bytes(&mut bytes)?,
+        };
+
+        // Trailing data means the blob does not match our format
+        if !bytes.is_empty() {
+            return None;
+        }
+        Some(snapshot)
+    }
+}
+
+/// A mesh generated from a single Surface Nets grid of voxels.
+#[derive(Clone, Default)]
+struct GridMesh {
+    mesh: Option<TriangleMesh>,
+    /// Approximate volume of the solid voxels within the grid.
+    volume: f32,
+}
+
 #[derive(Default)]
 pub struct Data {
     settings_voxels: SettingsVoxels,
@@ -221,19 +582,37 @@ pub struct Data {
 
     noise_sdf_density: Perlin1D,
     noise_sdf_sampling: Perlin3D,
+    /// Cross-platform deterministic alternative to the SDF sampling noise.
+    noise_sdf_sampling_value: ValueNoise3D,
     noise_striation: Perlin1D,
     noise_mask: Perlin1D,
+    /// Custom noise to use in place of the SDF density noise, if any.
+    noise_sdf_density_custom: Option<Box<dyn NoiseSource>>,
+    /// Custom noise to use in place of the striation noise, if any.
+    noise_striation_custom: Option<Box<dyn NoiseSource>>,
 
     shapes: Vec<Shape>,
+    /// Transform from Island space into the space noise and UVs are sampled in.
+    sampling_space: Mat4,
 
     bounds: BoundingBox,
     voxels: Option<VolumeData<f32>>,
     mesh_preview: Option<TriangleMesh>,
+    /// Preview mesh of each Surface Nets grid, for re-meshing only part of the preview.
+    preview_grids: Vec<GridMesh>,
     mesh_baked: Option<TriangleMesh>,
     hulls: Vec<TriangleMesh>,
+    hulls_coarse: Vec<TriangleMesh>,
+    /// Source shape of each collision hull.
+    hull_shapes: Vec<Shape>,
+    /// Source shape of each coarse collision hull.
+    hull_shapes_coarse: Vec<Shape>,
 
     /// Approximate volume of the Island.
     volume: f32,
+
+    /// Flag for aborting in-progress bakes, such as superseded real-time previews.
+    cancel: Option<Arc<AtomicBool>>,
 }
 
 impl Data {
@@ -251,18 +630,48 @@ impl Data {
             tweaks: settings_tweaks,
             noise_sdf_density: Perlin1D::default(),
             noise_sdf_sampling: Perlin3D::default(),
+            noise_sdf_sampling_value: ValueNoise3D::default(),
             noise_striation: Perlin1D::default(),
             noise_mask: Perlin1D::default(),
+            noise_sdf_density_custom: None,
+            noise_striation_custom: None,
             shapes: vec![],
+            sampling_space: Mat4::IDENTITY,
             bounds: BoundingBox::default(),
             voxels: None,
             mesh_preview: None,
+            preview_grids: vec![],
             mesh_baked: None,
             hulls: vec![],
+            hulls_coarse: vec![],
+            hull_shapes: vec![],
+            hull_shapes_coarse: vec![],
             volume: 0.0,
+            cancel: None,
+        }
+    }
+
+    /// Returns a snapshot of all current settings.
+    pub fn export_settings(&self) -> IslandSettingsSnapshot {
+        IslandSettingsSnapshot {
+            voxels: self.settings_voxels,
+            mesh: self.settings_mesh,
+            collision: self.settings_collision,
+            tweaks: self.tweaks,
         }
     }
 
+    /// Applies all settings from the given snapshot.
+    /// Returns true if any settings changed.
+    pub fn import_settings(&mut self, snapshot: IslandSettingsSnapshot) -> bool {
+        // Apply every setting, without short-circuiting
+        let voxels = self.set_voxel_settings(snapshot.voxels);
+        let mesh = self.set_mesh_settings(snapshot.mesh);
+        let collision = self.set_collision_settings(snapshot.collision);
+        let tweaks = self.set_tweaks(snapshot.tweaks);
+        voxels || mesh || collision || tweaks
+    }
+
     /// Performs a clone which only includes necessary data for baking a preview mesh.
     pub fn clone_for_preview(&self) -> Self {
         Self {
@@ -272,16 +681,31 @@ impl Data {
             tweaks: self.tweaks,
             noise_sdf_density: self.noise_sdf_density.clone(),
             noise_sdf_sampling: self.noise_sdf_sampling.clone(),
+            noise_sdf_sampling_value: self.noise_sdf_sampling_value.clone(),
             noise_striation: self.noise_striation.clone(),
             noise_mask: self.noise_mask.clone(),
+            noise_sdf_density_custom: self
+                .noise_sdf_density_custom
+                .as_ref()
+                .map(|noise| noise.clone_box()),
+            noise_striation_custom: self
+                .noise_striation_custom
+                .as_ref()
+                .map(|noise| noise.clone_box()),
             shapes: self.shapes.clone(),
+            sampling_space: self.sampling_space,
 
             bounds: BoundingBox::default(),
             voxels: None,
             mesh_preview: None,
+            preview_grids: vec![],
             mesh_baked: None,
             hulls: vec![],
+            hulls_coarse: vec![],
+            hull_shapes: vec![],
+            hull_shapes_coarse: vec![],
             volume: 0.0,
+            cancel: None,
         }
     }
 
@@ -294,19 +718,99 @@ impl Data {
         self.bounds
     }
 
+    /// Returns the dimensions of the baked voxel grid, or zero if voxels are not baked.
+    pub fn get_voxel_dimensions(&self) -> [usize; 3] {
+        self.voxels
+            .as_ref()
+            .map_or([0; 3], |voxels| voxels.get_dimensions())
+    }
+
+    /// Returns a 2D slice of the baked voxel field perpendicular to the given axis, for visualization.
+    /// See [VolumeData::slice].
+    /// Returns [None] if voxels are not baked or the axis is invalid.
+    pub fn voxel_slice(&self, axis: usize, slice: usize) -> Option<Vec<f32>> {
+        self.voxels.as_ref()?.slice(axis, slice)
+    }
+
     pub fn get_shapes(&self) -> &Vec<Shape> {
         &self.shapes
     }
 
+    /// Returns line segments outlining all serialized shapes, for drawing whitebox geometry.
+    pub fn get_shape_outlines(&self) -> Vec<(Vec3, Vec3)> {
+        shape_list_outline(&self.shapes, OUTLINE_CIRCLE_SEGMENTS)
+    }
+
     pub fn get_mesh_preview(&self) -> Option<&TriangleMesh> {
         self.mesh_preview.as_ref()
     }
 
+    /// Returns the number of bytes allocated for all cached bake data,
+    /// including voxels, preview and baked meshes, and collision hulls.
+    /// Use this to decide when to clear the cache.
+    pub fn total_memory_footprint(&self) -> usize {
+        self.voxels
+            .as_ref()
+            .map_or(0, |voxels| voxels.memory_footprint())
+            + self
+                .mesh_preview
+                .as_ref()
+                .map_or(0, |mesh| mesh.memory_footprint())
+            + self
+                .preview_grids
+                .iter()
+                .filter_map(|grid| grid.mesh.as_ref())
+                .map(|mesh| mesh.memory_footprint())
+                .sum::<usize>()
+            + self
+                .mesh_baked
+                .as_ref()
+                .map_or(0, |mesh| mesh.memory_footprint())
+            + self
+                .hulls
+                .iter()
+                .chain(self.hulls_coarse.iter())
+                .map(|hull| hull.memory_footprint())
+                .sum::<usize>()
+    }
+
     /// Returns the original reference to the preview mesh, clearing the cache.
     pub fn take_mesh_preview(&mut self) -> Option<TriangleMesh> {
+        self.preview_grids.clear();
         self.mesh_preview.take()
     }
 
+    /// Takes the intermediate voxel grid, clearing the cache.
+    /// Intended for inspecting bake determinism.
+    #[doc(hidden)]
+    #[cfg(any(test, feature = "debug_hooks"))]
+    pub fn debug_take_voxels(&mut self) -> Option<VolumeData<f32>> {
+        self.voxels.take()
+    }
+
+    /// Replaces the intermediate voxel grid, such as with one previously taken and saved to disk,
+    /// clearing all data generated from it.
+    /// Returns false if the grid does not match the current voxel dimensions.
+    #[doc(hidden)]
+    #[cfg(any(test, feature = "debug_hooks"))]
+    pub fn debug_restore_voxels(&mut self, voxels: VolumeData<f32>) -> bool {
+        if voxels.get_dimensions() != self.get_dimensions() {
+            return false;
+        }
+
+        self.dirty_voxels();
+        self.voxels = Some(voxels);
+        true
+    }
+
+    /// Takes the intermediate preview mesh, clearing the cache.
+    /// Intended for inspecting bake determinism.
+    #[doc(hidden)]
+    #[cfg(any(test, feature = "debug_hooks"))]
+    pub fn debug_take_preview(&mut self) -> Option<TriangleMesh> {
+        self.take_mesh_preview()
+    }
+
     pub fn get_mesh_baked(&self) -> Option<&TriangleMesh> {
         self.mesh_baked.as_ref()
     }
@@ -315,10 +819,19 @@ impl Data {
         self.hulls.as_ref()
     }
 
+    /// Returns the collision hulls baked for the given level of detail.
+    pub fn get_hulls_lod(&self, lod: CollisionLod) -> &Vec<TriangleMesh> {
+        match lod {
+            CollisionLod::Full => self.hulls.as_ref(),
+            CollisionLod::Coarse => self.hulls_coarse.as_ref(),
+        }
+    }
+
     /// Clears all generated data.
     pub fn dirty_voxels(&mut self) {
         self.voxels = None;
         self.mesh_preview = None;
+        self.preview_grids.clear();
         self.volume = 0.0;
         self.dirty_mesh();
         self.dirty_collision();
@@ -333,6 +846,14 @@ impl Data {
     /// Clears generated collision data.
     pub fn dirty_collision(&mut self) {
         self.hulls.clear();
+        self.hulls_coarse.clear();
+        self.hull_shapes.clear();
+        self.hull_shapes_coarse.clear();
+    }
+
+    /// Returns the current voxel settings.
+    pub fn get_voxel_settings(&self) -> SettingsVoxels {
+        self.settings_voxels
     }
 
     /// Updates the settings, dirtying the data if changed.
@@ -362,6 +883,10 @@ impl Data {
             let amplitude = self.settings_voxels.sampling_offset_noise_amplitude;
             self.noise_sdf_sampling.amplitude =
                 [amplitude.x as f64, amplitude.y as f64, amplitude.z as f64];
+            self.noise_sdf_sampling.octaves = self.settings_voxels.sampling_offset_noise_octaves;
+            self.noise_sdf_sampling_value.frequency = self.noise_sdf_sampling.frequency;
+            self.noise_sdf_sampling_value.amplitude = self.noise_sdf_sampling.amplitude;
+            self.noise_sdf_sampling_value.octaves = self.noise_sdf_sampling.octaves;
 
             let frequency = self.settings_voxels.striation_frequency;
             self.noise_striation.frequency = [
@@ -371,6 +896,7 @@ impl Data {
                 self.tweaks.w_striation,
             ];
             self.noise_striation.amplitude = self.settings_voxels.striation_amplitude;
+            self.noise_striation.octaves = self.settings_voxels.striation_octaves;
             return true;
         }
 
@@ -417,8 +943,54 @@ impl Data {
             // update noise seeds
             self.noise_sdf_sampling.set_seed(settings.seed);
             self.noise_sdf_sampling.set_seed(settings.seed + 3);
+            self.noise_sdf_sampling_value.set_seed(settings.seed + 3);
             self.noise_striation.set_seed(settings.seed + 6);
             self.noise_mask.set_seed(settings.seed + 9);
+            if let Some(noise) = self.noise_sdf_density_custom.as_mut() {
+                noise.set_seed(settings.seed + 3);
+            }
+            if let Some(noise) = self.noise_striation_custom.as_mut() {
+                noise.set_seed(settings.seed + 6);
+            }
+
+            return true;
+        }
+        false
+    }
+
+    /// Replaces the noise added to the SDF density with a custom noise function, dirtying the data.
+    /// The noise is seeded from the current tweaks. Pass None to restore the built-in perlin noise.
+    pub fn set_density_noise(&mut self, noise: Option<Box<dyn NoiseSource>>) {
+        self.noise_sdf_density_custom = noise;
+        if let Some(noise) = self.noise_sdf_density_custom.as_mut() {
+            noise.set_seed(self.tweaks.seed + 3);
+        }
+        self.dirty_voxels();
+    }
+
+    /// Replaces the striation noise with a custom noise function, dirtying the data.
+    /// The noise is seeded from the current tweaks. Pass None to restore the built-in perlin noise.
+    pub fn set_striation_noise(&mut self, noise: Option<Box<dyn NoiseSource>>) {
+        self.noise_striation_custom = noise;
+        if let Some(noise) = self.noise_striation_custom.as_mut() {
+            noise.set_seed(self.tweaks.seed + 6);
+        }
+        self.dirty_voxels();
+    }
+
+    /// Returns the transform from Island space into the space noise and UVs are sampled in.
+    pub fn get_sampling_space(&self) -> Mat4 {
+        self.sampling_space
+    }
+
+    /// Sets the transform from Island space into the space noise and UVs are sampled in, dirtying the data if changed.
+    /// Shapes are still sampled in Island space, so only the noise and UVs are affected.
+    /// Islands sharing a sampling space, such as a parent's space, get continuous noise and UVs across each other.
+    /// Returns true if changed.
+    pub fn set_sampling_space(&mut self, space: Mat4) -> bool {
+        if self.sampling_space != space {
+            self.sampling_space = space;
+            self.dirty_voxels();
 
             return true;
         }
@@ -426,7 +998,21 @@ impl Data {
     }
 
     /// Updates the shape list, dirtying the data if changed.
+    /// If only a single shape changed, it is updated incrementally when possible. See [Self::update_shape].
     pub fn set_shapes(&mut self, shapes: Vec<Shape>) -> bool {
+        // If only a single shape changed, try updating it in place
+        if self.shapes.len() == shapes.len() {
+            let mut changed = self
+                .shapes
+                .iter()
+                .zip(shapes.iter())
+                .enumerate()
+                .filter(|(_, (current, new))| current != new);
+            if let (Some((index, (_, shape))), None) = (changed.next(), changed.next()) {
+                return self.update_shape(index, *shape);
+            }
+        }
+
         if self.shapes != shapes {
             self.shapes = shapes;
             self.dirty_voxels();
@@ -435,6 +1021,129 @@ impl Data {
         false
     }
 
+    /// Transforms the given shapes by the given parent transform, and appends them to the shape list.
+    /// Useful for assembling a large island from prefab shape sets.
+    /// Returns true if any shapes were appended.
+    pub fn append_shapes(&mut self, shapes: &[Shape], transform: Mat4) -> bool {
+        if shapes.is_empty() {
+            return false;
+        }
+        self.shapes
+            .extend(shapes.iter().map(|shape| shape.transformed(transform)));
+        self.dirty_voxels();
+        true
+    }
+
+    /// Replaces the shape at the given index, returning true if it changed.
+    ///
+    /// If voxels are baked, only the voxels near the old and new shape are re-sampled,
+    /// and only the affected grids of the preview mesh are re-meshed, if baked.
+    /// Falls back to dirtying all voxels if the island bounds change, the shape is not a union,
+    /// or any shapes have custom noise weights or preserve sharp features, as these can affect voxels far from the shape.
+    /// Voxels are also dirtied if the bake exceeds the triangle budget, so they are not re-baked.
+    pub fn update_shape(&mut self, index: usize, shape: Shape) -> bool {
+        let Some(previous) = self.shapes.get(index).copied() else {
+            return false;
+        };
+        if previous == shape {
+            return false;
+        }
+        self.shapes[index] = shape;
+
+        let incremental = self.voxels.is_some()
+            && previous.operation == ShapeOperation::Union
+            && shape.operation == ShapeOperation::Union
+            // Smooth operations blend with shapes well past the reach of the field
+            && self.shapes.iter().all(|shape| shape.operation.blend() == 0.0)
+            && self.shapes.iter().all(|shape| shape.noise_weight == 1.0)
+            && previous.noise_weight == 1.0
+            && self.shapes.iter().all(|shape| !shape.preserve_sharp)
+            && !previous.preserve_sharp
+            && self.compute_bounds() == self.bounds
+            && !self.exceeds_triangle_budget();
+        if !incremental {
+            self.dirty_voxels();
+            return true;
+        }
+
+        // Union shapes only affect cells within a distance of 1 of their surface, as the field is capped at 1.
+        // The margin is applied in world space, so scaled shapes do not shrink it.
+        // Account for sampling positions being offset by noise.
+        let influence = |shape: &Shape| -> BoundingBox {
+            (shape.transform() * shape.relative_bounds()).expand_margin(
+                1.0 + self
+                    .settings_voxels
+                    .sampling_offset_noise_amplitude
+                    .max_element(),
+            )
+        };
+        if let Some((cell_min, cell_max)) =
+            self.region_cells(influence(&previous).join(&influence(&shape)))
+        {
+            self.update_voxel_range(cell_min, cell_max);
+        }
+
+        self.dirty_mesh();
+        self.dirty_collision();
+        true
+    }
+
+    /// Re-samples the baked voxels affected by changes between the given first and last cells, inclusive,
+    /// and re-meshes the affected grids of the preview mesh if it is baked.
+    fn update_voxel_range(&mut self, cell_min: [usize; 3], cell_max: [usize; 3]) {
+        // Smoothing spreads changes outward
+        let dim = self.get_dimensions();
+        let reach = self.smoothing_reach();
+        let cell_min = cell_min.map(|c| c.saturating_sub(reach));
+        let cell_max = [0, 1, 2].map(|i| (cell_max[i] + reach).min(dim[i] - 1));
+
+        let sampled = self.sample_voxel_range(cell_min, cell_max, reach + 1);
+        // Partially sampled voxels would leave the bake inconsistent
+        if self.is_canceled() {
+            self.clear_voxels();
+            self.clear_mesh_preview();
+            return;
+        }
+        let Some(voxels) = self.voxels.as_mut() else {
+            return;
+        };
+        for i in 0..sampled.get_buffer_size() {
+            let [x, y, z] = sampled.delinearize(i);
+            let idx = voxels.linearize(x + cell_min[0], y + cell_min[1], z + cell_min[2]);
+            voxels.set_linear(idx, sampled.get_linear(i));
+        }
+
+        if self.mesh_preview.is_none() {
+            return;
+        }
+        let Some(voxels) = &self.voxels else {
+            return;
+        };
+
+        let updated = self.mesh_voxel_grids(voxels, self.bounds.minimum, |first, last| {
+            (0..3).all(|axis| first[axis] <= cell_max[axis] && last[axis] >= cell_min[axis])
+        });
+        if self.is_canceled() {
+            self.clear_mesh_preview();
+            return;
+        }
+        if updated.len() != self.preview_grids.len() {
+            // Grid layout does not match the cached preview, re-mesh everything
+            self.clear_mesh_preview();
+            self.bake_preview();
+            return;
+        }
+
+        for (cached, grid) in self.preview_grids.iter_mut().zip(updated) {
+            if let Some(grid) = grid {
+                *cached = grid;
+            }
+        }
+        let (mesh, volume) = Self::join_grids(&self.preview_grids);
+        self.mesh_preview = Some(mesh);
+        self.volume = volume;
+    }
+
     /// Unsets the voxel bake without dirtying.
     pub fn clear_voxels(&mut self) {
         self.voxels = None;
@@ -443,6 +1152,7 @@ impl Data {
     /// Unsets the mesh preview without dirtying.
     pub fn clear_mesh_preview(&mut self) {
         self.mesh_preview = None;
+        self.preview_grids.clear();
     }
 
     /// Unsets the baked mesh without dirtying.
@@ -453,10 +1163,18 @@ impl Data {
     /// Unsets the collision without dirtying.
     pub fn clear_collision(&mut self) {
         self.hulls.clear();
+        self.hulls_coarse.clear();
+        self.hull_shapes.clear();
+        self.hull_shapes_coarse.clear();
     }
 
     /// Automatically computes the axis-aligned bounding box for the Island.
     pub fn bake_bounding_box(&mut self) {
+        self.bounds = self.compute_bounds();
+    }
+
+    /// Computes the bounding box of the current shapes, including noise margins and voxel padding.
+    fn compute_bounds(&self) -> BoundingBox {
         let padding_size: Vec3 =
             self.settings_voxels.voxel_size * self.settings_voxels.voxel_padding as f32;
 
@@ -467,11 +1185,9 @@ impl Data {
                 .sampling_offset_noise_amplitude
                 .max_element();
 
-        let bounds = shape_list_bounds(&self.shapes)
+        shape_list_bounds(&self.shapes)
             .expand_margin(margin * 2.0)
-            .expand_vector(padding_size.abs() * 2.0);
-
-        self.bounds = bounds;
+            .expand_vector(padding_size.abs() * 2.0)
     }
 
     fn get_dimensions(&self) -> [usize; 3] {
@@ -488,22 +1204,84 @@ impl Data {
     pub fn bake_voxels_init(&self) -> (VolumeData<f32>, Mat4) {
         (
             VolumeData::new(1.0f32, self.get_dimensions()),
-            Mat4::from_scale_rotation_translation(
-                self.settings_voxels.voxel_size,
-                Quat::IDENTITY,
-                self.bounds.minimum,
-            ),
+            self.voxel_transform(),
+        )
+    }
+
+    /// Returns the transform from voxel cell coordinates to Island space.
+    fn voxel_transform(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(
+            self.settings_voxels.voxel_size,
+            Quat::IDENTITY,
+            self.bounds.minimum,
         )
     }
 
+    /// Returns an approximate number of triangles a bake would generate,
+    /// based on the surface area of the voxel grid bounds.
+    /// This is only a heuristic, as noisy or hollow islands may generate more.
+    pub fn projected_triangle_count(&self) -> u64 {
+        Self::projected_triangles(self.get_dimensions())
+    }
+
+    /// Returns an approximate number of triangles generated by meshing a voxel grid of the given dimensions.
+    fn projected_triangles(dim: [usize; 3]) -> u64 {
+        let [x, y, z] = dim.map(|d| d as u64);
+        // Two triangles per face of every cell along the grid's outer surface
+        4 * (x * y + y * z + x * z)
+    }
+
+    /// Returns true if the projected triangle count of a bake exceeds the triangle budget.
+    pub fn exceeds_triangle_budget(&self) -> bool {
+        self.exceeds_triangle_budget_within(self.get_dimensions())
+    }
+
+    /// Returns true if the projected triangle count of meshing a voxel grid of the given dimensions
+    /// exceeds the triangle budget.
+    fn exceeds_triangle_budget_within(&self, dim: [usize; 3]) -> bool {
+        let budget = self.settings_voxels.max_triangle_budget;
+        budget > 0 && Self::projected_triangles(dim) > budget as u64
+    }
+
+    /// Sets a flag that cancels in-progress voxel and preview bakes when raised.
+    /// Canceled bakes stop between voxel workers and Surface Nets grids, and store no results.
+    pub fn set_cancel_flag(&mut self, cancel: Option<Arc<AtomicBool>>) {
+        self.cancel = cancel;
+    }
+
+    /// Returns true if the cancel flag has been raised.
+    pub fn is_canceled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+    }
+
     /// Bakes the voxel data if able.
+    /// Does nothing if the projected triangle count exceeds the triangle budget.
     pub fn bake_voxels(&mut self) {
         // Voxels already baked or no shapes to work from
         if self.voxels.is_some() || self.shapes.is_empty() {
             return;
         }
+        // Bake would likely exhaust memory
+        if self.exceeds_triangle_budget() {
+            return;
+        }
+
+        let voxels = self.sample_voxels([0; 3], self.get_dimensions());
+        // Don't store partially sampled voxels
+        if self.is_canceled() {
+            return;
+        }
+        self.voxels = Some(voxels);
+    }
 
-        let (mut voxels, transform) = self.bake_voxels_init();
+    /// Samples, smooths and pads the range of voxels starting at the given cell offset,
+    /// with the given dimensions.
+    /// Cells within the smoothing apron of a sub-range's border may differ from a full bake.
+    fn sample_voxels(&self, offset: [usize; 3], dim: [usize; 3]) -> VolumeData<f32> {
+        let transform = self.voxel_transform();
+        let mut voxels = VolumeData::new(1.0f32, dim);
 
         #[cfg(debug_assertions)]
         assert_ne!(
@@ -518,65 +1296,168 @@ impl Data {
         );
 
         // Sample island SDF in chunks
-        let noise_density = &self.noise_sdf_density;
-        let noise_sampling = &self.noise_sdf_sampling;
-        voxels.data = voxel_workers
-            .par_iter_mut()
-            .flat_map(|worker| -> Vec<f32> {
-                for i in 0..worker.range_width {
-                    let [x, y, z] = voxels.delinearize(i + worker.range_min);
-
-                    let mut sample_pos =
-                        transform.transform_point3(Vec3::new(x as f32, y as f32, z as f32));
-                    sample_pos += noise_sampling.sample(Vec4::from((
-                        sample_pos,
-                        self.tweaks.w_sampling_offset as f32,
-                    )));
-
-                    let sample = sample_shape_list(
-                        &self.shapes,
-                        sample_pos,
-                        self.settings_voxels.sdf_edge_radius,
-                    );
-                    let add_in = noise_density.sample(Vec4::from((
-                        sample_pos,
-                        self.tweaks.w_sampling_density as f32,
-                    )));
-
-                    worker.data[i] = sample + add_in as f32;
-                }
+        let edge_radius = self.settings_voxels.edge_radius();
+        let noise_density: &dyn NoiseSource = self
+            .noise_sdf_density_custom
+            .as_deref()
+            .unwrap_or(&self.noise_sdf_density);
+        let noise_sampling = |position: Vec4| -> Vec3 {
+            match self.settings_voxels.sampling_offset_noise_source {
+                SamplingNoise::Perlin => self.noise_sdf_sampling.sample_octaves(position),
+                SamplingNoise::Value => self.noise_sdf_sampling_value.sample_octaves(position),
+            }
+        };
+        let space = self.sampling_space;
+        let space_inverse = space.inverse();
+
+        // Only compute per-voxel noise weights if any shape opts out of noise
+        let noise_weights: Option<Vec<f32>> = self
+            .shapes
+            .iter()
+            .any(|shape| shape.noise_weight != 1.0)
+            .then(|| {
+                (0..voxels.get_buffer_size())
+                    .into_par_iter()
+                    .map(|i| {
+                        let [x, y, z] = voxels.delinearize(i);
+                        let sample_pos = transform.transform_point3(Vec3::new(
+                            (x + offset[0]) as f32,
+                            (y + offset[1]) as f32,
+                            (z + offset[2]) as f32,
+                        ));
+                        sample_noise_weight(
+                            &self.shapes,
+                            sample_pos,
+                            self.settings_voxels.noise_weight_blend,
+                        )
+                    })
+                    .collect()
+            });
 
-                worker.data.clone()
-            })
-            .collect();
+        voxel_workers.par_iter_mut().for_each(|worker| {
+            if self.is_canceled() {
+                return;
+            }
+            for i in 0..worker.range_width {
+                let [x, y, z] = voxels.delinearize(i + worker.range_min);
+                let noise_weight = noise_weights
+                    .as_ref()
+                    .map_or(1.0, |weights| weights[i + worker.range_min]);
+
+                let mut sample_pos = transform.transform_point3(Vec3::new(
+                    (x + offset[0]) as f32,
+                    (y + offset[1]) as f32,
+                    (z + offset[2]) as f32,
+                ));
+                let offset = noise_sampling(Vec4::from((
+                    space.transform_point3(sample_pos),
+                    self.tweaks.w_sampling_offset as f32,
+                )));
+                sample_pos += space_inverse.transform_vector3(offset) * noise_weight;
+
+                let sample = sample_shape_list(&self.shapes, sample_pos, edge_radius);
+                let add_in = noise_density.sample(Vec4::from((
+                    space.transform_point3(sample_pos),
+                    self.tweaks.w_sampling_density as f32,
+                )));
+
+                worker.data[i] = sample + add_in * noise_weight;
+            }
+        });
+        voxels.gather_workers(&voxel_workers);
+        if self.is_canceled() {
+            return voxels;
+        }
 
         if self.settings_voxels.sdf_smooth_iterations > 0 {
             // Perform smoothing blurs, swapping between current and a buffer.
             // DON'T recreate the buffer each time, because it guzzles performance.
-            let blur_buffer = VolumeData::new(1.0, self.get_dimensions());
-
-            voxels.blur(
-                BlurSettings {
-                    iterations: self.settings_voxels.sdf_smooth_iterations,
-                    radius: self.settings_voxels.sdf_smooth_radius_voxels as usize,
-                    weight: self.settings_voxels.sdf_smooth_weight,
-                    cell_padding: 1,
-                    padding_value: 1.0,
-                },
-                blur_buffer,
-                voxel_workers,
-            );
+            let blur_buffer = VolumeData::new(1.0, dim);
+            let blur_settings = BlurSettings {
+                iterations: self.settings_voxels.sdf_smooth_iterations,
+                radius: self.settings_voxels.sdf_smooth_radius_voxels as usize,
+                weight: self.settings_voxels.sdf_smooth_weight,
+                cell_padding: 1,
+                padding_value: 1.0,
+            };
+            // Smoothing radius covers two standard deviations of the gaussian kernel
+            let sigma =
+                self.settings_voxels.sdf_smooth_radius_voxels as f32 * GAUSSIAN_SIGMA_PER_RADIUS;
+
+            // Only compute per-voxel smoothing weights if any shape preserves sharp features
+            if self.shapes.iter().any(|shape| shape.preserve_sharp) {
+                let smooth_weights: Vec<f32> = (0..voxels.get_buffer_size())
+                    .into_par_iter()
+                    .map(|i| {
+                        let [x, y, z] = voxels.delinearize(i);
+                        let sample_pos = transform.transform_point3(Vec3::new(
+                            (x + offset[0]) as f32,
+                            (y + offset[1]) as f32,
+                            (z + offset[2]) as f32,
+                        ));
+                        sample_smooth_weight(
+                            &self.shapes,
+                            sample_pos,
+                            self.settings_voxels.preserve_sharp_blend,
+                        )
+                    })
+                    .collect();
+                if self.settings_voxels.sdf_smooth_gaussian {
+                    voxels.blur_gaussian_weighted(
+                        blur_settings,
+                        sigma,
+                        blur_buffer,
+                        voxel_workers,
+                        &smooth_weights,
+                    );
+                } else {
+                    voxels.blur_weighted(
+                        blur_settings,
+                        blur_buffer,
+                        voxel_workers,
+                        &smooth_weights,
+                    );
+                }
+            } else if self.settings_voxels.sdf_smooth_gaussian {
+                voxels.blur_gaussian(blur_settings, sigma, blur_buffer, voxel_workers);
+            } else {
+                voxels.blur(blur_settings, blur_buffer, voxel_workers);
+            }
         }
 
-        voxels.noise_add(
-            &self.noise_striation,
-            transform,
-            self.tweaks.w_striation as f32,
-        );
+        let noise_striation: &dyn NoiseSource = self
+            .noise_striation_custom
+            .as_deref()
+            .unwrap_or(&self.noise_striation);
+        let striation_transform = space
+            * transform
+            * Mat4::from_translation(Vec3::new(
+                offset[0] as f32,
+                offset[1] as f32,
+                offset[2] as f32,
+            ));
+        match &noise_weights {
+            Some(weights) => voxels.noise_add_weighted(
+                noise_striation,
+                striation_transform,
+                self.tweaks.w_striation as f32,
+                weights,
+            ),
+            None => voxels.noise_add(
+                noise_striation,
+                striation_transform,
+                self.tweaks.w_striation as f32,
+            ),
+        }
 
-        voxels.set_padding(self.settings_voxels.voxel_padding as usize, 10.0);
+        voxels.set_padding_within(
+            offset,
+            self.get_dimensions(),
+            self.settings_voxels.voxel_padding as usize,
+            10.0,
+        );
 
-        self.voxels = Some(voxels);
+        voxels
     }
 
     /// Bakes a preview mesh if able.
@@ -586,92 +1467,305 @@ impl Data {
         }
 
         if let Some(voxels) = &self.voxels {
-            let dim = voxels.get_dimensions();
+            let grids: Vec<GridMesh> = self
+                .mesh_voxel_grids(voxels, self.bounds.minimum, |_, _| true)
+                .into_iter()
+                .flatten()
+                .collect();
+            // Don't store partially meshed previews
+            if self.is_canceled() {
+                return;
+            }
+            let (mesh, volume) = Self::join_grids(&grids);
 
-            let grids_x = (dim[0] as f32 / VOLUME_MAX_CELLS_TRIM as f32).ceil() as usize;
-            let grids_y = (dim[1] as f32 / VOLUME_MAX_CELLS_TRIM as f32).ceil() as usize;
-            let grids_z = (dim[2] as f32 / VOLUME_MAX_CELLS_TRIM as f32).ceil() as usize;
+            self.volume = volume;
+            self.mesh_preview = Some(mesh);
+            self.preview_grids = grids;
+        }
+    }
 
-            let grid_count = grids_x * grids_y * grids_z;
-            let grid_strides = [1, grids_x, grids_x * grids_y];
+    /// Bakes a partial preview mesh of only the voxels intersecting the given region,
+    /// rounded outward to the nearest voxel.
+    /// Only the voxels near the region are sampled, making this much cheaper than a full bake.
+    ///
+    /// Returns None if there are no shapes to bake, the region does not intersect the Island bounds,
+    /// or the projected triangle count of the region exceeds the triangle budget.
+    pub fn bake_region(&mut self, region: BoundingBox) -> Option<TriangleMesh> {
+        if self.shapes.is_empty() {
+            return None;
+        }
+        if self.bounds.zero() {
+            self.bake_bounding_box();
+        }
 
-            fn linearize_nets(strides: [usize; 3], x: usize, y: usize, z: usize) -> usize {
-                x + strides[1].wrapping_mul(y) + strides[2].wrapping_mul(z)
-            }
+        let (region_min, region_max) = self.region_cells(region)?;
+        // Bake would likely exhaust memory
+        if self.exceeds_triangle_budget_within([0, 1, 2].map(|i| region_max[i] - region_min[i] + 1))
+        {
+            return None;
+        }
+        let voxels = self.sample_voxel_range(region_min, region_max, self.smoothing_reach() + 1);
 
-            // Then, allocate our grids
-            let mut grids: Vec<[f32; IslandChunkSize::USIZE]> =
-                vec![[1.0f32; IslandChunkSize::USIZE]; grid_count];
-            let mut grid_offset: Vec<Vec3> = vec![Vec3::ZERO; grid_count];
-
-            let volume_per_voxel = self.settings_voxels.voxel_size.x
-                * self.settings_voxels.voxel_size.y
-                * self.settings_voxels.voxel_size.z;
-            let mut volume: f32 = 0.0;
-
-            // Fill our constant-size grids with voxel data for surface nets
-            for x in 0..grids_x {
-                for y in 0..grids_y {
-                    for z in 0..grids_z {
-                        let grid_idx = linearize_nets(grid_strides, x, y, z);
-                        let offset = Vec3::new(
-                            (x * (VOLUME_MAX_CELLS - 2)) as f32,
-                            (y * (VOLUME_MAX_CELLS - 2)) as f32,
-                            (z * (VOLUME_MAX_CELLS - 2)) as f32,
-                        ) * self.settings_voxels.voxel_size
-                            + self.bounds.minimum;
-                        grid_offset[grid_idx] = offset;
-
-                        for (i, result) in grids[grid_idx].iter_mut().enumerate() {
-                            // Local XYZ coordinate of Surface Nets volume
-                            let coord = IslandChunkSize::delinearize(i as u32);
-                            // Global index of Voxel Grid
-                            let voxels_idx = voxels.linearize(
-                                x * (VOLUME_MAX_CELLS - 2) + coord[0] as usize,
-                                y * (VOLUME_MAX_CELLS - 2) + coord[1] as usize,
-                                z * (VOLUME_MAX_CELLS - 2) + coord[2] as usize,
-                            );
+        let origin = self.bounds.minimum
+            + Vec3::new(
+                region_min[0] as f32,
+                region_min[1] as f32,
+                region_min[2] as f32,
+            ) * self.settings_voxels.voxel_size;
+        let (mesh, _) = self.mesh_voxels(&voxels, origin);
 
-                            let sample = voxels.get_linear(voxels_idx);
-                            *result = -sample;
+        Some(mesh)
+    }
 
-                            if sample < 0.0 {
-                                volume += volume_per_voxel;
-                            }
-                        }
-                    }
-                }
-            }
+    /// Returns the first and last voxel cells intersecting the given region, rounded outward,
+    /// or None if the region does not intersect the voxel grid.
+    fn region_cells(&self, region: BoundingBox) -> Option<([usize; 3], [usize; 3])> {
+        let dim = self.get_dimensions();
+        let voxel_size = self.settings_voxels.voxel_size;
+        let cell_min = ((region.minimum - self.bounds.minimum) / voxel_size).floor();
+        let cell_max = ((region.maximum - self.bounds.minimum) / voxel_size).ceil();
+
+        // Region is entirely outside the voxel grid
+        if cell_max.cmplt(Vec3::ZERO).any()
+            || cell_min
+                .cmpgt(Vec3::new(
+                    (dim[0] - 1) as f32,
+                    (dim[1] - 1) as f32,
+                    (dim[2] - 1) as f32,
+                ))
+                .any()
+        {
+            return None;
+        }
 
-            // Perform Surface Nets algorithm on all grids in parallel, storing corresponding mesh
-            let voxel_size = self.settings_voxels.voxel_size;
-            let meshes: Vec<Option<TriangleMesh>> = grids
-                .par_iter_mut()
-                .enumerate()
-                .map(|(idx, grid)| -> Option<TriangleMesh> {
-                    let mut buffer = SurfaceNetsBuffer::default();
-                    surface_nets(
-                        grid,
-                        &IslandChunkSize {},
-                        [0; 3],
-                        [(VOLUME_MAX_CELLS - 1) as u32; 3],
-                        &mut buffer,
-                    );
+        let cell_min = cell_min.max(Vec3::ZERO);
+        Some((
+            [0, 1, 2].map(|i| cell_min[i] as usize),
+            [0, 1, 2].map(|i| (cell_max[i] as usize).min(dim[i] - 1)),
+        ))
+    }
 
-                    mesh_from_nets(buffer, voxel_size, grid_offset[idx])
-                })
-                .collect();
+    /// Returns the number of voxels that smoothing can spread a change across.
+    fn smoothing_reach(&self) -> usize {
+        (self.settings_voxels.sdf_smooth_radius_voxels * self.settings_voxels.sdf_smooth_iterations)
+            as usize
+    }
 
-            // Now, join all meshes together
-            let mut mesh_final = TriangleMesh::default();
+    /// Samples the voxels between the given first and last cells, inclusive.
+    /// An apron of the given number of cells is sampled around the range and cropped off afterward,
+    /// so smoothing matches the full bake within the range when the apron exceeds the smoothing reach.
+    fn sample_voxel_range(
+        &self,
+        cell_min: [usize; 3],
+        cell_max: [usize; 3],
+        apron: usize,
+    ) -> VolumeData<f32> {
+        let dim = self.get_dimensions();
+        let sample_min = cell_min.map(|c| c.saturating_sub(apron));
+        let sample_max = [0, 1, 2].map(|i| (cell_max[i] + apron).min(dim[i] - 1));
+
+        let voxels = self.sample_voxels(
+            sample_min,
+            [0, 1, 2].map(|i| sample_max[i] - sample_min[i] + 1),
+        );
+        voxels.crop(
+            [0, 1, 2].map(|i| cell_min[i] - sample_min[i]),
+            [0, 1, 2].map(|i| cell_max[i] - cell_min[i] + 1),
+        )
+    }
 
-            for mesh in meshes.iter().flatten() {
+    /// Meshes the given voxels with the configured meshing algorithm, with the first voxel located at the given origin.
+    /// Returns the mesh and approximate volume of the voxels.
+    fn mesh_voxels(&self, voxels: &VolumeData<f32>, origin: Vec3) -> (TriangleMesh, f32) {
+        let grids: Vec<GridMesh> = self
+            .mesh_voxel_grids(voxels, origin, |_, _| true)
+            .into_iter()
+            .flatten()
+            .collect();
+        Self::join_grids(&grids)
+    }
+
+    /// Joins the meshes of all grids together, returning the mesh and total approximate volume.
+    fn join_grids(grids: &[GridMesh]) -> (TriangleMesh, f32) {
+        let mut mesh_final = TriangleMesh::default();
+        let mut volume: f32 = 0.0;
+
+        for grid in grids.iter() {
+            if let Some(mesh) = &grid.mesh {
                 mesh_final.join(mesh);
             }
+            volume += grid.volume;
+        }
 
-            self.volume = volume;
-            self.mesh_preview = Some(mesh_final);
+        mesh_final.debug_validate();
+
+        (mesh_final, volume)
+    }
+
+    /// Meshes each Surface Nets grid of the given voxels, with the first voxel located at the given origin.
+    /// Grids are only meshed if `remesh` returns true for the first and last voxel cell they cover,
+    /// otherwise they are returned as None.
+    fn mesh_voxel_grids(
+        &self,
+        voxels: &VolumeData<f32>,
+        origin: Vec3,
+        remesh: impl Fn([usize; 3], [usize; 3]) -> bool,
+    ) -> Vec<Option<GridMesh>> {
+        let dim = voxels.get_dimensions();
+
+        let grids_x = (dim[0] as f32 / VOLUME_MAX_CELLS_TRIM as f32).ceil() as usize;
+        let grids_y = (dim[1] as f32 / VOLUME_MAX_CELLS_TRIM as f32).ceil() as usize;
+        let grids_z = (dim[2] as f32 / VOLUME_MAX_CELLS_TRIM as f32).ceil() as usize;
+
+        let grid_count = grids_x * grids_y * grids_z;
+        let grid_strides = [1, grids_x, grids_x * grids_y];
+
+        fn linearize_nets(strides: [usize; 3], x: usize, y: usize, z: usize) -> usize {
+            x + strides[1].wrapping_mul(y) + strides[2].wrapping_mul(z)
         }
+
+        // Find bricks of voxels containing a surface, so surface nets can skip empty grids
+        let bricks_dim = dim.map(|d| d.div_ceil(MESH_BRICK_SIZE));
+        let mut occupied = vec![false; bricks_dim[0] * bricks_dim[1] * bricks_dim[2]];
+        for [x, y, z] in voxels.occupied_bricks(MESH_BRICK_SIZE) {
+            occupied[x + bricks_dim[0] * (y + bricks_dim[1] * z)] = true;
+        }
+        let has_surface = |first: [usize; 3], last: [usize; 3]| {
+            let brick_first = first.map(|f| f.saturating_sub(1) / MESH_BRICK_SIZE);
+            let brick_last =
+                [0, 1, 2].map(|axis| (last[axis] / MESH_BRICK_SIZE).min(bricks_dim[axis] - 1));
+            (brick_first[2]..=brick_last[2]).any(|z| {
+                (brick_first[1]..=brick_last[1]).any(|y| {
+                    (brick_first[0]..=brick_last[0])
+                        .any(|x| occupied[x + bricks_dim[0] * (y + bricks_dim[1] * z)])
+                })
+            })
+        };
+
+        let volume_per_voxel = self.settings_voxels.voxel_size.x
+            * self.settings_voxels.voxel_size.y
+            * self.settings_voxels.voxel_size.z;
+
+        /// Voxel data of a single grid to mesh.
+        struct GridJob {
+            idx: usize,
+            grid: Box<[f32; IslandChunkSize::USIZE]>,
+            /// First cell of the grid within the voxels.
+            first: [usize; 3],
+            offset: Vec3,
+            /// Last cell of the grid within the voxels, so clamped edges are not extruded into surfaces.
+            grid_max: [u32; 3],
+            volume: f32,
+            /// Whether the grid contains a surface, or can skip surface nets entirely.
+            surface: bool,
+        }
+        let mut jobs: Vec<GridJob> = vec![];
+
+        // Fill our constant-size grids with voxel data for surface nets
+        for x in 0..grids_x {
+            for y in 0..grids_y {
+                for z in 0..grids_z {
+                    let grid_idx = linearize_nets(grid_strides, x, y, z);
+                    let first = [x, y, z].map(|g| g * (VOLUME_MAX_CELLS - 2));
+                    let grid_max = [0, 1, 2].map(|axis| {
+                        (dim[axis] - 1)
+                            .saturating_sub(first[axis])
+                            .min(VOLUME_MAX_CELLS - 1) as u32
+                    });
+                    let last = [0, 1, 2].map(|axis| first[axis] + grid_max[axis] as usize);
+
+                    if !remesh(first, last) || self.is_canceled() {
+                        continue;
+                    }
+
+                    let offset = Vec3::new(first[0] as f32, first[1] as f32, first[2] as f32)
+                        * self.settings_voxels.voxel_size
+                        + origin;
+
+                    let mut grid = Box::new([1.0f32; IslandChunkSize::USIZE]);
+                    let mut volume: f32 = 0.0;
+                    for (i, result) in grid.iter_mut().enumerate() {
+                        // Local XYZ coordinate of Surface Nets volume
+                        let coord = IslandChunkSize::delinearize(i as u32);
+                        // Global index of Voxel Grid
+                        let voxels_idx = voxels.linearize(
+                            first[0] + coord[0] as usize,
+                            first[1] + coord[1] as usize,
+                            first[2] + coord[2] as usize,
+                        );
+
+                        let sample = voxels.get_linear(voxels_idx);
+                        *result = -sample;
+
+                        if sample < 0.0 {
+                            volume += volume_per_voxel;
+                        }
+                    }
+
+                    jobs.push(GridJob {
+                        idx: grid_idx,
+                        grid,
+                        first,
+                        offset,
+                        grid_max,
+                        volume,
+                        surface: has_surface(first, last),
+                    });
+                }
+            }
+        }
+
+        // Mesh all grids in parallel, storing corresponding mesh
+        let voxel_size = self.settings_voxels.voxel_size;
+        let algorithm = self.settings_voxels.meshing_algorithm;
+        let meshes: Vec<(usize, GridMesh)> = jobs
+            .into_par_iter()
+            .map(|job| {
+                let surface = job.surface && !self.is_canceled();
+                let mesh = match algorithm {
+                    MeshingAlgorithm::SurfaceNets => {
+                        let mut buffer = SurfaceNetsBuffer::default();
+                        if surface {
+                            surface_nets(
+                                job.grid.as_ref(),
+                                &IslandChunkSize {},
+                                [0; 3],
+                                job.grid_max,
+                                &mut buffer,
+                            );
+                        }
+                        mesh_from_nets(buffer, voxel_size, job.offset)
+                    }
+                    MeshingAlgorithm::MarchingCubes if surface => {
+                        // Grids overlap, so only march the cells up to where the next grid begins
+                        let cells = job
+                            .grid_max
+                            .map(|max| (max as usize).min(VOLUME_MAX_CELLS - 2) + 1);
+                        mesh_from_marching_cubes(
+                            &voxels.crop(job.first, cells),
+                            0.0,
+                            voxel_size,
+                            job.offset,
+                        )
+                    }
+                    MeshingAlgorithm::MarchingCubes => None,
+                };
+
+                (
+                    job.idx,
+                    GridMesh {
+                        mesh,
+                        volume: job.volume,
+                    },
+                )
+            })
+            .collect();
+
+        let mut grids: Vec<Option<GridMesh>> = vec![None; grid_count];
+        for (idx, grid) in meshes {
+            grids[idx] = Some(grid);
+        }
+        grids
     }
 
     pub fn bake_mesh(&mut self) {
@@ -682,7 +1776,9 @@ impl Data {
         self.bake_preview();
         if let Some(mut mesh) = self.mesh_preview.clone() {
             mesh.optimize(self.settings_mesh.vertex_merge_distance); // Combine and smooth out mesh
+            mesh.material_index = self.bake_material_indices(&mesh); // Assign materials by shape
             mesh.bake_normals_smooth(); // Bake weighted normals
+            mesh.debug_validate();
             mesh.bake_raycast_planes(); // Bake planes for faster raycasting
 
             let thread_count = utils::thread_count(16);
@@ -692,6 +1788,7 @@ impl Data {
                 mesh.get_ambient_occlusion(
                     self.settings_mesh.ao_samples as usize,
                     self.settings_mesh.ao_radius,
+                    self.settings_mesh.ao_cone_angle.to_radians(),
                     self.noise_mask.seed(),
                     thread_count,
                 )
@@ -699,130 +1796,2107 @@ impl Data {
                 vec![]
             };
 
-            let mut colors: Vec<Vec4> = Vec::with_capacity(mesh.count_vertices());
-            let mut uv1: Vec<Vec2> = Vec::with_capacity(mesh.count_vertices());
-            let mut uv2: Vec<Vec2> = Vec::with_capacity(mesh.count_vertices());
-
-            for (idx, position) in mesh.positions.iter().enumerate() {
-                let normal = mesh.normals[idx];
-
-                uv1.push(Vec2::new(position.x + position.z, position.y));
-                uv2.push(Vec2::new(position.x, position.z));
-
-                let dot = normal.dot(Vec3::Y);
-                let mask_dirt = dot
-                    .remap(
-                        self.settings_mesh.mask_dirt_minimum,
-                        self.settings_mesh.mask_dirt_maximum,
-                        0.0,
-                        1.0,
-                    )
-                    .powf(self.settings_mesh.mask_dirt_exponent)
-                    .clamp(0.0, 1.0);
-                let mask_sand = dot
-                    .remap(
-                        self.settings_mesh.mask_sand_minimum,
-                        self.settings_mesh.mask_sand_maximum,
-                        0.0,
-                        1.0,
-                    )
-                    .powf(self.settings_mesh.mask_sand_exponent)
-                    .clamp(0.0, 1.0);
+            // bake directional shadows
+            if self.settings_mesh.shadow_enabled {
+                mesh.custom0 = Some(mesh.directional_occlusion(
+                    self.settings_mesh.shadow_direction,
+                    self.settings_mesh.shadow_samples as usize,
+                ));
+            }
 
-                let noise = self
-                    .noise_mask
-                    .sample(Vec4::from((*position, self.tweaks.w_striation as f32)));
+            // bake curvature-based weathering
+            mesh.custom1 = Some(
+                mesh.mean_curvature()
+                    .into_iter()
+                    .map(|curvature| self.mask_weathering(curvature))
+                    .collect(),
+            );
 
-                let mut occlusion: f32 = 1.0;
-                if self.settings_mesh.ao_enabled {
-                    occlusion = glam::FloatExt::lerp(1.0, ao[idx], self.settings_mesh.ao_strength);
-                }
+            // bake height-based snow
+            let bounds = mesh.bounding_box();
+            let height = bounds.size().y.max(f32::EPSILON);
+            mesh.custom2 = Some(
+                mesh.positions
+                    .iter()
+                    .map(|position| self.mask_snow((position.y - bounds.minimum.y) / height))
+                    .collect(),
+            );
 
-                colors.push(Vec4::new(occlusion, mask_dirt, mask_sand, noise as f32));
-            }
+            // Project each face onto the plane it faces most directly,
+            // splitting vertices between faces on different planes so textures don't tear across them
+            let (ao, uv_planes) =
+                if self.settings_mesh.uv_projection == UvProjection::TriplanarSplit {
+                    let face_planes: Vec<usize> = mesh
+                        .triangles
+                        .iter()
+                        .map(|tri| {
+                            self.sampling_space
+                                .transform_vector3(tri.normal(&mesh.positions))
+                                .abs()
+                                .max_position()
+                        })
+                        .collect();
+                    let sources = mesh.split_by_group(&face_planes);
+
+                    let mut uv_planes = vec![0; mesh.positions.len()];
+                    for (tri, plane) in mesh.triangles.iter().zip(face_planes) {
+                        for idx in tri.iter() {
+                            uv_planes[*idx] = plane;
+                        }
+                    }
+                    let ao = if ao.is_empty() {
+                        ao
+                    } else {
+                        sources.iter().map(|source| ao[*source]).collect()
+                    };
+                    (ao, uv_planes)
+                } else {
+                    (ao, vec![])
+                };
+
+            let (colors, uv1, uv2) = self.bake_vertex_attributes(&mesh, &ao, &uv_planes);
 
             mesh.colors = colors;
             mesh.uv1 = Some(uv1);
             mesh.uv2 = Some(uv2);
+            // bake tangents for normal mapping along the UV1 projection
+            mesh.bake_tangents();
             self.mesh_baked = Some(mesh);
         }
     }
 
+    /// Assigns each vertex of the mesh the material index of its nearest union shape, in parallel,
+    /// using the same nearest-shape logic as collision hull generation.
+    /// Returns `None` if every shape uses the default material, so the mesh stays a single surface.
+    fn bake_material_indices(&self, mesh: &TriangleMesh) -> Option<Vec<u32>> {
+        if self.shapes.iter().all(|shape| shape.material_index == 0) {
+            return None;
+        }
+
+        let mut shapes = self.shapes.clone();
+        shapes.retain(|shape| shape.operation.is_union());
+
+        Some(
+            mesh.positions
+                .par_iter()
+                .map(|position| {
+                    self.nearest_shape(&shapes, *position)
+                        .map_or(0, |idx| shapes[idx].material_index)
+                })
+                .collect(),
+        )
+    }
+
+    /// Computes vertex colors and UV projections for every vertex on the given mesh, in parallel.
+    /// Requires vertex normals to be baked beforehand.
+    /// If the ambient occlusion list is empty, occlusion defaults to 1.0.
+    /// If the UV plane list is empty, UV1 wraps around the island instead. See [Self::bake_vertex].
+    fn bake_vertex_attributes(
+        &self,
+        mesh: &TriangleMesh,
+        ao: &[f32],
+        uv_planes: &[usize],
+    ) -> (Vec<Vec4>, Vec<Vec2>, Vec<Vec2>) {
+        let (colors, (uv1, uv2)): (Vec<Vec4>, (Vec<Vec2>, Vec<Vec2>)) = mesh
+            .positions
+            .par_iter()
+            .enumerate()
+            .map(|(idx, position)| {
+                let (color, uv1, uv2) = self.bake_vertex(
+                    *position,
+                    mesh.normals[idx],
+                    ao.get(idx).copied(),
+                    uv_planes.get(idx).copied(),
+                );
+                (color, (uv1, uv2))
+            })
+            .unzip();
+
+        (colors, uv1, uv2)
+    }
+
+    /// Computes the vertex color, UV1 and UV2 for a single vertex.
+    /// If a UV plane is given, UV1 is projected onto it, for the YZ, XZ or XY plane in that order.
+    /// Otherwise, UVs follow the UV projection setting. See [UvProjection].
+    fn bake_vertex(
+        &self,
+        position: Vec3,
+        normal: Vec3,
+        ao: Option<f32>,
+        uv_plane: Option<usize>,
+    ) -> (Vec4, Vec2, Vec2) {
+        let position = self.sampling_space.transform_point3(position);
+        let uv_scale = self.settings_mesh.uv_triplanar_scale;
+        let (uv1, uv2) = if let Some(plane) = uv_plane {
+            (
+                triplanar_projection(position, uv_scale)[plane],
+                Vec2::new(position.x, position.z),
+            )
+        } else if self.settings_mesh.uv_projection == UvProjection::Triplanar {
+            let [yz, xz, _] = triplanar_projection(position, uv_scale);
+            (yz, xz)
+        } else {
+            (
+                Vec2::new(position.x + position.z, position.y),
+                Vec2::new(position.x, position.z),
+            )
+        };
+
+        // Masks are clamped before applying exponents, so negative values don't become NaN
+        let dot = normal.dot(Vec3::Y);
+        let mask_dirt = dot
+            .remap(
+                self.settings_mesh.mask_dirt_minimum,
+                self.settings_mesh.mask_dirt_maximum,
+                0.0,
+                1.0,
+            )
+            .clamp(0.0, 1.0)
+            .powf(self.settings_mesh.mask_dirt_exponent);
+        let mask_sand = dot
+            .remap(
+                self.settings_mesh.mask_sand_minimum,
+                self.settings_mesh.mask_sand_maximum,
+                0.0,
+                1.0,
+            )
+            .clamp(0.0, 1.0)
+            .powf(self.settings_mesh.mask_sand_exponent);
+
+        let noise = self
+            .noise_mask
+            .sample(Vec4::from((position, self.tweaks.w_striation as f32)));
+
+        let mut occlusion: f32 = 1.0;
+        if let Some(ao) = ao {
+            occlusion = glam::FloatExt::lerp(1.0, ao, self.settings_mesh.ao_strength);
+        }
+
+        (
+            Vec4::new(occlusion, mask_dirt, mask_sand, noise as f32),
+            uv1,
+            uv2,
+        )
+    }
+
+    /// Computes the weathering mask for a vertex with the given mean curvature.
+    /// Returns 1.0 in concave crevices, fading to 0.0 on convex ridges.
+    fn mask_weathering(&self, curvature: f32) -> f32 {
+        curvature
+            .remap(
+                self.settings_mesh.mask_curvature_maximum,
+                self.settings_mesh.mask_curvature_minimum,
+                0.0,
+                1.0,
+            )
+            .clamp(0.0, 1.0)
+            .powf(self.settings_mesh.mask_curvature_exponent)
+            .clamp(0.0, 1.0)
+    }
+
+    /// Computes the snow mask for a vertex at the given normalized height within the island's bounding box.
+    /// Returns 0.0 below the snow threshold, fading to 1.0 over the falloff.
+    fn mask_snow(&self, height: f32) -> f32 {
+        let threshold = self.settings_mesh.mask_snow_threshold;
+        let falloff = self.settings_mesh.mask_snow_falloff;
+        if falloff <= 0.0 {
+            return if height >= threshold { 1.0 } else { 0.0 };
+        }
+        ((height - threshold) / falloff).clamp(0.0, 1.0)
+    }
+
     pub fn bake_collision(&mut self) {
-        if !self.hulls.is_empty() {
+        self.bake_collision_lod(CollisionLod::Full);
+    }
+
+    /// Bakes collision hulls for the given level of detail, if able.
+    pub fn bake_collision_lod(&mut self, lod: CollisionLod) {
+        if !self.get_hulls_lod(lod).is_empty() {
             return;
         }
 
-        if let Some(mut mesh) = self.mesh_preview.clone() {
-            // Get a list of all union shapes
-            let mut shapes = self.shapes.clone();
-            shapes.retain(|shape| shape.operation == ShapeOperation::Union);
-
-            if shapes.is_empty() {
-                return;
+        let (hulls, shapes) = self.generate_collision_hulls(lod);
+        match lod {
+            CollisionLod::Full => {
+                self.hulls = hulls;
+                self.hull_shapes = shapes;
             }
+            CollisionLod::Coarse => {
+                self.hulls_coarse = hulls;
+                self.hull_shapes_coarse = shapes;
+            }
+        }
+    }
 
-            // Join mesh and merge by distance before splitting into shapes,
-            // to help with edge decimation and prevent vertex merging causing issues on corners
-            mesh.optimize(self.settings_collision.vertex_merge_distance);
+    /// Returns the index of the baked collision hull containing the given point, if any hulls are baked.
+    /// Uses the same nearest-shape logic as collision hull generation. See [Self::hull_index_at_lod].
+    pub fn hull_index_at(&self, point: Vec3) -> Option<usize> {
+        self.hull_index_at_lod(point, CollisionLod::Full)
+    }
 
-            let mut hulls: Vec<TriangleMesh> = Vec::with_capacity(shapes.len());
-            let tri_prealloc = mesh.triangles.len(); // At most, we can hold this many triangles
+    /// Returns the index of the baked collision hull for the given level of detail containing the given point.
+    /// The point is assigned to the hull whose source shape has the smallest Signed Distance at the point,
+    /// matching how triangles are assigned to hulls during generation.
+    /// Returns `None` if no hulls are baked.
+    pub fn hull_index_at_lod(&self, point: Vec3, lod: CollisionLod) -> Option<usize> {
+        let shapes = match lod {
+            CollisionLod::Full => &self.hull_shapes,
+            CollisionLod::Coarse => &self.hull_shapes_coarse,
+        };
+        self.nearest_shape(shapes, point)
+    }
 
-            // Generate each triangle mesh with our original mesh positions
-            for _ in shapes.iter() {
-                let trimesh = TriangleMesh::new(
-                    Vec::with_capacity(tri_prealloc),
-                    mesh.positions.clone(),
-                    None,
-                    None,
-                );
+    /// Returns the shape list to generate collision from, with any collision operation overrides applied.
+    fn collision_shapes(&self) -> Vec<Shape> {
+        self.shapes
+            .iter()
+            .map(|shape| {
+                let mut shape = *shape;
+                shape.operation = shape.operation_for_collision();
+                shape
+            })
+            .collect()
+    }
 
-                hulls.push(trimesh);
+    /// Dilates the given voxels by the collision skin, then restores the voxel padding,
+    /// so the dilated surface stays closed at the borders of the volume.
+    fn dilate_skin(&self, voxels: &mut VolumeData<f32>) {
+        voxels.dilate(self.settings_collision.skin_voxels as usize);
+        voxels.set_padding(self.settings_voxels.voxel_padding as usize, 10.0);
+    }
+
+    /// Returns the mesh to generate collision hulls from.
+    /// If a collision skin is set, the voxels are dilated and re-meshed, otherwise the preview mesh is used.
+    /// If any shapes override their collision operation, a separate collision field is sampled and meshed instead.
+    fn collision_source_mesh(&self) -> Option<TriangleMesh> {
+        let skin = self.settings_collision.skin_voxels as usize;
+
+        if self
+            .shapes
+            .iter()
+            .any(|shape| shape.operation_for_collision() != shape.operation)
+        {
+            let mut collision = self.clone_for_preview();
+            collision.shapes = self.collision_shapes();
+            collision.bake_bounding_box();
+            collision.bake_voxels();
+            let mut voxels = collision.voxels.take()?;
+            self.dilate_skin(&mut voxels);
+            return Some(collision.mesh_voxels(&voxels, collision.bounds.minimum).0);
+        }
+
+        match (&self.voxels, &self.mesh_preview) {
+            (Some(voxels), Some(_)) if skin > 0 => {
+                let mut dilated = voxels.clone();
+                self.dilate_skin(&mut dilated);
+                Some(self.mesh_voxels(&dilated, self.bounds.minimum).0)
             }
+            (_, preview) => preview.clone(),
+        }
+    }
 
-            // Assign each triangle to the nearest collision hull
-            for tri in mesh.triangles.iter() {
-                let mut min_dist = f32::INFINITY;
-                let mut min_shape_idx = 0;
+    /// Generates collision hulls from the preview mesh for the given level of detail.
+    /// Returns an empty list if there is no preview mesh or no union shapes.
+    /// Returns the source shape of each hull alongside the hulls.
+    fn generate_collision_hulls(&self, lod: CollisionLod) -> (Vec<TriangleMesh>, Vec<Shape>) {
+        if let Some(mut mesh) = self.collision_source_mesh() {
+            // Get a list of all union shapes
+            let mut shapes = self.collision_shapes();
+            shapes.retain(|shape| shape.operation.is_union());
+
+            // Only keep the largest shapes for coarse collision
+            if lod == CollisionLod::Coarse {
+                let volume = |shape: &Shape| (shape.transform() * shape.relative_bounds()).volume();
+                shapes.sort_by(|a, b| volume(b).total_cmp(&volume(a)));
+                shapes.truncate(self.settings_collision.lod_max_hulls.max(1) as usize);
+            }
 
-                // Fetch centerpoint of triangle to use for comparison
-                let center = tri.centerpoint(&mesh.positions);
+            if shapes.is_empty() {
+                return (vec![], vec![]);
+            }
 
-                for (shape_idx, shape) in shapes.iter().enumerate() {
-                    // TODO: somehow take Intersection CSG into account when sampling shapes,
-                    // so collision shapes that are cut off via intersections,
-                    // do not include shapes added after said intersection.
+            // Join mesh and merge by distance before splitting into shapes,
+            // to help with edge decimation and prevent vertex merging causing issues on corners
+            mesh.optimize(self.settings_collision.vertex_merge_distance);
+            mesh.debug_validate();
 
-                    let d = shape.sample(center, self.settings_voxels.sdf_edge_radius);
-                    if d < min_dist {
-                        min_dist = d;
-                        min_shape_idx = shape_idx;
-                    }
-                }
+            // A single shape holds every triangle, so skip the nearest-shape search
+            let assignment = if shapes.len() == 1 {
+                vec![mesh.triangles.clone()]
+            } else {
+                self.assign_hull_triangles(&mesh, &shapes)
+            };
 
-                hulls[min_shape_idx].triangles.push(*tri);
-            }
+            // Generate each triangle mesh with our original mesh positions
+            let mut hulls: Vec<TriangleMesh> = assignment
+                .into_iter()
+                .map(|triangles| TriangleMesh::new(triangles, mesh.positions.clone(), None, None))
+                .collect();
 
             // Optimize collision meshes in parallel
+            let decimation_angle = match lod {
+                CollisionLod::Full => self.settings_collision.decimation_angle,
+                CollisionLod::Coarse => self.settings_collision.lod_decimation_angle,
+            };
+            let max_edge_length = match self.settings_collision.decimation_max_edge_length {
+                length if length > 0.0 => length,
+                _ => f32::INFINITY,
+            };
             hulls.par_iter_mut().for_each(|mesh| {
-                if self.settings_collision.decimation_angle > 0.0 {
+                if decimation_angle > 0.0 {
                     mesh.decimate_planar(
-                        self.settings_collision.decimation_angle.to_radians(),
+                        decimation_angle.to_radians(),
                         self.settings_collision.decimation_iterations,
                         self.settings_collision.decimation_dropout,
+                        max_edge_length,
                     );
                 }
 
                 // Optimize the mesh again after decimation,
                 // but don't worry about merging loose vertices
                 mesh.optimize(0.0);
+                mesh.debug_validate();
             });
 
             // Remove hulls with an insignificant triangle count
-            hulls.retain(|hull| hull.triangles.len() >= 6);
+            return hulls
+                .into_iter()
+                .zip(shapes)
+                .filter(|(hull, _)| hull.triangles.len() >= 6)
+                .unzip();
+        }
+
+        (vec![], vec![])
+    }
 
-            self.hulls = hulls;
+    /// Bakes collision hulls by approximate convex decomposition of the whole collision mesh,
+    /// instead of assigning triangles to the nearest union shape.
+    /// This is better suited to heavily overlapping shapes, and concave shapes carved by subtractions.
+    /// See [TriangleMesh::convex_decompose] for how `max_hulls` and `concavity` are applied.
+    ///
+    /// Hulls are not associated with any source shape, so [Self::hull_index_at] returns `None` afterward.
+    pub fn bake_collision_vhacd(&mut self, max_hulls: usize, concavity: f32) {
+        self.hulls = self.generate_collision_vhacd(max_hulls, concavity);
+        self.hull_shapes.clear();
+    }
+
+    /// Generates collision hulls by convex decomposition of the collision source mesh.
+    /// Returns an empty list if there is no preview mesh.
+    fn generate_collision_vhacd(&self, max_hulls: usize, concavity: f32) -> Vec<TriangleMesh> {
+        let Some(mut mesh) = self.collision_source_mesh() else {
+            return vec![];
+        };
+
+        // Decomposition expects welded vertices and outward faces
+        mesh.optimize(self.settings_collision.vertex_merge_distance);
+        mesh.orient_outward();
+        mesh.debug_validate();
+
+        let mut hulls = mesh.convex_decompose(max_hulls.max(1), concavity);
+        hulls.par_iter_mut().for_each(|hull| {
+            // Pieces are triangle soups, so weld them to reduce hull points
+            hull.optimize(self.settings_collision.vertex_merge_distance);
+        });
+
+        // Remove pieces too small to form a hull
+        hulls.retain(|hull| hull.positions.len() >= 4);
+        hulls
+    }
+
+    /// Assigns each triangle of the mesh to the nearest of the given shapes,
+    /// returning a list of triangles for each shape.
+    fn assign_hull_triangles(&self, mesh: &TriangleMesh, shapes: &[Shape]) -> Vec<Vec<Triangle>> {
+        let tri_prealloc = mesh.triangles.len(); // At most, we can hold this many triangles
+        let mut assignment: Vec<Vec<Triangle>> =
+            vec![Vec::with_capacity(tri_prealloc); shapes.len()];
+
+        for tri in mesh.triangles.iter() {
+            // Fetch centerpoint of triangle to use for comparison
+            let center = tri.centerpoint(&mesh.positions);
+            let min_shape_idx = self.nearest_shape(shapes, center).unwrap_or(0);
+            assignment[min_shape_idx].push(*tri);
         }
+
+        assignment
+    }
+
+    /// Returns the index of the shape with the smallest Signed Distance at the given point,
+    /// or `None` if there are no shapes.
+    fn nearest_shape(&self, shapes: &[Shape], point: Vec3) -> Option<usize> {
+        // TODO: somehow take Intersection CSG into account when sampling shapes,
+        // so collision shapes that are cut off via intersections,
+        // do not include shapes added after said intersection.
+        let edge_radius = self.settings_voxels.edge_radius();
+        shapes
+            .iter()
+            .map(|shape| shape.sample(point, edge_radius.for_operation(shape.operation)))
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(idx, _)| idx)
+    }
+}
+
+// UNIT TESTS //
+#[cfg(test)]
+mod tests {
+    use super::{
+        CollisionLod, Data, HULL_DEBUG_PALETTE_SIZE, IslandSettingsSnapshot, MeshingAlgorithm,
+        SettingsMesh, SettingsTweaks, SettingsVoxels, UvProjection, heatmap_color,
+        hull_debug_color,
+    };
+    use crate::math::bounding_box::BoundingBox;
+    use crate::math::delta::{assert_in_delta, assert_in_delta_vector};
+    use crate::math::noise::NoiseSource;
+    use crate::math::sdf::{Shape, ShapeOperation, sample_shape_list};
+    use crate::math::volumetric::VolumeData;
+    use crate::mesh::trimesh::{TriangleMesh, TriangleOperations, triplanar_projection};
+    use glam::{Mat4, Vec2, Vec3, Vec4};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Returns island data with default settings, baked from the given shapes up to the preview mesh.
+    fn baked_island(shapes: Vec<Shape>) -> Data {
+        baked_island_with(Data::default(), shapes)
+    }
+
+    /// Bakes the given island data from the given shapes up to the preview mesh, keeping its settings.
+    fn baked_island_with(mut data: Data, shapes: Vec<Shape>) -> Data {
+        data.set_shapes(shapes);
+        data.bake_bounding_box();
+        data.bake_voxels();
+        data.bake_preview();
+        data
+    }
+
+    #[test]
+    fn test_parallel_vertex_bake() {
+        let data = baked_island(vec![
+            Shape::sphere(Mat4::IDENTITY, 3.0, ShapeOperation::Union),
+            Shape::torus(
+                Mat4::from_translation(Vec3::Y),
+                0.75,
+                5.0,
+                ShapeOperation::Union,
+            ),
+        ]);
+
+        let mut mesh = data
+            .get_mesh_preview()
+            .expect("preview mesh should bake")
+            .clone();
+        mesh.bake_normals_smooth();
+        assert!(
+            mesh.positions.len() > 1000,
+            "mesh should be reasonably dense"
+        );
+
+        let ao: Vec<f32> = (0..mesh.positions.len())
+            .map(|idx| (idx % 17) as f32 / 16.0)
+            .collect();
+        let uv_planes: Vec<usize> = (0..mesh.positions.len()).map(|idx| idx % 3).collect();
+
+        for (ao, uv_planes) in [(vec![], vec![]), (ao, uv_planes)] {
+            let mut colors: Vec<Vec4> = vec![];
+            let mut uv1: Vec<Vec2> = vec![];
+            let mut uv2: Vec<Vec2> = vec![];
+            for (idx, position) in mesh.positions.iter().enumerate() {
+                let (color, u1, u2) = data.bake_vertex(
+                    *position,
+                    mesh.normals[idx],
+                    ao.get(idx).copied(),
+                    uv_planes.get(idx).copied(),
+                );
+                colors.push(color);
+                uv1.push(u1);
+                uv2.push(u2);
+            }
+
+            let (par_colors, par_uv1, par_uv2) =
+                data.bake_vertex_attributes(&mesh, &ao, &uv_planes);
+            // Compare bit patterns, so NaN values are still considered identical
+            let to_bits = |colors: &[Vec4]| -> Vec<[u32; 4]> {
+                colors
+                    .iter()
+                    .map(|c| c.to_array().map(f32::to_bits))
+                    .collect()
+            };
+            assert_eq!(
+                to_bits(&colors),
+                to_bits(&par_colors),
+                "parallel colors should match serial"
+            );
+            assert_eq!(uv1, par_uv1, "parallel UV1 should match serial");
+            assert_eq!(uv2, par_uv2, "parallel UV2 should match serial");
+        }
+    }
+
+    #[test]
+    fn test_bake_region() {
+        let mut data = baked_island(vec![
+            Shape::sphere(Mat4::IDENTITY, 3.0, ShapeOperation::Union),
+            Shape::torus(
+                Mat4::from_translation(Vec3::Y),
+                0.75,
+                5.0,
+                ShapeOperation::Union,
+            ),
+        ]);
+        let full = data
+            .get_mesh_preview()
+            .expect("preview mesh should bake")
+            .clone();
+
+        let region = BoundingBox::new(Vec3::new(1.0, -1.0, -2.0), Vec3::new(5.0, 2.0, 2.0));
+        let partial = data
+            .bake_region(region)
+            .expect("region intersecting island should bake");
+        assert!(
+            !partial.positions.is_empty(),
+            "region mesh should contain geometry"
+        );
+        assert!(
+            partial.positions.len() < full.positions.len(),
+            "region mesh should be smaller than the full mesh"
+        );
+
+        let voxel = data.settings_voxels.voxel_size.max_element();
+        let outer = region.expand_margin(voxel * 2.0);
+        let inner = region.expand_margin(voxel * -2.0);
+        for position in partial.positions.iter() {
+            assert!(
+                position.cmpge(outer.minimum).all() && position.cmple(outer.maximum).all(),
+                "region vertex {position} should be within the requested bounds"
+            );
+
+            if position.cmpge(inner.minimum).all() && position.cmple(inner.maximum).all() {
+                let nearest = full
+                    .positions
+                    .iter()
+                    .map(|p| p.distance(*position))
+                    .fold(f32::INFINITY, f32::min);
+                assert!(
+                    nearest < 1e-3,
+                    "region vertex {position} should match the full bake, nearest was {nearest}"
+                );
+            }
+        }
+
+        assert!(
+            data.bake_region(region.translate(Vec3::splat(100.0)))
+                .is_none(),
+            "region outside of island bounds should not bake"
+        );
+    }
+
+    #[test]
+    fn test_collision_lod() {
+        let mut data = baked_island(
+            (0..6)
+                .map(|i| {
+                    Shape::rounded_box(
+                        Mat4::from_translation(Vec3::new(i as f32 * 2.5, 0.0, 0.0)),
+                        Vec3::new(3.0, 2.0 + i as f32 * 0.3, 3.0),
+                        0.5,
+                        ShapeOperation::Union,
+                    )
+                })
+                .collect(),
+        );
+        data.bake_collision_lod(CollisionLod::Full);
+        data.bake_collision_lod(CollisionLod::Coarse);
+
+        let full = data.get_hulls_lod(CollisionLod::Full);
+        let coarse = data.get_hulls_lod(CollisionLod::Coarse);
+        assert!(!coarse.is_empty(), "coarse collision should bake");
+        assert!(
+            coarse.len() <= data.settings_collision.lod_max_hulls as usize,
+            "coarse collision should have at most {} hulls, got {}",
+            data.settings_collision.lod_max_hulls,
+            coarse.len()
+        );
+        assert!(
+            coarse.len() < full.len(),
+            "coarse collision should have fewer hulls than full collision"
+        );
+
+        let triangles = |hulls: &[TriangleMesh]| -> usize {
+            hulls.iter().map(|hull| hull.triangles.len()).sum()
+        };
+        assert!(
+            triangles(coarse) < triangles(full),
+            "coarse collision should have fewer triangles ({}) than full collision ({})",
+            triangles(coarse),
+            triangles(full)
+        );
+
+        data.dirty_collision();
+        assert!(
+            data.get_hulls_lod(CollisionLod::Coarse).is_empty(),
+            "dirtying collision should clear coarse hulls"
+        );
+    }
+
+    #[test]
+    fn test_single_shape_collision() {
+        let shape = Shape::rounded_box(
+            Mat4::from_rotation_y(0.4),
+            Vec3::new(4.0, 2.0, 3.0),
+            0.5,
+            ShapeOperation::Union,
+        );
+        let mut data = baked_island(vec![
+            shape,
+            Shape::sphere(
+                Mat4::from_translation(Vec3::new(0.0, 1.0, 0.0)),
+                0.5,
+                ShapeOperation::Subtraction,
+            ),
+        ]);
+        data.bake_collision();
+
+        let hulls = data.get_hulls_lod(CollisionLod::Full);
+        assert_eq!(
+            1,
+            hulls.len(),
+            "single union shape should bake a single hull"
+        );
+
+        // The general nearest-shape assignment should give every triangle to the one shape
+        let mut mesh = data
+            .collision_source_mesh()
+            .expect("collision source mesh should exist");
+        mesh.optimize(data.settings_collision.vertex_merge_distance);
+        let assignment = data.assign_hull_triangles(&mesh, &[shape]);
+        assert_eq!(
+            vec![mesh.triangles.clone()],
+            assignment,
+            "general path should assign every triangle to the single hull"
+        );
+    }
+
+    #[test]
+    fn test_marching_cubes() {
+        let bake = |algorithm: MeshingAlgorithm| -> TriangleMesh {
+            let mut data = Data::default();
+            data.set_voxel_settings(SettingsVoxels {
+                meshing_algorithm: algorithm,
+                ..Default::default()
+            });
+            // Large enough to span several meshing grids
+            let data = baked_island_with(
+                data,
+                vec![Shape::sphere(Mat4::IDENTITY, 8.0, ShapeOperation::Union)],
+            );
+            let mut mesh = data
+                .get_mesh_preview()
+                .expect("preview mesh should bake")
+                .clone();
+            mesh.optimize(1e-4);
+            mesh
+        };
+
+        let nets = bake(MeshingAlgorithm::SurfaceNets);
+        let cubes = bake(MeshingAlgorithm::MarchingCubes);
+        cubes.debug_validate();
+
+        let report = cubes.manifold_report();
+        assert_eq!(
+            0, report.boundary_edges,
+            "grid seams should be welded closed"
+        );
+        assert!(
+            cubes.signed_volume() < 0.0,
+            "marching cubes should be wound like surface nets"
+        );
+        assert_in_delta(
+            nets.signed_volume(),
+            cubes.signed_volume(),
+            nets.signed_volume().abs() * 0.05,
+            "marching cubes should enclose the same volume as surface nets".to_string(),
+        );
+    }
+
+    #[test]
+    fn test_triplanar_uvs() {
+        let bake = |uv_projection: UvProjection| -> (TriangleMesh, Mat4) {
+            let mut data = Data::default();
+            data.set_mesh_settings(SettingsMesh {
+                uv_projection,
+                uv_triplanar_scale: 0.5,
+                ..Default::default()
+            });
+            let mut data = baked_island_with(
+                data,
+                vec![Shape::rounded_box(
+                    Mat4::IDENTITY,
+                    Vec3::new(6.0, 3.0, 4.0),
+                    0.2,
+                    ShapeOperation::Union,
+                )],
+            );
+            data.bake_mesh();
+            let mesh = data.get_mesh_baked().expect("mesh should be baked");
+            (mesh.clone(), data.get_sampling_space())
+        };
+
+        // Blended projections keep every plane available to shaders
+        let (mesh, space) = bake(UvProjection::Triplanar);
+        let uv1 = mesh.uv1.as_ref().expect("UV1 should be baked");
+        let uv2 = mesh.uv2.as_ref().expect("UV2 should be baked");
+        let weights = mesh.triplanar_weights(4.0);
+        let mut side_vertices: usize = 0;
+        for (idx, position) in mesh.positions.iter().enumerate() {
+            let [yz, xz, xy] = triplanar_projection(space.transform_point3(*position), 0.5);
+            assert_eq!(
+                yz, uv1[idx],
+                "UV1 of vertex {idx} should be the YZ projection"
+            );
+            assert_eq!(
+                xz, uv2[idx],
+                "UV2 of vertex {idx} should be the XZ projection"
+            );
+            assert_eq!(
+                xy,
+                Vec2::new(uv2[idx].x, uv1[idx].y),
+                "XY projection of vertex {idx} should be recoverable from UV1 and UV2"
+            );
+
+            // Vertices on +X-facing sides take their dominant UV from the YZ plane
+            if mesh.normals[idx].x > 0.9 {
+                side_vertices += 1;
+                assert_eq!(
+                    0,
+                    weights[idx].max_position(),
+                    "vertex {idx} should be dominated by the YZ plane, weights were {0}",
+                    weights[idx]
+                );
+            }
+        }
+        assert!(side_vertices > 0, "box should have +X-facing vertices");
+
+        // Split projections pick one plane per face
+        let (mesh, space) = bake(UvProjection::TriplanarSplit);
+        let uv1 = mesh.uv1.as_ref().expect("UV1 should be baked");
+        let uv2 = mesh.uv2.as_ref().expect("UV2 should be baked");
+        for (idx, position) in mesh.positions.iter().enumerate() {
+            let position = space.transform_point3(*position);
+            assert_eq!(
+                Vec2::new(position.x, position.z),
+                uv2[idx],
+                "UV2 should stay top-down"
+            );
+        }
+
+        // Every corner of a face projects onto the plane the face faces most directly
+        let mut side_faces: usize = 0;
+        for (face, tri) in mesh.triangles.iter().enumerate() {
+            let normal = space.transform_vector3(tri.normal(&mesh.positions));
+            let plane = normal.abs().max_position();
+            for idx in tri.iter() {
+                let position = space.transform_point3(mesh.positions[*idx]);
+                assert_eq!(
+                    triplanar_projection(position, 0.5)[plane],
+                    uv1[*idx],
+                    "face {face} facing {normal} should project vertex {idx} onto plane {plane}"
+                );
+            }
+
+            // Faces on +X-facing sides take their UV from the YZ plane
+            if normal.x > 0.9 {
+                side_faces += 1;
+                assert_eq!(
+                    0, plane,
+                    "face {face} facing {normal} should use the YZ plane"
+                );
+            }
+        }
+        assert!(side_faces > 0, "box should have +X-facing faces");
+    }
+
+    #[test]
+    fn test_material_surfaces() {
+        let mut left = Shape::rounded_box(
+            Mat4::from_translation(Vec3::new(-2.0, 0.0, 0.0)),
+            Vec3::splat(3.0),
+            0.2,
+            ShapeOperation::Union,
+        );
+        left.material_index = 2;
+        let mut right = Shape::rounded_box(
+            Mat4::from_translation(Vec3::new(2.0, 0.0, 0.0)),
+            Vec3::splat(3.0),
+            0.2,
+            ShapeOperation::Union,
+        );
+        right.material_index = 5;
+
+        let mut data = baked_island(vec![left, right]);
+        data.bake_mesh();
+
+        let mesh = data.get_mesh_baked().expect("mesh should be baked");
+        assert!(
+            mesh.material_index.is_some(),
+            "material indices should be baked"
+        );
+
+        let surfaces = mesh.split_by_material();
+        assert_eq!(
+            vec![2, 5],
+            surfaces
+                .iter()
+                .map(|(material, _)| *material)
+                .collect::<Vec<u32>>(),
+            "each box should produce its own surface"
+        );
+        assert_eq!(
+            mesh.triangles.len(),
+            surfaces
+                .iter()
+                .map(|(_, surface)| surface.triangles.len())
+                .sum::<usize>(),
+            "surfaces should hold every triangle"
+        );
+
+        for (material, surface) in surfaces.iter() {
+            surface.debug_validate();
+            let center = surface.bounding_box().center();
+            let side = if *material == 2 { -1.0 } else { 1.0 };
+            assert!(
+                center.x * side > 0.5,
+                "surface for material {material} should be on its box's side, centered at {center}"
+            );
+        }
+
+        // Islands without material indices stay as a single surface
+        data.set_shapes(vec![Shape::sphere(
+            Mat4::IDENTITY,
+            1.0,
+            ShapeOperation::Union,
+        )]);
+        data.bake_bounding_box();
+        data.bake_voxels();
+        data.bake_mesh();
+        let mesh = data.get_mesh_baked().expect("mesh should be baked");
+        assert!(mesh.material_index.is_none());
+        assert_eq!(1, mesh.split_by_material().len());
+    }
+
+    #[test]
+    fn test_weathering_mask() {
+        // Two overlapping spheres form a concave crease where they meet
+        let mut data = baked_island(vec![
+            Shape::sphere(
+                Mat4::from_translation(Vec3::new(-1.5, 0.0, 0.0)),
+                2.0,
+                ShapeOperation::Union,
+            ),
+            Shape::sphere(
+                Mat4::from_translation(Vec3::new(1.5, 0.0, 0.0)),
+                2.0,
+                ShapeOperation::Union,
+            ),
+        ]);
+        data.bake_mesh();
+
+        let mesh = data.get_mesh_baked().expect("mesh should bake");
+        let weathering = mesh
+            .custom1
+            .as_ref()
+            .expect("weathering mask should be baked");
+        assert_eq!(
+            mesh.positions.len(),
+            weathering.len(),
+            "should have one weathering value per vertex"
+        );
+        assert!(
+            weathering.iter().all(|value| (0.0..=1.0).contains(value)),
+            "weathering should be within 0 to 1"
+        );
+
+        // Average weathering of vertices nearest the crease, versus the convex outer caps
+        let average = |filter: &dyn Fn(Vec3) -> bool| -> f32 {
+            let values: Vec<f32> = mesh
+                .positions
+                .iter()
+                .zip(weathering.iter())
+                .filter(|(position, _)| filter(**position))
+                .map(|(_, value)| *value)
+                .collect();
+            assert!(!values.is_empty(), "filter should match some vertices");
+            values.iter().sum::<f32>() / values.len() as f32
+        };
+        let concave = average(&|position| position.x.abs() < 0.25);
+        let convex = average(&|position| position.x.abs() > 3.0);
+        assert!(
+            concave > convex,
+            "concave crease should weather more than convex caps, got {concave} and {convex}"
+        );
+    }
+
+    #[test]
+    fn test_snow_mask() {
+        let mut data = Data::default();
+        let mut settings = data.settings_mesh;
+        settings.mask_snow_threshold = 0.6;
+        settings.mask_snow_falloff = 0.1;
+        data.set_mesh_settings(settings);
+        let mut data = baked_island_with(
+            data,
+            vec![Shape::rounded_box(
+                Mat4::IDENTITY,
+                Vec3::new(2.0, 6.0, 2.0),
+                0.5,
+                ShapeOperation::Union,
+            )],
+        );
+        data.bake_mesh();
+
+        let mesh = data.get_mesh_baked().expect("mesh should bake");
+        let snow = mesh.custom2.as_ref().expect("snow mask should be baked");
+        assert_eq!(
+            mesh.positions.len(),
+            snow.len(),
+            "should have one snow value per vertex"
+        );
+
+        let bounds = mesh.bounding_box();
+        for (position, value) in mesh.positions.iter().zip(snow.iter()) {
+            let height = (position.y - bounds.minimum.y) / bounds.size().y;
+            if height >= 0.7 {
+                assert_eq!(
+                    1.0, *value,
+                    "vertex at height {height} above the threshold and falloff should be fully snowed"
+                );
+            } else if height <= 0.6 {
+                assert_eq!(
+                    0.0, *value,
+                    "vertex at height {height} below the threshold should have no snow"
+                );
+            } else {
+                assert!(
+                    (0.0..=1.0).contains(value),
+                    "vertex at height {height} within the falloff should be partially snowed"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_append_shapes() {
+        let prefab = vec![
+            Shape::sphere(Mat4::IDENTITY, 1.0, ShapeOperation::Union),
+            Shape::rounded_box(
+                Mat4::from_translation(Vec3::new(0.0, -1.0, 0.0)),
+                Vec3::new(2.0, 1.0, 2.0),
+                0.25,
+                ShapeOperation::Union,
+            ),
+        ];
+
+        let mut data = baked_island(prefab.clone());
+        let prefab_bounds = data.get_bounds();
+
+        let offset = Vec3::new(10.0, 0.0, 0.0);
+        assert!(
+            data.append_shapes(&prefab, Mat4::from_translation(offset)),
+            "shapes should be appended"
+        );
+        assert!(
+            !data.append_shapes(&[], Mat4::IDENTITY),
+            "appending nothing should not change the island"
+        );
+        assert_eq!(
+            4,
+            data.get_shapes().len(),
+            "shape list should combine both sets"
+        );
+        assert!(
+            data.debug_take_voxels().is_none(),
+            "appending shapes should dirty the voxels"
+        );
+
+        data.bake_bounding_box();
+        let bounds = data.get_bounds();
+        assert_eq!(
+            prefab_bounds.minimum, bounds.minimum,
+            "combined bounds should start at the original set"
+        );
+        assert_in_delta_vector(
+            prefab_bounds.maximum + offset,
+            bounds.maximum,
+            1e-4,
+            "combined bounds should extend to the appended set",
+        );
+
+        let edge_radius = data.settings_voxels.edge_radius();
+        for center in [Vec3::ZERO, offset] {
+            assert!(
+                sample_shape_list(data.get_shapes(), center, edge_radius) < 0.0,
+                "SDF should be solid at the center of each set, at {center}"
+            );
+        }
+        assert!(
+            sample_shape_list(data.get_shapes(), offset * 0.5, edge_radius) > 0.0,
+            "SDF should be empty between the two sets"
+        );
+    }
+
+    #[test]
+    fn test_collision_operation() {
+        let body = Shape::rounded_box(
+            Mat4::IDENTITY,
+            Vec3::new(6.0, 2.0, 6.0),
+            0.5,
+            ShapeOperation::Union,
+        );
+        // Visually fills in a notch, but carves it out of collision
+        let mut notch = Shape::sphere(
+            Mat4::from_translation(Vec3::new(0.0, 1.0, 0.0)),
+            1.5,
+            ShapeOperation::Union,
+        );
+        notch.collision_operation = Some(ShapeOperation::Subtraction);
+
+        let mut data = baked_island(vec![body, notch]);
+        data.bake_collision();
+
+        let preview = data.get_mesh_preview().expect("preview mesh should bake");
+        let collision = data
+            .collision_source_mesh()
+            .expect("collision source mesh should exist");
+        // Highest point of each mesh directly above the notch
+        let top = |mesh: &TriangleMesh| -> f32 {
+            mesh.positions
+                .iter()
+                .filter(|position| position.x.abs() < 0.5 && position.z.abs() < 0.5)
+                .map(|position| position.y)
+                .fold(f32::NEG_INFINITY, f32::max)
+        };
+        assert!(
+            top(preview) > 2.0,
+            "visual mesh should include the unioned notch, top is {}",
+            top(preview)
+        );
+        assert!(
+            top(&collision) < 1.5,
+            "collision mesh should have the notch subtracted, top is {}",
+            top(&collision)
+        );
+        assert_eq!(
+            1,
+            data.get_hulls().len(),
+            "only the body should be a collision hull"
+        );
+    }
+
+    #[test]
+    fn test_collision_vhacd() {
+        // A slab with one corner carved out, leaving an L-shape
+        let mut data = Data::default();
+        // Disable noise, so no stray pieces break off
+        let mut settings = data.settings_voxels;
+        settings.sampling_density_noise_amplitude = 0.0;
+        settings.sampling_offset_noise_amplitude = Vec3::ZERO;
+        data.set_voxel_settings(settings);
+        let mut data = baked_island_with(
+            data,
+            vec![
+                Shape::rounded_box(
+                    Mat4::IDENTITY,
+                    Vec3::new(8.0, 2.0, 8.0),
+                    0.2,
+                    ShapeOperation::Union,
+                ),
+                Shape::rounded_box(
+                    Mat4::from_translation(Vec3::new(2.0, 0.0, 2.0)),
+                    Vec3::new(4.2, 4.0, 4.2),
+                    0.2,
+                    ShapeOperation::Subtraction,
+                ),
+            ],
+        );
+        data.bake_collision_vhacd(8, 0.5);
+
+        let hulls = data.get_hulls();
+        assert!(
+            hulls.len() >= 2,
+            "L-shape should decompose into at least two hulls, got {}",
+            hulls.len()
+        );
+        let carved = Vec3::new(2.0, 0.0, 2.0);
+        for (idx, hull) in hulls.iter().enumerate() {
+            let bounds = hull.bounding_box();
+            assert!(
+                !(bounds.minimum.cmple(carved).all() && bounds.maximum.cmpge(carved).all()),
+                "hull {idx} with bounds {bounds:?} should not cover the carved region"
+            );
+        }
+        assert_eq!(
+            None,
+            data.hull_index_at(Vec3::new(-2.0, 0.0, -2.0)),
+            "decomposed hulls have no source shapes"
+        );
+    }
+
+    #[test]
+    fn test_triangle_budget() {
+        let mut data = Data::default();
+        let mut settings = data.settings_voxels;
+        settings.voxel_size = Vec3::splat(0.01);
+        settings.max_triangle_budget = 100_000;
+        data.set_voxel_settings(settings);
+        data.set_shapes(vec![Shape::sphere(
+            Mat4::IDENTITY,
+            50.0,
+            ShapeOperation::Union,
+        )]);
+        data.bake_bounding_box();
+
+        assert!(
+            data.exceeds_triangle_budget(),
+            "tiny voxels on a huge island should exceed the triangle budget, projected {}",
+            data.projected_triangle_count()
+        );
+
+        data.bake_voxels();
+        data.bake_preview();
+        assert!(
+            data.get_mesh_preview().is_none(),
+            "preview should not bake when over budget"
+        );
+        assert_eq!(
+            0,
+            data.total_memory_footprint(),
+            "no bake data should be allocated when over budget"
+        );
+
+        // Disabling the budget should allow the bake to proceed
+        settings.max_triangle_budget = 0;
+        data.set_voxel_settings(settings);
+        assert!(
+            !data.exceeds_triangle_budget(),
+            "a zero budget should disable the limit"
+        );
+
+        // Partial bakes should respect the budget too
+        settings.max_triangle_budget = 100_000;
+        data.set_voxel_settings(settings);
+        assert!(
+            data.bake_region(BoundingBox::new(Vec3::splat(-40.0), Vec3::splat(40.0)))
+                .is_none(),
+            "region should not bake when over budget"
+        );
+        assert!(
+            data.bake_region(BoundingBox::new(Vec3::splat(-0.25), Vec3::splat(0.25)))
+                .is_some(),
+            "small region should bake within budget"
+        );
+
+        // Moving a small shape within a larger one keeps the island bounds, so it could update incrementally
+        let mut small = baked_island(vec![
+            Shape::sphere(Mat4::IDENTITY, 3.0, ShapeOperation::Union),
+            Shape::sphere(Mat4::IDENTITY, 1.0, ShapeOperation::Union),
+        ]);
+        small.settings_voxels.max_triangle_budget = 1;
+        small.update_shape(
+            1,
+            Shape::sphere(
+                Mat4::from_translation(Vec3::new(0.0, 0.1, 0.0)),
+                1.0,
+                ShapeOperation::Union,
+            ),
+        );
+        assert!(
+            small.debug_take_voxels().is_none(),
+            "updating a shape should not keep voxels when over budget"
+        );
+    }
+
+    #[test]
+    fn test_hull_index_at() {
+        let centers = [Vec3::new(-4.0, 0.0, 0.0), Vec3::new(4.0, 0.0, 0.0)];
+        let mut data = Data::default();
+        data.set_shapes(
+            centers
+                .iter()
+                .map(|center| {
+                    Shape::sphere(Mat4::from_translation(*center), 2.5, ShapeOperation::Union)
+                })
+                .collect(),
+        );
+        assert_eq!(
+            None,
+            data.hull_index_at(centers[0]),
+            "no hull should be found before collision is baked"
+        );
+
+        data.bake_bounding_box();
+        data.bake_voxels();
+        data.bake_preview();
+        data.bake_collision();
+        assert_eq!(2, data.get_hulls().len(), "each sphere should bake a hull");
+
+        for (idx, center) in centers.iter().enumerate() {
+            for offset in [Vec3::ZERO, Vec3::Y * 2.4, Vec3::NEG_Z * 3.0] {
+                let point = *center + offset;
+                assert_eq!(
+                    Some(idx),
+                    data.hull_index_at(point),
+                    "point {point} should be in hull {idx}"
+                );
+            }
+
+            // Hull indices should line up with the baked hulls
+            let hull_center = data.get_hulls()[idx].bounding_box().center();
+            assert!(
+                (hull_center.x < 0.0) == (center.x < 0.0),
+                "hull {idx} at {hull_center} should surround its source shape at {center}"
+            );
+        }
+
+        data.dirty_collision();
+        assert_eq!(
+            None,
+            data.hull_index_at(centers[0]),
+            "no hull should be found after collision is dirtied"
+        );
+    }
+
+    #[test]
+    fn test_collision_skin() {
+        let mut data = Data::default();
+        data.settings_collision.skin_voxels = 2;
+        let mut data = baked_island_with(
+            data,
+            vec![Shape::rounded_box(
+                Mat4::IDENTITY,
+                Vec3::new(4.0, 0.4, 4.0),
+                0.1,
+                ShapeOperation::Union,
+            )],
+        );
+        data.bake_collision();
+
+        let visual = data
+            .get_mesh_preview()
+            .expect("preview mesh should bake")
+            .bounding_box();
+        let hulls = data.get_hulls();
+        assert!(!hulls.is_empty(), "collision should bake");
+
+        let thickness = |bounds: BoundingBox| bounds.maximum.y - bounds.minimum.y;
+        for hull in hulls.iter() {
+            assert!(
+                thickness(hull.bounding_box()) > thickness(visual),
+                "skinned collision should be thicker ({}) than the visual mesh ({})",
+                thickness(hull.bounding_box()),
+                thickness(visual)
+            );
+        }
+    }
+
+    #[test]
+    fn test_collision_skin_padding() {
+        let mut data = Data::default();
+        // Skin is wider than the padding, so dilation reaches the border of the volume
+        data.settings_voxels.voxel_padding = 1;
+        data.settings_collision.skin_voxels = 4;
+        let data = baked_island_with(
+            data,
+            vec![Shape::sphere(Mat4::IDENTITY, 2.0, ShapeOperation::Union)],
+        );
+
+        let mesh = data
+            .collision_source_mesh()
+            .expect("collision source mesh should bake")
+            .bounding_box();
+        // The outermost layer of cells is padding, so the surface should not reach it
+        let inner = data
+            .get_bounds()
+            .expand_vector(data.settings_voxels.voxel_size * -0.5);
+        assert!(
+            mesh.minimum.cmpgt(inner.minimum).all() && mesh.maximum.cmplt(inner.maximum).all(),
+            "dilated collision surface ({mesh:?}) should stay within the voxel padding ({inner:?})"
+        );
+    }
+
+    #[test]
+    fn test_shape_noise_weight() {
+        let centers = [Vec3::new(-4.0, 0.0, 0.0), Vec3::new(4.0, 0.0, 0.0)];
+        let mut smooth = Shape::sphere(
+            Mat4::from_translation(centers[0]),
+            2.5,
+            ShapeOperation::Union,
+        );
+        smooth.noise_weight = 0.0;
+        let noisy = Shape::sphere(
+            Mat4::from_translation(centers[1]),
+            2.5,
+            ShapeOperation::Union,
+        );
+
+        let data = baked_island(vec![smooth, noisy]);
+        let mesh = data.get_mesh_preview().expect("preview mesh should bake");
+
+        // Variance of each surface's distance from its sphere center
+        let variance = |center: Vec3| -> f32 {
+            let distances: Vec<f32> = mesh
+                .positions
+                .iter()
+                .filter(|pos| (pos.x < 0.0) == (center.x < 0.0))
+                .map(|pos| pos.distance(center))
+                .collect();
+            let mean = distances.iter().sum::<f32>() / distances.len() as f32;
+            distances.iter().map(|d| (d - mean).powi(2)).sum::<f32>() / distances.len() as f32
+        };
+
+        let (smooth_variance, noisy_variance) = (variance(centers[0]), variance(centers[1]));
+        assert!(
+            smooth_variance * 2.0 < noisy_variance,
+            "noise-exempt shape should be smoother (variance {smooth_variance}) than a noisy shape (variance {noisy_variance})"
+        );
+    }
+
+    #[test]
+    fn test_shape_preserve_sharp() {
+        let centers = [Vec3::new(-4.0, 0.0, 0.0), Vec3::new(4.0, 0.0, 0.0)];
+        let mut sharp = Shape::rounded_box(
+            Mat4::from_translation(centers[0]),
+            Vec3::splat(4.0),
+            0.0,
+            ShapeOperation::Union,
+        );
+        sharp.preserve_sharp = true;
+        let mut smooth = Shape::rounded_box(
+            Mat4::from_translation(centers[1]),
+            Vec3::splat(4.0),
+            0.0,
+            ShapeOperation::Union,
+        );
+        // Isolate smoothing as the only source of rounding
+        sharp.noise_weight = 0.0;
+        smooth.noise_weight = 0.0;
+
+        let mut data = Data::default();
+        let mut settings = data.settings_voxels;
+        settings.sdf_edge_radius = 0.0;
+        data.set_voxel_settings(settings);
+        let data = baked_island_with(data, vec![sharp, smooth]);
+        let mut mesh = data
+            .get_mesh_preview()
+            .expect("preview mesh should bake")
+            .clone();
+        mesh.optimize(data.settings_mesh.vertex_merge_distance);
+        mesh.bake_normals_smooth();
+        let curvature = mesh.mean_curvature();
+
+        // Average of the most convex vertices on each box, which lie along its edges and corners
+        let edge_curvature = |center: Vec3| -> f32 {
+            let mut values: Vec<f32> = mesh
+                .positions
+                .iter()
+                .zip(curvature.iter())
+                .filter(|(pos, _)| (pos.x < 0.0) == (center.x < 0.0))
+                .map(|(_, value)| *value)
+                .collect();
+            values.sort_by(|a, b| b.total_cmp(a));
+            let count = (values.len() / 10).max(1);
+            values.iter().take(count).sum::<f32>() / count as f32
+        };
+
+        let (sharp_curvature, smooth_curvature) =
+            (edge_curvature(centers[0]), edge_curvature(centers[1]));
+        assert!(
+            sharp_curvature > smooth_curvature * 1.2,
+            "sharp-preserving shape should have sharper edges (curvature {sharp_curvature}) than a smoothed shape (curvature {smooth_curvature})"
+        );
+    }
+
+    #[test]
+    fn test_total_memory_footprint() {
+        let mut data = Data::default();
+        data.set_shapes(vec![Shape::sphere(
+            Mat4::IDENTITY,
+            2.0,
+            ShapeOperation::Union,
+        )]);
+        assert_eq!(
+            0,
+            data.total_memory_footprint(),
+            "nothing should be cached before baking"
+        );
+
+        data.bake_bounding_box();
+        data.bake_voxels();
+        data.bake_preview();
+        data.bake_collision();
+
+        let voxels = data.voxels.as_ref().expect("voxels should bake");
+        let preview = data.get_mesh_preview().expect("preview should bake");
+        let expected = voxels.memory_footprint()
+            + preview.memory_footprint()
+            + data
+                .preview_grids
+                .iter()
+                .filter_map(|grid| grid.mesh.as_ref())
+                .map(|mesh| mesh.memory_footprint())
+                .sum::<usize>()
+            + data
+                .get_hulls()
+                .iter()
+                .map(|hull| hull.memory_footprint())
+                .sum::<usize>();
+        assert_eq!(
+            expected,
+            data.total_memory_footprint(),
+            "total footprint should sum all cached data"
+        );
+
+        data.dirty_voxels();
+        assert_eq!(
+            0,
+            data.total_memory_footprint(),
+            "clearing the cache should free all bake data"
+        );
+    }
+
+    #[test]
+    fn test_update_shape() {
+        let shapes = vec![
+            Shape::rounded_box(
+                Mat4::IDENTITY,
+                Vec3::new(12.0, 3.0, 12.0),
+                0.5,
+                ShapeOperation::Union,
+            ),
+            Shape::sphere(
+                Mat4::from_translation(Vec3::new(-2.0, 1.5, 0.0)),
+                1.5,
+                ShapeOperation::Union,
+            ),
+            Shape::sphere(
+                Mat4::from_translation(Vec3::new(3.0, 0.0, 3.0)),
+                1.0,
+                ShapeOperation::Subtraction,
+            ),
+        ];
+        let moved = Shape::sphere(
+            Mat4::from_translation(Vec3::new(-1.0, 1.5, 1.0)),
+            1.5,
+            ShapeOperation::Union,
+        );
+
+        let mut data = baked_island(shapes.clone());
+        let bounds = data.get_bounds();
+
+        assert!(data.update_shape(1, moved), "moved shape should update");
+        assert!(
+            data.voxels.is_some(),
+            "moving a union shape within the bounds should update voxels in place"
+        );
+        assert_eq!(bounds, data.get_bounds(), "bounds should not change");
+        assert!(
+            !data.update_shape(1, moved),
+            "unchanged shape should not update"
+        );
+
+        let mut expected_shapes = shapes.clone();
+        expected_shapes[1] = moved;
+        let mut expected = baked_island(expected_shapes);
+
+        let incremental = data.debug_take_voxels().expect("voxels should be baked");
+        let full = expected
+            .debug_take_voxels()
+            .expect("voxels should be baked");
+        assert_eq!(
+            full.data, incremental.data,
+            "incrementally updated voxels should match a full re-bake"
+        );
+
+        let incremental = data.take_mesh_preview().expect("preview should be updated");
+        let full = expected
+            .take_mesh_preview()
+            .expect("preview should be baked");
+        assert!(
+            incremental.triangles == full.triangles && incremental.positions == full.positions,
+            "incrementally updated preview should match a full re-bake"
+        );
+        assert_eq!(
+            expected.get_volume(),
+            data.get_volume(),
+            "volume should match a full re-bake"
+        );
+
+        // Changing a subtraction falls back to a full re-bake
+        data.bake_voxels();
+        let mut subtraction = shapes[2];
+        subtraction.set_transform(Mat4::from_translation(Vec3::new(2.0, 0.0, 3.0)));
+        assert!(
+            data.update_shape(2, subtraction),
+            "subtraction should update"
+        );
+        assert!(
+            data.voxels.is_none(),
+            "changing a subtraction should dirty all voxels"
+        );
+    }
+
+    #[test]
+    fn test_update_shape_scaled() {
+        // A scaled down sphere, whose local-space margin falls short of the field's reach in world space
+        let scaled = |translation: Vec3| -> Shape {
+            Shape::sphere(
+                Mat4::from_translation(translation) * Mat4::from_scale(Vec3::splat(0.25)),
+                6.0,
+                ShapeOperation::Union,
+            )
+        };
+        let shapes = vec![
+            Shape::rounded_box(
+                Mat4::IDENTITY,
+                Vec3::new(12.0, 3.0, 12.0),
+                0.5,
+                ShapeOperation::Union,
+            ),
+            scaled(Vec3::new(-2.0, 1.5, 0.0)),
+        ];
+        let moved = scaled(Vec3::new(-1.0, 1.5, 1.0));
+
+        let mut data = baked_island(shapes.clone());
+
+        assert!(data.update_shape(1, moved), "moved shape should update");
+        assert!(
+            data.voxels.is_some(),
+            "moving a scaled union shape within the bounds should update voxels in place"
+        );
+
+        let mut expected = baked_island(vec![shapes[0], moved]);
+
+        let incremental = data.debug_take_voxels().expect("voxels should be baked");
+        let full = expected
+            .debug_take_voxels()
+            .expect("voxels should be baked");
+        assert_eq!(
+            full.data, incremental.data,
+            "incrementally updated voxels of a scaled shape should match a full re-bake"
+        );
+
+        let incremental = data.take_mesh_preview().expect("preview should be updated");
+        let full = expected
+            .take_mesh_preview()
+            .expect("preview should be baked");
+        assert!(
+            incremental.triangles == full.triangles && incremental.positions == full.positions,
+            "incrementally updated preview of a scaled shape should match a full re-bake"
+        );
+    }
+
+    #[test]
+    fn test_update_shape_smooth_neighbor() {
+        // A smoothly blended neighbor picks up changes from well past the moved shape's reach
+        let shapes = vec![
+            Shape::rounded_box(
+                Mat4::IDENTITY,
+                Vec3::new(12.0, 3.0, 12.0),
+                0.5,
+                ShapeOperation::Union,
+            ),
+            Shape::sphere(
+                Mat4::from_translation(Vec3::new(-2.0, 1.5, 0.0)),
+                1.5,
+                ShapeOperation::Union,
+            ),
+            Shape::sphere(
+                Mat4::from_translation(Vec3::new(2.5, 1.5, 0.0)),
+                1.0,
+                ShapeOperation::SmoothUnion { k: 3.0 },
+            ),
+        ];
+        let moved = Shape::sphere(
+            Mat4::from_translation(Vec3::new(-1.0, 1.5, 1.0)),
+            1.5,
+            ShapeOperation::Union,
+        );
+
+        let mut data = baked_island(shapes.clone());
+
+        assert!(data.update_shape(1, moved), "moved shape should update");
+        data.bake_voxels();
+
+        let mut expected_shapes = shapes.clone();
+        expected_shapes[1] = moved;
+        let mut expected = baked_island(expected_shapes);
+
+        let updated = data.debug_take_voxels().expect("voxels should be baked");
+        let full = expected
+            .debug_take_voxels()
+            .expect("voxels should be baked");
+        assert_eq!(
+            full.data, updated.data,
+            "updating a shape next to a smooth operation should match a full re-bake"
+        );
+    }
+
+    #[test]
+    fn test_position_seed() {
+        let tweaks = SettingsTweaks::default();
+        let here = tweaks.with_position_seed(Vec3::new(10.0, -2.0, 35.5));
+
+        assert_eq!(
+            here.seed,
+            tweaks.with_position_seed(Vec3::new(10.0, -2.0, 35.5)).seed,
+            "same position should yield a stable seed"
+        );
+        assert_eq!(
+            here.seed,
+            tweaks
+                .with_position_seed(Vec3::new(10.000001, -2.0, 35.5))
+                .seed,
+            "floating-point drift should not change the seed"
+        );
+        assert_ne!(
+            here.seed,
+            tweaks.with_position_seed(Vec3::new(10.0, -2.0, 36.5)).seed,
+            "different positions should yield different seeds"
+        );
+        assert_ne!(
+            tweaks.with_position_seed(Vec3::X).seed,
+            tweaks.with_position_seed(Vec3::Y).seed,
+            "seed should depend on each axis separately"
+        );
+        assert_eq!(
+            here.w_striation, tweaks.w_striation,
+            "only the seed should change"
+        );
+    }
+
+    #[test]
+    fn test_hull_debug_color() {
+        let palette: Vec<Vec4> = (0..HULL_DEBUG_PALETTE_SIZE)
+            .map(|idx| hull_debug_color(idx, 0.5))
+            .collect();
+
+        for (i, a) in palette.iter().enumerate() {
+            assert_eq!(0.5, a.w, "hull color should keep the given alpha");
+            for (j, b) in palette.iter().enumerate().skip(i + 1) {
+                assert!(
+                    a.distance(*b) > 0.1,
+                    "hull colors {i} ({a}) and {j} ({b}) should be distinct"
+                );
+            }
+        }
+
+        for (idx, color) in palette.iter().enumerate() {
+            assert_eq!(
+                *color,
+                hull_debug_color(idx + HULL_DEBUG_PALETTE_SIZE, 0.5),
+                "hull colors should repeat once the palette wraps"
+            );
+        }
+    }
+
+    #[test]
+    fn test_heatmap_color() {
+        assert_eq!(
+            Vec4::new(0.0, 0.0, 1.0, 1.0),
+            heatmap_color(0.0),
+            "heatmap should start at blue"
+        );
+        assert_eq!(
+            Vec4::new(0.0, 1.0, 0.0, 1.0),
+            heatmap_color(0.5),
+            "heatmap should pass through green"
+        );
+        assert_eq!(
+            Vec4::new(1.0, 0.0, 0.0, 1.0),
+            heatmap_color(1.0),
+            "heatmap should end at red"
+        );
+        assert_eq!(
+            heatmap_color(1.0),
+            heatmap_color(4.0),
+            "heatmap values should be clamped"
+        );
+    }
+
+    #[test]
+    fn test_custom_density_noise() {
+        #[derive(Clone)]
+        struct ConstantNoise(f32);
+        impl NoiseSource for ConstantNoise {
+            fn sample(&self, _p: Vec4) -> f32 {
+                self.0
+            }
+            fn set_seed(&mut self, _seed: u32) {}
+            fn clone_box(&self) -> Box<dyn NoiseSource> {
+                Box::new(self.clone())
+            }
+        }
+
+        let bake = |constant: f32| {
+            let mut data = Data::default();
+            let mut settings = data.settings_voxels;
+            settings.sdf_smooth_iterations = 0; // Keep the field linear with respect to the noise
+            data.set_voxel_settings(settings);
+            data.set_density_noise(Some(Box::new(ConstantNoise(constant))));
+            let mut data = baked_island_with(
+                data,
+                vec![Shape::sphere(Mat4::IDENTITY, 3.0, ShapeOperation::Union)],
+            );
+            data.debug_take_voxels().expect("voxels should bake")
+        };
+
+        let base = bake(0.0);
+        let shifted = bake(0.5);
+        let padding = Data::default().settings_voxels.voxel_padding as usize;
+
+        let [dx, dy, dz] = base.get_dimensions();
+        let mut checked = 0;
+        for i in 0..base.get_buffer_size() {
+            let [x, y, z] = base.delinearize(i);
+            if base.is_margin(x, y, z, padding) {
+                continue;
+            }
+            let difference = shifted.get_linear(i) - base.get_linear(i);
+            assert!(
+                (difference - 0.5).abs() < 1e-4,
+                "voxel ({x}, {y}, {z}) in a {dx}x{dy}x{dz} grid should shift by the constant, got {difference}"
+            );
+            checked += 1;
+        }
+        assert!(checked > 0, "should check interior voxels");
+    }
+
+    #[test]
+    fn test_sampling_space() {
+        let translation = Vec3::new(12.0, -3.0, 5.5);
+        let space = Mat4::from_translation(translation);
+
+        // Without noise, geometry should not depend on the sampling space
+        let bake = |space: Mat4| {
+            let mut data = Data::default();
+            data.set_voxel_settings(SettingsVoxels {
+                sampling_density_noise_amplitude: 0.0,
+                sampling_offset_noise_amplitude: Vec3::ZERO,
+                striation_amplitude: 0.0,
+                ..Default::default()
+            });
+            assert!(
+                data.set_sampling_space(space) == (space != Mat4::IDENTITY),
+                "setting the sampling space should only report a change when it differs"
+            );
+            let mut data = baked_island_with(
+                data,
+                vec![Shape::sphere(Mat4::IDENTITY, 3.0, ShapeOperation::Union)],
+            );
+            data.bake_mesh();
+            data.get_mesh_baked()
+                .expect("baked mesh should bake")
+                .clone()
+        };
+        let local = bake(Mat4::IDENTITY);
+        let shifted = bake(space);
+
+        assert_eq!(
+            local.positions, shifted.positions,
+            "geometry should not change with the sampling space"
+        );
+        let uv_local = local.uv1.expect("UV1 should be baked");
+        let uv_shifted = shifted.uv1.expect("UV1 should be baked");
+        let uv_offset = Vec2::new(translation.x + translation.z, translation.y);
+        for (a, b) in uv_local.iter().zip(uv_shifted.iter()) {
+            assert!(
+                (*a + uv_offset - *b).length() < 1e-4,
+                "UV1 {b} should be offset by the sampling space from {a}"
+            );
+        }
+
+        // Noise sampled at a point in a translated space should match noise sampled at the translated point
+        let mut translated = Data::default();
+        translated.set_sampling_space(space);
+        let identity = Data::default();
+        for position in [Vec3::ZERO, Vec3::new(1.5, 2.0, -4.0), Vec3::splat(-7.25)] {
+            let (color_a, uv1_a, uv2_a) = translated.bake_vertex(position, Vec3::Y, None, None);
+            let (color_b, uv1_b, uv2_b) =
+                identity.bake_vertex(position + translation, Vec3::Y, None, None);
+            assert_eq!(
+                color_b, color_a,
+                "mask noise at {position} should be sampled in the sampling space"
+            );
+            assert_eq!(uv1_b, uv1_a, "UV1 at {position} should match");
+            assert_eq!(uv2_b, uv2_a, "UV2 at {position} should match");
+        }
+    }
+
+    #[test]
+    fn test_voxel_determinism() {
+        let bake = || {
+            let mut data = baked_island(vec![
+                Shape::sphere(Mat4::IDENTITY, 3.0, ShapeOperation::Union),
+                Shape::rounded_box(
+                    Mat4::from_translation(Vec3::X * 2.0),
+                    Vec3::new(4.0, 2.0, 3.0),
+                    0.2,
+                    ShapeOperation::Subtraction,
+                ),
+            ]);
+
+            let voxels = data.debug_take_voxels().expect("voxels should bake");
+            let preview = data.debug_take_preview().expect("preview should bake");
+            assert!(
+                data.debug_take_voxels().is_none(),
+                "voxels should be cleared after being taken"
+            );
+
+            (
+                voxels.get_dimensions(),
+                voxels
+                    .data
+                    .iter()
+                    .map(|v| v.to_bits())
+                    .collect::<Vec<u32>>(),
+                preview.positions,
+            )
+        };
+
+        let (dim_a, voxels_a, positions_a) = bake();
+        let (dim_b, voxels_b, positions_b) = bake();
+        assert_eq!(dim_a, dim_b, "voxel dimensions should match");
+        assert!(
+            voxels_a == voxels_b,
+            "voxel grids should be byte-identical between bakes"
+        );
+        assert_eq!(
+            positions_a, positions_b,
+            "preview meshes should be identical between bakes"
+        );
+    }
+
+    #[test]
+    fn test_bake_determinism() {
+        let bake = || {
+            let mut data = Data::default();
+            data.set_tweaks(SettingsTweaks {
+                seed: 1234,
+                ..Default::default()
+            });
+            let mut data = baked_island_with(
+                data,
+                vec![
+                    Shape::sphere(
+                        Mat4::from_translation(Vec3::new(-1.5, 0.0, 0.0)),
+                        2.0,
+                        ShapeOperation::Union,
+                    ),
+                    Shape::rounded_box(
+                        Mat4::from_rotation_y(0.4)
+                            * Mat4::from_translation(Vec3::new(1.5, 0.5, 0.0)),
+                        Vec3::new(3.0, 2.0, 2.5),
+                        0.2,
+                        ShapeOperation::Union,
+                    ),
+                    Shape::sphere(
+                        Mat4::from_translation(Vec3::new(0.0, 2.0, 0.0)),
+                        1.0,
+                        ShapeOperation::Subtraction,
+                    ),
+                ],
+            );
+            data.bake_mesh();
+            data.bake_collision();
+
+            let mesh = data.get_mesh_baked().expect("mesh should bake").clone();
+            (mesh, data.get_hulls().clone())
+        };
+        let bits = |values: &[Vec3]| -> Vec<[u32; 3]> {
+            values
+                .iter()
+                .map(|v| v.to_array().map(f32::to_bits))
+                .collect()
+        };
+
+        let (mesh_a, hulls_a) = bake();
+        let (mesh_b, hulls_b) = bake();
+        assert_eq!(
+            mesh_a.triangles, mesh_b.triangles,
+            "baked triangles should be identical between bakes"
+        );
+        assert!(
+            bits(&mesh_a.positions) == bits(&mesh_b.positions),
+            "baked positions should be byte-identical between bakes"
+        );
+        assert!(
+            bits(&mesh_a.normals) == bits(&mesh_b.normals),
+            "baked normals should be byte-identical between bakes"
+        );
+        assert!(
+            !mesh_a.colors.iter().any(|color| color.is_nan()),
+            "baked colors should not contain NaN"
+        );
+        assert!(
+            mesh_a.colors == mesh_b.colors,
+            "baked colors should be identical between bakes"
+        );
+
+        assert!(hulls_a.len() > 1, "should bake multiple hulls");
+        assert_eq!(hulls_a.len(), hulls_b.len(), "hull count should match");
+        for (idx, (a, b)) in hulls_a.iter().zip(hulls_b.iter()).enumerate() {
+            assert_eq!(
+                a.triangles, b.triangles,
+                "hull {idx} triangles should be identical between bakes"
+            );
+            assert!(
+                bits(&a.positions) == bits(&b.positions),
+                "hull {idx} positions should be byte-identical between bakes"
+            );
+        }
+    }
+
+    #[test]
+    fn test_voxel_bytes_round_trip() {
+        let mut data = baked_island(vec![Shape::sphere(
+            Mat4::IDENTITY,
+            3.0,
+            ShapeOperation::Union,
+        )]);
+        let expected = data
+            .debug_take_preview()
+            .expect("preview should bake")
+            .positions;
+
+        let voxels = data.debug_take_voxels().expect("voxels should bake");
+        let dim = voxels.get_dimensions();
+        let bytes = voxels.to_bytes();
+
+        let restored = VolumeData::from_bytes(&bytes, dim).expect("voxels should read");
+        assert!(
+            !data.debug_restore_voxels(VolumeData::new(1.0, [1, 1, 1])),
+            "voxels of mismatched dimensions should not be restored"
+        );
+        assert!(
+            data.debug_restore_voxels(restored),
+            "voxels should be restored"
+        );
+        data.bake_preview();
+        assert_eq!(
+            expected,
+            data.debug_take_preview()
+                .expect("preview should bake from restored voxels")
+                .positions,
+            "preview from restored voxels should match the original"
+        );
+    }
+
+    #[test]
+    fn test_voxel_slice() {
+        let data = Data::default();
+        assert_eq!(
+            [0; 3],
+            data.get_voxel_dimensions(),
+            "dimensions should be zero before baking"
+        );
+        assert!(
+            data.voxel_slice(0, 0).is_none(),
+            "there should be no slice before baking"
+        );
+
+        let data = baked_island(vec![Shape::sphere(
+            Mat4::IDENTITY,
+            3.0,
+            ShapeOperation::Union,
+        )]);
+
+        let dim = data.get_voxel_dimensions();
+        let slice = data.voxel_slice(2, dim[2] / 2).expect("slice should exist");
+        assert_eq!(dim[0] * dim[1], slice.len(), "Z slice should span X and Y");
+        assert!(
+            slice.iter().any(|v| *v < 0.0) && slice.iter().any(|v| *v > 0.0),
+            "middle slice should cross the sphere surface"
+        );
+        assert_eq!(
+            data.voxel_slice(1, dim[1] - 1),
+            data.voxel_slice(1, usize::MAX),
+            "out-of-range slices should be clamped"
+        );
+    }
+
+    #[test]
+    fn test_cancel_bake() {
+        let cancel = Arc::new(AtomicBool::new(true));
+        let mut data = Data::default();
+        data.set_shapes(vec![Shape::sphere(
+            Mat4::IDENTITY,
+            3.0,
+            ShapeOperation::Union,
+        )]);
+        data.set_cancel_flag(Some(cancel.clone()));
+        data.bake_bounding_box();
+        assert!(data.is_canceled(), "raised flag should cancel bakes");
+
+        data.bake_voxels();
+        assert!(
+            data.debug_take_voxels().is_none(),
+            "canceled bake should not store voxels"
+        );
+
+        cancel.store(false, Ordering::Relaxed);
+        data.bake_voxels();
+        cancel.store(true, Ordering::Relaxed);
+        data.bake_preview();
+        assert!(
+            data.get_mesh_preview().is_none(),
+            "canceled bake should not store a preview"
+        );
+
+        cancel.store(false, Ordering::Relaxed);
+        data.bake_preview();
+        let mut expected = baked_island(data.get_shapes().clone());
+        assert_eq!(
+            expected
+                .debug_take_preview()
+                .expect("preview should bake")
+                .positions,
+            data.debug_take_preview()
+                .expect("preview should bake once the flag is lowered")
+                .positions,
+            "bake after a cancel should match an uncanceled bake"
+        );
+    }
+
+    #[test]
+    fn test_settings_snapshot_round_trip() {
+        let mut data = Data::default();
+        let mut snapshot = data.export_settings();
+        snapshot.voxels.voxel_size = Vec3::new(0.3, 0.4, 0.5);
+        snapshot.mesh.ao_enabled = true;
+        snapshot.mesh.ao_samples = 7;
+        snapshot.collision.decimation_angle = 4.5;
+        snapshot.tweaks.seed = 1234;
+        snapshot.tweaks.w_striation = 0.25;
+        assert!(data.import_settings(snapshot), "settings should change");
+        assert!(
+            !data.import_settings(snapshot),
+            "settings should not change when re-imported"
+        );
+
+        let bytes = data.export_settings().to_bytes();
+        let restored =
+            IslandSettingsSnapshot::from_bytes(&bytes).expect("snapshot should deserialize");
+
+        let mut restored_data = Data::default();
+        restored_data.import_settings(restored);
+        assert!(
+            data.export_settings() == restored_data.export_settings(),
+            "restored settings should be identical"
+        );
+
+        assert!(
+            IslandSettingsSnapshot::from_bytes(&bytes[..bytes.len() - 1]).is_none(),
+            "truncated snapshot should fail"
+        );
+        assert!(
+            IslandSettingsSnapshot::from_bytes(&bytes[1..]).is_none(),
+            "snapshot without header should fail"
+        );
     }
 }
@@ -0,0 +1,313 @@
+use super::trimesh::{Triangle, TriangleMesh};
+use crate::math::types::Vec3;
+use crate::math::volumetric::VolumeData;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Edges of a cube cell, as the index of their lower corner and their axis.
+/// Corner indices store the X offset in bit 0, the Y offset in bit 1, and the Z offset in bit 2.
+const CUBE_EDGES: [(usize, usize); 12] = [
+    (0, 0),
+    (2, 0),
+    (4, 0),
+    (6, 0),
+    (0, 1),
+    (1, 1),
+    (4, 1),
+    (5, 1),
+    (0, 2),
+    (1, 2),
+    (2, 2),
+    (3, 2),
+];
+
+/// Returns the triangles of cube edge indices for each of the 256 cube cases,
+/// where bit `i` of the case is set if corner `i` is inside the surface.
+/// Built on first use.
+fn case_table() -> &'static [Vec<[u8; 3]>; 256] {
+    static TABLE: OnceLock<[Vec<[u8; 3]>; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| std::array::from_fn(|case| case_triangles(case as u8)))
+}
+
+/// Returns the index of the cube edge between the two given corners, which must differ along a single axis.
+fn edge_between(a: usize, b: usize) -> usize {
+    let edge = (a & b, (a ^ b).trailing_zeros() as usize);
+    CUBE_EDGES
+        .iter()
+        .position(|cube_edge| *cube_edge == edge)
+        .expect("corners should share a cube edge")
+}
+
+/// Returns true if the two given cube edges lie on a common face of the cube.
+fn edges_share_face(a: usize, b: usize) -> bool {
+    let faces = |edge: usize| {
+        let (corner, axis) = CUBE_EDGES[edge];
+        (0..3)
+            .filter(move |face_axis| *face_axis != axis)
+            .map(move |face_axis| (face_axis, (corner >> face_axis) & 1))
+    };
+    faces(a).any(|face| faces(b).any(|other| face == other))
+}
+
+/// Triangulates a single cube case by tracing the surface contour across each face of the cube.
+///
+/// Each face is split like Marching Squares, walking its corners counter-clockwise around the outward face normal.
+/// Contours run from where the walk leaves an inside corner, back to where it last entered one,
+/// so ambiguous faces always keep their inside corners separate, and neighboring cells agree on the faces they share.
+/// Contours are then chained into loops across faces, and each loop is triangulated as a fan.
+///
+/// Fans start from a vertex with no diagonals lying on a cube face,
+/// as the neighboring cell could otherwise triangulate the same diagonal, making the edge non-manifold.
+fn case_triangles(case: u8) -> Vec<[u8; 3]> {
+    let inside = |corner: usize| case & (1 << corner) != 0;
+
+    // Following contour edge for each crossing edge, where one starts
+    let mut next: [Option<usize>; 12] = [None; 12];
+    for axis in 0..3 {
+        let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+        for side in 0..2 {
+            // Corners counter-clockwise around the positive axis, reversed for the negative side
+            let mut cycle = [(0, 0), (1, 0), (1, 1), (0, 1)]
+                .map(|(cu, cv)| (side << axis) | (cu << u) | (cv << v));
+            if side == 0 {
+                cycle.reverse();
+            }
+
+            // Crossing edges in walk order, and whether the walk leaves an inside corner across them
+            let crossings: Vec<(usize, bool)> = (0..4)
+                .filter_map(|k| {
+                    let (a, b) = (cycle[k], cycle[(k + 1) % 4]);
+                    (inside(a) != inside(b)).then(|| (edge_between(a, b), inside(a)))
+                })
+                .collect();
+            for (k, (edge, exits)) in crossings.iter().enumerate() {
+                if *exits {
+                    next[*edge] = Some(crossings[(k + crossings.len() - 1) % crossings.len()].0);
+                }
+            }
+        }
+    }
+
+    let mut triangles: Vec<[u8; 3]> = vec![];
+    let mut visited = [false; 12];
+    for start in 0..12 {
+        if visited[start] || next[start].is_none() {
+            continue;
+        }
+
+        let mut contour: Vec<u8> = vec![];
+        let mut edge = start;
+        while !visited[edge] {
+            visited[edge] = true;
+            contour.push(edge as u8);
+            edge = next[edge].expect("contours should form closed loops");
+        }
+
+        let len = contour.len();
+        let first = (0..len)
+            .find(|first| {
+                (2..len - 1).all(|i| {
+                    !edges_share_face(
+                        contour[*first] as usize,
+                        contour[(first + i) % len] as usize,
+                    )
+                })
+            })
+            .unwrap_or(0);
+        for i in 1..len - 1 {
+            triangles.push([
+                contour[first],
+                contour[(first + i) % len],
+                contour[(first + i + 1) % len],
+            ]);
+        }
+    }
+    triangles
+}
+
+/// Converts voxels into a `TriangleMesh` with Marching Cubes, returning `None` if there is no surface.
+///
+/// Voxels with values below `iso` are considered inside.
+/// Each voxel is located at its cell coordinate multiplied by `cell_size`, plus `offset`.
+/// Vertices are shared between neighboring cells, so the mesh is closed if the surface does not touch the volume bounds.
+///
+/// Triangles are wound like Island Builder Surface Nets meshes, so smooth normals face outward,
+/// and are baked with [TriangleMesh::get_normals_smooth].
+pub fn mesh_from_marching_cubes(
+    voxels: &VolumeData<f32>,
+    iso: f32,
+    cell_size: Vec3,
+    offset: Vec3,
+) -> Option<TriangleMesh> {
+    let dim = voxels.get_dimensions();
+    if dim.iter().any(|axis| *axis < 2) {
+        return None;
+    }
+
+    let table = case_table();
+    let mut positions: Vec<Vec3> = vec![];
+    let mut triangles: Vec<Triangle> = vec![];
+    // Vertex index of each voxel edge, keyed by the linear index of its lower voxel and its axis
+    let mut vertices: HashMap<usize, usize> = HashMap::new();
+
+    for z in 0..dim[2] - 1 {
+        for y in 0..dim[1] - 1 {
+            for x in 0..dim[0] - 1 {
+                let corners: [usize; 8] = std::array::from_fn(|i| {
+                    voxels.linearize_fast(x + (i & 1), y + ((i >> 1) & 1), z + ((i >> 2) & 1))
+                });
+                let case = (0..8)
+                    .filter(|i| voxels.get_linear(corners[*i]) < iso)
+                    .fold(0u8, |case, i| case | (1 << i));
+
+                let mut vertex = |edge: u8| -> usize {
+                    let (corner, axis) = CUBE_EDGES[edge as usize];
+                    let lower = corners[corner];
+                    let upper = corners[corner | (1 << axis)];
+                    *vertices.entry(lower * 3 + axis).or_insert_with(|| {
+                        let a = voxels.get_linear(lower);
+                        let b = voxels.get_linear(upper);
+                        let t = ((iso - a) / (b - a)).clamp(0.0, 1.0);
+
+                        let mut position = Vec3::new(
+                            (x + (corner & 1)) as f32,
+                            (y + ((corner >> 1) & 1)) as f32,
+                            (z + ((corner >> 2) & 1)) as f32,
+                        );
+                        position[axis] += t;
+                        positions.push(position * cell_size + offset);
+                        positions.len() - 1
+                    })
+                };
+
+                for tri in table[case as usize].iter() {
+                    triangles.push(tri.map(&mut vertex));
+                }
+            }
+        }
+    }
+
+    if triangles.is_empty() {
+        return None;
+    }
+
+    let mut mesh = TriangleMesh::new(triangles, positions, None, None);
+    mesh.normals = mesh.get_normals_smooth();
+    Some(mesh)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{case_table, mesh_from_marching_cubes};
+    use crate::math::volumetric::VolumeData;
+    use crate::mesh::nets::mesh_from_nets;
+    use fast_surface_nets::ndshape::{ConstShape, ConstShape3u32};
+    use fast_surface_nets::{SurfaceNetsBuffer, surface_nets};
+    use glam::Vec3;
+    use noise::{NoiseFn, Perlin};
+    use std::f32::consts::PI;
+
+    type TestShape = ConstShape3u32<24, 24, 24>;
+
+    /// Builds a sphere field of the given radius at the center of the test volume, with optional noise added.
+    fn sphere_field(radius: f32, noise_amplitude: f32) -> VolumeData<f32> {
+        let perlin = Perlin::new(7);
+        let center = Vec3::splat(11.5);
+        let mut voxels = VolumeData::new(1.0f32, [24; 3]);
+        for (i, sample) in voxels.data.iter_mut().enumerate() {
+            let [x, y, z] = TestShape::delinearize(i as u32);
+            let point = Vec3::new(x as f32, y as f32, z as f32);
+            let noise = perlin.get([
+                point.x as f64 * 0.45,
+                point.y as f64 * 0.45,
+                point.z as f64 * 0.45,
+            ]) as f32;
+            *sample = point.distance(center) - radius + noise * noise_amplitude;
+        }
+        voxels
+    }
+
+    #[test]
+    fn test_case_table() {
+        let table = case_table();
+        assert!(table[0].is_empty(), "empty cube should have no triangles");
+        assert!(table[255].is_empty(), "full cube should have no triangles");
+        assert_eq!(1, table[1].len(), "single corner should be one triangle");
+        assert_eq!(2, table[3].len(), "single edge should be a quad");
+        assert_eq!(
+            2,
+            table[0b1000_0001].len(),
+            "opposite corners should be separate triangles"
+        );
+    }
+
+    #[test]
+    fn test_sphere() {
+        let radius = 7.0;
+        let voxels = sphere_field(radius, 0.0);
+        let offset = Vec3::new(-3.0, 1.0, 2.0);
+        let mesh = mesh_from_marching_cubes(&voxels, 0.0, Vec3::splat(0.5), offset)
+            .expect("sphere should produce a mesh");
+        mesh.debug_validate();
+
+        assert!(mesh.is_manifold(), "sphere should be closed and manifold");
+
+        let center = Vec3::splat(11.5) * 0.5 + offset;
+        for position in mesh.positions.iter() {
+            let distance = position.distance(center);
+            assert!(
+                (distance - radius * 0.5).abs() < 0.05,
+                "vertex {position} should be on the sphere, was {distance} from the center"
+            );
+        }
+
+        // Winding should match Surface Nets meshes from the Island Builder, which mesh the negated field
+        let expected = -4.0 / 3.0 * PI * (radius * 0.5).powi(3);
+        let volume = mesh.signed_volume();
+        assert!(
+            (volume - expected).abs() < expected.abs() * 0.05,
+            "signed volume should be {expected}, was {volume}"
+        );
+        let negated: Vec<f32> = voxels.data.iter().map(|sample| -sample).collect();
+        let mut buffer = SurfaceNetsBuffer::default();
+        surface_nets(&negated, &TestShape {}, [0; 3], [23; 3], &mut buffer);
+        let nets = mesh_from_nets(buffer, Vec3::splat(0.5), offset)
+            .expect("sphere should produce a nets mesh");
+        assert!(
+            nets.signed_volume() < 0.0,
+            "marching cubes should be wound like surface nets"
+        );
+
+        for (position, normal) in mesh.positions.iter().zip(mesh.normals.iter()) {
+            assert!(
+                normal.dot(*position - center) > 0.0,
+                "normal {normal} at {position} should face outward"
+            );
+        }
+    }
+
+    #[test]
+    fn test_noisy_closed() {
+        // Noise creates plenty of ambiguous faces, which neighboring cells must agree on
+        let voxels = sphere_field(7.0, 6.0);
+        let mesh = mesh_from_marching_cubes(&voxels, 0.0, Vec3::ONE, Vec3::ZERO)
+            .expect("noisy sphere should produce a mesh");
+        mesh.debug_validate();
+
+        let report = mesh.manifold_report();
+        assert!(
+            report.is_manifold(),
+            "noisy sphere should be closed and manifold, found {0} boundary and {1} non-manifold edges",
+            report.boundary_edges,
+            report.non_manifold_edges
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        let voxels = VolumeData::new(1.0f32, [8; 3]);
+        assert!(mesh_from_marching_cubes(&voxels, 0.0, Vec3::ONE, Vec3::ZERO).is_none());
+        let voxels = VolumeData::new(-1.0f32, [1, 8, 8]);
+        assert!(mesh_from_marching_cubes(&voxels, 0.0, Vec3::ONE, Vec3::ZERO).is_none());
+    }
+}
@@ -3,6 +3,8 @@ use crate::math::types::{ToVector3, Vec3};
 use fast_surface_nets::SurfaceNetsBuffer;
 
 /// Converts a `SurfaceNetsBuffer` to a `TriangleMesh`, returning `None` upon failure.
+///
+/// Vertices with non-finite positions are placed at the center of the voxel cell that generated them.
 pub fn mesh_from_nets(
     nets: SurfaceNetsBuffer,
     scale: Vec3,
@@ -20,8 +22,12 @@ pub fn mesh_from_nets(
     let positions = nets
         .positions
         .iter()
-        .map(|pos| -> Vec3 {
-            let p: Vec3 = pos.to_vector3();
+        .zip(nets.surface_points.iter())
+        .map(|(pos, cell)| -> Vec3 {
+            let mut p: Vec3 = pos.to_vector3();
+            if !p.is_finite() {
+                p = Vec3::new(cell[0] as f32, cell[1] as f32, cell[2] as f32) + Vec3::splat(0.5);
+            }
             p * scale + translation
         })
         .collect::<Vec<Vec3>>();
@@ -40,3 +46,101 @@ pub fn mesh_from_nets(
         Some(normals),
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::mesh_from_nets;
+    use fast_surface_nets::ndshape::{ConstShape, ConstShape3u32};
+    use fast_surface_nets::{SurfaceNetsBuffer, surface_nets};
+    use glam::Vec3;
+    use noise::{NoiseFn, Perlin};
+
+    type TestShape = ConstShape3u32<24, 24, 24>;
+
+    /// Runs Surface Nets over a field sampled from the given function.
+    fn nets_from_field(field: impl Fn(Vec3) -> f32) -> SurfaceNetsBuffer {
+        let mut grid = [1.0f32; TestShape::USIZE];
+        for (i, sample) in grid.iter_mut().enumerate() {
+            let [x, y, z] = TestShape::delinearize(i as u32);
+            *sample = field(Vec3::new(x as f32, y as f32, z as f32));
+        }
+
+        let mut buffer = SurfaceNetsBuffer::default();
+        surface_nets(&grid, &TestShape {}, [0; 3], [23; 3], &mut buffer);
+        buffer
+    }
+
+    /// Builds a sphere field with high-amplitude noise added to it.
+    fn noisy_sphere() -> SurfaceNetsBuffer {
+        let perlin = Perlin::new(7);
+        let center = Vec3::splat(11.5);
+        nets_from_field(|point| {
+            let noise = perlin.get([
+                point.x as f64 * 0.45,
+                point.y as f64 * 0.45,
+                point.z as f64 * 0.45,
+            ]) as f32;
+            point.distance(center) - 7.0 + noise * 6.0
+        })
+    }
+
+    /// Asserts that every vertex lies within the voxel cell that generated it.
+    fn assert_within_cells(nets: SurfaceNetsBuffer, scale: Vec3, translation: Vec3) {
+        let cells = nets.surface_points.clone();
+        let mesh =
+            mesh_from_nets(nets, scale, translation).expect("noisy field should produce a mesh");
+
+        for (position, cell) in mesh.positions.iter().zip(cells.iter()) {
+            let cell_min =
+                Vec3::new(cell[0] as f32, cell[1] as f32, cell[2] as f32) * scale + translation;
+            let cell_max = cell_min + scale;
+            assert!(
+                position.cmpge(cell_min - 1e-4).all() && position.cmple(cell_max + 1e-4).all(),
+                "vertex {position} should be within its cell from {cell_min} to {cell_max}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_vertices_within_cells() {
+        // Vertices are averaged from edge crossings of their own cell, so they never leave it, even on noisy fields
+        assert_within_cells(noisy_sphere(), Vec3::ONE, Vec3::ZERO);
+        assert_within_cells(
+            noisy_sphere(),
+            Vec3::new(0.5, 2.0, 0.25),
+            Vec3::new(-3.0, 1.5, 10.0),
+        );
+
+        // Uncorrelated samples with a huge amplitude flip sign between almost every pair of neighbors
+        let perlin = Perlin::new(13);
+        let static_field = nets_from_field(|point| {
+            let noise = perlin.get([
+                point.x as f64 * 7.31 + 0.17,
+                point.y as f64 * 7.31 + 0.17,
+                point.z as f64 * 7.31 + 0.17,
+            ]) as f32;
+            noise * 1.0e4
+        });
+        assert!(
+            static_field.surface_points.len() > 1000,
+            "static field should produce many vertices, got {}",
+            static_field.surface_points.len()
+        );
+        assert_within_cells(static_field, Vec3::ONE, Vec3::ZERO);
+    }
+
+    #[test]
+    fn test_non_finite_vertices() {
+        let mut nets = noisy_sphere();
+        nets.positions[0] = [f32::NAN, 0.0, 0.0];
+        let cell = nets.surface_points[0];
+
+        let mesh = mesh_from_nets(nets, Vec3::ONE, Vec3::ZERO)
+            .expect("noisy sphere should produce a mesh");
+        assert_eq!(
+            Vec3::new(cell[0] as f32, cell[1] as f32, cell[2] as f32) + Vec3::splat(0.5),
+            mesh.positions[0],
+            "non-finite vertex should be placed at its cell center"
+        );
+    }
+}
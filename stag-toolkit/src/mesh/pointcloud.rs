@@ -1,5 +1,9 @@
 use crate::math::{bounding_box::BoundingBox, projection::Plane};
 use glam::{Vec3, Vec4};
+use std::sync::OnceLock;
+
+/// Maximum number of points in a k-d tree leaf, which are scanned directly rather than split further.
+const KD_LEAF_SIZE: usize = 8;
 
 /// A set of points for analysis.
 pub trait PointCloud {
@@ -14,6 +18,13 @@ pub trait PointCloud {
 
     /// Returns the index of the most distant point from the given plane.
     fn distant_plane(&self, from: Vec4) -> usize;
+
+    /// Returns the index of the nearest point to the given point, or [None] if the cloud is empty.
+    /// Ties are broken by the lowest index.
+    fn nearest(&self, point: Vec3) -> Option<usize>;
+
+    /// Returns the indices of all points within the given radius of the given point, in ascending order.
+    fn within_radius(&self, point: Vec3, radius: f32) -> Vec<usize>;
 }
 
 impl PointCloud for Vec<Vec3> {
@@ -107,6 +118,241 @@ impl PointCloud for Vec<Vec3> {
 
         i
     }
+
+    fn nearest(&self, point: Vec3) -> Option<usize> {
+        // Single queries are cheaper as a linear scan than building a tree
+        self.iter()
+            .map(|pt| pt.distance_squared(point))
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(idx, _)| idx)
+    }
+
+    fn within_radius(&self, point: Vec3, radius: f32) -> Vec<usize> {
+        let radius_squared = radius * radius;
+        self.iter()
+            .enumerate()
+            .filter(|(_, pt)| pt.distance_squared(point) <= radius_squared)
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+}
+
+/// A k-d tree over a list of points, for fast nearest-neighbor and radius queries.
+///
+/// The tree only stores point indices, so queries must be given the same points the tree was built from.
+#[derive(Clone, Debug, Default)]
+pub struct KdTree {
+    /// Point indices, ordered so that each subtree is a contiguous range split at its middle element.
+    /// Subtrees split along X, Y and Z in turn.
+    order: Vec<usize>,
+}
+
+impl KdTree {
+    /// Builds a k-d tree over the given points.
+    pub fn new(points: &[Vec3]) -> Self {
+        let mut order: Vec<usize> = (0..points.len()).collect();
+        Self::build(points, &mut order, 0);
+        Self { order }
+    }
+
+    /// Recursively partitions the given range of indices around its median along the depth's axis.
+    fn build(points: &[Vec3], order: &mut [usize], depth: usize) {
+        if order.len() <= KD_LEAF_SIZE {
+            return;
+        }
+
+        let axis = depth % 3;
+        let mid = order.len() / 2;
+        order.select_nth_unstable_by(mid, |a, b| points[*a][axis].total_cmp(&points[*b][axis]));
+
+        let (below, above) = order.split_at_mut(mid);
+        Self::build(points, below, depth + 1);
+        Self::build(points, &mut above[1..], depth + 1);
+    }
+
+    /// Returns the index of the nearest point to the given point, or [None] if the tree is empty.
+    /// Ties are broken by the lowest index.
+    pub fn nearest(&self, points: &[Vec3], point: Vec3) -> Option<usize> {
+        let mut best: Option<(f32, usize)> = None;
+        self.nearest_within(points, point, &self.order, 0, &mut best);
+        best.map(|(_, idx)| idx)
+    }
+
+    fn nearest_within(
+        &self,
+        points: &[Vec3],
+        point: Vec3,
+        order: &[usize],
+        depth: usize,
+        best: &mut Option<(f32, usize)>,
+    ) {
+        let mut consider = |idx: usize| {
+            let d = points[idx].distance_squared(point);
+            if best.is_none_or(|(best_d, best_idx)| d < best_d || (d == best_d && idx < best_idx)) {
+                *best = Some((d, idx));
+            }
+        };
+
+        if order.len() <= KD_LEAF_SIZE {
+            order.iter().for_each(|idx| consider(*idx));
+            return;
+        }
+
+        let axis = depth % 3;
+        let mid = order.len() / 2;
+        let split = order[mid];
+        consider(split);
+
+        // Search the side containing the point first, then the other side only if it could be closer
+        let diff = point[axis] - points[split][axis];
+        let (near, far) = if diff < 0.0 {
+            (&order[..mid], &order[mid + 1..])
+        } else {
+            (&order[mid + 1..], &order[..mid])
+        };
+        self.nearest_within(points, point, near, depth + 1, best);
+        if best.is_none_or(|(best_d, _)| diff * diff <= best_d) {
+            self.nearest_within(points, point, far, depth + 1, best);
+        }
+    }
+
+    /// Returns the indices of all points within the given radius of the given point, in ascending order.
+    pub fn within_radius(&self, points: &[Vec3], point: Vec3, radius: f32) -> Vec<usize> {
+        let mut found = vec![];
+        if radius >= 0.0 {
+            self.within_radius_of(points, point, radius, &self.order, 0, &mut found);
+        }
+        found.sort_unstable();
+        found
+    }
+
+    fn within_radius_of(
+        &self,
+        points: &[Vec3],
+        point: Vec3,
+        radius: f32,
+        order: &[usize],
+        depth: usize,
+        found: &mut Vec<usize>,
+    ) {
+        let radius_squared = radius * radius;
+        if order.len() <= KD_LEAF_SIZE {
+            found.extend(
+                order
+                    .iter()
+                    .filter(|idx| points[**idx].distance_squared(point) <= radius_squared),
+            );
+            return;
+        }
+
+        let axis = depth % 3;
+        let mid = order.len() / 2;
+        let split = order[mid];
+        if points[split].distance_squared(point) <= radius_squared {
+            found.push(split);
+        }
+
+        // Only descend into sides the radius reaches
+        let diff = point[axis] - points[split][axis];
+        if diff <= radius {
+            self.within_radius_of(points, point, radius, &order[..mid], depth + 1, found);
+        }
+        if diff >= -radius {
+            self.within_radius_of(points, point, radius, &order[mid + 1..], depth + 1, found);
+        }
+    }
+}
+
+/// A list of points, with a k-d tree for spatial queries that is built lazily on the first query,
+/// and invalidated whenever the points are modified.
+#[derive(Clone, Debug, Default)]
+pub struct PointSet {
+    points: Vec<Vec3>,
+    tree: OnceLock<KdTree>,
+}
+
+impl PointSet {
+    /// Creates a new point set from the given points.
+    pub fn new(points: Vec<Vec3>) -> Self {
+        Self {
+            points,
+            tree: OnceLock::new(),
+        }
+    }
+
+    /// Returns the points of the set.
+    pub fn points(&self) -> &Vec<Vec3> {
+        &self.points
+    }
+
+    /// Returns the points of the set for modification, invalidating the k-d tree.
+    pub fn points_mut(&mut self) -> &mut Vec<Vec3> {
+        self.tree.take();
+        &mut self.points
+    }
+
+    /// Adds a point to the set, invalidating the k-d tree.
+    pub fn push(&mut self, point: Vec3) {
+        self.points_mut().push(point);
+    }
+
+    /// Moves the point at the given index, invalidating the k-d tree.
+    pub fn set(&mut self, index: usize, point: Vec3) {
+        self.points_mut()[index] = point;
+    }
+
+    /// Returns the number of points in the set.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Returns true if the set has no points.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Consumes the set, returning its points.
+    pub fn into_points(self) -> Vec<Vec3> {
+        self.points
+    }
+
+    /// Returns the k-d tree of the set, building it if necessary.
+    fn tree(&self) -> &KdTree {
+        self.tree.get_or_init(|| KdTree::new(&self.points))
+    }
+}
+
+impl From<Vec<Vec3>> for PointSet {
+    fn from(points: Vec<Vec3>) -> Self {
+        Self::new(points)
+    }
+}
+
+impl PointCloud for PointSet {
+    fn bounds(&self) -> BoundingBox {
+        self.points.bounds()
+    }
+
+    fn distant(&self, aabb: BoundingBox) -> (usize, usize) {
+        self.points.distant(aabb)
+    }
+
+    fn distant_line(&self, from: Vec3, to: Vec3) -> usize {
+        self.points.distant_line(from, to)
+    }
+
+    fn distant_plane(&self, from: Vec4) -> usize {
+        self.points.distant_plane(from)
+    }
+
+    fn nearest(&self, point: Vec3) -> Option<usize> {
+        self.tree().nearest(&self.points, point)
+    }
+
+    fn within_radius(&self, point: Vec3, radius: f32) -> Vec<usize> {
+        self.tree().within_radius(&self.points, point, radius)
+    }
 }
 
 #[cfg(test)]
@@ -182,4 +428,80 @@ mod tests {
             "furthest point from plane should be 1, got {furthest_from_plane}"
         );
     }
+
+    #[test]
+    fn nearest_and_radius() {
+        // Jittered grid of points, with a duplicate point to test tie-breaking
+        let mut pts: Vec<Vec3> = vec![];
+        for x in 0..12 {
+            for y in 0..9 {
+                for z in 0..7 {
+                    let jitter = ((x * 7 + y * 13 + z * 31) % 11) as f32 * 0.01;
+                    pts.push(Vec3::new(x as f32, y as f32 * 0.5, z as f32 * 2.0) + jitter);
+                }
+            }
+        }
+        pts.push(pts[100]);
+
+        let mut set = PointSet::from(pts.clone());
+        assert_eq!(pts.len(), set.len());
+
+        let queries = [
+            Vec3::ZERO,
+            Vec3::new(5.3, 2.1, 6.6),
+            Vec3::new(-4.0, 10.0, 3.0),
+            Vec3::new(11.5, 0.2, 13.9),
+            pts[100],
+            Vec3::new(6.02, 1.97, 4.05),
+        ];
+        for query in queries {
+            // Brute-force reference
+            let expected = pts
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| {
+                    a.distance_squared(query)
+                        .total_cmp(&b.distance_squared(query))
+                })
+                .map(|(idx, _)| idx);
+            assert_eq!(expected, pts.nearest(query), "linear nearest to {query}");
+            assert_eq!(expected, set.nearest(query), "k-d tree nearest to {query}");
+
+            for radius in [0.0, 0.3, 1.0, 2.5, 100.0] {
+                let expected: Vec<usize> = (0..pts.len())
+                    .filter(|idx| pts[*idx].distance(query) <= radius)
+                    .collect();
+                assert_eq!(
+                    expected,
+                    pts.within_radius(query, radius),
+                    "linear points within {radius} of {query}"
+                );
+                assert_eq!(
+                    expected,
+                    set.within_radius(query, radius),
+                    "k-d tree points within {radius} of {query}"
+                );
+            }
+        }
+
+        // Duplicate points resolve to the lowest index
+        assert_eq!(Some(100), set.nearest(pts[100]));
+
+        // Mutation invalidates the tree
+        let far = Vec3::splat(50.0);
+        assert_ne!(Some(5), set.nearest(far));
+        set.set(5, far);
+        assert_eq!(Some(5), set.nearest(far), "moved point should be found");
+        set.push(far + Vec3::X);
+        assert_eq!(
+            vec![5, pts.len()],
+            set.within_radius(far, 1.0),
+            "pushed point should be found"
+        );
+
+        // Empty sets have no neighbors
+        let empty = PointSet::default();
+        assert_eq!(None, empty.nearest(Vec3::ZERO));
+        assert!(empty.within_radius(Vec3::ZERO, 10.0).is_empty());
+    }
 }
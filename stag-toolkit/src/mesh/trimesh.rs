@@ -1,17 +1,20 @@
 use crate::math::bounding_box::BoundingBox;
-use crate::math::projection::{direction_to_quaternion, vector_in_cone};
-use crate::math::raycast::{Raycast, RaycastParameters, RaycastResult, RaycastResultReducer};
+use crate::math::projection::fibonacci_cone;
+use crate::math::raycast::{Raycast, RaycastParameters, RaycastResult};
 use crate::math::{
     projection::{Plane, plane},
     types::*,
 };
+use crate::mesh::bvh::TriangleMeshBVH;
 use glam::Vec4Swizzles;
 use noise::{NoiseFn, Perlin};
 use rayon::prelude::*;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, VecDeque};
 use std::f64::consts::PI;
 use std::io;
 use std::num::NonZero;
+use std::ops::{Add, Mul};
+use std::sync::OnceLock;
 
 // EDGES //
 
@@ -72,6 +75,11 @@ pub trait TriangleOperations {
     fn area(&self, positions: &[Vec3]) -> f32;
     /// Returns a face-winded list of edges on this triangle.
     fn edges(&self) -> [Edge; 3];
+    /// Returns the point on the surface of the triangle nearest to the given point.
+    fn closest_point(&self, positions: &[Vec3], point: Vec3) -> Vec3;
+    /// Intersects the triangle with the finite line segment from `a` to `b`, from either side,
+    /// returning the intersection point if there is one. See [Plane::segment_intersection].
+    fn segment_intersection(&self, positions: &[Vec3], a: Vec3, b: Vec3) -> Option<Vec3>;
 }
 
 impl TriangleOperations for Triangle {
@@ -136,7 +144,8 @@ impl TriangleOperations for Triangle {
         let v = (d11 * d20 - d01 * d21) / denom;
         let w = (d00 * d21 - d01 * d20) / denom;
 
-        Vec3::new(1.0 - v - w, v, w) // Return UVW coordinates
+        // v is the weight of C, and w is the weight of B
+        Vec3::new(1.0 - v - w, w, v) // Return UVW coordinates, ordered by vertex
     }
 
     fn contains_barycentric(&self, barycentric_point: Vec3) -> bool {
@@ -193,6 +202,70 @@ impl TriangleOperations for Triangle {
     fn edges(&self) -> [Edge; 3] {
         [[self[0], self[1]], [self[1], self[2]], [self[2], self[0]]]
     }
+
+    // Real-Time Collision Detection, Christer Ericson, section 5.1.5
+    fn closest_point(&self, positions: &[Vec3], point: Vec3) -> Vec3 {
+        let a = positions[self[0]];
+        let b = positions[self[1]];
+        let c = positions[self[2]];
+
+        let ab = b - a;
+        let ac = c - a;
+
+        // Check if point is in vertex region outside A
+        let ap = point - a;
+        let d1 = ab.dot(ap);
+        let d2 = ac.dot(ap);
+        if d1 <= 0.0 && d2 <= 0.0 {
+            return a;
+        }
+
+        // Check if point is in vertex region outside B
+        let bp = point - b;
+        let d3 = ab.dot(bp);
+        let d4 = ac.dot(bp);
+        if d3 >= 0.0 && d4 <= d3 {
+            return b;
+        }
+
+        // Check if point is in edge region of AB
+        let vc = d1 * d4 - d3 * d2;
+        if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+            return a + ab * (d1 / (d1 - d3));
+        }
+
+        // Check if point is in vertex region outside C
+        let cp = point - c;
+        let d5 = ab.dot(cp);
+        let d6 = ac.dot(cp);
+        if d6 >= 0.0 && d5 <= d6 {
+            return c;
+        }
+
+        // Check if point is in edge region of AC
+        let vb = d5 * d2 - d1 * d6;
+        if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+            return a + ac * (d2 / (d2 - d6));
+        }
+
+        // Check if point is in edge region of BC
+        let va = d3 * d6 - d5 * d4;
+        if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+            return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+        }
+
+        // Point is inside face region
+        let denom = 1.0 / (va + vb + vc);
+        a + ab * (vb * denom) + ac * (vc * denom)
+    }
+
+    fn segment_intersection(&self, positions: &[Vec3], a: Vec3, b: Vec3) -> Option<Vec3> {
+        let hit = self.plane(positions).segment_intersection(a, b)?;
+        if self.contains_barycentric(self.barycentric(positions, hit)) {
+            return Some(hit);
+        }
+        None
+    }
 }
 
 // MESHES //
@@ -215,19 +288,340 @@ pub struct TriangleMesh {
 
     /// Optional plane-per-triangle, used for raycast optimization.
     pub planes: Vec<Vec4>,
+    /// Optional bounding box of the vertex positions, baked alongside planes for raycast optimization.
+    pub bounds: Option<BoundingBox>,
+    /// Edge-neighbors per triangle, computed lazily for graph algorithms.
+    /// See [Self::triangle_adjacency].
+    adjacency: OnceLock<Vec<[Option<usize>; 3]>>,
 
     pub uv1: Option<Vec<Vec2>>,
     pub uv2: Option<Vec<Vec2>>,
+    /// Optional tangents for normal mapping, assigned to vertices of the corresponding index.
+    /// The XYZ components are the tangent direction, and W is the handedness of the binormal.
+    pub tangents: Option<Vec<Vec4>>,
+    /// Optional single-channel custom data, assigned to vertices of the corresponding index.
+    pub custom0: Option<Vec<f32>>,
+    /// Optional single-channel custom data, assigned to vertices of the corresponding index.
+    pub custom1: Option<Vec<f32>>,
+    /// Optional single-channel custom data, assigned to vertices of the corresponding index.
+    pub custom2: Option<Vec<f32>>,
+    /// Optional material index, assigned to vertices of the corresponding index.
+    /// Used for splitting the mesh into surfaces. See [Self::split_by_material].
+    pub material_index: Option<Vec<u32>>,
+}
+
+/// Half-angle of the cone that directional occlusion samples are jittered within, in radians.
+const DIRECTIONAL_OCCLUSION_SPREAD: f32 = 0.05;
+/// Distance to pad mesh bounds by when checking if a ray misses the mesh.
+const RAYCAST_BOUNDS_MARGIN: f32 = 1e-4;
+/// Number of intervals along each axis that split planes are tested at, during convex decomposition.
+const CONVEX_SPLIT_CANDIDATES: usize = 8;
+/// Distance within which points are considered to lie on a split plane, during convex decomposition.
+const CONVEX_PLANE_EPSILON: f32 = 1e-5;
+
+/// Weight of the perpendicular planes added along open boundaries during quadric decimation,
+/// relative to the squared boundary edge length.
+const QUADRIC_BOUNDARY_PENALTY: f64 = 1000.0;
+
+/// A triangle soup being convex decomposed, alongside its concavity.
+type ConvexPiece = (f32, Vec<[Vec3; 3]>);
+
+/// Projects the given position onto the YZ, XZ and XY planes for triplanar texture mapping, in that order.
+/// Each projection is multiplied by the given scale.
+pub fn triplanar_projection(position: Vec3, scale: f32) -> [Vec2; 3] {
+    let p = position * scale;
+    [
+        Vec2::new(p.z, p.y),
+        Vec2::new(p.x, p.z),
+        Vec2::new(p.x, p.y),
+    ]
+}
+
+/// Returns triplanar blend weights for the given normal, for the YZ, XZ and XY projections in the XYZ components.
+/// Weights sum to 1, and higher sharpness narrows the blend between projections.
+pub fn triplanar_blend(normal: Vec3, sharpness: f32) -> Vec3 {
+    let weights = normal.abs().powf(sharpness.max(0.0));
+    let total = weights.element_sum();
+    if total > 0.0 {
+        weights / total
+    } else {
+        Vec3::splat(1.0 / 3.0)
+    }
+}
+
+/// Result data from a closest-point query.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ClosestPointResult {
+    /// Nearest point on the surface of the mesh.
+    pub point: Vec3,
+    /// Face normal of the nearest triangle.
+    pub normal: Vec3,
+    /// Distance from the query point to the nearest point.
+    pub distance: f32,
+    /// Index of the nearest triangle, or [None] if the mesh has no triangles.
+    pub face_index: Option<usize>,
+}
+
+impl Default for ClosestPointResult {
+    fn default() -> Self {
+        Self {
+            point: Vec3::ZERO,
+            normal: Vec3::ZERO,
+            distance: f32::INFINITY,
+            face_index: None,
+        }
+    }
+}
+
+/// Spatial hash of vertex positions, for finding nearby vertices when merging vertices or appending triangles to a mesh.
+/// See [TriangleMesh::add_triangle_deduped].
+#[derive(Clone, Debug, Default)]
+pub struct VertexHash {
+    /// Distance within which vertices are reused, also used as the cell size.
+    distance: f32,
+    /// Indices of the vertices within each cell.
+    cells: HashMap<[i32; 3], Vec<usize>>,
+}
+
+impl VertexHash {
+    /// Creates an empty vertex hash, reusing vertices within the given distance of one another.
+    pub fn new(distance: f32) -> Self {
+        Self {
+            distance: distance.max(f32::EPSILON),
+            cells: HashMap::new(),
+        }
+    }
+
+    /// Creates a vertex hash containing all existing vertices of the given mesh.
+    pub fn from_mesh(mesh: &TriangleMesh, distance: f32) -> Self {
+        let mut hash = Self::new(distance);
+        for (idx, position) in mesh.positions.iter().enumerate() {
+            hash.cells
+                .entry(hash.cell(*position))
+                .or_default()
+                .push(idx);
+        }
+        hash
+    }
+
+    /// Returns the cell key containing the given position.
+    fn cell(&self, position: Vec3) -> [i32; 3] {
+        let cell = (position / self.distance).floor();
+        [cell.x as i32, cell.y as i32, cell.z as i32]
+    }
+
+    /// Returns the indices of all hashed vertices within range of the given position.
+    fn within<'a>(
+        &'a self,
+        positions: &'a [Vec3],
+        position: Vec3,
+    ) -> impl Iterator<Item = usize> + 'a {
+        let [x, y, z] = self.cell(position);
+        let distance_squared = self.distance * self.distance;
+
+        // Vertices within range may be in any neighboring cell
+        (0..27)
+            .filter_map(move |offset| {
+                self.cells.get(&[
+                    x + offset % 3 - 1,
+                    y + (offset / 3) % 3 - 1,
+                    z + offset / 9 - 1,
+                ])
+            })
+            .flatten()
+            .copied()
+            .filter(move |idx| positions[*idx].distance_squared(position) <= distance_squared)
+    }
+
+    /// Adds the vertex index to the cell containing the given position.
+    fn insert(&mut self, idx: usize, position: Vec3) {
+        self.cells.entry(self.cell(position)).or_default().push(idx);
+    }
+
+    /// Removes the vertex index from the cell containing the given position.
+    fn remove(&mut self, idx: usize, position: Vec3) {
+        if let Some(cell) = self.cells.get_mut(&self.cell(position))
+            && let Some(slot) = cell.iter().position(|other| *other == idx)
+        {
+            cell.swap_remove(slot);
+        }
+    }
+
+    /// Returns the index of a vertex within range of the given position,
+    /// appending the position to the list and returning its new index if there is none.
+    fn find_or_insert(&mut self, positions: &mut Vec<Vec3>, position: Vec3) -> usize {
+        if let Some(nearby) = self.within(positions, position).next() {
+            return nearby;
+        }
+
+        positions.push(position);
+        let idx = positions.len() - 1;
+        self.insert(idx, position);
+        idx
+    }
+}
+
+/// Summary of the topological defects of a mesh, from [TriangleMesh::manifold_report].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ManifoldReport {
+    /// Number of undirected edges used by only a single face, where the mesh is open.
+    pub boundary_edges: usize,
+    /// Number of undirected edges shared by three or more faces, such as T-junctions.
+    pub non_manifold_edges: usize,
+    /// Number of vertices not referenced by any triangle.
+    pub isolated_vertices: usize,
+}
+
+impl ManifoldReport {
+    /// Returns true if no defects were found.
+    pub fn is_manifold(&self) -> bool {
+        self.boundary_edges == 0 && self.non_manifold_edges == 0 && self.isolated_vertices == 0
+    }
 }
 
-/// Worker data for an ambient occlusion baker.
-struct AmbientOcclusionWorker<'a> {
-    from: usize,
-    to: usize,
+/// Symmetric 4x4 error quadric for Quadric Error Metric decimation,
+/// storing the upper triangle of the matrix.
+#[derive(Copy, Clone, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    /// Returns the quadric of the given plane, scaled by the given weight.
+    fn from_plane(plane: Vec4, weight: f64) -> Self {
+        let [a, b, c, d] = plane.to_array().map(|v| v as f64);
+        Self(
+            [
+                a * a,
+                a * b,
+                a * c,
+                a * d,
+                b * b,
+                b * c,
+                b * d,
+                c * c,
+                c * d,
+                d * d,
+            ]
+            .map(|v| v * weight),
+        )
+    }
+
+    /// Returns the squared error of the given point against this quadric.
+    fn error(&self, point: Vec3) -> f64 {
+        let [aa, ab, ac, ad, bb, bc, bd, cc, cd, dd] = self.0;
+        let (x, y, z) = (point.x as f64, point.y as f64, point.z as f64);
+        aa * x * x
+            + 2.0 * ab * x * y
+            + 2.0 * ac * x * z
+            + 2.0 * ad * x
+            + bb * y * y
+            + 2.0 * bc * y * z
+            + 2.0 * bd * y
+            + cc * z * z
+            + 2.0 * cd * z
+            + dd
+    }
+
+    /// Returns the point minimizing the error of this quadric, if the quadric is well-conditioned.
+    fn optimal(&self) -> Option<Vec3> {
+        let [aa, ab, ac, ad, bb, bc, bd, cc, cd, _] = self.0;
+        let det = aa * (bb * cc - bc * bc) - ab * (ab * cc - bc * ac) + ac * (ab * bc - bb * ac);
+        if det.abs() < 1e-12 {
+            return None;
+        }
+
+        // Cramer's rule
+        let x = -(ad * (bb * cc - bc * bc) - ab * (bd * cc - bc * cd) + ac * (bd * bc - bb * cd));
+        let y = -(aa * (bd * cc - cd * bc) - ad * (ab * cc - bc * ac) + ac * (ab * cd - bd * ac));
+        let z = -(aa * (bb * cd - bc * bd) - ab * (ab * cd - bd * ac) + ad * (ab * bc - bb * ac));
+        Some(Vec3::new(
+            (x / det) as f32,
+            (y / det) as f32,
+            (z / det) as f32,
+        ))
+    }
+}
+
+impl Add for Quadric {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut sum = self.0;
+        for (value, other) in sum.iter_mut().zip(rhs.0) {
+            *value += other;
+        }
+        Self(sum)
+    }
+}
+
+/// A candidate edge collapse for Quadric Error Metric decimation, ordered by lowest cost first.
+struct CollapseCandidate {
+    cost: f64,
+    edge: Edge,
+    position: Vec3,
+}
+
+impl PartialEq for CollapseCandidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+impl Eq for CollapseCandidate {}
+impl PartialOrd for CollapseCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for CollapseCandidate {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // Reversed, so the binary heap pops the lowest cost first.
+        // Ties are broken by edge, so collapse order does not depend on insertion order.
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| other.edge.cmp(&self.edge))
+    }
+}
+
+/// Averages the values of each cluster of vertices into the cluster's root vertex,
+/// given the root of every vertex and the number of vertices in each cluster.
+/// Values of non-root vertices are left as-is.
+/// Returns false, doing nothing, if the values do not match the number of vertices.
+fn average_clusters<T>(values: &mut [T], roots: &[usize], counts: &[usize]) -> bool
+where
+    T: Copy + Default + Add<Output = T> + Mul<f32, Output = T>,
+{
+    if values.len() != roots.len() {
+        return false;
+    }
+
+    let mut sums = vec![T::default(); values.len()];
+    for (value, root) in values.iter().zip(roots) {
+        sums[*root] = sums[*root] + *value;
+    }
+    for (idx, (sum, count)) in sums.into_iter().zip(counts).enumerate() {
+        if *count > 1 {
+            values[idx] = sum * (1.0 / *count as f32);
+        }
+    }
+    true
+}
+
+/// Applies a list of `(later, earlier)` vertex merges to a vertex attribute buffer, in order,
+/// moving each earlier value to the midpoint between itself and the original later value.
+/// Returns false, doing nothing, if the buffer does not have a value for every vertex.
+fn blend_merges<T>(values: &mut [T], merges: &[(usize, usize)], count: usize) -> bool
+where
+    T: Copy + Add<Output = T> + Mul<f32, Output = T>,
+{
+    if values.len() != count {
+        return false;
+    }
 
-    positions: &'a [Vec3],
-    normals: &'a [Vec3],
-    // results: &'a [f32],
+    let original = values.to_vec();
+    for (later, earlier) in merges.iter() {
+        values[*earlier] = (original[*later] + values[*earlier]) * 0.5;
+    }
+    true
 }
 
 impl TriangleMesh {
@@ -245,8 +639,15 @@ impl TriangleMesh {
             normals: normals.unwrap_or_default(),
             colors: colors.unwrap_or_default(),
             planes: vec![],
+            bounds: None,
+            adjacency: OnceLock::new(),
             uv1: None,
             uv2: None,
+            tangents: None,
+            custom0: None,
+            custom1: None,
+            custom2: None,
+            material_index: None,
         }
     }
 
@@ -274,9 +675,135 @@ impl TriangleMesh {
             normals: normals.unwrap_or_default(),
             colors: vec![],
             planes: vec![],
+            bounds: None,
+            adjacency: OnceLock::new(),
             uv1: None,
             uv2: None,
+            tangents: None,
+            custom0: None,
+            custom1: None,
+            custom2: None,
+            material_index: None,
+        }
+    }
+
+    /// Creates a new TriangleMesh from a list of indices, like [Self::from_indices].
+    /// If `assume_ccw` is false, the winding is detected from the mesh's signed volume,
+    /// and all triangles are flipped to face outward if the mesh was wound clockwise.
+    pub fn from_indices_oriented(
+        indices: Vec<usize>,
+        positions: Vec<Vec3>,
+        normals: Option<Vec<Vec3>>,
+        assume_ccw: bool,
+    ) -> Self {
+        let mut mesh = Self::from_indices(indices, positions, normals);
+        if !assume_ccw {
+            mesh.orient_outward();
+        }
+        mesh
+    }
+
+    /// Returns the signed volume enclosed by the mesh.
+    /// Positive for closed meshes with outward-facing, counter-clockwise winding, and negative if inverted.
+    pub fn signed_volume(&self) -> f32 {
+        self.triangles
+            .iter()
+            .map(|tri| {
+                let [a, b, c] = tri.map(|idx| self.positions[idx]);
+                a.dot(b.cross(c))
+            })
+            .sum::<f32>()
+            / 6.0
+    }
+
+    /// Returns a line segment for every vertex normal, starting at the vertex and extending along the normal by the given length.
+    /// Useful for visualizing normals when debugging.
+    pub fn normal_segments(&self, length: f32) -> Vec<(Vec3, Vec3)> {
+        self.positions
+            .iter()
+            .zip(self.normals.iter())
+            .map(|(position, normal)| (*position, *position + *normal * length))
+            .collect()
+    }
+
+    /// Estimates the signed mean curvature at every vertex, using the umbrella operator
+    /// projected onto the vertex normal.
+    /// Positive values are convex (ridges), negative values are concave (crevices).
+    /// On a sphere, this approaches `1 / radius`.
+    ///
+    /// Requires vertex normals to be baked beforehand. Vertices without neighbors have zero curvature.
+    pub fn mean_curvature(&self) -> Vec<f32> {
+        let count = self.positions.len();
+        let mut neighbor_sum = vec![Vec3::ZERO; count];
+        let mut edge_length_sq = vec![0.0f32; count];
+        let mut neighbors = vec![0usize; count];
+
+        for tri in self.triangles.iter() {
+            for edge in 0..3 {
+                let (a, b) = (tri[edge], tri[(edge + 1) % 3]);
+                let length_sq = self.positions[a].distance_squared(self.positions[b]);
+                for (from, to) in [(a, b), (b, a)] {
+                    neighbor_sum[from] += self.positions[to];
+                    edge_length_sq[from] += length_sq;
+                    neighbors[from] += 1;
+                }
+            }
+        }
+
+        (0..count)
+            .map(|idx| {
+                if neighbors[idx] == 0 || edge_length_sq[idx] <= 0.0 {
+                    return 0.0;
+                }
+                let n = neighbors[idx] as f32;
+                let centroid = neighbor_sum[idx] / n;
+                let normal = self.normals.get(idx).copied().unwrap_or(Vec3::ZERO);
+                // A neighbor at distance e sits e^2 / (2r) below the tangent plane of a sphere with radius r
+                2.0 * (self.positions[idx] - centroid).dot(normal) / (edge_length_sq[idx] / n)
+            })
+            .collect()
+    }
+
+    /// Translates every vertex of the mesh by the given offset, in place.
+    /// Any baked raycast planes and bounds are re-baked.
+    pub fn translate(&mut self, offset: Vec3) {
+        for position in self.positions.iter_mut() {
+            *position += offset;
+        }
+        if !self.planes.is_empty() {
+            self.bake_raycast_planes();
+        } else if let Some(bounds) = self.bounds {
+            self.bounds = Some(bounds.translate(offset));
+        }
+    }
+
+    /// Translates the mesh so its bounding box center sits at the origin, improving floating-point precision.
+    /// Returns the original center, which translates the mesh back to its original position.
+    /// Apply it to the node transform to keep the mesh in place.
+    pub fn recenter(&mut self) -> Vec3 {
+        let center = self.bounding_box().center();
+        self.translate(-center);
+        center
+    }
+
+    /// Flips the winding of every triangle in the mesh, in place.
+    /// Any baked raycast planes and triangle adjacency are cleared.
+    pub fn flip_winding(&mut self) {
+        for tri in self.triangles.iter_mut() {
+            *tri = tri.flip();
         }
+        self.planes.clear();
+        self.clear_triangle_adjacency();
+    }
+
+    /// Flips all triangles to face outward if the mesh has a negative signed volume.
+    /// Returns true if the mesh was flipped.
+    pub fn orient_outward(&mut self) -> bool {
+        if self.signed_volume() < 0.0 {
+            self.flip_winding();
+            return true;
+        }
+        false
     }
 
     /// Joins the given mesh with this one, in place.
@@ -296,6 +823,77 @@ impl TriangleMesh {
             self.triangles
                 .push([tri[0] + idx_count, tri[1] + idx_count, tri[2] + idx_count]);
         }
+        self.clear_triangle_adjacency();
+    }
+
+    /// Returns the material index of the given triangle, by majority vote of its vertices.
+    /// If all three vertices differ, the first vertex's material is used.
+    /// Returns 0 if the mesh has no material indices.
+    pub fn triangle_material(&self, tri: &Triangle) -> u32 {
+        let Some(material_index) = &self.material_index else {
+            return 0;
+        };
+        let [a, b, c] = tri.map(|idx| material_index[idx]);
+        if b == c { b } else { a }
+    }
+
+    /// Splits the mesh into one mesh per material index, sorted by material index.
+    /// Each triangle goes to the material of [Self::triangle_material],
+    /// and vertices shared between materials are duplicated into each mesh alongside their attributes.
+    /// Baked planes and adjacency are not kept.
+    ///
+    /// If the mesh has no material indices, the whole mesh is returned under material 0.
+    pub fn split_by_material(&self) -> Vec<(u32, Self)> {
+        let mut groups: BTreeMap<u32, Vec<Triangle>> = BTreeMap::new();
+        for tri in self.triangles.iter() {
+            groups
+                .entry(self.triangle_material(tri))
+                .or_default()
+                .push(*tri);
+        }
+
+        groups
+            .into_iter()
+            .map(|(material, triangles)| {
+                let mut mesh = Self {
+                    triangles,
+                    planes: vec![],
+                    bounds: None,
+                    adjacency: OnceLock::new(),
+                    ..self.clone()
+                };
+                mesh.remove_unused();
+                (material, mesh)
+            })
+            .collect()
+    }
+
+    /// Appends a triangle of the given positions to the mesh, in counter-clockwise winding,
+    /// returning the triangle of new vertex indices.
+    /// Other vertex attributes are not extended, so they should be baked after building the mesh.
+    pub fn add_triangle(&mut self, a: Vec3, b: Vec3, c: Vec3) -> Triangle {
+        let idx = self.positions.len();
+        self.positions.extend([a, b, c]);
+        let tri = [idx, idx + 1, idx + 2];
+        self.triangles.push(tri);
+        self.clear_triangle_adjacency();
+        tri
+    }
+
+    /// Appends a triangle of the given positions to the mesh, like [Self::add_triangle],
+    /// but reuses any vertex in the vertex hash within range of each position, instead of adding a new one.
+    /// New vertices are added to the hash, so it should be shared between calls.
+    pub fn add_triangle_deduped(
+        &mut self,
+        hash: &mut VertexHash,
+        a: Vec3,
+        b: Vec3,
+        c: Vec3,
+    ) -> Triangle {
+        let tri = [a, b, c].map(|position| hash.find_or_insert(&mut self.positions, position));
+        self.triangles.push(tri);
+        self.clear_triangle_adjacency();
+        tri
     }
 
     /// Returns the first left and right faces of an edge, if they exist.
@@ -340,6 +938,35 @@ impl TriangleMesh {
         indices
     }
 
+    /// Replaces the triangles of the mesh with the given list of indices, keeping existing vertex data.
+    /// Every three indices are expected to represent a triangle, with counter-clockwise face winding.
+    /// Any baked raycast planes and triangle adjacency are cleared.
+    ///
+    /// Returns false and leaves the mesh unchanged if the index count is not divisible by 3,
+    /// or if any index is out of range of the vertex positions.
+    pub fn set_indices(&mut self, indices: &[usize]) -> bool {
+        if !indices.len().is_multiple_of(3)
+            || indices.iter().any(|idx| *idx >= self.positions.len())
+        {
+            return false;
+        }
+
+        self.triangles = indices
+            .chunks_exact(3)
+            .map(|tri| [tri[0], tri[1], tri[2]])
+            .collect();
+        self.planes.clear();
+        self.clear_triangle_adjacency();
+        true
+    }
+
+    /// Returns an iterator over the vertex positions of each triangle, in winding order.
+    pub fn iter_triangle_positions(&self) -> impl Iterator<Item = [Vec3; 3]> + '_ {
+        self.triangles
+            .iter()
+            .map(|tri| tri.map(|idx| self.positions[idx]))
+    }
+
     /// Returns a hash map of edges.
     /// For each edge, the left and right face index is returned, in order.
     /// This method assumes that each edge has a maximum of two faces,
@@ -369,1046 +996,4667 @@ impl TriangleMesh {
         edges
     }
 
-    /// Calculates the angle between two faces.
-    pub fn face_angle(&self, a: &Triangle, b: &Triangle) -> f32 {
-        a.normal(&self.positions)
-            .angle_between(b.normal(&self.positions))
+    /// Returns an iterator over each undirected edge of the mesh, with the faces on either side.
+    /// Yields the edge as wound in its left face, the left face index, and the right face index, if any.
+    /// The left face is the lowest-indexed face sharing the edge, like in [Self::edge_map].
+    ///
+    /// Unlike [Self::edge_map], edges are yielded in a deterministic order,
+    /// sorted by their lowest vertex index, then their highest vertex index.
+    /// For non-manifold edges, only the two lowest-indexed faces are reported.
+    pub fn edges_with_faces(&self) -> impl Iterator<Item = (Edge, usize, Option<usize>)> + '_ {
+        // Sorting half-edges by their undirected key groups each edge's faces together, in face order
+        let mut half_edges: Vec<(Edge, usize, Edge)> = self
+            .triangles
+            .iter()
+            .enumerate()
+            .flat_map(|(face, tri)| {
+                tri.edges()
+                    .map(|edge| ([edge[0].min(edge[1]), edge[0].max(edge[1])], face, edge))
+            })
+            .collect();
+        half_edges.sort_unstable();
+
+        let mut half_edges = half_edges.into_iter().peekable();
+        std::iter::from_fn(move || {
+            let (key, left, edge) = half_edges.next()?;
+            let right = half_edges
+                .next_if(|(next, _, _)| *next == key)
+                .map(|(_, face, _)| face);
+            // Skip any additional faces on non-manifold edges
+            while half_edges.next_if(|(next, _, _)| *next == key).is_some() {}
+            Some((edge, left, right))
+        })
     }
 
-    /// Removes an edge from the mesh by merging both vertices into a centerpoint.
-    /// Does not remove degenerate geometry.
-    pub fn edge_collapse(&mut self, edge: &Edge) {
-        // Create a new vertex at the center of the edge
-        let center = (self.positions[edge[0]] + self.positions[edge[1]]) * 0.5;
+    /// Returns the number of faces on each undirected edge of the mesh,
+    /// keyed by the edge with its lowest vertex index first.
+    /// Unlike [Self::edge_map], edges shared by more than two faces are counted rather than overwritten.
+    pub fn edge_face_counts(&self) -> HashMap<Edge, usize> {
+        let mut edge_faces: HashMap<Edge, usize> = HashMap::with_capacity(self.triangles.len() * 3);
+        for tri in self.triangles.iter() {
+            for edge in tri.edges() {
+                *edge_faces
+                    .entry([edge[0].min(edge[1]), edge[0].max(edge[1])])
+                    .or_insert(0) += 1;
+            }
+        }
+        edge_faces
+    }
 
-        // Append vertex to end of positions list
-        let new_idx = self.positions.len();
-        self.positions.push(center);
+    /// Counts the topological defects of the mesh, such as open or non-manifold edges.
+    /// See [Self::edge_face_counts].
+    pub fn manifold_report(&self) -> ManifoldReport {
+        let edge_faces = self.edge_face_counts();
+        let mut used = vec![false; self.positions.len()];
+        for tri in self.triangles.iter() {
+            for idx in tri.iter() {
+                if let Some(used) = used.get_mut(*idx) {
+                    *used = true;
+                }
+            }
+        }
 
-        // Swap out old vertex indices for new one
-        self.swap_indices(vec![(edge[0], new_idx), (edge[1], new_idx)]);
+        ManifoldReport {
+            boundary_edges: edge_faces.values().filter(|count| **count == 1).count(),
+            non_manifold_edges: edge_faces.values().filter(|count| **count > 2).count(),
+            isolated_vertices: used.iter().filter(|used| !**used).count(),
+        }
     }
 
-    /// Decimates the mesh by removing all immediate edges with an angle less than the given threshold.
-    /// When the number of triangles removed per decimation falls under the `minimum_dropout` threshold,
-    /// the algorithm stops decimating triangles.
-    pub fn decimate_planar(&mut self, threshold: f32, iterations: u32, minimum_dropout: u32) {
-        // Do nothing if invalid.
-        if iterations == 0 {
-            return;
-        }
+    /// Returns true if the mesh is closed and manifold, with every edge shared by exactly two faces,
+    /// and no isolated vertices. See [Self::manifold_report] for a breakdown of defects.
+    pub fn is_manifold(&self) -> bool {
+        self.manifold_report().is_manifold()
+    }
 
-        for _ in 0..iterations {
-            // Get a list of all edges in the trimesh
-            let edges = self.edge_map();
+    /// Returns the [TriangleMesh::edge_map] as a list sorted by edge,
+    /// so edges can be visited in a deterministic order.
+    pub fn edge_list(&self) -> Vec<(Edge, EdgeTriangles)> {
+        let mut edges: Vec<(Edge, EdgeTriangles)> = self.edge_map().into_iter().collect();
+        edges.sort_unstable_by_key(|(edge, _)| *edge);
+        edges
+    }
 
-            // Collapse all edges below the threshold
-            let mut count = 0;
-            for (edge, (left_idx, right_idx)) in edges.iter() {
-                if let Some(right_idx) = right_idx
-                    && self.face_angle(&self.triangles[*left_idx], &self.triangles[right_idx.get()])
-                        < threshold
-                {
-                    self.edge_collapse(edge);
-                    count += 1;
+    /// Returns the edge-neighbors of each triangle, in the same order as [Triangle::edges].
+    /// A neighbor is [None] if the edge is a boundary.
+    /// Computed from the [TriangleMesh::edge_map] on first use, and cached until the mesh topology changes.
+    pub fn triangle_adjacency(&self) -> &[[Option<usize>; 3]] {
+        self.adjacency.get_or_init(|| {
+            let mut adjacency = vec![[None; 3]; self.triangles.len()];
+            for (edge, (left, right)) in self.edge_list() {
+                if let Some(right) = right {
+                    let right = right.get();
+                    let edge_index = |tri: usize, edge: Edge| {
+                        self.triangles[tri].edges().iter().position(|e| *e == edge)
+                    };
+
+                    if let (Some(left_edge), Some(right_edge)) =
+                        (edge_index(left, edge), edge_index(right, edge.flip()))
+                    {
+                        adjacency[left][left_edge] = Some(right);
+                        adjacency[right][right_edge] = Some(left);
+                    }
                 }
             }
 
-            // Clean up mesh after decimation
-            self.remove_degenerate();
-
-            // End decimation if nothing changed
-            if count <= minimum_dropout {
-                break;
-            }
-        }
+            adjacency
+        })
+    }
 
-        self.remove_unused();
+    /// Clears the cached triangle adjacency, so it is recomputed on next use.
+    /// Methods that change the mesh topology do this automatically,
+    /// but it must be called after modifying [Self::triangles] directly.
+    pub fn clear_triangle_adjacency(&mut self) {
+        self.adjacency.take();
     }
 
-    /// Merges all vertices within the given threshold distance of each other, merging later vertices into earlier ones.
-    /// This operation occurs in place.
-    ///
-    /// **Does not remove degenerate triangles or unused vertices.**
-    /// Call `remove_degenerate` and `remove_unused` to clean up the mesh when you are done editing it.
-    /// Or, to do everything at once, call `optimize`.
-    pub fn merge_by_distance(&mut self, threshold: f32) {
-        if threshold <= 0.0 {
-            // Don't do anything if disabled
-            return;
+    /// Returns all triangles reachable from the seed triangle, in breadth-first order, starting with the seed.
+    /// Shared edges are only crossed if `accept(current, neighbor)` returns true.
+    /// Returns an empty list if the seed is out of range.
+    pub fn flood_fill(&self, seed: usize, accept: impl Fn(usize, usize) -> bool) -> Vec<usize> {
+        if seed >= self.triangles.len() {
+            return vec![];
         }
 
-        let thresh_squared = threshold * threshold;
-
-        // Array of new, merged vertices
-        let mut new_verts = self.positions.clone();
-        // List of vertex indices: (replace, new)
-        // Estimate that we'll roughly need 10% of our vertex list to deal with
-        let mut replace: Vec<(usize, usize)> =
-            Vec::with_capacity((new_verts.len() as f64 * 0.1) as usize);
+        let adjacency = self.triangle_adjacency();
+        let mut visited = vec![false; self.triangles.len()];
+        let mut filled: Vec<usize> = vec![];
+        let mut queue = VecDeque::from([seed]);
+        visited[seed] = true;
 
-        // Start from the back of the array
-        for (i, vert) in self.positions.iter().enumerate().rev() {
-            // ...read forward until we hit our current index
-            for j in 0..i {
-                if vert.distance_squared(new_verts[j]) <= thresh_squared {
-                    // TODO: look over how the new_verts buffer is handled
-                    // Remove vertices at the back of the new list
-                    new_verts.remove(i);
-                    // ...and modify the vertices at the front to be the midpoint
-                    new_verts[j] = (vert + new_verts[j]) * 0.5;
-
-                    // ...and note what vertices to replace
-                    replace.push((i, j));
+        while let Some(current) = queue.pop_front() {
+            filled.push(current);
 
-                    break;
+            for neighbor in adjacency[current].iter().flatten() {
+                if !visited[*neighbor] && accept(current, *neighbor) {
+                    visited[*neighbor] = true;
+                    queue.push_back(*neighbor);
                 }
             }
         }
 
-        // Finally, update triangle indices
-        self.swap_indices(replace);
+        filled
     }
 
-    /// Iterates over all triangles, replacing each vertex index value using the given tuple: (old, new).
-    /// Does not remove degenerate triangles.
-    pub fn swap_indices(&mut self, replace: Vec<(usize, usize)>) {
-        if replace.is_empty() {
-            return;
+    /// Splits the mesh into approximately convex pieces, such as for generating multiple convex collision hulls.
+    ///
+    /// Each connected component is recursively split along axis-aligned planes, most concave piece first,
+    /// until every piece is within `concavity` distance of being convex, or there are `max_pieces` pieces.
+    /// Concavity is the furthest distance any vertex of a piece lies in front of one of its faces,
+    /// so faces are expected to be wound outward, and vertices welded beforehand.
+    ///
+    /// Triangles are clipped exactly along split planes, and pieces are left open along the cuts.
+    /// Components are never merged, so the result may exceed `max_pieces` if there are more components.
+    pub fn convex_decompose(&self, max_pieces: usize, concavity: f32) -> Vec<Self> {
+        let mesh = self.clone();
+
+        // Gather each connected component as a triangle soup
+        let mut visited = vec![false; mesh.triangles.len()];
+        let mut pieces: Vec<ConvexPiece> = vec![];
+        for seed in 0..mesh.triangles.len() {
+            if visited[seed] {
+                continue;
+            }
+
+            let component: Vec<[Vec3; 3]> = mesh
+                .flood_fill(seed, |_, _| true)
+                .into_iter()
+                .map(|face| {
+                    visited[face] = true;
+                    mesh.triangles[face].map(|i| mesh.positions[i])
+                })
+                .collect();
+            pieces.push((Self::piece_concavity(&component), component));
         }
 
-        // Iterate over every swap item
-        for idx_swap in replace.iter() {
-            for tri in self.triangles.iter_mut() {
-                // Update the triangle indices
-                for idx in tri.iter_mut() {
-                    if idx_swap.0 == *idx {
-                        *idx = idx_swap.1;
-                    }
+        // Split the most concave piece until all are convex enough, or we run out of pieces
+        let mut finished: Vec<Vec<[Vec3; 3]>> = vec![];
+        while pieces.len() + finished.len() < max_pieces {
+            let Some(worst) = pieces
+                .iter()
+                .enumerate()
+                .max_by(|(_, a), (_, b)| a.0.total_cmp(&b.0))
+                .map(|(idx, _)| idx)
+            else {
+                break;
+            };
+            if pieces[worst].0 <= concavity {
+                break;
+            }
+
+            let (_, piece) = pieces.swap_remove(worst);
+            match Self::split_concave_piece(&piece) {
+                Some((below, above)) => {
+                    pieces.push(below);
+                    pieces.push(above);
                 }
+                // No plane splits this piece, so leave it be
+                None => finished.push(piece),
             }
         }
+
+        pieces
+            .into_iter()
+            .map(|(_, piece)| piece)
+            .chain(finished)
+            .map(|piece| {
+                let positions: Vec<Vec3> = piece.iter().flatten().copied().collect();
+                let triangles: Vec<Triangle> = (0..piece.len())
+                    .map(|i| [i * 3, i * 3 + 1, i * 3 + 2])
+                    .collect();
+                let mut result = Self::new(triangles, positions, None, None);
+                result.optimize(CONVEX_PLANE_EPSILON);
+                result
+            })
+            .collect()
     }
 
-    /// Removes degenerate triangles from the mesh.
-    pub fn remove_degenerate(&mut self) {
-        // Ensure no vertex indices on the triangle match
-        self.triangles
-            .retain(|tri| !(tri[0] == tri[1] || tri[0] == tri[2] || tri[1] == tri[2]));
+    /// Returns the furthest distance any vertex of the triangle soup lies in front of one of its faces.
+    /// Zero if the soup is convex.
+    fn piece_concavity(piece: &[[Vec3; 3]]) -> f32 {
+        piece
+            .par_iter()
+            .map(|tri| {
+                let normal = (tri[1] - tri[0]).cross(tri[2] - tri[0]).normalize_or_zero();
+                if normal == Vec3::ZERO {
+                    return 0.0;
+                }
+
+                piece
+                    .iter()
+                    .flatten()
+                    .fold(0.0f32, |worst, p| worst.max(normal.dot(*p - tri[0])))
+            })
+            .reduce(|| 0.0, f32::max)
     }
 
-    /// Removes all unused vertex positions in the mesh.
-    pub fn remove_unused(&mut self) {
-        // Keep track of all used points
-        let mut used: Vec<bool> = vec![false; self.positions.len()];
+    /// Finds the axis-aligned plane that best splits the triangle soup into less concave pieces,
+    /// returning both pieces alongside their concavity.
+    /// Returns None if no plane splits the soup.
+    fn split_concave_piece(piece: &[[Vec3; 3]]) -> Option<(ConvexPiece, ConvexPiece)> {
+        let points: Vec<Vec3> = piece.iter().flatten().copied().collect();
+        let bounds = BoundingBox::from(&points);
+        let size = bounds.size();
+
+        let mut best: Option<(f32, ConvexPiece, ConvexPiece)> = None;
+        for (axis, normal) in Vec3::AXES.into_iter().enumerate() {
+            if size[axis] <= CONVEX_PLANE_EPSILON {
+                continue;
+            }
 
-        // Figure out what points are used
-        for tri in self.triangles.iter() {
-            for item in tri {
-                used[*item] = true;
+            for k in 1..CONVEX_SPLIT_CANDIDATES {
+                let offset =
+                    bounds.minimum[axis] + size[axis] * k as f32 / CONVEX_SPLIT_CANDIDATES as f32;
+                let (below, above) = Self::split_piece(piece, normal, offset);
+                if below.is_empty() || above.is_empty() {
+                    continue;
+                }
+
+                let below = (Self::piece_concavity(&below), below);
+                let above = (Self::piece_concavity(&above), above);
+                let score = below.0 + above.0;
+                if best
+                    .as_ref()
+                    .is_none_or(|(best_score, _, _)| score < *best_score)
+                {
+                    best = Some((score, below, above));
+                }
             }
         }
 
-        // Drop points that are not associated with anything
-        let mut idx: usize = 0;
-        self.positions.retain(|_item| {
-            let i = idx;
-            idx += 1;
-            used[i]
-        });
+        best.map(|(_, below, above)| (below, above))
+    }
 
-        // ...do the same for normals.
-        idx = 0;
-        self.normals.retain(|_item| {
-            let i = idx;
-            idx += 1;
-            used[i]
-        });
+    /// Clips the triangle soup along the plane with the given normal and offset,
+    /// returning the triangles below and above the plane.
+    /// Triangles lying on the plane are kept on the side they face away from.
+    fn split_piece(
+        piece: &[[Vec3; 3]],
+        normal: Vec3,
+        offset: f32,
+    ) -> (Vec<[Vec3; 3]>, Vec<[Vec3; 3]>) {
+        let mut below: Vec<[Vec3; 3]> = vec![];
+        let mut above: Vec<[Vec3; 3]> = vec![];
+
+        /// Clips the triangle to the side where the signed distances are non-negative, as a triangle fan.
+        fn clip(tri: &[Vec3; 3], dist: [f32; 3], out: &mut Vec<[Vec3; 3]>) {
+            let mut polygon: Vec<Vec3> = Vec::with_capacity(4);
+            for i in 0..3 {
+                let j = (i + 1) % 3;
+                if dist[i] >= 0.0 {
+                    polygon.push(tri[i]);
+                }
+                if (dist[i] > 0.0 && dist[j] < 0.0) || (dist[i] < 0.0 && dist[j] > 0.0) {
+                    let t = dist[i] / (dist[i] - dist[j]);
+                    polygon.push(tri[i].lerp(tri[j], t));
+                }
+            }
 
-        // Create an array for remapping vertex index values
-        let mut remapped: Vec<usize> = vec![0; used.len()];
-        let mut new_idx: usize = 0; // Current available index
-        for (idx, used) in used.iter().enumerate() {
-            if *used {
-                remapped[idx] = new_idx;
-                new_idx += 1;
+            for i in 1..polygon.len().saturating_sub(1) {
+                let fan = [polygon[0], polygon[i], polygon[i + 1]];
+                if (fan[1] - fan[0]).cross(fan[2] - fan[0]).length_squared() > f32::EPSILON {
+                    out.push(fan);
+                }
             }
         }
 
-        // Adjust all triangle indices accordingly
-        let mut new_tris: Vec<Triangle> = vec![];
-        new_tris.reserve_exact(self.triangles.len());
-
-        for tri in self.triangles.iter() {
-            new_tris.push([remapped[tri[0]], remapped[tri[1]], remapped[tri[2]]]);
+        for tri in piece.iter() {
+            let dist = tri.map(|p| normal.dot(p) - offset);
+            if dist.iter().all(|d| d.abs() <= CONVEX_PLANE_EPSILON) {
+                if (tri[1] - tri[0]).cross(tri[2] - tri[0]).dot(normal) > 0.0 {
+                    below.push(*tri);
+                } else {
+                    above.push(*tri);
+                }
+            } else if dist.iter().all(|d| *d <= CONVEX_PLANE_EPSILON) {
+                below.push(*tri);
+            } else if dist.iter().all(|d| *d >= -CONVEX_PLANE_EPSILON) {
+                above.push(*tri);
+            } else {
+                clip(tri, dist.map(|d| -d), &mut below);
+                clip(tri, dist, &mut above);
+            }
         }
 
-        self.triangles = new_tris;
+        (below, above)
     }
 
-    /// Calculates smooth vertex normals, using each triangle's surface area as a weight.
-    /// Returns as a list of surface normals for each corresponding vertex.
+    /// Splits vertices so that no edge is shared by more than two faces, making the mesh manifold.
+    /// Around each vertex, triangles stay joined only across edges shared by exactly two faces,
+    /// and each separate sheet of triangles receives its own copy of the vertex.
+    /// The result may have more components than before, but the triangle count is preserved.
     ///
-    /// This method works best when the mesh is already optimized (no unused vertices, mesh is continuous).
-    pub fn get_normals_smooth(&self) -> Vec<Vec3> {
-        // Map of vertices, with a list of corresponding triangle normals and associated area
-        let mut vertices: HashMap<usize, Vec<(Vec3, f32)>> =
-            HashMap::with_capacity(self.positions.len());
-
-        for tri in self.triangles.iter() {
-            let norm = tri.normal(&self.positions);
-            let area = tri.area(&self.positions);
-
-            for idx in tri.iter() {
-                if let Some(vertex_normals) = vertices.get_mut(idx) {
-                    vertex_normals.push((norm, area));
-                } else {
-                    vertices.insert(*idx, vec![(norm, area)]);
-                }
+    /// Any baked triangle adjacency is cleared.
+    pub fn make_manifold(&mut self) {
+        let original = self.triangles.clone();
+
+        let edge_faces = self.edge_face_counts();
+        let manifold_edge =
+            |a: usize, b: usize| edge_faces.get(&[a.min(b), a.max(b)]).copied() == Some(2);
+
+        // List triangles surrounding each vertex
+        let mut vertex_faces: Vec<Vec<usize>> = vec![vec![]; self.positions.len()];
+        for (idx, tri) in original.iter().enumerate() {
+            for vertex in tri.iter() {
+                vertex_faces[*vertex].push(idx);
             }
         }
 
-        // Allocate new normal buffer and fill with default values
-        let mut normals: Vec<Vec3> = vec![Vec3::ZERO; self.positions.len()];
+        for (vertex, faces) in vertex_faces.iter().enumerate() {
+            if faces.len() < 2 {
+                continue;
+            }
 
-        // Fill in normals based on triangle normals weighted by triangle area
-        for (idx, vertex_data) in vertices {
-            let mut total_area: f32 = 0.0;
-            for (_, area) in vertex_data.iter() {
-                total_area += area;
+            // Group surrounding triangles into sheets joined by manifold edges on this vertex
+            let mut sheet: Vec<usize> = (0..faces.len()).collect();
+            let root = |sheet: &[usize], mut i: usize| {
+                while sheet[i] != i {
+                    i = sheet[i];
+                }
+                i
+            };
+            for i in 0..faces.len() {
+                for j in (i + 1)..faces.len() {
+                    let joined = original[faces[i]].iter().any(|other| {
+                        *other != vertex
+                            && original[faces[j]].contains(other)
+                            && manifold_edge(vertex, *other)
+                    });
+                    if joined {
+                        let (a, b) = (root(&sheet, i), root(&sheet, j));
+                        sheet[a.max(b)] = a.min(b);
+                    }
+                }
             }
 
-            let mut vertex_normal = Vec3::ZERO;
-            for (normal, area) in vertex_data.iter() {
-                vertex_normal += normal * (area / total_area);
+            // The first sheet keeps the vertex, every other sheet gets a copy
+            let mut copies: HashMap<usize, usize> = HashMap::new();
+            for (i, face) in faces.iter().enumerate() {
+                let sheet_root = root(&sheet, i);
+                if sheet_root == root(&sheet, 0) {
+                    continue;
+                }
+
+                let copy = *copies
+                    .entry(sheet_root)
+                    .or_insert_with(|| self.duplicate_vertex(vertex));
+                for index in self.triangles[*face].iter_mut() {
+                    if *index == vertex {
+                        *index = copy;
+                    }
+                }
             }
-            normals[idx] = -vertex_normal.normalize_or_zero();
         }
 
-        normals
+        self.clear_triangle_adjacency();
     }
 
-    // Computes a corresponding normal for each mesh vertex by sampling a list of SDF shapes.
-    // pub fn get_normals_sdf(&self) -> Vec<Vec3> {
-    //     vec![]
-    // }
+    /// Splits vertices shared between triangles of different groups, given a group for each triangle.
+    /// Each group of triangles surrounding a vertex receives its own copy of the vertex and all of its attributes.
+    /// Returns the index of the original vertex for every vertex in the mesh, including copies.
+    ///
+    /// Any baked triangle adjacency is cleared.
+    pub fn split_by_group(&mut self, groups: &[usize]) -> Vec<usize> {
+        let mut sources: Vec<usize> = (0..self.positions.len()).collect();
+        // The first group to use a vertex keeps it, every other group gets a copy
+        let mut kept: Vec<Option<usize>> = vec![None; self.positions.len()];
+        let mut copies: HashMap<(usize, usize), usize> = HashMap::new();
+
+        for (face, group) in groups.iter().enumerate().take(self.triangles.len()) {
+            for corner in 0..3 {
+                let vertex = self.triangles[face][corner];
+                match kept[vertex] {
+                    None => kept[vertex] = Some(*group),
+                    Some(owner) if owner == *group => {}
+                    Some(_) => {
+                        let copy = *copies.entry((vertex, *group)).or_insert_with(|| {
+                            sources.push(vertex);
+                            self.duplicate_vertex(vertex)
+                        });
+                        self.triangles[face][corner] = copy;
+                    }
+                }
+            }
+        }
 
-    /// Bakes out smooth vertex normals, using each triangle's surface area as a weight.
-    pub fn bake_normals_smooth(&mut self) {
-        self.normals = self.get_normals_smooth();
+        self.clear_triangle_adjacency();
+        sources
     }
 
-    /// Computes and returns an ambient occlusion for every vertex on the mesh.
-    /// Requires vertex normals to be baked beforehand.
-    /// This occlusion method is based on raycasting.
-    pub fn get_ambient_occlusion(
-        &self,
-        samples: usize,
-        radius: f32,
-        seed: u32,
-        threads: NonZero<usize>,
-    ) -> Vec<f32> {
-        #[cfg(debug_assertions)]
-        assert_eq!(
-            self.normals.len(),
-            self.positions.len(),
-            "each vertex must have a corresponding normal"
-        );
-
-        // pre-allocate occlusion
-        let point_count = self.positions.len();
-        // let mut occlusion: Vec<f32> = vec![1.0; point_count];
-
-        let perlin = Perlin::new(seed);
+    /// Appends a copy of the given vertex and all of its attributes, returning the index of the copy.
+    fn duplicate_vertex(&mut self, vertex: usize) -> usize {
+        fn duplicate<T: Copy>(values: &mut Vec<T>, vertex: usize, count: usize) {
+            if values.len() == count {
+                values.push(values[vertex]);
+            }
+        }
 
-        let worker_group_size = (point_count as f64 / threads.get() as f64).ceil() as usize;
-        let worker_count = (point_count as f64 / worker_group_size as f64).ceil() as usize;
+        let count = self.positions.len();
+        duplicate(&mut self.normals, vertex, count);
+        duplicate(&mut self.colors, vertex, count);
+        if let Some(uv1) = self.uv1.as_mut() {
+            duplicate(uv1, vertex, count);
+        }
+        if let Some(uv2) = self.uv2.as_mut() {
+            duplicate(uv2, vertex, count);
+        }
+        if let Some(tangents) = self.tangents.as_mut() {
+            duplicate(tangents, vertex, count);
+        }
+        if let Some(custom0) = self.custom0.as_mut() {
+            duplicate(custom0, vertex, count);
+        }
+        if let Some(custom1) = self.custom1.as_mut() {
+            duplicate(custom1, vertex, count);
+        }
+        if let Some(custom2) = self.custom2.as_mut() {
+            duplicate(custom2, vertex, count);
+        }
+        if let Some(material_index) = self.material_index.as_mut() {
+            duplicate(material_index, vertex, count);
+        }
+        self.positions.push(self.positions[vertex]);
 
-        let mut workers: Vec<AmbientOcclusionWorker> = Vec::with_capacity(worker_count);
+        count
+    }
 
-        for i in 0..worker_count {
-            let from = i * worker_group_size;
-            let to = ((i + 1) * worker_group_size).min(self.positions.len());
-            workers.push(AmbientOcclusionWorker {
-                from: i * worker_group_size,
-                to,
-                positions: &self.positions[from..to],
-                normals: &self.normals[from..to],
-            });
+    /// Drops any vertex attribute buffers that do not match the number of vertices,
+    /// so they cannot fall out of step with positions as vertices are welded or removed.
+    /// Dropped buffers are reported in debug builds.
+    fn drop_mismatched_attributes(&mut self) {
+        let count = self.positions.len();
+
+        if !self.normals.is_empty() && self.normals.len() != count {
+            #[cfg(debug_assertions)]
+            eprintln!(
+                "StagToolkit: dropping normals buffer of {} values, mesh has {count} vertices",
+                self.normals.len()
+            );
+            self.normals.clear();
+        }
+        if !self.colors.is_empty() && self.colors.len() != count {
+            #[cfg(debug_assertions)]
+            eprintln!(
+                "StagToolkit: dropping colors buffer of {} values, mesh has {count} vertices",
+                self.colors.len()
+            );
+            self.colors.clear();
+        }
+        if let Some(uv1) = &self.uv1
+            && uv1.len() != count
+        {
+            #[cfg(debug_assertions)]
+            eprintln!(
+                "StagToolkit: dropping uv1 buffer of {} values, mesh has {count} vertices",
+                uv1.len()
+            );
+            self.uv1 = None;
+        }
+        if let Some(uv2) = &self.uv2
+            && uv2.len() != count
+        {
+            #[cfg(debug_assertions)]
+            eprintln!(
+                "StagToolkit: dropping uv2 buffer of {} values, mesh has {count} vertices",
+                uv2.len()
+            );
+            self.uv2 = None;
+        }
+        if let Some(tangents) = &self.tangents
+            && tangents.len() != count
+        {
+            #[cfg(debug_assertions)]
+            eprintln!(
+                "StagToolkit: dropping tangents buffer of {} values, mesh has {count} vertices",
+                tangents.len()
+            );
+            self.tangents = None;
+        }
+        if let Some(custom0) = &self.custom0
+            && custom0.len() != count
+        {
+            #[cfg(debug_assertions)]
+            eprintln!(
+                "StagToolkit: dropping custom0 buffer of {} values, mesh has {count} vertices",
+                custom0.len()
+            );
+            self.custom0 = None;
+        }
+        if let Some(custom1) = &self.custom1
+            && custom1.len() != count
+        {
+            #[cfg(debug_assertions)]
+            eprintln!(
+                "StagToolkit: dropping custom1 buffer of {} values, mesh has {count} vertices",
+                custom1.len()
+            );
+            self.custom1 = None;
+        }
+        if let Some(custom2) = &self.custom2
+            && custom2.len() != count
+        {
+            #[cfg(debug_assertions)]
+            eprintln!(
+                "StagToolkit: dropping custom2 buffer of {} values, mesh has {count} vertices",
+                custom2.len()
+            );
+            self.custom2 = None;
+        }
+        if let Some(material_index) = &self.material_index
+            && material_index.len() != count
+        {
+            #[cfg(debug_assertions)]
+            eprintln!(
+                "StagToolkit: dropping material_index buffer of {} values, mesh has {count} vertices",
+                material_index.len()
+            );
+            self.material_index = None;
         }
+    }
 
-        workers
-            .par_iter_mut()
-            .flat_map(|worker| -> Vec<f32> {
-                let width = worker.to - worker.from;
-                let mut ao = vec![1.0; width];
+    /// Calculates the angle between two faces.
+    pub fn face_angle(&self, a: &Triangle, b: &Triangle) -> f32 {
+        a.normal(&self.positions)
+            .angle_between(b.normal(&self.positions))
+    }
 
-                let mut raycasts = vec![RaycastParameters::default(); samples];
+    /// Returns all feature edges of the mesh, sorted by vertex index.
+    /// Feature edges are boundary edges, and edges where the angle between both faces exceeds the given threshold (in radians).
+    pub fn feature_edges(&self, angle_threshold: f32) -> Vec<Edge> {
+        let mut features: Vec<Edge> = self
+            .edge_map()
+            .iter()
+            .filter(|(_, (left_idx, right_idx))| match right_idx {
+                Some(right_idx) => {
+                    self.face_angle(&self.triangles[*left_idx], &self.triangles[right_idx.get()])
+                        > angle_threshold
+                }
+                None => true,
+            })
+            .map(|(edge, _)| *edge)
+            .collect();
 
-                for (local_index, ao) in ao.iter_mut().enumerate().take(width) {
-                    let normal = worker.normals[local_index];
-                    let pt = worker.positions[local_index];
+        features.sort_unstable();
+        features
+    }
 
-                    let orientation = direction_to_quaternion(normal);
-                    for (iteration, raycast) in raycasts.iter_mut().enumerate().take(samples) {
-                        let mut sample_point = [
-                            pt.x as f64,
-                            pt.y as f64,
-                            pt.z as f64,
-                            ((worker.from + local_index) * samples + iteration) as f64 * 0.5,
-                        ];
+    /// Removes an edge from the mesh by merging both vertices into a centerpoint.
+    /// Does not remove degenerate geometry.
+    pub fn edge_collapse(&mut self, edge: &Edge) {
+        // Create a new vertex at the center of the edge
+        let center = (self.positions[edge[0]] + self.positions[edge[1]]) * 0.5;
 
-                        let z = perlin.get(sample_point) as f32;
-                        sample_point[3] += point_count as f64;
-                        let theta = (perlin.get(sample_point) * PI * 0.5) as f32;
-                        let dir = vector_in_cone(orientation, z, theta);
+        // Append vertex to end of positions list
+        let new_idx = self.positions.len();
+        self.positions.push(center);
 
-                        let origin = pt + dir * 0.001;
+        // Swap out old vertex indices for new one
+        self.swap_indices(vec![(edge[0], new_idx), (edge[1], new_idx)]);
+    }
 
-                        *raycast = RaycastParameters::new(origin, dir, radius, false);
-                    }
+    /// Decimates the mesh by removing all immediate edges with an angle less than the given threshold.
+    /// When the number of triangles removed per decimation falls under the `minimum_dropout` threshold,
+    /// the algorithm stops decimating triangles.
+    /// Edges longer than `max_edge_length` are never collapsed, preserving the boundaries of large flat panels.
+    pub fn decimate_planar(
+        &mut self,
+        threshold: f32,
+        iterations: u32,
+        minimum_dropout: u32,
+        max_edge_length: f32,
+    ) {
+        // Do nothing if invalid.
+        if iterations == 0 {
+            return;
+        }
 
-                    let results: Vec<Option<RaycastResult>> =
-                        raycasts.iter().map(|param| self.raycast(*param)).collect();
+        for _ in 0..iterations {
+            // Get a list of all edges in the trimesh, in a deterministic order
+            let edges = self.edge_list();
 
-                    let hit_count = results.total_hits();
-                    *ao = 1.0 - (hit_count as f32 / samples as f32);
+            // Collapse all edges below the threshold
+            let mut count = 0;
+            for (edge, (left_idx, right_idx)) in edges.iter() {
+                if let Some(right_idx) = right_idx
+                    && edge.length(&self.positions) <= max_edge_length
+                    && self.face_angle(&self.triangles[*left_idx], &self.triangles[right_idx.get()])
+                        < threshold
+                {
+                    self.edge_collapse(edge);
+                    count += 1;
                 }
+            }
 
-                ao
-            })
-            .collect()
-    }
-
-    /// Returns the calculated surface area of the mesh.
-    pub fn surface_area(&self) -> f32 {
-        let mut sum: f32 = 0.0;
-        for tri in self.triangles.iter() {
-            sum += tri.area(&self.positions);
-        }
-        sum
-    }
+            // Clean up mesh after decimation
+            self.remove_degenerate();
 
-    /// Shrinks mesh buffers to only use the necessary amount of memory.
-    pub fn shrink_to_fit(&mut self) {
-        self.triangles.shrink_to_fit();
-        self.positions.shrink_to_fit();
-        self.normals.shrink_to_fit();
-        self.colors.shrink_to_fit();
-        if let Some(mut uv1) = self.uv1.take() {
-            uv1.shrink_to_fit();
-            self.uv1 = Some(uv1);
-        }
-        if let Some(mut uv2) = self.uv2.take() {
-            uv2.shrink_to_fit();
-            self.uv1 = Some(uv2);
+            // End decimation if nothing changed
+            if count <= minimum_dropout {
+                break;
+            }
         }
-    }
 
-    /// Performs all existing optimization steps on the triangle mesh.
-    pub fn optimize(&mut self, merge_distance: f32) {
-        self.merge_by_distance(merge_distance);
-        self.remove_degenerate();
         self.remove_unused();
-        self.shrink_to_fit();
-    }
-
-    /// Returns a [BoundingBox] for the mesh.
-    pub fn bounding_box(&self) -> BoundingBox {
-        BoundingBox::from(&self.positions)
     }
 
-    /// Bakes plane data into the mesh for optimized raycasting.
+    /// Decimates the mesh down to the given triangle count using Quadric Error Metric edge collapses,
+    /// collapsing the edges that least change the shape of the mesh first.
+    /// Returns the number of edges collapsed.
     ///
-    /// Planes are *not* automatically updated when modifying the mesh.
-    pub fn bake_raycast_planes(&mut self) {
-        self.planes = vec![Vec4::ZERO; self.triangles.len()];
-        for (i, triangle) in self.triangles.iter().enumerate() {
-            self.planes[i] = triangle.plane(&self.positions);
+    /// Collapses that would make the mesh non-manifold or flip a triangle are skipped,
+    /// and edges touching non-manifold edges are never collapsed,
+    /// so watertight meshes remain watertight. Decimation stops early if no collapse is safe.
+    ///
+    /// Open boundaries may only collapse along themselves, and are penalized by planes perpendicular to the boundary,
+    /// so open meshes keep their outline instead of shrinking.
+    ///
+    /// Each collapse goes through [Self::edge_collapse], with the new vertex moved to the error-minimizing position,
+    /// and collapsed triangles are cleaned up with [Self::remove_degenerate] and [Self::remove_unused].
+    ///
+    /// Vertex normals are re-baked if present. Colors, UVs and custom channels are cleared.
+    pub fn decimate_qem(&mut self, target_triangle_count: usize) -> usize {
+        if self.triangles.len() <= target_triangle_count {
+            return 0;
         }
-    }
 
-    /// Writes the mesh as an OBJ file to the given write buffer.
-    pub fn export_obj(&self, out: &'_ mut dyn io::Write) -> io::Result<()> {
-        if let Err(e) = write!(out, "# StagToolkit\no trimesh") {
-            return Err(e);
+        let vertex_count = self.positions.len();
+        let mut alive = vec![true; self.triangles.len()];
+        let mut alive_count = self.triangles.len();
+        let mut removed = vec![false; vertex_count];
+        let mut vertex_faces: Vec<Vec<usize>> = vec![vec![]; vertex_count];
+        let mut quadrics = vec![Quadric::default(); vertex_count];
+
+        // Accumulate area-weighted face quadrics on each vertex
+        for (face, tri) in self.triangles.iter().enumerate() {
+            let plane = tri.plane(&self.positions);
+            let quadric = Quadric::from_plane(plane, tri.area(&self.positions).max(1e-8) as f64);
+            for idx in tri {
+                vertex_faces[*idx].push(face);
+                quadrics[*idx] = quadrics[*idx] + quadric;
+            }
         }
 
-        // First, write vertices
-        if !self.colors.is_empty() {
-            for (p, c) in self.positions.iter().zip(&self.colors) {
-                let x = p.x;
-                let y = p.y;
-                let z = p.z;
-                let r = c.x;
-                let g = c.y;
-                let b = c.z;
-                let a = c.w;
-                if let Err(e) = write!(out, "v {x} {y} {z} {r} {g} {b} {a}") {
-                    return Err(e);
-                }
-            }
-        } else {
-            for p in self.positions.iter() {
-                let x = p.x;
-                let y = p.y;
-                let z = p.z;
-                if let Err(e) = write!(out, "v {x} {y} {z}") {
-                    return Err(e);
-                }
+        // Lock vertices on non-manifold edges
+        let edge_counts = self.edge_face_counts();
+        let mut locked = vec![false; vertex_count];
+        for (edge, count) in edge_counts.iter() {
+            if *count > 2 {
+                locked[edge[0]] = true;
+                locked[edge[1]] = true;
             }
         }
 
-        // Write texture normals
-        let mut normals = false;
-        if !self.normals.is_empty() {
-            normals = true;
-            for n in self.normals.iter() {
-                let x = n.x;
-                let y = n.y;
-                let z = n.z;
-                if let Err(e) = write!(out, "vn {x} {y} {z}") {
-                    return Err(e);
+        // Penalize moving open boundaries away from their outline
+        let mut boundary = vec![false; vertex_count];
+        for tri in self.triangles.iter() {
+            for edge in tri.edges() {
+                if edge_counts.get(&[edge[0].min(edge[1]), edge[0].max(edge[1])]) != Some(&1) {
+                    continue;
+                }
+
+                let (a, b) = (self.positions[edge[0]], self.positions[edge[1]]);
+                let normal = (b - a)
+                    .cross(tri.normal(&self.positions))
+                    .normalize_or_zero();
+                let quadric = Quadric::from_plane(
+                    plane(a, normal),
+                    QUADRIC_BOUNDARY_PENALTY * a.distance_squared(b) as f64,
+                );
+                for idx in edge {
+                    boundary[idx] = true;
+                    quadrics[idx] = quadrics[idx] + quadric;
                 }
             }
         }
 
-        // Write UV coordinates
-        let mut has_uvs = false;
-        if let Some(uvs) = &self.uv1
-            && !uvs.is_empty()
+        let candidate = |edge: Edge, positions: &[Vec3], quadrics: &[Quadric]| {
+            let quadric = quadrics[edge[0]] + quadrics[edge[1]];
+            let (a, b) = (positions[edge[0]], positions[edge[1]]);
+            let position = quadric
+                .optimal()
+                .filter(|p| p.distance_squared((a + b) * 0.5) <= a.distance_squared(b))
+                .unwrap_or_else(|| {
+                    [a, b, (a + b) * 0.5]
+                        .into_iter()
+                        .min_by(|x, y| quadric.error(*x).total_cmp(&quadric.error(*y)))
+                        .unwrap_or(a)
+                });
+            CollapseCandidate {
+                cost: quadric.error(position),
+                edge,
+                position,
+            }
+        };
+
+        let mut heap: BinaryHeap<CollapseCandidate> = edge_counts
+            .keys()
+            .filter(|edge| !locked[edge[0]] && !locked[edge[1]])
+            .map(|edge| candidate(*edge, &self.positions, &quadrics))
+            .collect();
+
+        let neighbors = |vertex: usize, faces: &[usize], triangles: &[Triangle]| {
+            let mut neighbors: Vec<usize> = faces
+                .iter()
+                .flat_map(|face| triangles[*face])
+                .filter(|idx| *idx != vertex)
+                .collect();
+            neighbors.sort_unstable();
+            neighbors.dedup();
+            neighbors
+        };
+
+        let mut collapses = 0;
+        while alive_count > target_triangle_count
+            && let Some(collapse) = heap.pop()
         {
-            has_uvs = true;
-            for t in uvs.iter() {
-                let u = t.x;
-                let v = t.y;
-                if let Err(e) = write!(out, "vt {u} {v}") {
-                    return Err(e);
-                }
+            // Collapsed vertices are never reused, so candidates touching them are stale
+            let [keep, drop] = collapse.edge;
+            if removed[keep] || removed[drop] {
+                continue;
             }
-        }
 
-        // Specify surface
-        if normals {
-            if let Err(e) = write!(out, "s 1") {
-                return Err(e);
-            }
-        } else {
-            if let Err(e) = write!(out, "s 0") {
-                return Err(e);
+            // Link condition: both vertices may only share the vertices opposite the edge
+            let keep_neighbors = neighbors(keep, &vertex_faces[keep], &self.triangles);
+            let drop_neighbors = neighbors(drop, &vertex_faces[drop], &self.triangles);
+            let shared = keep_neighbors
+                .iter()
+                .filter(|idx| drop_neighbors.binary_search(idx).is_ok())
+                .count();
+            let shared_faces: Vec<usize> = vertex_faces[keep]
+                .iter()
+                .filter(|face| vertex_faces[drop].contains(face))
+                .copied()
+                .collect();
+            let valid = match shared_faces.len() {
+                // Boundary edges collapse along the boundary
+                1 => shared == 1,
+                // Interior edges may not pinch two boundary vertices together
+                2 => shared == 2 && !(boundary[keep] && boundary[drop]),
+                _ => false,
+            };
+            if !valid {
+                continue;
             }
-        }
 
-        // Finally, define mesh buffer
-        // vertex, uv coordinate, normal
-        if has_uvs && normals {
-            for tri in self.triangles.iter() {
-                let a = tri[0];
-                let b = tri[1];
-                let c = tri[2];
-                if let Err(e) = write!(out, "f {a}/{a}/{a} {b}/{b}/{b} {c}/{c}/{c}") {
-                    return Err(e);
-                }
+            // Ensure no surrounding triangle flips or degenerates
+            let flips = [keep, drop].iter().any(|vertex| {
+                vertex_faces[*vertex].iter().any(|face| {
+                    let tri = self.triangles[*face];
+                    if tri.contains(&keep) && tri.contains(&drop) {
+                        return false;
+                    }
+                    let before = tri.normal(&self.positions);
+                    let moved = tri.map(|idx| {
+                        if idx == *vertex {
+                            collapse.position
+                        } else {
+                            self.positions[idx]
+                        }
+                    });
+                    let after = (moved[1] - moved[0]).cross(moved[2] - moved[0]);
+                    after.length_squared() < 1e-12 || before.dot(after.normalize()) < 0.2
+                })
+            });
+            if flips {
+                continue;
             }
-        } else if normals {
-            for tri in self.triangles.iter() {
-                let a = tri[0];
-                let b = tri[1];
-                let c = tri[2];
-                if let Err(e) = write!(out, "f {a}//{a} {b}//{b} {c}//{c}") {
-                    return Err(e);
-                }
+
+            // Collapse both vertices into a new vertex at the error-minimizing position
+            self.edge_collapse(&collapse.edge);
+            let merged = self.positions.len() - 1;
+            self.positions[merged] = collapse.position;
+            for face in shared_faces {
+                alive[face] = false;
+                alive_count -= 1;
             }
-        } else if has_uvs {
-            for tri in self.triangles.iter() {
-                let a = tri[0];
-                let b = tri[1];
-                let c = tri[2];
-                if let Err(e) = write!(out, "f {a}/{a} {b}/{a} {c}/{a}") {
-                    return Err(e);
-                }
+            let mut faces = std::mem::take(&mut vertex_faces[keep]);
+            faces.append(&mut vertex_faces[drop]);
+            faces.retain(|face| alive[*face]);
+            faces.sort_unstable();
+            faces.dedup();
+            for neighbor in keep_neighbors.iter().chain(drop_neighbors.iter()) {
+                vertex_faces[*neighbor].retain(|face| alive[*face]);
             }
-        } else {
-            for tri in self.triangles.iter() {
-                let a = tri[0];
-                let b = tri[1];
-                let c = tri[2];
-                if let Err(e) = write!(out, "f {a} {b} {c}") {
-                    return Err(e);
+
+            vertex_faces.push(faces);
+            quadrics.push(quadrics[keep] + quadrics[drop]);
+            boundary.push(boundary[keep] || boundary[drop]);
+            locked.push(false);
+            removed.push(false);
+            removed[keep] = true;
+            removed[drop] = true;
+            collapses += 1;
+
+            // Queue up new collapses around the merged vertex
+            for neighbor in neighbors(merged, &vertex_faces[merged], &self.triangles) {
+                if !locked[neighbor] {
+                    heap.push(candidate([merged, neighbor], &self.positions, &quadrics));
                 }
             }
         }
 
-        Ok(())
+        self.remove_degenerate();
+
+        // Clean up attributes that no longer match the geometry
+        self.colors.clear();
+        self.uv1 = None;
+        self.uv2 = None;
+        self.tangents = None;
+        self.custom0 = None;
+        self.custom1 = None;
+        self.custom2 = None;
+        self.material_index = None;
+        self.clear_triangle_adjacency();
+        let had_normals = !self.normals.is_empty();
+        self.normals.clear();
+        self.remove_unused();
+        if had_normals {
+            self.bake_normals_smooth();
+        }
+        if !self.planes.is_empty() {
+            self.bake_raycast_planes();
+        }
+
+        collapses
     }
-}
 
-impl Raycast for TriangleMesh {
-    fn raycast(&self, params: RaycastParameters) -> Option<RaycastResult> {
-        let mut result = RaycastResult::default();
+    /// Decimates the mesh with Quadric Error Metric edge collapses until its memory footprint fits within the given number of bytes,
+    /// or no collapse is safe. See [Self::decimate_qem].
+    /// Mesh buffers are shrunk to fit. Returns true if the mesh fits within the budget.
+    pub fn decimate_to_memory(&mut self, max_bytes: usize) -> bool {
+        self.shrink_to_fit();
+        while self.memory_footprint() > max_bytes {
+            // Estimate the triangle count that fits, assuming memory scales with triangle count
+            let ratio = max_bytes as f64 / self.memory_footprint() as f64;
+            let target = ((self.triangles.len() as f64 * ratio) as usize)
+                .min(self.triangles.len().saturating_sub(1));
+
+            let collapsed = self.decimate_qem(target);
+            self.shrink_to_fit();
+            if collapsed == 0 {
+                break;
+            }
+        }
+        self.memory_footprint() <= max_bytes
+    }
 
-        // For all triangles
-        for (idx, tri) in self.triangles.iter().enumerate() {
-            // Perform a ray intersection
-            let plane = match self.planes.get(idx) {
-                Some(plane) => plane,
-                _ => &tri.plane(&self.positions),
+    /// Merges all vertices within the given threshold distance of each other, merging later vertices into earlier ones.
+    /// Each later vertex merges into the first earlier vertex within range,
+    /// which is moved to the midpoint between the two, alongside its vertex attributes.
+    /// Results depend on vertex order, see `merge_by_distance_clustered` for an order-independent merge.
+    /// This operation occurs in place.
+    ///
+    /// **Does not remove degenerate triangles or unused vertices.**
+    /// Call `remove_degenerate` and `remove_unused` to clean up the mesh when you are done editing it.
+    /// Or, to do everything at once, call `optimize`.
+    pub fn merge_by_distance(&mut self, threshold: f32) {
+        if threshold <= 0.0 {
+            // Don't do anything if disabled
+            return;
+        }
+
+        // Bucket vertices into cells of the threshold size by their current position,
+        // so only neighboring cells need to be searched
+        let mut hash = VertexHash::from_mesh(self, threshold);
+        let mut positions = self.positions.clone();
+        let mut replace: Vec<(usize, usize)> = vec![];
+        for (i, vert) in self.positions.iter().enumerate().rev() {
+            // Only earlier vertices can be merged into, so this one is never searched for again
+            hash.remove(i, positions[i]);
+
+            let Some(j) = hash.within(&positions, *vert).min() else {
+                continue;
             };
+            let midpoint = (vert + positions[j]) * 0.5;
+            hash.remove(j, positions[j]);
+            hash.insert(j, midpoint);
+            positions[j] = midpoint;
+            replace.push((i, j));
+        }
 
-            // First, make sure this is shorter than our current collision depth
-            // Also make sure it's not back-facing, if possible
-            let depth = plane.signed_distance(params.origin);
-            if (params.hit_backfaces || depth >= 0.0)
-                && depth < params.max_depth
-                && depth < result.depth
-            {
-                // Project point onto the plane
-                let projection = plane.ray_intersection(params.origin, params.direction, depth);
+        // Blend vertex attributes the same way as positions
+        self.drop_mismatched_attributes();
+        let count = self.positions.len();
+        self.positions = positions;
+        if blend_merges(&mut self.normals, &replace, count) {
+            for normal in self.normals.iter_mut() {
+                *normal = normal.normalize_or_zero();
+            }
+        }
+        blend_merges(&mut self.colors, &replace, count);
+        if let Some(uv1) = self.uv1.as_mut() {
+            blend_merges(uv1, &replace, count);
+        }
+        if let Some(uv2) = self.uv2.as_mut() {
+            blend_merges(uv2, &replace, count);
+        }
+        if let Some(tangents) = self.tangents.as_mut()
+            && blend_merges(tangents, &replace, count)
+        {
+            for tangent in tangents.iter_mut() {
+                *tangent = tangent.xyz().normalize_or_zero().extend(tangent.w.signum());
+            }
+        }
+        if let Some(custom0) = self.custom0.as_mut() {
+            blend_merges(custom0, &replace, count);
+        }
+        if let Some(custom1) = self.custom1.as_mut() {
+            blend_merges(custom1, &replace, count);
+        }
+        if let Some(custom2) = self.custom2.as_mut() {
+            blend_merges(custom2, &replace, count);
+        }
+        // Material indices can't be blended, so earlier vertices keep their own
 
-                // TODO: better method for checking if ray direction is not hitting plane
-                if projection.collided && (!projection.reversed || params.hit_backfaces) {
-                    // Get barycentric coordinate of triangle
-                    let coord = tri.barycentric(&self.positions, projection.intersection);
-                    // Finally, check if the point is contained by the triangle
-                    let contained = tri.contains_barycentric(coord);
+        self.swap_indices(replace);
+    }
 
-                    if contained {
-                        result = RaycastResult {
-                            depth,
-                            point: projection.intersection,
-                            normal: plane.xyz(),
-                            face_index: Some(idx),
-                            barycentric: Some(coord),
-                        }
+    /// Merges all clusters of vertices chained within the given threshold distance of each other,
+    /// placing each cluster at its centroid, independent of vertex order.
+    ///
+    /// **Does not remove degenerate triangles or unused vertices.**
+    pub fn merge_by_distance_clustered(&mut self, threshold: f32) {
+        self.merge_by_distance_where(threshold, None);
+    }
+
+    /// Merges all clusters of vertices within the given threshold distance of each other, like `merge_by_distance_clustered`,
+    /// but vertices are only merged if their incident face normals differ by no more than the given angle (in radians).
+    /// This welds coplanar duplicates while preserving intentional creases and sharp corners.
+    ///
+    /// **Does not remove degenerate triangles or unused vertices.**
+    pub fn merge_by_distance_preserving_edges(&mut self, threshold: f32, angle_threshold: f32) {
+        let normals = self.get_normals_smooth();
+        self.merge_by_distance_where(
+            threshold,
+            Some(&|i, j| normals[i].angle_between(normals[j]) <= angle_threshold),
+        );
+    }
+
+    /// Merges all vertices within the given threshold distance of each other,
+    /// only if `can_merge(later, earlier)` permits merging the two vertex indices.
+    /// All vertices within range may merge if no predicate is given.
+    ///
+    /// Vertices are grouped into clusters of chained neighbors, regardless of vertex order.
+    /// Two clusters are only joined if `can_merge` permits every pair of vertices between them,
+    /// so a chain of permitted merges can't weld together vertices that were rejected.
+    /// Each cluster is welded into its lowest vertex index, placed at the centroid of the cluster,
+    /// with its vertex attributes averaged across the cluster.
+    fn merge_by_distance_where(
+        &mut self,
+        threshold: f32,
+        can_merge: Option<&dyn Fn(usize, usize) -> bool>,
+    ) {
+        if threshold <= 0.0 {
+            // Don't do anything if disabled
+            return;
+        }
+
+        // Bucket vertices into cells of the threshold size, so only neighboring cells need to be searched
+        let hash = VertexHash::from_mesh(self, threshold);
+
+        // Union all vertices within the threshold of each other, rooting each cluster at its lowest index
+        let mut cluster: Vec<usize> = (0..self.positions.len()).collect();
+        // Cluster members are only tracked when there is a predicate to check them against
+        let mut members: Vec<Vec<usize>> = match can_merge {
+            Some(_) => (0..self.positions.len()).map(|i| vec![i]).collect(),
+            None => vec![],
+        };
+        let root = |cluster: &[usize], mut i: usize| {
+            while cluster[i] != i {
+                i = cluster[i];
+            }
+            i
+        };
+        for (i, position) in self.positions.iter().enumerate() {
+            for j in hash.within(&self.positions, *position).filter(|j| *j < i) {
+                let (a, b) = (root(&cluster, i), root(&cluster, j));
+                let permitted = |can_merge: &dyn Fn(usize, usize) -> bool| {
+                    members[a]
+                        .iter()
+                        .all(|x| members[b].iter().all(|y| can_merge(*x.max(y), *x.min(y))))
+                };
+                if a != b && can_merge.is_none_or(permitted) {
+                    let (low, high) = (a.min(b), a.max(b));
+                    cluster[high] = low;
+                    if can_merge.is_some() {
+                        let moved = std::mem::take(&mut members[high]);
+                        members[low].extend(moved);
                     }
                 }
             }
         }
 
-        // No collision, return nothing
-        if result.depth >= params.max_depth {
-            return None;
+        // Move each cluster root to the centroid of its cluster, averaging vertex attributes alongside
+        let replace: Vec<usize> = (0..self.positions.len())
+            .map(|i| root(&cluster, i))
+            .collect();
+        let mut counts: Vec<usize> = vec![0; self.positions.len()];
+        for cluster_root in replace.iter() {
+            counts[*cluster_root] += 1;
+        }
+        self.drop_mismatched_attributes();
+        average_clusters(&mut self.positions, &replace, &counts);
+        if average_clusters(&mut self.normals, &replace, &counts) {
+            for normal in self.normals.iter_mut() {
+                *normal = normal.normalize_or_zero();
+            }
+        }
+        average_clusters(&mut self.colors, &replace, &counts);
+        if let Some(uv1) = self.uv1.as_mut() {
+            average_clusters(uv1, &replace, &counts);
+        }
+        if let Some(uv2) = self.uv2.as_mut() {
+            average_clusters(uv2, &replace, &counts);
+        }
+        if let Some(tangents) = self.tangents.as_mut()
+            && average_clusters(tangents, &replace, &counts)
+        {
+            for tangent in tangents.iter_mut() {
+                *tangent = tangent.xyz().normalize_or_zero().extend(tangent.w.signum());
+            }
+        }
+        if let Some(custom0) = self.custom0.as_mut() {
+            average_clusters(custom0, &replace, &counts);
+        }
+        if let Some(custom1) = self.custom1.as_mut() {
+            average_clusters(custom1, &replace, &counts);
         }
+        if let Some(custom2) = self.custom2.as_mut() {
+            average_clusters(custom2, &replace, &counts);
+        }
+        // Material indices can't be averaged, so cluster roots keep their own
 
-        Some(result)
+        // Finally, update triangle indices
+        for tri in self.triangles.iter_mut() {
+            for idx in tri.iter_mut() {
+                *idx = replace[*idx];
+            }
+        }
+        self.clear_triangle_adjacency();
     }
-}
 
-// UNIT TESTS //
-#[cfg(test)]
-mod tests {
-    use super::{Edge, EdgeTriangles, TriangleMesh};
-    use crate::math::raycast::RaycastParameters;
-    use crate::{
-        math::raycast::Raycast,
-        mesh::trimesh::{Triangle, TriangleOperations},
-    };
-    use glam::{Vec3, vec3};
+    /// Snaps each vertex to a grid of the given cell size, and welds all vertices that snap to the same point.
+    /// Vertex attributes of welded vertices are averaged together.
+    /// Unlike `merge_by_distance`, this operation runs in linear time and does not depend on vertex order.
+    ///
+    /// **Does not remove degenerate triangles.**
+    pub fn quantize_weld(&mut self, cell: f32) {
+        if cell <= 0.0 {
+            // Don't do anything if disabled
+            return;
+        }
 
-    const MAX_DIFFERENCE: f32 = 1e-7;
+        let mut cells: HashMap<[i32; 3], usize> = HashMap::with_capacity(self.positions.len());
+        let mut positions: Vec<Vec3> = Vec::with_capacity(self.positions.len());
+        let mut counts: Vec<usize> = Vec::with_capacity(self.positions.len());
+
+        // Find the welded vertex index for every vertex
+        let remap: Vec<usize> = self
+            .positions
+            .iter()
+            .map(|position| {
+                let snapped = (*position / cell).round();
+                let key = [snapped.x as i32, snapped.y as i32, snapped.z as i32];
+                let idx = *cells.entry(key).or_insert_with(|| {
+                    positions.push(snapped * cell);
+                    counts.push(0);
+                    positions.len() - 1
+                });
+                counts[idx] += 1;
+                idx
+            })
+            .collect();
 
-    /// Sanity test. Validate that EdgeMapEdge does not take extra memory.
-    #[cfg(target_pointer_width = "64")]
-    #[test]
-    fn type_sizes() {
-        assert_eq!(8 * 2, size_of::<Edge>(), "edge");
-        assert_eq!(8 * 2, size_of::<EdgeTriangles>(), "edge triangles");
-        assert_eq!(8 * 3, size_of::<Triangle>(), "triangle");
+        // Average out vertex attributes
+        fn weld<T>(values: &[T], remap: &[usize], counts: &[usize]) -> Vec<T>
+        where
+            T: Copy + Default + Add<Output = T> + Mul<f32, Output = T>,
+        {
+            let mut welded = vec![T::default(); counts.len()];
+            for (value, idx) in values.iter().zip(remap) {
+                welded[*idx] = welded[*idx] + *value;
+            }
+            for (value, count) in welded.iter_mut().zip(counts) {
+                *value = *value * (1.0 / *count as f32);
+            }
+            welded
+        }
+
+        if self.normals.len() == remap.len() {
+            self.normals = weld(&self.normals, &remap, &counts)
+                .iter()
+                .map(|normal| normal.normalize_or_zero())
+                .collect();
+        }
+        if self.colors.len() == remap.len() {
+            self.colors = weld(&self.colors, &remap, &counts);
+        }
+        if let Some(uv1) = &self.uv1 {
+            self.uv1 = Some(weld(uv1, &remap, &counts));
+        }
+        if let Some(uv2) = &self.uv2 {
+            self.uv2 = Some(weld(uv2, &remap, &counts));
+        }
+        if let Some(tangents) = &self.tangents {
+            self.tangents = Some(
+                weld(tangents, &remap, &counts)
+                    .iter()
+                    .map(|tangent| tangent.xyz().normalize_or_zero().extend(tangent.w.signum()))
+                    .collect(),
+            );
+        }
+        if let Some(custom0) = &self.custom0 {
+            self.custom0 = Some(weld(custom0, &remap, &counts));
+        }
+        if let Some(custom1) = &self.custom1 {
+            self.custom1 = Some(weld(custom1, &remap, &counts));
+        }
+        if let Some(custom2) = &self.custom2 {
+            self.custom2 = Some(weld(custom2, &remap, &counts));
+        }
+        if let Some(material_index) = &self.material_index {
+            // Material indices can't be averaged, so the first vertex in each cell wins
+            let mut welded: Vec<Option<u32>> = vec![None; counts.len()];
+            for (value, idx) in material_index.iter().zip(&remap) {
+                welded[*idx].get_or_insert(*value);
+            }
+            self.material_index = Some(welded.into_iter().map(Option::unwrap_or_default).collect());
+        }
+
+        self.positions = positions;
+
+        // Finally, update triangle indices
+        for tri in self.triangles.iter_mut() {
+            for idx in tri.iter_mut() {
+                *idx = remap[*idx];
+            }
+        }
+        self.clear_triangle_adjacency();
     }
 
-    #[test]
-    fn test_calculate_normal() {
-        struct TestFaceNormal {
-            vertices: Vec<Vec3>,
-            normal: Vec3,
+    /// Iterates over all triangles, replacing each vertex index value using the given tuple: (old, new).
+    /// Replacements are applied in order, so later tuples also replace indices written by earlier ones.
+    /// Does not remove degenerate triangles.
+    pub fn swap_indices(&mut self, replace: Vec<(usize, usize)>) {
+        if replace.is_empty() {
+            return;
         }
 
-        let test_cases: Vec<TestFaceNormal> = vec![
-            TestFaceNormal {
-                vertices: vec![Vec3::NEG_X, Vec3::X, Vec3::Y],
-                normal: Vec3::Z,
-            },
-            // Flipping the vertex order flips the face
-            TestFaceNormal {
-                vertices: vec![Vec3::Y, Vec3::X, Vec3::NEG_X],
-                normal: Vec3::NEG_Z,
-            },
-            // A raised face still has the same normal
-            TestFaceNormal {
-                vertices: vec![
-                    Vec3::new(-1.0, 0.0, 1.0),
-                    Vec3::new(1.0, 0.0, 1.0),
-                    Vec3::new(0.0, 1.0, 1.0),
-                ],
-                normal: Vec3::Z,
-            },
-            TestFaceNormal {
-                vertices: vec![Vec3::NEG_X, Vec3::X, Vec3::Z],
-                normal: Vec3::NEG_Y,
-            },
-            // Degenerate/non-manifold geometry simply returns an up vector
-            TestFaceNormal {
-                vertices: vec![Vec3::ZERO, Vec3::ZERO, Vec3::ZERO],
-                normal: Vec3::Y,
-            },
-        ];
+        // Resolve replacements back to front, so each old index maps straight to the end of its chain
+        let mut remap: HashMap<usize, usize> = HashMap::with_capacity(replace.len());
+        for (old, new) in replace.into_iter().rev() {
+            let resolved = remap.get(&new).copied().unwrap_or(new);
+            remap.insert(old, resolved);
+        }
 
-        for (idx, case) in test_cases.iter().enumerate() {
-            let tri: Triangle = [0, 1, 2];
-            let normal = tri.normal(&case.vertices);
+        // Update the triangle indices
+        for idx in self.triangles.iter_mut().flatten() {
+            if let Some(new) = remap.get(idx) {
+                *idx = *new;
+            }
+        }
+        self.clear_triangle_adjacency();
+    }
+
+    /// Removes degenerate triangles from the mesh.
+    pub fn remove_degenerate(&mut self) {
+        // Ensure no vertex indices on the triangle match
+        self.triangles
+            .retain(|tri| !(tri[0] == tri[1] || tri[0] == tri[2] || tri[1] == tri[2]));
+        self.clear_triangle_adjacency();
+    }
+
+    /// Removes all unused vertex positions in the mesh, alongside their vertex attributes.
+    pub fn remove_unused(&mut self) {
+        // Keep track of all used points
+        let mut used: Vec<bool> = vec![false; self.positions.len()];
+
+        // Figure out what points are used
+        for tri in self.triangles.iter() {
+            for item in tri {
+                used[*item] = true;
+            }
+        }
+
+        // Drop points that are not associated with anything, and their attributes
+        fn retain_used<T>(values: &mut Vec<T>, used: &[bool]) {
+            let mut idx: usize = 0;
+            values.retain(|_item| {
+                let i = idx;
+                idx += 1;
+                used[i]
+            });
+        }
+
+        self.drop_mismatched_attributes();
+        retain_used(&mut self.positions, &used);
+        if !self.normals.is_empty() {
+            retain_used(&mut self.normals, &used);
+        }
+        if !self.colors.is_empty() {
+            retain_used(&mut self.colors, &used);
+        }
+        if let Some(uv1) = self.uv1.as_mut() {
+            retain_used(uv1, &used);
+        }
+        if let Some(uv2) = self.uv2.as_mut() {
+            retain_used(uv2, &used);
+        }
+        if let Some(tangents) = self.tangents.as_mut() {
+            retain_used(tangents, &used);
+        }
+        if let Some(custom0) = self.custom0.as_mut() {
+            retain_used(custom0, &used);
+        }
+        if let Some(custom1) = self.custom1.as_mut() {
+            retain_used(custom1, &used);
+        }
+        if let Some(custom2) = self.custom2.as_mut() {
+            retain_used(custom2, &used);
+        }
+        if let Some(material_index) = self.material_index.as_mut() {
+            retain_used(material_index, &used);
+        }
+
+        // Create an array for remapping vertex index values
+        let mut remapped: Vec<usize> = vec![0; used.len()];
+        let mut new_idx: usize = 0; // Current available index
+        for (idx, used) in used.iter().enumerate() {
+            if *used {
+                remapped[idx] = new_idx;
+                new_idx += 1;
+            }
+        }
+
+        // Adjust all triangle indices accordingly
+        let mut new_tris: Vec<Triangle> = vec![];
+        new_tris.reserve_exact(self.triangles.len());
+
+        for tri in self.triangles.iter() {
+            new_tris.push([remapped[tri[0]], remapped[tri[1]], remapped[tri[2]]]);
+        }
+
+        self.triangles = new_tris;
+    }
+
+    /// Calculates smooth vertex normals, using each triangle's surface area as a weight.
+    /// Returns as a list of surface normals for each corresponding vertex.
+    ///
+    /// This method works best when the mesh is already optimized (no unused vertices, mesh is continuous).
+    pub fn get_normals_smooth(&self) -> Vec<Vec3> {
+        let faces: Vec<(Vec3, f32)> = self
+            .triangles
+            .iter()
+            .map(|tri| (tri.normal(&self.positions), tri.area(&self.positions)))
+            .collect();
+
+        // Total up the area surrounding each vertex, in triangle order so results are reproducible
+        let mut total_area: Vec<f32> = vec![0.0; self.positions.len()];
+        for (tri, (_, area)) in self.triangles.iter().zip(faces.iter()) {
+            for idx in tri.iter() {
+                total_area[*idx] += area;
+            }
+        }
+
+        // Fill in normals based on triangle normals weighted by triangle area
+        let mut normals: Vec<Vec3> = vec![Vec3::ZERO; self.positions.len()];
+        for (tri, (normal, area)) in self.triangles.iter().zip(faces.iter()) {
+            for idx in tri.iter() {
+                normals[*idx] += normal * (area / total_area[*idx]);
+            }
+        }
+
+        normals
+            .into_iter()
+            .map(|normal| -normal.normalize_or_zero())
+            .collect()
+    }
+
+    // Computes a corresponding normal for each mesh vertex by sampling a list of SDF shapes.
+    // pub fn get_normals_sdf(&self) -> Vec<Vec3> {
+    //     vec![]
+    // }
+
+    /// Bakes out smooth vertex normals, using each triangle's surface area as a weight.
+    pub fn bake_normals_smooth(&mut self) {
+        self.normals = self.get_normals_smooth();
+    }
+
+    /// Calculates flat, faceted vertex normals, where every triangle has its own three vertices.
+    /// Returns a list of normals for each new vertex, and the new triangles.
+    /// New vertices are laid out sequentially, so new vertex `i` is a copy of corner `i % 3` of triangle `i / 3`.
+    ///
+    /// Normals face the same direction as those from [Self::get_normals_smooth].
+    pub fn get_normals_flat(&self) -> (Vec<Vec3>, Vec<Triangle>) {
+        let normals: Vec<Vec3> = self
+            .triangles
+            .iter()
+            .flat_map(|tri| [-tri.normal(&self.positions); 3])
+            .collect();
+        let triangles: Vec<Triangle> = (0..self.triangles.len())
+            .map(|face| [face * 3, face * 3 + 1, face * 3 + 2])
+            .collect();
+
+        (normals, triangles)
+    }
+
+    /// Bakes out flat, faceted vertex normals, duplicating vertices so that no two triangles share a vertex.
+    /// All vertex attributes are duplicated alongside their positions.
+    pub fn bake_normals_flat(&mut self) {
+        fn remap<T: Copy>(values: &[T], sources: &[usize]) -> Vec<T> {
+            sources.iter().map(|source| values[*source]).collect()
+        }
+
+        let sources: Vec<usize> = self.triangles.iter().flatten().copied().collect();
+        let (normals, triangles) = self.get_normals_flat();
+
+        self.positions = remap(&self.positions, &sources);
+        self.normals = normals;
+        if !self.colors.is_empty() {
+            self.colors = remap(&self.colors, &sources);
+        }
+        self.uv1 = self.uv1.as_ref().map(|uv1| remap(uv1, &sources));
+        self.uv2 = self.uv2.as_ref().map(|uv2| remap(uv2, &sources));
+        self.tangents = self
+            .tangents
+            .as_ref()
+            .map(|tangents| remap(tangents, &sources));
+        self.custom0 = self
+            .custom0
+            .as_ref()
+            .map(|custom0| remap(custom0, &sources));
+        self.custom1 = self
+            .custom1
+            .as_ref()
+            .map(|custom1| remap(custom1, &sources));
+        self.custom2 = self
+            .custom2
+            .as_ref()
+            .map(|custom2| remap(custom2, &sources));
+        self.material_index = self
+            .material_index
+            .as_ref()
+            .map(|material_index| remap(material_index, &sources));
+        self.triangles = triangles;
+
+        // Triangles no longer share vertices, so adjacency must be re-baked
+        self.clear_triangle_adjacency();
+    }
+
+    /// Calculates per-vertex tangents for normal mapping from the UV1 projection, using Lengyel's method.
+    /// The XYZ components are orthogonalized against the vertex normal, and W is the handedness of the binormal,
+    /// such that `binormal = normal.cross(tangent.xyz()) * tangent.w`.
+    /// Smooth normals are calculated if no vertex normals are present.
+    ///
+    /// Returns an empty list if the mesh has no UV1 projection.
+    pub fn get_tangents(&self) -> Vec<Vec4> {
+        let Some(uv1) = self
+            .uv1
+            .as_ref()
+            .filter(|uv1| uv1.len() == self.positions.len())
+        else {
+            return vec![];
+        };
+        let smooth_normals;
+        let normals = if self.normals.len() == self.positions.len() {
+            &self.normals
+        } else {
+            smooth_normals = self.get_normals_smooth();
+            &smooth_normals
+        };
+
+        // Accumulate UV-space directions of each triangle onto its vertices
+        let mut tangents = vec![Vec3::ZERO; self.positions.len()];
+        let mut binormals = vec![Vec3::ZERO; self.positions.len()];
+        for tri in self.triangles.iter() {
+            let edge1 = self.positions[tri[1]] - self.positions[tri[0]];
+            let edge2 = self.positions[tri[2]] - self.positions[tri[0]];
+            let uv_edge1 = uv1[tri[1]] - uv1[tri[0]];
+            let uv_edge2 = uv1[tri[2]] - uv1[tri[0]];
+
+            let det = uv_edge1.x * uv_edge2.y - uv_edge2.x * uv_edge1.y;
+            if det.abs() < 1e-12 {
+                // Skip triangles with a degenerate UV projection
+                continue;
+            }
+            let r = 1.0 / det;
+            let tangent = (edge1 * uv_edge2.y - edge2 * uv_edge1.y) * r;
+            let binormal = (edge2 * uv_edge1.x - edge1 * uv_edge2.x) * r;
+
+            for idx in tri.iter() {
+                tangents[*idx] += tangent;
+                binormals[*idx] += binormal;
+            }
+        }
+
+        // Gram-Schmidt orthogonalize against the normal, and calculate handedness
+        tangents
+            .iter()
+            .zip(binormals.iter())
+            .zip(normals.iter())
+            .map(|((tangent, binormal), normal)| {
+                let orthogonal = (*tangent - *normal * normal.dot(*tangent)).normalize_or_zero();
+                let orthogonal = if orthogonal == Vec3::ZERO {
+                    normal.any_orthonormal_vector()
+                } else {
+                    orthogonal
+                };
+                let handedness = if normal.cross(orthogonal).dot(*binormal) < 0.0 {
+                    -1.0
+                } else {
+                    1.0
+                };
+                orthogonal.extend(handedness)
+            })
+            .collect()
+    }
+
+    /// Generates UV projections of every vertex onto the YZ, XZ and XY planes, in that order,
+    /// so shaders can blend between them for triplanar texture mapping without recomputing them.
+    /// Each projection is multiplied by the given scale. See [triplanar_projection].
+    pub fn generate_triplanar_uvs(&self, scale: f32) -> (Vec<Vec2>, Vec<Vec2>, Vec<Vec2>) {
+        let mut yz: Vec<Vec2> = Vec::with_capacity(self.positions.len());
+        let mut xz: Vec<Vec2> = Vec::with_capacity(self.positions.len());
+        let mut xy: Vec<Vec2> = Vec::with_capacity(self.positions.len());
+        for position in self.positions.iter() {
+            let [a, b, c] = triplanar_projection(*position, scale);
+            yz.push(a);
+            xz.push(b);
+            xy.push(c);
+        }
+        (yz, xz, xy)
+    }
+
+    /// Computes triplanar blend weights for every vertex from its normal, matching [Self::generate_triplanar_uvs].
+    /// Returns an empty list if the mesh has no normals. See [triplanar_blend].
+    pub fn triplanar_weights(&self, sharpness: f32) -> Vec<Vec3> {
+        self.normals
+            .iter()
+            .map(|normal| triplanar_blend(*normal, sharpness))
+            .collect()
+    }
+
+    /// Stores triplanar blend weights for every vertex in the RGB components of its color,
+    /// so shaders can blend between the projections of [Self::generate_triplanar_uvs].
+    /// Existing alpha values are kept, or set to 1 if the mesh has no colors.
+    /// Does nothing if the mesh has no normals. See [Self::triplanar_weights].
+    pub fn bake_triplanar_weights(&mut self, sharpness: f32) {
+        if self.normals.len() != self.positions.len() {
+            return;
+        }
+
+        let keep_alpha = self.colors.len() == self.positions.len();
+        self.colors = self
+            .triplanar_weights(sharpness)
+            .into_iter()
+            .enumerate()
+            .map(|(idx, weights)| weights.extend(if keep_alpha { self.colors[idx].w } else { 1.0 }))
+            .collect();
+    }
+
+    /// Bakes out per-vertex tangents for normal mapping. See [Self::get_tangents].
+    /// Tangents are cleared if the mesh has no UV1 projection.
+    pub fn bake_tangents(&mut self) {
+        let tangents = self.get_tangents();
+        self.tangents = if tangents.is_empty() {
+            None
+        } else {
+            Some(tangents)
+        };
+    }
+
+    /// Computes and returns an ambient occlusion for every vertex on the mesh.
+    /// Requires vertex normals to be baked beforehand.
+    /// This occlusion method is based on raycasting.
+    ///
+    /// Each sample casts a ray within a cone of the given half-angle around the vertex normal, in radians,
+    /// clamped between 0 and PI/2 for a full hemisphere.
+    /// Samples are evenly spread across the cone, cosine-weighted, and twisted around the normal by noise.
+    /// Nearer hits occlude more, falling off linearly to no occlusion at the given radius.
+    /// Vertices with no hits are fully lit, at 1.0.
+    pub fn get_ambient_occlusion(
+        &self,
+        samples: usize,
+        radius: f32,
+        cone_angle: f32,
+        seed: u32,
+        threads: NonZero<usize>,
+    ) -> Vec<f32> {
+        #[cfg(debug_assertions)]
+        assert_eq!(
+            self.normals.len(),
+            self.positions.len(),
+            "each vertex must have a corresponding normal"
+        );
+
+        let point_count = self.positions.len();
+        let perlin = Perlin::new(seed);
+        // Evenly spread sample directions within the cone, around the local Y axis
+        let pattern = fibonacci_cone(samples, Vec3::Y, cone_angle.max(f32::EPSILON));
+
+        // Noise is sampled by vertex index, so results are identical regardless of how work is split
+        let group_size = point_count.div_ceil(threads.get()).max(1);
+
+        // Every vertex casts many rays against the same mesh, so build a hierarchy once up front
+        let bvh = TriangleMeshBVH::build(self);
+
+        self.positions
+            .par_iter()
+            .zip(self.normals.par_iter())
+            .enumerate()
+            .with_min_len(group_size)
+            .map_init(
+                || vec![RaycastParameters::default(); samples],
+                |raycasts, (idx, (pt, normal))| -> f32 {
+                    // Twist the pattern around the normal by noise, so neighboring vertices don't share artifacts
+                    let twist =
+                        perlin.get([pt.x as f64, pt.y as f64, pt.z as f64, idx as f64 * 0.5]) * PI;
+                    let orientation = Quat::from_rotation_arc(Vec3::Y, *normal)
+                        * Quat::from_rotation_y(twist as f32);
+                    for (raycast, dir) in raycasts.iter_mut().zip(pattern.iter()) {
+                        let dir = orientation * *dir;
+                        let origin = *pt + dir * 0.001;
+
+                        *raycast = RaycastParameters::new(origin, dir, radius, false);
+                    }
+
+                    let occlusion: f32 = raycasts
+                        .iter()
+                        .filter_map(|param| bvh.raycast(*param))
+                        .map(|hit| 1.0 - (hit.depth / radius).clamp(0.0, 1.0))
+                        .sum();
+                    1.0 - (occlusion / samples as f32)
+                },
+            )
+            .collect()
+    }
+
+    /// Computes and returns a directional occlusion for every vertex on the mesh,
+    /// where 1 is fully lit and 0 is fully shadowed, as if lit by a distant light (i.e. the sun).
+    /// `light_dir` points from the surface towards the light.
+    /// If vertex normals are baked, vertices facing away from the light are considered fully shadowed.
+    /// Using more than one sample jitters rays within a narrow cone, softening shadow edges.
+    pub fn directional_occlusion(&self, light_dir: Vec3, samples: usize) -> Vec<f32> {
+        let light_dir = light_dir.normalize_or(Vec3::Y);
+        let samples = samples.max(1);
+        let has_normals = self.normals.len() == self.positions.len();
+
+        // Spread sample directions across a narrow cone facing the light, or straight at it for a single sample
+        let directions = if samples > 1 {
+            fibonacci_cone(samples, light_dir, DIRECTIONAL_OCCLUSION_SPREAD)
+        } else {
+            vec![light_dir]
+        };
+
+        // Every vertex casts rays against the same mesh, so build a hierarchy once up front
+        let bvh = TriangleMeshBVH::build(self);
+
+        self.positions
+            .par_iter()
+            .enumerate()
+            .map(|(idx, pt)| -> f32 {
+                if has_normals && self.normals[idx].dot(light_dir) <= 0.0 {
+                    return 0.0;
+                }
+
+                let hit_count = directions
+                    .iter()
+                    .filter(|dir| {
+                        let params = RaycastParameters::new(
+                            *pt + **dir * 0.001,
+                            **dir,
+                            f32::INFINITY,
+                            false,
+                        );
+                        bvh.raycast(params).is_some()
+                    })
+                    .count();
+
+                1.0 - (hit_count as f32 / samples as f32)
+            })
+            .collect()
+    }
+
+    /// Asserts that all triangle indices are in range, that all present vertex attribute buffers
+    /// match the number of vertices, and that no vertex data is NaN.
+    /// Does nothing when debug assertions are disabled.
+    pub fn debug_validate(&self) {
+        #[cfg(debug_assertions)]
+        {
+            let count = self.positions.len();
+
+            for (idx, tri) in self.triangles.iter().enumerate() {
+                assert!(
+                    tri.iter().all(|vertex| *vertex < count),
+                    "triangle {idx} has an index out of range: {tri:?}, with {count} vertices"
+                );
+            }
+
+            if !self.normals.is_empty() {
+                assert_eq!(count, self.normals.len(), "normals should match vertices");
+            }
+            if !self.colors.is_empty() {
+                assert_eq!(count, self.colors.len(), "colors should match vertices");
+            }
+            if let Some(uv1) = &self.uv1 {
+                assert_eq!(count, uv1.len(), "UV1 should match vertices");
+                assert!(!uv1.iter().any(|uv| uv.is_nan()), "UV1 should not be NaN");
+            }
+            if let Some(uv2) = &self.uv2 {
+                assert_eq!(count, uv2.len(), "UV2 should match vertices");
+                assert!(!uv2.iter().any(|uv| uv.is_nan()), "UV2 should not be NaN");
+            }
+            if let Some(tangents) = &self.tangents {
+                assert_eq!(count, tangents.len(), "tangents should match vertices");
+                assert!(
+                    !tangents.iter().any(|tangent| tangent.is_nan()),
+                    "tangents should not be NaN"
+                );
+            }
+            if let Some(custom0) = &self.custom0 {
+                assert_eq!(count, custom0.len(), "custom0 should match vertices");
+                assert!(
+                    !custom0.iter().any(|value| value.is_nan()),
+                    "custom0 should not be NaN"
+                );
+            }
+            if let Some(custom1) = &self.custom1 {
+                assert_eq!(count, custom1.len(), "custom1 should match vertices");
+                assert!(
+                    !custom1.iter().any(|value| value.is_nan()),
+                    "custom1 should not be NaN"
+                );
+            }
+            if let Some(custom2) = &self.custom2 {
+                assert_eq!(count, custom2.len(), "custom2 should match vertices");
+                assert!(
+                    !custom2.iter().any(|value| value.is_nan()),
+                    "custom2 should not be NaN"
+                );
+            }
+            if let Some(material_index) = &self.material_index {
+                assert_eq!(
+                    count,
+                    material_index.len(),
+                    "material indices should match vertices"
+                );
+            }
+
+            assert!(
+                !self.positions.iter().any(|position| position.is_nan()),
+                "positions should not be NaN"
+            );
+            assert!(
+                !self.normals.iter().any(|normal| normal.is_nan()),
+                "normals should not be NaN"
+            );
+            assert!(
+                !self.colors.iter().any(|color| color.is_nan()),
+                "colors should not be NaN"
+            );
+        }
+    }
+
+    /// Returns the nearest point on the surface of the mesh to the given point.
+    /// If the mesh has no triangles, the result has an infinite distance and no face index.
+    pub fn closest_point(&self, point: Vec3) -> ClosestPointResult {
+        let mut result = ClosestPointResult::default();
+        let mut distance_squared = f32::INFINITY;
+
+        for (idx, tri) in self.triangles.iter().enumerate() {
+            let nearest = tri.closest_point(&self.positions, point);
+            let d = nearest.distance_squared(point);
+            if d < distance_squared {
+                distance_squared = d;
+                result.point = nearest;
+                result.face_index = Some(idx);
+            }
+        }
+
+        if let Some(idx) = result.face_index {
+            result.distance = distance_squared.sqrt();
+            result.normal = self.triangles[idx].normal(&self.positions);
+        }
+
+        result
+    }
+
+    /// Performs a closest-point query for every given point in parallel, returning results in the same order.
+    pub fn closest_points(&self, points: &[Vec3]) -> Vec<ClosestPointResult> {
+        points
+            .par_iter()
+            .map(|point| self.closest_point(*point))
+            .collect()
+    }
+
+    /// Returns the calculated surface area of the mesh.
+    pub fn surface_area(&self) -> f32 {
+        let mut sum: f32 = 0.0;
+        for tri in self.triangles.iter() {
+            sum += tri.area(&self.positions);
+        }
+        sum
+    }
+
+    /// Returns the number of bytes allocated for all mesh buffers, including any spare capacity.
+    /// Does not include the size of the [TriangleMesh] struct itself.
+    pub fn memory_footprint(&self) -> usize {
+        self.triangles.capacity() * size_of::<Triangle>()
+            + self.positions.capacity() * size_of::<Vec3>()
+            + self.normals.capacity() * size_of::<Vec3>()
+            + self.colors.capacity() * size_of::<Vec4>()
+            + self.planes.capacity() * size_of::<Vec4>()
+            + self.adjacency.get().map_or(0, |adjacency| {
+                adjacency.capacity() * size_of::<[Option<usize>; 3]>()
+            })
+            + self
+                .uv1
+                .as_ref()
+                .map_or(0, |uv1| uv1.capacity() * size_of::<Vec2>())
+            + self
+                .uv2
+                .as_ref()
+                .map_or(0, |uv2| uv2.capacity() * size_of::<Vec2>())
+            + self
+                .tangents
+                .as_ref()
+                .map_or(0, |tangents| tangents.capacity() * size_of::<Vec4>())
+            + self
+                .custom0
+                .as_ref()
+                .map_or(0, |custom0| custom0.capacity() * size_of::<f32>())
+            + self
+                .custom1
+                .as_ref()
+                .map_or(0, |custom1| custom1.capacity() * size_of::<f32>())
+            + self
+                .custom2
+                .as_ref()
+                .map_or(0, |custom2| custom2.capacity() * size_of::<f32>())
+            + self.material_index.as_ref().map_or(0, |material_index| {
+                material_index.capacity() * size_of::<u32>()
+            })
+    }
+
+    /// Shrinks mesh buffers to only use the necessary amount of memory.
+    pub fn shrink_to_fit(&mut self) {
+        self.triangles.shrink_to_fit();
+        self.positions.shrink_to_fit();
+        self.normals.shrink_to_fit();
+        self.colors.shrink_to_fit();
+        if let Some(mut uv1) = self.uv1.take() {
+            uv1.shrink_to_fit();
+            self.uv1 = Some(uv1);
+        }
+        if let Some(mut uv2) = self.uv2.take() {
+            uv2.shrink_to_fit();
+            self.uv2 = Some(uv2);
+        }
+        if let Some(mut tangents) = self.tangents.take() {
+            tangents.shrink_to_fit();
+            self.tangents = Some(tangents);
+        }
+        if let Some(mut custom0) = self.custom0.take() {
+            custom0.shrink_to_fit();
+            self.custom0 = Some(custom0);
+        }
+        if let Some(mut custom1) = self.custom1.take() {
+            custom1.shrink_to_fit();
+            self.custom1 = Some(custom1);
+        }
+        if let Some(mut custom2) = self.custom2.take() {
+            custom2.shrink_to_fit();
+            self.custom2 = Some(custom2);
+        }
+        if let Some(mut material_index) = self.material_index.take() {
+            material_index.shrink_to_fit();
+            self.material_index = Some(material_index);
+        }
+    }
+
+    /// Performs all existing optimization steps on the triangle mesh.
+    pub fn optimize(&mut self, merge_distance: f32) {
+        self.merge_by_distance(merge_distance);
+        self.remove_degenerate();
+        self.remove_unused();
+        self.shrink_to_fit();
+    }
+
+    /// Returns a [BoundingBox] for the mesh.
+    /// Uses the baked bounds if available, otherwise they are computed from the vertex positions.
+    pub fn bounding_box(&self) -> BoundingBox {
+        self.bounds
+            .unwrap_or_else(|| BoundingBox::from(&self.positions))
+    }
+
+    /// Bakes plane data and bounds into the mesh for optimized raycasting.
+    ///
+    /// Planes and bounds are *not* automatically updated when modifying the mesh.
+    pub fn bake_raycast_planes(&mut self) {
+        self.planes = vec![Vec4::ZERO; self.triangles.len()];
+        for (i, triangle) in self.triangles.iter().enumerate() {
+            self.planes[i] = triangle.plane(&self.positions);
+        }
+        self.bounds = Some(BoundingBox::from(&self.positions));
+    }
+
+    /// Writes the mesh as an OBJ file to the given write buffer.
+    pub fn export_obj(&self, out: &'_ mut dyn io::Write) -> io::Result<()> {
+        write!(out, "# StagToolkit\no trimesh")?;
+
+        // First, write vertices
+        if !self.colors.is_empty() {
+            for (p, c) in self.positions.iter().zip(&self.colors) {
+                let x = p.x;
+                let y = p.y;
+                let z = p.z;
+                let r = c.x;
+                let g = c.y;
+                let b = c.z;
+                let a = c.w;
+                write!(out, "v {x} {y} {z} {r} {g} {b} {a}")?;
+            }
+        } else {
+            for p in self.positions.iter() {
+                let x = p.x;
+                let y = p.y;
+                let z = p.z;
+                write!(out, "v {x} {y} {z}")?;
+            }
+        }
+
+        // Write texture normals
+        let mut normals = false;
+        if !self.normals.is_empty() {
+            normals = true;
+            for n in self.normals.iter() {
+                let x = n.x;
+                let y = n.y;
+                let z = n.z;
+                write!(out, "vn {x} {y} {z}")?;
+            }
+        }
+
+        // Write UV coordinates
+        let mut has_uvs = false;
+        if let Some(uvs) = &self.uv1
+            && !uvs.is_empty()
+        {
+            has_uvs = true;
+            for t in uvs.iter() {
+                let u = t.x;
+                let v = t.y;
+                write!(out, "vt {u} {v}")?;
+            }
+        }
+
+        // Specify surface
+        if normals {
+            write!(out, "s 1")?;
+        } else {
+            write!(out, "s 0")?;
+        }
+
+        // Finally, define mesh buffer
+        // vertex, uv coordinate, normal
+        if has_uvs && normals {
+            for tri in self.triangles.iter() {
+                let a = tri[0];
+                let b = tri[1];
+                let c = tri[2];
+                write!(out, "f {a}/{a}/{a} {b}/{b}/{b} {c}/{c}/{c}")?;
+            }
+        } else if normals {
+            for tri in self.triangles.iter() {
+                let a = tri[0];
+                let b = tri[1];
+                let c = tri[2];
+                write!(out, "f {a}//{a} {b}//{b} {c}//{c}")?;
+            }
+        } else if has_uvs {
+            for tri in self.triangles.iter() {
+                let a = tri[0];
+                let b = tri[1];
+                let c = tri[2];
+                write!(out, "f {a}/{a} {b}/{a} {c}/{a}")?;
+            }
+        } else {
+            for tri in self.triangles.iter() {
+                let a = tri[0];
+                let b = tri[1];
+                let c = tri[2];
+                write!(out, "f {a} {b} {c}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl TriangleMesh {
+    /// Raycasts against only the given faces, in the given order, with the same semantics as [Raycast::raycast].
+    /// Faces should be listed in ascending order to match a full raycast exactly, as ties go to the first face.
+    pub(crate) fn raycast_faces(
+        &self,
+        params: RaycastParameters,
+        faces: impl IntoIterator<Item = usize>,
+    ) -> Option<RaycastResult> {
+        let mut result = RaycastResult::default();
+        // Distance to the nearest backface in front of the ray, for detecting rays starting inside the mesh
+        let mut inside_depth = f32::INFINITY;
+
+        for idx in faces {
+            let tri = &self.triangles[idx];
+            // Perform a ray intersection
+            let plane = match self.planes.get(idx) {
+                Some(plane) => plane,
+                _ => &tri.plane(&self.positions),
+            };
+
+            // First, make sure this is shorter than our current collision depth
+            // Also make sure it's not back-facing, if possible
+            let depth = plane.signed_distance(params.origin);
+
+            // If the ray starts behind this face and travels toward it, it may be inside the mesh
+            if params.hit_from_inside && depth < 0.0 && -depth < inside_depth {
+                let projection = plane.ray_intersection(params.origin, params.direction, depth);
+                if projection.collided
+                    && projection.reversed
+                    && tri.contains_barycentric(
+                        tri.barycentric(&self.positions, projection.intersection),
+                    )
+                {
+                    inside_depth = -depth;
+                }
+            }
+
+            if (params.hit_backfaces || depth >= 0.0)
+                && depth < params.max_depth
+                && depth < result.depth
+            {
+                // Project point onto the plane
+                let projection = plane.ray_intersection(params.origin, params.direction, depth);
+
+                // TODO: better method for checking if ray direction is not hitting plane
+                if projection.collided && (!projection.reversed || params.hit_backfaces) {
+                    // Get barycentric coordinate of triangle
+                    let coord = tri.barycentric(&self.positions, projection.intersection);
+                    // Finally, check if the point is contained by the triangle
+                    let contained = tri.contains_barycentric(coord);
+
+                    if contained {
+                        let mut normal = plane.xyz();
+                        // Blend vertex normals, if requested and available
+                        if params.interpolate_normals && self.normals.len() == self.positions.len()
+                        {
+                            normal = (self.normals[tri[0]] * coord.x
+                                + self.normals[tri[1]] * coord.y
+                                + self.normals[tri[2]] * coord.z)
+                                .normalize_or(normal);
+                        }
+
+                        result = RaycastResult {
+                            depth,
+                            point: projection.intersection,
+                            normal,
+                            face_index: Some(idx),
+                            barycentric: Some(coord),
+                        }
+                    }
+                }
+            }
+        }
+
+        // Nearest face is a backface, so the ray started inside the mesh
+        if inside_depth < params.max_depth && inside_depth < result.depth {
+            return Some(RaycastResult {
+                point: params.origin,
+                normal: Vec3::ZERO,
+                depth: 0.0,
+                ..Default::default()
+            });
+        }
+
+        // No collision, return nothing
+        if result.depth >= params.max_depth {
+            return None;
+        }
+
+        Some(result)
+    }
+}
+
+/// Returns true if the ray could hit anything within the given bounds.
+/// Rays that hit backfaces are treated as lines, as faces behind the ray origin can be hit.
+/// Bounds are padded slightly, so hits along the boundary are not lost to rounding.
+pub(crate) fn ray_may_hit_bounds(bounds: BoundingBox, params: &RaycastParameters) -> bool {
+    let bounds = bounds.expand_margin(RAYCAST_BOUNDS_MARGIN);
+    bounds
+        .ray_intersection(params.origin, params.direction)
+        .is_some()
+        || (params.hit_backfaces
+            && bounds
+                .ray_intersection(params.origin, -params.direction)
+                .is_some())
+}
+
+impl Raycast for TriangleMesh {
+    fn raycast(&self, params: RaycastParameters) -> Option<RaycastResult> {
+        // Skip testing every triangle if the ray misses the mesh entirely
+        if !ray_may_hit_bounds(self.bounding_box(), &params) {
+            return None;
+        }
+
+        self.raycast_faces(params, 0..self.triangles.len())
+    }
+
+    /// Performs many raycasts in parallel, building a [TriangleMeshBVH] once for all rays.
+    fn raycast_many(&self, parameters: &[RaycastParameters]) -> Vec<Option<RaycastResult>> {
+        if parameters.len() <= 1 {
+            return parameters
+                .iter()
+                .map(|params| self.raycast(*params))
+                .collect();
+        }
+
+        TriangleMeshBVH::build(self).raycast_many(parameters)
+    }
+}
+
+// UNIT TESTS //
+#[cfg(test)]
+mod tests {
+    use super::{
+        Edge, EdgeOperations, EdgeTriangles, ManifoldReport, TriangleMesh, VertexHash,
+        triplanar_blend,
+    };
+    use crate::math::bounding_box::BoundingBox;
+    use crate::math::delta::{assert_in_delta, assert_in_delta_vector};
+    use crate::math::raycast::RaycastParameters;
+    use crate::{
+        math::raycast::Raycast,
+        mesh::trimesh::{Triangle, TriangleOperations},
+    };
+    use glam::{Vec2, Vec3, Vec4, Vec4Swizzles, vec2, vec3};
+    use std::collections::HashMap;
+    use std::num::NonZero;
+
+    const MAX_DIFFERENCE: f32 = 1e-7;
+
+    /// Sanity test. Validate that EdgeMapEdge does not take extra memory.
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn type_sizes() {
+        assert_eq!(8 * 2, size_of::<Edge>(), "edge");
+        assert_eq!(8 * 2, size_of::<EdgeTriangles>(), "edge triangles");
+        assert_eq!(8 * 3, size_of::<Triangle>(), "triangle");
+    }
+
+    #[test]
+    fn test_calculate_normal() {
+        struct TestFaceNormal {
+            vertices: Vec<Vec3>,
+            normal: Vec3,
+        }
+
+        let test_cases: Vec<TestFaceNormal> = vec![
+            TestFaceNormal {
+                vertices: vec![Vec3::NEG_X, Vec3::X, Vec3::Y],
+                normal: Vec3::Z,
+            },
+            // Flipping the vertex order flips the face
+            TestFaceNormal {
+                vertices: vec![Vec3::Y, Vec3::X, Vec3::NEG_X],
+                normal: Vec3::NEG_Z,
+            },
+            // A raised face still has the same normal
+            TestFaceNormal {
+                vertices: vec![
+                    Vec3::new(-1.0, 0.0, 1.0),
+                    Vec3::new(1.0, 0.0, 1.0),
+                    Vec3::new(0.0, 1.0, 1.0),
+                ],
+                normal: Vec3::Z,
+            },
+            TestFaceNormal {
+                vertices: vec![Vec3::NEG_X, Vec3::X, Vec3::Z],
+                normal: Vec3::NEG_Y,
+            },
+            // Degenerate/non-manifold geometry simply returns an up vector
+            TestFaceNormal {
+                vertices: vec![Vec3::ZERO, Vec3::ZERO, Vec3::ZERO],
+                normal: Vec3::Y,
+            },
+        ];
+
+        for (idx, case) in test_cases.iter().enumerate() {
+            let tri: Triangle = [0, 1, 2];
+            let normal = tri.normal(&case.vertices);
+
+            let dot = case.normal.dot(normal);
+            assert!(
+                dot >= 1.0 - MAX_DIFFERENCE,
+                "Case {0}, expected {1} but got {2}, with dot product {3}",
+                idx,
+                case.normal,
+                normal,
+                dot
+            );
+
+            assert!(
+                normal.length() > 1.0 - MAX_DIFFERENCE,
+                "Case {idx}, {normal} isn't normalized ( >=1.0 )"
+            );
+            assert!(
+                normal.length() < 1.0 + MAX_DIFFERENCE,
+                "Case {idx}, {normal} isn't normalized ( <=1.0 )"
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_point_behind() {
+        struct TestFaceIsPointBehind {
+            tri: Vec<Vec3>,
+            pt: Vec3,
+            behind: bool,
+        }
+        let test_cases: Vec<TestFaceIsPointBehind> = vec![
+            TestFaceIsPointBehind {
+                tri: vec![
+                    Vec3::new(0.0, 1.0, -1.0),
+                    Vec3::new(1.0, -1.0, -1.0),
+                    Vec3::new(-1.0, -1.0, -1.0),
+                ],
+                pt: Vec3::Z,
+                behind: true,
+            },
+            // Face normal is flipped, point should be in front of face
+            TestFaceIsPointBehind {
+                tri: vec![
+                    Vec3::new(-1.0, -1.0, -1.0),
+                    Vec3::new(1.0, -1.0, -1.0),
+                    Vec3::new(0.0, 1.0, -1.0),
+                ],
+                pt: Vec3::Z,
+                behind: false,
+            },
+            // Tested point is behind, but out of face's bounds
+            TestFaceIsPointBehind {
+                tri: vec![
+                    Vec3::new(0.0, 1.0, -1.0),
+                    Vec3::new(1.0, -1.0, -1.0),
+                    Vec3::new(-1.0, -1.0, -1.0),
+                ],
+                pt: Vec3::new(10.0, 10.0, 10.0),
+                behind: false,
+            },
+        ];
+
+        for (idx, case) in test_cases.iter().enumerate() {
+            let tri: Triangle = [0, 1, 2];
+            let was_behind = tri.is_point_behind(&case.tri, case.pt);
+
+            assert_eq!(
+                was_behind,
+                case.behind,
+                "case {0}: triangle normal {1}",
+                idx,
+                tri.normal(&case.tri)
+            );
+        }
+    }
+
+    #[test]
+    fn test_segment_intersection() {
+        let positions: Vec<Vec3> = vec![
+            Vec3::new(0.0, 1.0, -1.0),
+            Vec3::new(1.0, -1.0, -1.0),
+            Vec3::new(-1.0, -1.0, -1.0),
+        ];
+        let tri: Triangle = [0, 1, 2];
+
+        // Crossing the face from either side should hit
+        assert_eq!(
+            Some(Vec3::new(0.0, 0.0, -1.0)),
+            tri.segment_intersection(&positions, Vec3::ZERO, Vec3::NEG_Z * 2.0)
+        );
+        assert_eq!(
+            Some(Vec3::new(0.0, 0.0, -1.0)),
+            tri.segment_intersection(&positions, Vec3::NEG_Z * 2.0, Vec3::ZERO)
+        );
+        // Segment stops short of the face
+        assert_eq!(
+            None,
+            tri.segment_intersection(&positions, Vec3::ZERO, Vec3::NEG_Z * 0.5)
+        );
+        // Segment crosses the plane, but outside of the face
+        assert_eq!(
+            None,
+            tri.segment_intersection(
+                &positions,
+                Vec3::new(2.0, 2.0, 0.0),
+                Vec3::new(2.0, 2.0, -2.0)
+            )
+        );
+        // Endpoint exactly on the face
+        assert_eq!(
+            Some(Vec3::new(0.0, -0.5, -1.0)),
+            tri.segment_intersection(&positions, Vec3::ZERO, Vec3::new(0.0, -0.5, -1.0))
+        );
+        // Segment lies within the face's plane
+        assert_eq!(
+            None,
+            tri.segment_intersection(
+                &positions,
+                Vec3::new(-0.5, -0.5, -1.0),
+                Vec3::new(0.5, -0.5, -1.0)
+            )
+        );
+    }
+
+    #[test]
+    fn test_area() {
+        let positions: Vec<Vec3> = vec![
+            Vec3::new(0.0, 0.0, 0.0),  // 0
+            Vec3::new(1.0, 0.0, 0.0),  // 1
+            Vec3::new(0.0, 1.0, 0.0),  // 2
+            Vec3::new(-1.0, 0.0, 0.0), // 3
+            Vec3::new(0.0, -1.0, 0.0), // 4
+            Vec3::new(2.0, 0.0, 0.0),  // 5
+            Vec3::new(0.0, 2.0, 0.0),  // 6
+        ];
+        let triangle_a: Triangle = [0, 2, 1];
+        let triangle_b: Triangle = [0, 4, 3];
+        let triangle_c: Triangle = [0, 6, 5];
+
+        assert_eq!(0.5, triangle_a.area(&positions), "Triangle A");
+        assert_eq!(0.5, triangle_b.area(&positions), "Triangle B");
+        assert_eq!(2.0, triangle_c.area(&positions), "Triangle C");
+
+        let triangles: Vec<Triangle> = vec![triangle_a, triangle_b, triangle_c];
+        let mesh = TriangleMesh::new(triangles, positions, None, None);
+
+        assert_eq!(3.0, mesh.surface_area(), "Mesh Surface Area");
+    }
+
+    #[test]
+    fn test_remove_unused() {
+        let positions: Vec<Vec3> = vec![
+            Vec3::new(0.0, 0.0, 0.0), // 0
+            Vec3::new(1.0, 0.0, 0.0), // 1
+            Vec3::new(0.0, 1.0, 0.0), // 2
+            Vec3::new(0.0, 1.0, 0.0), // 3
+            Vec3::new(0.0, 0.0, 1.0), // 4
+            Vec3::new(0.0, 0.0, 1.0), // 5
+        ];
+        let normals: Vec<Vec3> = positions.clone();
+        let tris: Vec<Triangle> = vec![[1, 4, 3], [3, 4, 1]];
+        let indices: Vec<usize> = vec![1, 4, 3, 3, 4, 1];
+
+        let mut mesh =
+            TriangleMesh::new(tris.clone(), positions.clone(), Some(normals.clone()), None);
+        let mut index_mesh =
+            TriangleMesh::from_indices(indices.clone(), positions.clone(), Some(normals.clone()));
+
+        assert_eq!(
+            tris, mesh.triangles,
+            "created TriangleMesh has the same triangles"
+        );
+        assert_eq!(
+            positions, mesh.positions,
+            "created TriangleMesh has the same vertex positions"
+        );
+        assert_eq!(
+            normals, mesh.normals,
+            "created TriangleMesh has the same vertex normals"
+        );
+        assert_eq!(
+            indices,
+            mesh.indices(),
+            "created TriangleMesh has the same indices"
+        );
+
+        assert_eq!(
+            tris, index_mesh.triangles,
+            "INDEXED TriangleMesh has the same triangles"
+        );
+        assert_eq!(
+            positions, index_mesh.positions,
+            "INDEXED TriangleMesh has the same vertex positions"
+        );
+        assert_eq!(
+            normals, index_mesh.normals,
+            "INDEXED TriangleMesh has the same vertex normals"
+        );
+        assert_eq!(
+            indices,
+            mesh.indices(),
+            "created TriangleMesh has the same indices"
+        );
+
+        // Remove unused positions
+        mesh.remove_unused();
+        index_mesh.remove_unused();
+
+        // Mesh vertex buffers shrank
+        assert_eq!(3, mesh.positions.len(), "only 3 positions remain");
+        assert_eq!(3, mesh.normals.len(), "only 3 normals remain");
+        assert_eq!(3, index_mesh.positions.len(), "only 3 positions remain");
+        assert_eq!(3, index_mesh.normals.len(), "only 3 normals remain");
+
+        // Mesh should only contain our expected positions
+        let positions_expected: Vec<Vec3> = vec![
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+        ];
+        assert_eq!(positions_expected, mesh.positions);
+        assert_eq!(positions_expected, index_mesh.positions);
+
+        // Indices should have been adjusted for resized buffers
+        let indices_expected: Vec<usize> = vec![0, 2, 1, 1, 2, 0];
+        assert_eq!(
+            indices_expected,
+            mesh.indices(),
+            "should return expected vertex indices"
+        );
+        assert_eq!(
+            indices_expected,
+            index_mesh.indices(),
+            "should return expected vertex indices"
+        );
+    }
+
+    #[test]
+    fn test_decimate_qem() {
+        let mut sphere = icosphere(3);
+        assert!(is_watertight(&sphere), "icosphere should start watertight");
+        sphere.bake_normals_smooth();
+
+        let collapses = sphere.decimate_qem(300);
+        assert!(collapses > 0, "sphere should be decimated");
+        assert!(
+            sphere.triangles.len() <= 300,
+            "sphere should be decimated to the target, got {} triangles",
+            sphere.triangles.len()
+        );
+        assert!(
+            is_watertight(&sphere),
+            "decimated sphere should stay watertight"
+        );
+        assert_eq!(
+            sphere.positions.len(),
+            sphere.normals.len(),
+            "normals should be re-baked"
+        );
+        for position in sphere.positions.iter() {
+            assert!(
+                (position.length() - 1.0).abs() < 0.1,
+                "decimated vertex {position} should stay near the sphere surface"
+            );
+        }
+        sphere.debug_validate();
+
+        // Flat faces of a subdivided cube should collapse without changing the shape
+        let mut cube = unit_cube();
+        for _ in 0..2 {
+            let mut subdivided = TriangleMesh::new(vec![], cube.positions.clone(), None, None);
+            for [a, b, c] in cube.triangles.iter() {
+                let mid = |x: usize, y: usize, mesh: &mut TriangleMesh| {
+                    mesh.positions
+                        .push((mesh.positions[x] + mesh.positions[y]) * 0.5);
+                    mesh.positions.len() - 1
+                };
+                let ab = mid(*a, *b, &mut subdivided);
+                let bc = mid(*b, *c, &mut subdivided);
+                let ca = mid(*c, *a, &mut subdivided);
+                subdivided.triangles.extend([
+                    [*a, ab, ca],
+                    [*b, bc, ab],
+                    [*c, ca, bc],
+                    [ab, bc, ca],
+                ]);
+            }
+            subdivided.optimize(1e-5);
+            cube = subdivided;
+        }
+        assert!(is_watertight(&cube), "subdivided cube should be watertight");
+        let bounds = cube.bounding_box();
+        cube.decimate_qem(12);
+        assert!(
+            is_watertight(&cube),
+            "decimated cube should stay watertight"
+        );
+        assert!(
+            cube.triangles.len() <= 24,
+            "flat cube faces should decimate, got {} triangles",
+            cube.triangles.len()
+        );
+        assert_eq!(
+            bounds,
+            cube.bounding_box(),
+            "decimating flat faces should not change the cube's shape"
+        );
+    }
+
+    #[test]
+    fn test_decimate_qem_open() {
+        let sphere = icosphere(3);
+
+        // Open hemisphere, with the bottom half cut away
+        let mut hemisphere = sphere.clone();
+        hemisphere
+            .triangles
+            .retain(|tri| tri.iter().all(|idx| hemisphere.positions[*idx].y >= -1e-4));
+        hemisphere.remove_unused();
+        let original = hemisphere.clone();
+        let bounds = hemisphere.bounding_box();
+        let target = hemisphere.triangles.len() / 4;
+
+        let collapses = hemisphere.decimate_qem(target);
+        assert!(collapses > 0, "hemisphere should be decimated");
+        assert!(
+            hemisphere.triangles.len() <= target + target / 4,
+            "hemisphere should be decimated near the target of {target}, got {} triangles",
+            hemisphere.triangles.len()
+        );
+        assert!(
+            hemisphere.manifold_report().boundary_edges > 0,
+            "hemisphere should stay open"
+        );
+        hemisphere.debug_validate();
+
+        // Hausdorff distance in both directions should stay small
+        let tolerance = 0.1;
+        for position in original.positions.iter() {
+            let distance = hemisphere.closest_point(*position).distance;
+            assert!(
+                distance < tolerance,
+                "original vertex {position} is {distance} from the decimated surface"
+            );
+        }
+        for position in hemisphere.positions.iter() {
+            assert!(
+                (position.length() - 1.0).abs() < tolerance && position.y > -tolerance,
+                "decimated vertex {position} should stay near the hemisphere surface"
+            );
+        }
+
+        // Boundary should keep its outline rather than shrinking inward
+        let decimated = hemisphere.bounding_box();
+        assert!(
+            decimated.minimum.distance(bounds.minimum) < tolerance
+                && decimated.maximum.distance(bounds.maximum) < tolerance,
+            "open boundary should not shrink, bounds went from {bounds:?} to {decimated:?}"
+        );
+    }
+
+    #[test]
+    fn test_decimate_to_memory() {
+        let mut sphere = icosphere(4);
+        sphere.shrink_to_fit();
+        let budget = sphere.memory_footprint() / 10;
+
+        assert!(
+            sphere.decimate_to_memory(budget),
+            "sphere should decimate to fit the budget"
+        );
+        assert!(
+            sphere.memory_footprint() <= budget,
+            "sphere should use at most {budget} bytes, got {}",
+            sphere.memory_footprint()
+        );
+        assert!(
+            is_watertight(&sphere),
+            "decimated sphere should stay watertight"
+        );
+
+        let mut cube = unit_cube();
+        assert!(
+            !cube.decimate_to_memory(16),
+            "cube cannot be decimated to fit a tiny budget"
+        );
+        assert!(is_watertight(&cube), "cube should stay watertight");
+    }
+
+    #[test]
+    fn test_planar_decimation() {
+        let positions: Vec<Vec3> = vec![
+            vec3(1.0, 0.0, 0.0),
+            vec3(-1.0, 0.0, 0.0),
+            vec3(0.0, 0.0, 1.0),
+            vec3(0.0, 0.0, -1.0),
+        ];
+        let triangles: Vec<Triangle> = vec![[0, 1, 2], [0, 3, 1]];
+        let mut mesh = TriangleMesh::new(triangles.clone(), positions.clone(), None, None);
+
+        assert_eq!(
+            1.0,
+            triangles[0].orientation(&positions).signum(),
+            "first triangle should be facing up"
+        );
+        assert_eq!(
+            1.0,
+            triangles[1].orientation(&positions).signum(),
+            "second triangle should be facing up"
+        );
+
+        assert_eq!(
+            0.0,
+            mesh.face_angle(&triangles[0], &triangles[1]),
+            "faces sharing same plane should have an angle of zero"
+        );
+
+        let edges = mesh.edge_map();
+        assert_eq!(5, edges.len());
+
+        mesh.decimate_planar(0.1, 10, 0, f32::INFINITY);
+        assert_eq!(0, mesh.triangles.len());
+    }
+
+    #[test]
+    fn test_planar_decimation_max_edge_length() {
+        // Two coplanar quads, one long and one short
+        let positions: Vec<Vec3> = vec![
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 0.0, 1.0),
+            vec3(10.0, 0.0, 0.0),
+            vec3(10.0, 0.0, 1.0),
+            vec3(20.0, 0.0, 0.0),
+            vec3(20.0, 0.0, 0.5),
+            vec3(20.5, 0.0, 0.0),
+            vec3(20.5, 0.0, 0.5),
+        ];
+        let triangles: Vec<Triangle> = vec![[0, 1, 2], [2, 1, 3], [4, 5, 6], [6, 5, 7]];
+        let mesh = TriangleMesh::new(triangles, positions, None, None);
+
+        let mut limited = mesh.clone();
+        limited.decimate_planar(0.1, 10, 0, 2.0);
+        assert_eq!(
+            2,
+            limited.triangles.len(),
+            "long coplanar edge should be preserved, while the short one collapses"
+        );
+        assert_eq!(
+            4,
+            limited.positions.len(),
+            "only the long quad's vertices should remain"
+        );
+        for tri in limited.triangles.iter() {
+            assert!(
+                tri.area(&limited.positions) > 1.0,
+                "remaining triangles should belong to the long quad"
+            );
+        }
+
+        let mut unlimited = mesh.clone();
+        unlimited.decimate_planar(0.1, 10, 0, f32::INFINITY);
+        assert_eq!(
+            0,
+            unlimited.triangles.len(),
+            "all coplanar edges should collapse without a length limit"
+        );
+    }
+
+    #[test]
+    fn test_add_triangle() {
+        let corners = [Vec3::ZERO, Vec3::X, vec3(1.0, 0.0, 1.0), Vec3::Z];
+
+        let mut mesh = TriangleMesh::default();
+        assert_eq!(
+            [0, 1, 2],
+            mesh.add_triangle(corners[0], corners[1], corners[2])
+        );
+        assert_eq!(
+            [3, 4, 5],
+            mesh.add_triangle(corners[0], corners[2], corners[3])
+        );
+        assert_eq!(
+            6,
+            mesh.positions.len(),
+            "plain triangles should not share vertices"
+        );
+
+        // Build a quad, where the shared edge should reuse vertices
+        let mut mesh = TriangleMesh::default();
+        let mut hash = VertexHash::new(1e-4);
+        let first = mesh.add_triangle_deduped(&mut hash, corners[0], corners[1], corners[2]);
+        let second = mesh.add_triangle_deduped(
+            &mut hash,
+            corners[0] + Vec3::splat(1e-5),
+            corners[2],
+            corners[3],
+        );
+        assert_eq!([0, 1, 2], first);
+        assert_eq!([0, 2, 3], second, "shared edge should reuse vertices");
+        assert_eq!(4, mesh.positions.len(), "quad should have four vertices");
+        assert_eq!(corners.to_vec(), mesh.positions);
+        mesh.debug_validate();
+
+        // Existing vertices of a mesh can be reused too
+        let mut hash = VertexHash::from_mesh(&mesh, 1e-4);
+        let tri = mesh.add_triangle_deduped(&mut hash, corners[3], corners[2], Vec3::Y);
+        assert_eq!([3, 2, 4], tri, "existing vertices should be reused");
+    }
+
+    #[test]
+    fn test_join() {
+        let positions1: Vec<Vec3> = vec![Vec3::X, Vec3::Y, Vec3::Z];
+        let positions2: Vec<Vec3> = vec![Vec3::NEG_X, Vec3::NEG_Y, Vec3::NEG_Z];
+
+        let triangles = vec![[0, 1, 2]];
+        let mut mesh1 = TriangleMesh::new(triangles.clone(), positions1.clone(), None, None);
+        let mesh2 = TriangleMesh::new(triangles.clone(), positions2.clone(), None, None);
+
+        assert_eq!(
+            1,
+            mesh1.triangles.len(),
+            "mesh 1 should only have one triangle"
+        );
+        assert_eq!(
+            1,
+            mesh2.triangles.len(),
+            "mesh 2 should only have one triangle"
+        );
+        assert_eq!(3, mesh1.positions.len(), "mesh 1 should have 3 vertices");
+        assert_eq!(3, mesh2.positions.len(), "mesh 2 should have 3 vertices");
+
+        mesh1.join(&mesh2);
+        assert_eq!(
+            6,
+            mesh1.positions.len(),
+            "joined mesh should have 6 vertices"
+        );
+        assert_eq!(
+            2,
+            mesh1.triangles.len(),
+            "joined mesh should have 2 triangles"
+        );
+
+        assert_eq!(
+            vec![
+                Vec3::X,
+                Vec3::Y,
+                Vec3::Z,
+                Vec3::NEG_X,
+                Vec3::NEG_Y,
+                Vec3::NEG_Z
+            ],
+            mesh1.positions
+        );
+        assert_eq!(vec![[0, 1, 2], [3, 4, 5]], mesh1.triangles);
+    }
+
+    #[test]
+    fn test_merge_by_distance() {
+        let positions: Vec<Vec3> = vec![
+            vec3(1.0, 0.0, -1.0),
+            vec3(-1.0, 0.0, -1.0),
+            vec3(0.0, 0.0, 1.0),
+            vec3(1.0, 1e-6, -1.0),
+            vec3(-1.0, 1e-6, -1.0),
+            vec3(0.0, 0.0, -1.0),
+        ];
+        let triangles = vec![[0, 1, 2], [3, 4, 5]];
+        let mut mesh = TriangleMesh::new(triangles.clone(), positions.clone(), None, None);
+
+        mesh.merge_by_distance(1e-5);
+
+        assert_eq!(2, mesh.triangles.len()); // Mesh still retains both triangles
+        assert_eq!(
+            vec![[0, 1, 2], [0, 1, 5]],
+            mesh.triangles,
+            "mesh uses only necessary points"
+        );
+        assert_eq!(6, mesh.positions.len()); // Mesh retained vertices but is not using them
+
+        mesh.remove_unused();
+        assert_eq!(4, mesh.positions.len(), "unused vertices should be removed");
+
+        let mut mesh = TriangleMesh::new(triangles.clone(), positions.clone(), None, None);
+        mesh.optimize(1e-5);
+        assert_eq!(
+            vec![[0, 1, 2], [0, 1, 3]],
+            mesh.triangles,
+            "optimize only uses necessary points"
+        );
+        assert_eq!(4, mesh.positions.len(), "optimize should do all cleanup");
+    }
+
+    /// Port of the original quadratic `merge_by_distance`, merging each later vertex into the first earlier vertex in range.
+    /// Earlier vertices are moved to the midpoint of each merge, so results depend on vertex order.
+    fn merge_by_distance_reference(mesh: &mut TriangleMesh, threshold: f32) {
+        let thresh_squared = threshold * threshold;
+        let mut new_verts = mesh.positions.clone();
+        let mut replace: Vec<(usize, usize)> = vec![];
+        for (i, vert) in mesh.positions.iter().enumerate().rev() {
+            for (j, earlier) in new_verts.iter_mut().enumerate().take(i) {
+                if vert.distance_squared(*earlier) <= thresh_squared {
+                    *earlier = (vert + *earlier) * 0.5;
+                    replace.push((i, j));
+                    break;
+                }
+            }
+        }
+        mesh.positions = new_verts;
+        for (old, new) in replace {
+            for idx in mesh.triangles.iter_mut().flatten() {
+                if *idx == old {
+                    *idx = new;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_merge_by_distance_reference() {
+        // Fixture from test_merge_by_distance
+        let fixture = TriangleMesh::new(
+            vec![[0, 1, 2], [3, 4, 5]],
+            vec![
+                vec3(1.0, 0.0, -1.0),
+                vec3(-1.0, 0.0, -1.0),
+                vec3(0.0, 0.0, 1.0),
+                vec3(1.0, 1e-6, -1.0),
+                vec3(-1.0, 1e-6, -1.0),
+                vec3(0.0, 0.0, -1.0),
+            ],
+            None,
+            None,
+        );
+
+        // Triangle soup of a sphere, with every vertex duplicated and slightly jittered across cell borders
+        let sphere = icosphere(2);
+        let mut positions: Vec<Vec3> = vec![];
+        let mut triangles: Vec<Triangle> = vec![];
+        for (i, tri) in sphere.triangles.iter().enumerate() {
+            triangles.push([positions.len(), positions.len() + 1, positions.len() + 2]);
+            for (k, idx) in tri.iter().enumerate() {
+                let jitter = ((i * 3 + k) % 7) as f32 * 1e-4 - 3e-4;
+                positions.push(sphere.positions[*idx] + Vec3::splat(jitter));
+            }
+        }
+        let soup = TriangleMesh::new(triangles, positions, None, None);
+
+        for (label, mesh, threshold) in [
+            ("fixture", &fixture, 1e-5),
+            ("fixture, wide", &fixture, 1.5),
+            ("sphere soup", &soup, 1e-3),
+            ("sphere soup, isolated", &soup, 2e-3),
+            ("sphere soup, tiny", &soup, 1e-6),
+            ("sphere soup, wide", &soup, 0.2),
+        ] {
+            let mut expected = mesh.clone();
+            merge_by_distance_reference(&mut expected, threshold);
+            let mut actual = mesh.clone();
+            actual.merge_by_distance(threshold);
+
+            assert_eq!(
+                expected.triangles, actual.triangles,
+                "{label}: merged triangles should match pairwise reference"
+            );
+            assert_eq!(
+                expected.positions, actual.positions,
+                "{label}: merged vertices should be placed at the same midpoints as pairwise reference"
+            );
+        }
+    }
+
+    #[test]
+    fn test_merge_by_distance_chain() {
+        // Vertex 1 is only in range of vertex 2, which is in range of vertex 0
+        let positions = vec![Vec3::ZERO, Vec3::X * 1.6, Vec3::X * 0.8];
+        let mesh = TriangleMesh::new(vec![[0, 1, 2]], positions, None, None);
+
+        // Vertex 2 merges into vertex 0 first, moving it out of range of vertex 1
+        let mut merged = mesh.clone();
+        merged.merge_by_distance(1.0);
+        assert_eq!(
+            vec![[0, 1, 0]],
+            merged.triangles,
+            "later vertices should merge into the first earlier vertex in range"
+        );
+        assert_in_delta_vector(
+            Vec3::X * 0.4,
+            merged.positions[0],
+            1e-6,
+            "earlier vertex should move to the midpoint",
+        );
+
+        // Clustering welds chains together as a whole
+        let mut clustered = mesh.clone();
+        clustered.merge_by_distance_clustered(1.0);
+        assert_eq!(
+            vec![[0, 0, 0]],
+            clustered.triangles,
+            "chain should merge into one vertex"
+        );
+        assert_in_delta_vector(
+            Vec3::X * 0.8,
+            clustered.positions[0],
+            1e-6,
+            "chain should merge at its centroid",
+        );
+
+        // Reordering vertices does not change the clustered result
+        let mut reordered = TriangleMesh::new(
+            vec![[0, 2, 1]],
+            vec![Vec3::ZERO, Vec3::X * 0.8, Vec3::X * 1.6],
+            None,
+            None,
+        );
+        reordered.merge_by_distance_clustered(1.0);
+        assert_eq!(vec![[0, 0, 0]], reordered.triangles);
+        assert_in_delta_vector(
+            clustered.positions[0],
+            reordered.positions[0],
+            1e-6,
+            "clustered merge should not depend on vertex order",
+        );
+    }
+
+    #[test]
+    fn test_optimize_attributes() {
+        // Two triangles sharing an edge through duplicate vertices, plus an unused vertex
+        let positions: Vec<Vec3> = vec![
+            vec3(0.0, 0.0, 0.0),
+            vec3(1.0, 0.0, 0.0),
+            vec3(0.0, 0.0, 1.0),
+            vec3(9.0, 9.0, 9.0), // Unused
+            vec3(1.0, 1e-6, 0.0),
+            vec3(0.0, 1e-6, 1.0),
+            vec3(1.0, 0.0, 1.0),
+        ];
+        let triangles = vec![[0, 1, 2], [4, 6, 5]];
+        // Attributes encode their own vertex index, so misaligned buffers are easy to spot
+        let uv1: Vec<Vec2> = (0..positions.len()).map(|i| vec2(i as f32, 0.0)).collect();
+        let uv2: Vec<Vec2> = (0..positions.len()).map(|i| vec2(0.0, i as f32)).collect();
+        let colors: Vec<Vec4> = (0..positions.len())
+            .map(|i| Vec4::splat(i as f32))
+            .collect();
+        let custom0: Vec<f32> = (0..positions.len()).map(|i| i as f32).collect();
+
+        let mut mesh = TriangleMesh::new(triangles, positions, None, None);
+        mesh.uv1 = Some(uv1);
+        mesh.uv2 = Some(uv2);
+        mesh.colors = colors;
+        mesh.custom0 = Some(custom0);
+        mesh.custom1 = Some(vec![1.0; 2]); // Too short, should be dropped
+        mesh.optimize(1e-5);
+        mesh.debug_validate();
+
+        assert_eq!(
+            4,
+            mesh.positions.len(),
+            "duplicates and unused should be removed"
+        );
+        assert_eq!(vec![[0, 1, 2], [1, 3, 2]], mesh.triangles);
+        assert!(
+            mesh.custom1.is_none(),
+            "mismatched buffer should be dropped"
+        );
+
+        // Welded vertices average their attributes, while the rest keep theirs
+        let uv1 = mesh.uv1.as_ref().expect("UV1 should be kept");
+        let uv2 = mesh.uv2.as_ref().expect("UV2 should be kept");
+        let custom0 = mesh.custom0.as_ref().expect("custom0 should be kept");
+        for (idx, original) in [(0, 0.0), (1, 2.5), (2, 3.5), (3, 6.0)] {
+            assert_eq!(vec2(original, 0.0), uv1[idx], "UV1 of vertex {idx}");
+            assert_eq!(vec2(0.0, original), uv2[idx], "UV2 of vertex {idx}");
+            assert_eq!(
+                Vec4::splat(original),
+                mesh.colors[idx],
+                "color of vertex {idx}"
+            );
+            assert_eq!(original, custom0[idx], "custom0 of vertex {idx}");
+        }
+
+        // Buffers that are absent stay absent
+        let mut mesh = unit_cube();
+        mesh.optimize(1e-3);
+        assert!(mesh.uv1.is_none() && mesh.uv2.is_none() && mesh.tangents.is_none());
+        assert!(mesh.colors.is_empty());
+    }
+
+    #[test]
+    fn test_merge_by_distance_clusters() {
+        // Three vertices chained within the threshold, plus distant vertices
+        let cluster = [
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.8, 0.0, 0.0),
+            vec3(1.6, 0.0, 0.0),
+        ];
+        let centroid = vec3(0.8, 0.0, 0.0);
+        let orders = [[0, 1, 2], [2, 1, 0], [1, 2, 0], [2, 0, 1]];
+
+        for order in orders {
+            let mut positions: Vec<Vec3> = order.iter().map(|i| cluster[*i]).collect();
+            positions.push(vec3(0.0, 5.0, 0.0));
+            positions.push(vec3(0.0, 5.0, 5.0));
+            let mut mesh =
+                TriangleMesh::new(vec![[0, 3, 4], [1, 3, 4], [2, 3, 4]], positions, None, None);
+
+            mesh.merge_by_distance_clustered(1.0);
+            mesh.remove_unused();
+
+            assert_eq!(
+                vec![[0, 1, 2]; 3],
+                mesh.triangles,
+                "order {order:?}: triangles should share the merged vertex"
+            );
+            assert_eq!(
+                3,
+                mesh.positions.len(),
+                "order {order:?}: clustered vertices should merge into one"
+            );
+            assert!(
+                mesh.positions[0].distance(centroid) < 1e-6,
+                "order {order:?}: cluster should merge to its centroid, got {}",
+                mesh.positions[0]
+            );
+            assert_eq!(
+                vec3(0.0, 5.0, 0.0),
+                mesh.positions[1],
+                "order {order:?}: distant vertices should be untouched"
+            );
+        }
+    }
+
+    #[test]
+    fn test_merge_by_distance_where_chain() {
+        // A chain of vertices, where A-B and B-C are within the threshold, but A-C may not merge
+        let positions = vec![
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.6, 0.0, 0.0),
+            vec3(1.2, 0.0, 0.0),
+            vec3(0.0, 5.0, 0.0),
+        ];
+        let mut mesh = TriangleMesh::new(vec![[0, 1, 3], [1, 2, 3]], positions, None, None);
+
+        mesh.merge_by_distance_where(0.7, Some(&|later, earlier| (later, earlier) != (2, 0)));
+        assert_eq!(
+            vec![[0, 0, 3], [0, 2, 3]],
+            mesh.triangles,
+            "B should merge into A, while C is kept apart from A"
+        );
+        assert_in_delta_vector(
+            vec3(0.3, 0.0, 0.0),
+            mesh.positions[0],
+            1e-6,
+            "A and B should merge to their centroid",
+        );
+        assert_eq!(
+            vec3(1.2, 0.0, 0.0),
+            mesh.positions[2],
+            "C should be untouched"
+        );
+    }
+
+    #[test]
+    fn test_merge_by_distance_preserving_edges() {
+        // Two faces meeting at a 90 degree corner along the X axis, each with their own vertices
+        let positions: Vec<Vec3> = vec![
+            // Floor
+            vec3(0.0, 0.0, 0.0),
+            vec3(1.0, 0.0, 0.0),
+            vec3(0.0, 0.0, 1.0),
+            // Wall
+            vec3(1.0, 1e-6, 0.0),
+            vec3(0.0, 1e-6, 0.0),
+            vec3(0.0, 1.0, 0.0),
+        ];
+        let triangles = vec![[0, 2, 1], [3, 4, 5]];
+
+        let mut corner = TriangleMesh::new(triangles.clone(), positions.clone(), None, None);
+        corner.merge_by_distance_preserving_edges(1e-5, 30f32.to_radians());
+        assert_eq!(
+            triangles, corner.triangles,
+            "vertices on a 90 degree corner should not merge"
+        );
+
+        let mut corner = TriangleMesh::new(triangles.clone(), positions.clone(), None, None);
+        corner.merge_by_distance(1e-5);
+        assert_eq!(
+            vec![[0, 2, 1], [1, 0, 5]],
+            corner.triangles,
+            "plain merge should weld vertices on a 90 degree corner"
+        );
+
+        // Two coplanar triangles sharing a duplicated edge
+        let positions: Vec<Vec3> = vec![
+            vec3(0.0, 0.0, 0.0),
+            vec3(1.0, 0.0, 0.0),
+            vec3(0.0, 0.0, 1.0),
+            vec3(1.0, 1e-6, 0.0),
+            vec3(1.0, 0.0, 1.0),
+            vec3(0.0, 1e-6, 1.0),
+        ];
+        let mut panel = TriangleMesh::new(vec![[0, 2, 1], [3, 5, 4]], positions, None, None);
+        panel.merge_by_distance_preserving_edges(1e-5, 30f32.to_radians());
+        assert_eq!(
+            vec![[0, 2, 1], [1, 2, 4]],
+            panel.triangles,
+            "coplanar near-duplicate vertices should merge"
+        );
+    }
+
+    // TODO: edge map test using a manifold cube
+
+    #[test]
+    fn test_raycast_backface_triangles() {
+        let positions: Vec<Vec3> = vec![
+            vec3(1.0, 0.0, -1.0),
+            vec3(-1.0, 0.0, -1.0),
+            vec3(0.0, 0.0, 1.0),
+        ];
+        let triangles: Vec<Triangle> = vec![[0, 1, 2]];
+        let mesh = TriangleMesh::new(triangles.clone(), positions.clone(), None, None);
+
+        let result = mesh
+            .raycast(RaycastParameters::new(
+                Vec3::Y,
+                Vec3::NEG_Y,
+                f32::INFINITY,
+                false,
+            ))
+            .expect("raycast should hit directly");
+
+        assert_eq!(
+            Vec3::ZERO,
+            result.point,
+            "ray should should intersect at origin"
+        );
+        assert_eq!(result.normal, Vec3::Y, "normal should be facing the ray");
+        assert_eq!(result.depth, 1.0, "depth should be 1");
+        assert_eq!(
+            0,
+            result
+                .face_index
+                .expect("face index should be set for trimesh")
+        );
+
+        assert!(
+            mesh.raycast(RaycastParameters::new(
+                Vec3::NEG_Y,
+                Vec3::Y,
+                f32::INFINITY,
+                false
+            ))
+            .is_none(),
+            "raycast should miss backface"
+        );
+        assert!(
+            mesh.raycast(RaycastParameters::new(
+                Vec3::NEG_Y,
+                Vec3::Y,
+                f32::INFINITY,
+                true
+            ))
+            .is_some(),
+            "raycast should hit backface"
+        );
+
+        assert!(
+            mesh.raycast(RaycastParameters::new(
+                Vec3::new(5.0, 5.0, 5.0),
+                Vec3::NEG_Y,
+                f32::INFINITY,
+                true
+            ))
+            .is_none(),
+            "raycast should miss triangle during barycentric projection"
+        );
+    }
+
+    #[test]
+    fn test_raycast_offset() {
+        let positions: Vec<Vec3> = vec![
+            vec3(1.0, 1.0, -1.0),
+            vec3(-1.0, 1.0, -1.0),
+            vec3(0.0, 1.0, 1.0),
+        ];
+        let triangles: Vec<Triangle> = vec![[0, 1, 2]];
+        let mesh = TriangleMesh::new(triangles.clone(), positions.clone(), None, None);
+
+        let result = mesh
+            .raycast(RaycastParameters::new(
+                Vec3::new(0.1, 2.0, -0.5),
+                Vec3::NEG_Y,
+                f32::INFINITY,
+                false,
+            ))
+            .expect("raycast should hit directly");
+
+        assert_eq!(
+            Vec3::new(0.1, 1.0, -0.5),
+            result.point,
+            "ray should should intersect at expected point"
+        );
+        assert_eq!(result.normal, Vec3::Y, "normal should be facing the ray");
+        assert_eq!(result.depth, 1.0, "depth should be 1");
+        assert_eq!(
+            0,
+            result
+                .face_index
+                .expect("face index should be set for trimesh")
+        );
+
+        // raycast that should completely miss
+        let result = mesh.raycast(RaycastParameters::new(
+            Vec3::new(0.1, 10.0, -0.5),
+            Vec3::Y,
+            f32::INFINITY,
+            false,
+        ));
+
+        assert_eq!(None, result, "raycast should have missed");
+    }
+
+    #[test]
+    fn test_raycast_layered() {
+        // Should return nearest face index
+        let positions_layered: Vec<Vec3> = vec![
+            vec3(1.0, 0.0, -1.0),
+            vec3(-1.0, 0.0, -1.0),
+            vec3(0.0, 0.0, 1.0),
+            vec3(1.0, 1.0, -1.0),
+            vec3(-1.0, 1.0, -1.0),
+            vec3(0.0, 1.0, 1.0),
+        ];
+        let triangles_layered: Vec<Triangle> = vec![[0, 1, 2], [3, 4, 5]];
+        let mesh_layered = TriangleMesh::new(
+            triangles_layered.clone(),
+            positions_layered.clone(),
+            None,
+            None,
+        );
+
+        let result = mesh_layered
+            .raycast(RaycastParameters::new(
+                Vec3::Y * 3.0,
+                Vec3::NEG_Y,
+                f32::INFINITY,
+                false,
+            ))
+            .expect("raycast should hit directly");
+
+        assert_eq!(2.0, result.depth, "raycast should be 2 units from surface");
+        assert_eq!(1, result.face_index.expect("face_index should exist"));
+        assert_eq!(
+            Vec3::Y,
+            result.point,
+            "raycast should intersect at (0, 1, 0)"
+        );
+    }
+
+    #[test]
+    fn test_ambient_occlusion_threads() {
+        // Sphere resting partway into a ground plane, for a mix of occluded and exposed vertices
+        let mut mesh = icosphere(2);
+        for tri in mesh.triangles.iter_mut() {
+            tri.swap(1, 2); // Icosphere faces are wound inward, flip them to face outward
+        }
+        for position in mesh.positions.iter_mut() {
+            *position += Vec3::new(0.0, 0.5, 0.0);
+        }
+        let base = mesh.positions.len();
+        mesh.positions.extend([
+            vec3(-4.0, 0.0, -4.0),
+            vec3(-4.0, 0.0, 4.0),
+            vec3(4.0, 0.0, -4.0),
+            vec3(4.0, 0.0, 4.0),
+        ]);
+        mesh.triangles
+            .extend([[base, base + 1, base + 2], [base + 2, base + 1, base + 3]]);
+        mesh.bake_normals_smooth();
+        mesh.bake_raycast_planes();
+
+        let bake = |threads: usize| -> Vec<u32> {
+            mesh.get_ambient_occlusion(
+                16,
+                2.0,
+                std::f32::consts::FRAC_PI_2,
+                7,
+                NonZero::new(threads).expect("thread count should be non-zero"),
+            )
+            .iter()
+            .map(|ao| ao.to_bits())
+            .collect()
+        };
+
+        let single = bake(1);
+        assert_eq!(
+            mesh.positions.len(),
+            single.len(),
+            "should have one value per vertex"
+        );
+        assert!(
+            single
+                .iter()
+                .any(|ao| f32::from_bits(*ao) > 0.0 && f32::from_bits(*ao) < 1.0),
+            "some vertices should be partially occluded"
+        );
+        for threads in [2, 3, 16, 1000] {
+            assert_eq!(
+                single,
+                bake(threads),
+                "ambient occlusion with {threads} threads should match single-threaded bit-for-bit"
+            );
+        }
+    }
+
+    #[test]
+    fn test_ambient_occlusion_cone() {
+        // Open-topped box, with a vertex at the center of its floor
+        let mut mesh = TriangleMesh::default();
+        let corners = [
+            vec3(-1.0, 0.0, -1.0),
+            vec3(1.0, 0.0, -1.0),
+            vec3(1.0, 0.0, 1.0),
+            vec3(-1.0, 0.0, 1.0),
+        ];
+        for i in 0..4 {
+            let (a, b) = (corners[i], corners[(i + 1) % 4]);
+            mesh.add_triangle(Vec3::ZERO, a, b);
+            // Double-sided walls, so rays hit them from either side
+            let (c, d) = (a + Vec3::Y * 2.0, b + Vec3::Y * 2.0);
+            for tri in [[a, b, d], [a, d, c]] {
+                mesh.add_triangle(tri[0], tri[1], tri[2]);
+                mesh.add_triangle(tri[0], tri[2], tri[1]);
+            }
+        }
+        // Open ground far away from the box
+        let ground = mesh.positions.len();
+        mesh.add_triangle(
+            vec3(50.0, 0.0, 0.0),
+            vec3(51.0, 0.0, 0.0),
+            vec3(50.0, 0.0, 1.0),
+        );
+        mesh.normals = vec![Vec3::Y; mesh.positions.len()];
+
+        let bake = |cone_angle: f32| {
+            mesh.get_ambient_occlusion(
+                64,
+                4.0,
+                cone_angle,
+                3,
+                NonZero::new(4).expect("thread count should be non-zero"),
+            )
+        };
+
+        let hemisphere = bake(std::f32::consts::FRAC_PI_2);
+        assert_eq!(1.0, hemisphere[ground], "open ground should be fully lit");
+        assert!(
+            hemisphere[0] < 0.75,
+            "box floor should be meaningfully darker than open ground, got {}",
+            hemisphere[0]
+        );
+
+        // A narrow cone only samples straight up, out of the box
+        let narrow = bake(10f32.to_radians());
+        assert_eq!(1.0, narrow[0], "narrow cone should escape the box");
+
+        for ao in bake(-1.0).iter().chain(bake(10.0).iter()) {
+            assert!(
+                (0.0..=1.0).contains(ao),
+                "out of range cone angles should be clamped, got {ao}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_directional_occlusion() {
+        // Upward-facing ground plane, with a downward-facing overhang above the first corner
+        let positions: Vec<Vec3> = vec![
+            vec3(-4.0, 0.0, -4.0),
+            vec3(-4.0, 0.0, 4.0),
+            vec3(4.0, 0.0, -4.0),
+            vec3(4.0, 0.0, 4.0),
+            vec3(-5.0, 2.0, -5.0),
+            vec3(-5.0, 2.0, -2.0),
+            vec3(-2.0, 2.0, -5.0),
+        ];
+        let normals: Vec<Vec3> = vec![
+            Vec3::Y,
+            Vec3::Y,
+            Vec3::Y,
+            Vec3::Y,
+            Vec3::NEG_Y,
+            Vec3::NEG_Y,
+            Vec3::NEG_Y,
+        ];
+        let triangles: Vec<Triangle> = vec![[0, 1, 2], [2, 1, 3], [4, 6, 5]];
+        let mut mesh = TriangleMesh::new(triangles, positions, Some(normals), None);
+        mesh.bake_raycast_planes();
+
+        for samples in [1, 8] {
+            let occlusion = mesh.directional_occlusion(Vec3::Y, samples);
+            assert_eq!(7, occlusion.len(), "should have one value per vertex");
+
+            assert_eq!(
+                0.0, occlusion[0],
+                "vertex under overhang should be shadowed, with {samples} samples"
+            );
+            assert_eq!(
+                1.0, occlusion[3],
+                "exposed vertex should be lit, with {samples} samples"
+            );
+            assert_eq!(
+                0.0, occlusion[4],
+                "vertex facing away from light should be shadowed, with {samples} samples"
+            );
+        }
+    }
+
+    #[test]
+    fn test_raycast_from_inside() {
+        // Upward-facing triangle, with the ray starting beneath it
+        let positions: Vec<Vec3> = vec![
+            vec3(-1.0, 0.0, -1.0),
+            vec3(-1.0, 0.0, 1.0),
+            vec3(1.0, 0.0, 0.0),
+        ];
+        let mesh = TriangleMesh::new(vec![[0, 1, 2]], positions, None, None);
+        let origin = Vec3::NEG_Y;
+
+        let result = mesh.raycast(RaycastParameters::builder(origin, Vec3::Y).build());
+        assert_eq!(None, result, "backface should not be hit from inside");
+
+        let result = mesh
+            .raycast(
+                RaycastParameters::builder(origin, Vec3::Y)
+                    .hit_from_inside(true)
+                    .build(),
+            )
+            .expect("raycast should hit from inside");
+        assert_eq!(0.0, result.depth, "hit should be at ray origin");
+        assert_eq!(origin, result.point, "hit should be at ray origin");
+        assert_eq!(Vec3::ZERO, result.normal, "hit should have no normal");
+
+        let result = mesh.raycast(
+            RaycastParameters::builder(origin, Vec3::NEG_Y)
+                .hit_from_inside(true)
+                .build(),
+        );
+        assert_eq!(None, result, "ray facing away from backface should miss");
+    }
+
+    #[test]
+    fn test_raycast_bounds() {
+        let mut mesh = unit_cube();
+        assert_eq!(None, mesh.bounds, "bounds should not be baked by default");
+        mesh.bake_raycast_planes();
+        assert_eq!(
+            Some(BoundingBox::new(Vec3::splat(-0.5), Vec3::splat(0.5))),
+            mesh.bounds,
+            "bounds should be baked alongside planes"
+        );
+
+        let origin = vec3(-2.0, 0.0, 0.0);
+        let result = mesh
+            .raycast(RaycastParameters::builder(origin, Vec3::X).build())
+            .expect("ray toward the mesh should hit");
+        assert_in_delta(1.5, result.depth, 1e-6, "hit depth".to_string());
+
+        let result = mesh
+            .raycast(RaycastParameters::builder(vec3(-2.0, 0.5, 0.5), Vec3::X).build())
+            .expect("ray grazing the mesh bounds should still hit");
+        assert_in_delta(1.5, result.depth, 1e-6, "grazing depth".to_string());
+
+        for params in [
+            RaycastParameters::builder(origin, Vec3::NEG_X).build(),
+            RaycastParameters::builder(origin, Vec3::Y).build(),
+            RaycastParameters::builder(vec3(-2.0, 0.6, 0.0), Vec3::X)
+                .hit_backfaces(true)
+                .build(),
+        ] {
+            assert_eq!(
+                None,
+                mesh.raycast(params),
+                "ray missing the mesh bounds should miss"
+            );
+        }
+
+        // Translating should keep baked bounds in sync
+        mesh.translate(Vec3::Y * 10.0);
+        assert_eq!(
+            Some(BoundingBox::new(
+                vec3(-0.5, 9.5, -0.5),
+                vec3(0.5, 10.5, 0.5)
+            )),
+            mesh.bounds,
+            "bounds should follow the mesh"
+        );
+        assert!(
+            mesh.raycast(RaycastParameters::builder(origin + Vec3::Y * 10.0, Vec3::X).build())
+                .is_some(),
+            "ray toward the translated mesh should hit"
+        );
+    }
+
+    #[test]
+    fn test_raycast_many() {
+        let mut mesh = icosphere(2);
+        mesh.bake_raycast_planes();
+
+        // Alternate between hits and misses, so reordering would be caught
+        let params: Vec<RaycastParameters> = (0..200)
+            .map(|i| {
+                let angle = i as f32 * 0.37;
+                let origin = vec3(angle.cos(), (i % 7) as f32 * 0.1 - 0.27, angle.sin()) * 3.0;
+                let direction = if i % 3 == 0 { origin } else { -origin };
+                RaycastParameters::builder(origin, direction.normalize()).build()
+            })
+            .collect();
+
+        let results = mesh.raycast_many(&params);
+        assert_eq!(params.len(), results.len(), "one result per ray");
+        for (idx, (param, result)) in params.iter().zip(results.iter()).enumerate() {
+            assert_eq!(
+                mesh.raycast(*param),
+                *result,
+                "batched result {idx} should match a single raycast"
+            );
+            assert_eq!(
+                idx % 3 != 0,
+                result.is_some(),
+                "ray {idx} should only hit when pointed at the mesh"
+            );
+        }
+
+        assert!(
+            mesh.raycast_many(&[]).is_empty(),
+            "no rays should give no results"
+        );
+        assert_eq!(
+            vec![mesh.raycast(params[1])],
+            mesh.raycast_many(&params[1..2]),
+            "single ray batch should match a single raycast"
+        );
+    }
+
+    #[test]
+    fn test_normals_flat() {
+        let mut cube = unit_cube();
+        cube.colors = cube.positions.iter().map(|pt| pt.extend(1.0)).collect();
+        cube.uv1 = Some(cube.positions.iter().map(|pt| pt.truncate()).collect());
+        let original = cube.clone();
+
+        cube.bake_normals_flat();
+        assert_eq!(
+            original.triangles.len() * 3,
+            cube.positions.len(),
+            "every triangle should have its own three vertices"
+        );
+        assert_eq!(
+            cube.positions.len(),
+            cube.normals.len(),
+            "every vertex should have a normal"
+        );
+        assert_eq!(
+            cube.positions.len(),
+            cube.colors.len(),
+            "every vertex should have a color"
+        );
+
+        let uv1 = cube.uv1.as_ref().expect("UVs should be kept");
+        for (face, (tri, source)) in cube
+            .triangles
+            .iter()
+            .zip(original.triangles.iter())
+            .enumerate()
+        {
+            // Normals face the same way as smooth normals, opposite the triangle winding normal
+            let face_normal = -tri.normal(&cube.positions);
+            for (corner, (vertex, source)) in tri.iter().zip(source.iter()).enumerate() {
+                assert_eq!(
+                    face_normal, cube.normals[*vertex],
+                    "corner {corner} of triangle {face} should have the face normal"
+                );
+                assert_eq!(
+                    original.positions[*source], cube.positions[*vertex],
+                    "corner {corner} of triangle {face} should keep its position"
+                );
+                assert_eq!(
+                    original.colors[*source], cube.colors[*vertex],
+                    "corner {corner} of triangle {face} should keep its color"
+                );
+                assert_eq!(
+                    original.positions[*source].truncate(),
+                    uv1[*vertex],
+                    "corner {corner} of triangle {face} should keep its UV"
+                );
+            }
+        }
+        assert!(cube.uv2.is_none(), "missing UVs should stay missing");
+        assert_in_delta(
+            original.signed_volume(),
+            cube.signed_volume(),
+            1e-6,
+            "volume should be unchanged".to_string(),
+        );
+    }
+
+    #[test]
+    fn test_triplanar_uvs() {
+        // A +X-facing quad, and a +Y-facing quad
+        let positions = vec![
+            vec3(1.0, 0.0, 0.0),
+            vec3(1.0, 2.0, 0.0),
+            vec3(1.0, 2.0, 3.0),
+            vec3(1.0, 0.0, 3.0),
+            vec3(0.0, 4.0, 0.0),
+            vec3(2.0, 4.0, 0.0),
+            vec3(2.0, 4.0, 1.0),
+            vec3(0.0, 4.0, 1.0),
+        ];
+        let normals = vec![
+            Vec3::X,
+            Vec3::X,
+            vec3(0.9, 0.3, 0.3).normalize(),
+            Vec3::X,
+            Vec3::Y,
+            Vec3::Y,
+            Vec3::Y,
+            Vec3::Y,
+        ];
+        let triangles: Vec<Triangle> = vec![[0, 1, 2], [0, 2, 3], [4, 5, 6], [4, 6, 7]];
+        let mesh = TriangleMesh::new(triangles, positions, Some(normals), None);
+
+        let (yz, xz, xy) = mesh.generate_triplanar_uvs(0.5);
+        assert_eq!(mesh.positions.len(), yz.len());
+        assert_eq!(mesh.positions.len(), xz.len());
+        assert_eq!(mesh.positions.len(), xy.len());
+        assert_eq!(vec2(1.5, 1.0), yz[2], "YZ projection should be scaled");
+        assert_eq!(vec2(1.0, 0.5), xz[6], "XZ projection should be scaled");
+        assert_eq!(vec2(0.5, 1.0), xy[2], "XY projection should be scaled");
+
+        let weights = mesh.triplanar_weights(4.0);
+        for (idx, weight) in weights.iter().enumerate() {
+            assert_in_delta(1.0, weight.element_sum(), 1e-5, format!("weights of {idx}"));
+        }
+
+        // Vertices on the +X-facing quad take their dominant UV from the YZ plane
+        for idx in 0..4 {
+            assert_eq!(
+                0,
+                weights[idx].max_position(),
+                "vertex {idx} should be dominated by the YZ plane, weights were {0}",
+                weights[idx]
+            );
+            assert_eq!(
+                vec2(mesh.positions[idx].z, mesh.positions[idx].y) * 0.5,
+                yz[idx],
+                "vertex {idx} YZ projection"
+            );
+        }
+        assert!(
+            weights[2].y > 0.0,
+            "tilted normal should blend in other planes"
+        );
+        for (idx, weight) in weights.iter().enumerate().skip(4) {
+            assert_eq!(
+                Vec3::Y,
+                *weight,
+                "vertex {idx} should only use the XZ plane"
+            );
+        }
+
+        // Degenerate normals blend evenly
+        assert_eq!(Vec3::splat(1.0 / 3.0), triplanar_blend(Vec3::ZERO, 4.0));
+        assert!(
+            TriangleMesh::default().triplanar_weights(1.0).is_empty(),
+            "mesh without normals should have no weights"
+        );
+
+        // Weights can be stored in the color channel, keeping any existing alpha
+        let mut baked = mesh.clone();
+        baked.bake_triplanar_weights(4.0);
+        assert_eq!(
+            weights
+                .iter()
+                .map(|weight| weight.extend(1.0))
+                .collect::<Vec<Vec4>>(),
+            baked.colors,
+            "weights should be stored in RGB, with full alpha"
+        );
+        baked.colors = vec![Vec4::splat(0.25); baked.positions.len()];
+        baked.bake_triplanar_weights(4.0);
+        assert_eq!(
+            weights
+                .iter()
+                .map(|weight| weight.extend(0.25))
+                .collect::<Vec<Vec4>>(),
+            baked.colors,
+            "existing alpha should be kept"
+        );
+        let mut bare = TriangleMesh::new(vec![[0, 1, 2]], mesh.positions[..3].to_vec(), None, None);
+        bare.normals.clear();
+        bare.bake_triplanar_weights(4.0);
+        assert!(
+            bare.colors.is_empty(),
+            "mesh without normals should not get colors"
+        );
+    }
+
+    #[test]
+    fn test_tangents() {
+        // Flat quad on the XZ plane, with UVs matching the X and Z axes
+        let mut mesh = TriangleMesh::new(
+            vec![[0, 1, 2], [0, 2, 3]],
+            vec![Vec3::ZERO, Vec3::X, vec3(1.0, 0.0, 1.0), Vec3::Z],
+            None,
+            None,
+        );
+        assert!(
+            mesh.get_tangents().is_empty(),
+            "mesh without UVs should have no tangents"
+        );
+        mesh.bake_tangents();
+        assert!(
+            mesh.tangents.is_none(),
+            "tangents should not be baked without UVs"
+        );
+
+        mesh.uv1 = Some(vec![Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y]);
+        mesh.bake_normals_smooth();
+        mesh.bake_tangents();
+        let tangents = mesh.tangents.clone().expect("tangents should be baked");
+        assert_eq!(4, tangents.len(), "should have one tangent per vertex");
+        for (tangent, normal) in tangents.iter().zip(mesh.normals.iter()) {
+            assert!(
+                tangent.xyz().distance(Vec3::X) < 1e-5,
+                "tangent {tangent} should follow the U axis"
+            );
+            let binormal = normal.cross(tangent.xyz()) * tangent.w;
+            assert!(
+                binormal.distance(Vec3::Z) < 1e-5,
+                "binormal {binormal} should follow the V axis"
+            );
+        }
+
+        // Mirroring the V axis should flip handedness
+        mesh.uv1 = Some(vec![Vec2::Y, vec2(1.0, 1.0), Vec2::X, Vec2::ZERO]);
+        let mirrored = mesh.get_tangents();
+        for (tangent, original) in mirrored.iter().zip(tangents.iter()) {
+            assert_eq!(
+                -original.w, tangent.w,
+                "mirrored UVs should flip tangent handedness"
+            );
+        }
+        mesh.debug_validate();
+    }
+
+    #[test]
+    fn test_quantize_weld() {
+        let positions: Vec<Vec3> = vec![
+            vec3(0.01, 0.0, 0.0),
+            vec3(1.0, 0.0, 0.0),
+            vec3(0.0, 0.0, 1.0),
+            vec3(0.02, 0.0, 0.01),
+            vec3(1.0, 0.0, 1.0),
+        ];
+        let triangles: Vec<Triangle> = vec![[0, 2, 1], [1, 2, 4], [3, 2, 1]];
+
+        let mut mesh = TriangleMesh::new(triangles.clone(), positions.clone(), None, None);
+        mesh.quantize_weld(0.1);
+        assert_eq!(
+            4,
+            mesh.positions.len(),
+            "vertices in the same cell should weld"
+        );
+        assert_eq!(
+            mesh.triangles[0], mesh.triangles[2],
+            "triangles sharing welded vertices should match"
+        );
+        assert_eq!(
+            Vec3::ZERO,
+            mesh.positions[mesh.triangles[0][0]],
+            "welded vertex should snap to grid"
+        );
+
+        // Reverse vertex order, and weld again
+        let count = positions.len();
+        let reversed_positions: Vec<Vec3> = positions.iter().rev().copied().collect();
+        let reversed_triangles: Vec<Triangle> = triangles
+            .iter()
+            .map(|tri| tri.map(|idx| count - 1 - idx))
+            .collect();
+        let mut reversed = TriangleMesh::new(reversed_triangles, reversed_positions, None, None);
+        reversed.quantize_weld(0.1);
+
+        let resolve = |mesh: &TriangleMesh| -> Vec<[Vec3; 3]> {
+            mesh.triangles
+                .iter()
+                .map(|tri| tri.map(|idx| mesh.positions[idx]))
+                .collect()
+        };
+        assert_eq!(
+            resolve(&mesh),
+            resolve(&reversed),
+            "welding should not depend on vertex order"
+        );
+    }
+
+    #[test]
+    fn test_from_indices_oriented() {
+        // Unit cube, offset away from the origin
+        let positions: Vec<Vec3> = (0..8)
+            .map(|i| {
+                vec3((i & 1) as f32, ((i >> 1) & 1) as f32, ((i >> 2) & 1) as f32)
+                    + vec3(2.0, 3.0, 4.0)
+            })
+            .collect();
+        let center = vec3(2.5, 3.5, 4.5);
+        let quads = [
+            [0, 2, 6, 4],
+            [1, 3, 7, 5],
+            [0, 1, 5, 4],
+            [2, 3, 7, 6],
+            [0, 1, 3, 2],
+            [4, 5, 7, 6],
+        ];
+        // Wind every triangle clockwise, facing inward
+        let indices: Vec<usize> = quads
+            .iter()
+            .flat_map(|q| [[q[0], q[1], q[2]], [q[0], q[2], q[3]]])
+            .flat_map(|tri: Triangle| {
+                let outward = tri
+                    .normal(&positions)
+                    .dot(tri.centerpoint(&positions) - center);
+                if outward > 0.0 { tri.flip() } else { tri }
+            })
+            .collect();
+
+        let trusted =
+            TriangleMesh::from_indices_oriented(indices.clone(), positions.clone(), None, true);
+        assert!(
+            (trusted.signed_volume() + 1.0).abs() < 1e-5,
+            "clockwise cube should have negative volume when winding is trusted, got {}",
+            trusted.signed_volume()
+        );
+
+        let oriented = TriangleMesh::from_indices_oriented(indices, positions, None, false);
+        assert!(
+            (oriented.signed_volume() - 1.0).abs() < 1e-5,
+            "clockwise cube should have positive volume after being oriented, got {}",
+            oriented.signed_volume()
+        );
+        for tri in oriented.triangles.iter() {
+            assert!(
+                tri.normal(&oriented.positions)
+                    .dot(tri.centerpoint(&oriented.positions) - center)
+                    > 0.0,
+                "triangle {tri:?} should face outward"
+            );
+        }
+    }
+
+    #[test]
+    fn test_debug_validate() {
+        let positions: Vec<Vec3> = vec![Vec3::X, Vec3::Y, Vec3::Z];
+        let mut mesh = TriangleMesh::new(vec![[0, 1, 2]], positions, Some(vec![Vec3::Y; 3]), None);
+        mesh.uv1 = Some(vec![Vec2::ZERO; 3]);
+        mesh.debug_validate();
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_debug_validate_index_out_of_range() {
+        let positions: Vec<Vec3> = vec![Vec3::X, Vec3::Y, Vec3::Z];
+        let mesh = TriangleMesh::new(vec![[0, 1, 3]], positions, None, None);
+        mesh.debug_validate();
+    }
+
+    /// Creates triangles from the given faces, winding them to face away from the origin.
+    fn outward_triangles(positions: &[Vec3], faces: &[Triangle]) -> Vec<Triangle> {
+        faces
+            .iter()
+            .map(|tri| {
+                if tri.normal(positions).dot(tri.centerpoint(positions)) < 0.0 {
+                    tri.flip()
+                } else {
+                    *tri
+                }
+            })
+            .collect()
+    }
+
+    /// Creates a closed unit sphere centered on the origin, by subdividing an icosahedron the given number of times.
+    fn icosphere(subdivisions: u32) -> TriangleMesh {
+        let t = (1.0 + 5f32.sqrt()) * 0.5;
+        let mut positions: Vec<Vec3> = [
+            vec3(-1.0, t, 0.0),
+            vec3(1.0, t, 0.0),
+            vec3(-1.0, -t, 0.0),
+            vec3(1.0, -t, 0.0),
+            vec3(0.0, -1.0, t),
+            vec3(0.0, 1.0, t),
+            vec3(0.0, -1.0, -t),
+            vec3(0.0, 1.0, -t),
+            vec3(t, 0.0, -1.0),
+            vec3(t, 0.0, 1.0),
+            vec3(-t, 0.0, -1.0),
+            vec3(-t, 0.0, 1.0),
+        ]
+        .iter()
+        .map(|p| p.normalize())
+        .collect();
+        let mut triangles: Vec<[usize; 3]> = vec![
+            [0, 11, 5],
+            [0, 5, 1],
+            [0, 1, 7],
+            [0, 7, 10],
+            [0, 10, 11],
+            [1, 5, 9],
+            [5, 11, 4],
+            [11, 10, 2],
+            [10, 7, 6],
+            [7, 1, 8],
+            [3, 9, 4],
+            [3, 4, 2],
+            [3, 2, 6],
+            [3, 6, 8],
+            [3, 8, 9],
+            [4, 9, 5],
+            [2, 4, 11],
+            [6, 2, 10],
+            [8, 6, 7],
+            [9, 8, 1],
+        ];
+
+        for _ in 0..subdivisions {
+            let mut midpoints: HashMap<[usize; 2], usize> = HashMap::new();
+            let mut midpoint = |a: usize, b: usize, positions: &mut Vec<Vec3>| -> usize {
+                *midpoints.entry([a.min(b), a.max(b)]).or_insert_with(|| {
+                    positions.push(((positions[a] + positions[b]) * 0.5).normalize());
+                    positions.len() - 1
+                })
+            };
+            triangles = triangles
+                .iter()
+                .flat_map(|[a, b, c]| {
+                    let ab = midpoint(*a, *b, &mut positions);
+                    let bc = midpoint(*b, *c, &mut positions);
+                    let ca = midpoint(*c, *a, &mut positions);
+                    [[*a, ab, ca], [*b, bc, ab], [*c, ca, bc], [ab, bc, ca]]
+                })
+                .collect();
+        }
+
+        TriangleMesh::new(triangles, positions, None, None)
+    }
+
+    /// Returns true if every edge of the mesh is shared by exactly two triangles.
+    fn is_watertight(mesh: &TriangleMesh) -> bool {
+        mesh.edge_face_counts().values().all(|count| *count == 2)
+    }
+
+    /// Creates a closed unit cube centered on the origin, with two triangles per face.
+    fn unit_cube() -> TriangleMesh {
+        let positions: Vec<Vec3> = (0..8)
+            .map(|i| vec3((i & 1) as f32, ((i >> 1) & 1) as f32, ((i >> 2) & 1) as f32) - 0.5)
+            .collect();
+        let quads = [
+            [0, 2, 6, 4],
+            [1, 3, 7, 5],
+            [0, 1, 5, 4],
+            [2, 3, 7, 6],
+            [0, 1, 3, 2],
+            [4, 5, 7, 6],
+        ];
+        let faces: Vec<Triangle> = quads
+            .iter()
+            .flat_map(|q| [[q[0], q[1], q[2]], [q[0], q[2], q[3]]])
+            .collect();
+        TriangleMesh::new(outward_triangles(&positions, &faces), positions, None, None)
+    }
 
-            let dot = case.normal.dot(normal);
-            assert!(
-                dot >= 1.0 - MAX_DIFFERENCE,
-                "Case {0}, expected {1} but got {2}, with dot product {3}",
-                idx,
-                case.normal,
-                normal,
-                dot
-            );
+    #[test]
+    fn test_triangle_adjacency() {
+        let mut cube = unit_cube();
+        let adjacency = cube.triangle_adjacency();
+        assert_eq!(
+            cube.triangles.len(),
+            adjacency.len(),
+            "every triangle should have an adjacency entry"
+        );
 
+        for (idx, neighbors) in adjacency.iter().enumerate() {
             assert!(
-                normal.length() > 1.0 - MAX_DIFFERENCE,
-                "Case {idx}, {normal} isn't normalized ( >=1.0 )"
+                neighbors.iter().all(|n| n.is_some()),
+                "triangle {idx} of a closed cube should have three neighbors, got {neighbors:?}"
             );
             assert!(
-                normal.length() < 1.0 + MAX_DIFFERENCE,
-                "Case {idx}, {normal} isn't normalized ( <=1.0 )"
+                !neighbors.contains(&Some(idx)),
+                "triangle {idx} should not neighbor itself"
             );
+
+            // Neighbors should be mutual
+            for neighbor in neighbors.iter().flatten() {
+                assert!(
+                    adjacency[*neighbor].contains(&Some(idx)),
+                    "triangle {neighbor} should list triangle {idx} as a neighbor"
+                );
+            }
         }
+
+        assert!(
+            std::ptr::eq(adjacency, cube.triangle_adjacency()),
+            "adjacency should be cached between calls"
+        );
+
+        // Removing a triangle leaves boundary edges on its neighbors
+        cube.triangles.pop();
+        cube.clear_triangle_adjacency();
+        let open = cube.triangle_adjacency();
+        let boundaries = open.iter().flatten().filter(|n| n.is_none()).count();
+        assert_eq!(3, boundaries, "open cube should have three boundary edges");
+
+        // Topology changes should clear the cache
+        let mut collapsed = unit_cube();
+        collapsed.triangle_adjacency();
+        let edge = collapsed.triangles[0].edges()[0];
+        collapsed.edge_collapse(&edge);
+        collapsed.remove_degenerate();
+        assert_eq!(
+            collapsed.triangles.len(),
+            collapsed.triangle_adjacency().len(),
+            "adjacency should be recomputed after collapsing an edge"
+        );
     }
 
     #[test]
-    fn test_is_point_behind() {
-        struct TestFaceIsPointBehind {
-            tri: Vec<Vec3>,
-            pt: Vec3,
-            behind: bool,
-        }
-        let test_cases: Vec<TestFaceIsPointBehind> = vec![
-            TestFaceIsPointBehind {
-                tri: vec![
-                    Vec3::new(0.0, 1.0, -1.0),
-                    Vec3::new(1.0, -1.0, -1.0),
-                    Vec3::new(-1.0, -1.0, -1.0),
-                ],
-                pt: Vec3::Z,
-                behind: true,
-            },
-            // Face normal is flipped, point should be in front of face
-            TestFaceIsPointBehind {
-                tri: vec![
-                    Vec3::new(-1.0, -1.0, -1.0),
-                    Vec3::new(1.0, -1.0, -1.0),
-                    Vec3::new(0.0, 1.0, -1.0),
-                ],
-                pt: Vec3::Z,
-                behind: false,
-            },
-            // Tested point is behind, but out of face's bounds
-            TestFaceIsPointBehind {
-                tri: vec![
-                    Vec3::new(0.0, 1.0, -1.0),
-                    Vec3::new(1.0, -1.0, -1.0),
-                    Vec3::new(-1.0, -1.0, -1.0),
-                ],
-                pt: Vec3::new(10.0, 10.0, 10.0),
-                behind: false,
-            },
-        ];
-
-        for (idx, case) in test_cases.iter().enumerate() {
-            let tri: Triangle = [0, 1, 2];
-            let was_behind = tri.is_point_behind(&case.tri, case.pt);
+    fn test_flood_fill() {
+        let cube = unit_cube();
+        let coplanar = |a: usize, b: usize| {
+            cube.face_angle(&cube.triangles[a], &cube.triangles[b]) < 5f32.to_radians()
+        };
+
+        let mut panel = cube.flood_fill(0, coplanar);
+        panel.sort();
+        assert_eq!(
+            vec![0, 1],
+            panel,
+            "flood fill should select only the flat cube face, stopping at sharp edges"
+        );
 
-            assert_eq!(
-                was_behind,
-                case.behind,
-                "case {0}: triangle normal {1}",
-                idx,
-                tri.normal(&case.tri)
-            );
-        }
+        assert_eq!(
+            cube.triangles.len(),
+            cube.flood_fill(3, |_, _| true).len(),
+            "unrestricted flood fill should reach every triangle of a closed cube"
+        );
+        assert_eq!(
+            vec![3],
+            cube.flood_fill(3, |_, _| false),
+            "flood fill should always include the seed"
+        );
+        assert!(
+            cube.flood_fill(cube.triangles.len(), |_, _| true)
+                .is_empty(),
+            "out of range seed should select nothing"
+        );
     }
 
     #[test]
-    fn test_area() {
+    fn test_make_manifold() {
+        // Three triangles sharing edge 0-1 like pages of a book,
+        // plus a bowtie pinched at vertex 5 with a fourth page
         let positions: Vec<Vec3> = vec![
-            Vec3::new(0.0, 0.0, 0.0),  // 0
-            Vec3::new(1.0, 0.0, 0.0),  // 1
-            Vec3::new(0.0, 1.0, 0.0),  // 2
-            Vec3::new(-1.0, 0.0, 0.0), // 3
-            Vec3::new(0.0, -1.0, 0.0), // 4
-            Vec3::new(2.0, 0.0, 0.0),  // 5
-            Vec3::new(0.0, 2.0, 0.0),  // 6
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+            vec3(1.0, 0.5, 0.0),
+            vec3(-1.0, 0.5, 0.0),
+            vec3(0.0, 0.5, 1.0),
+            vec3(0.0, 0.5, -1.0),
+            vec3(1.0, 0.5, -2.0),
+            vec3(-1.0, 0.5, -2.0),
         ];
-        let triangle_a: Triangle = [0, 2, 1];
-        let triangle_b: Triangle = [0, 4, 3];
-        let triangle_c: Triangle = [0, 6, 5];
+        let triangles = vec![[0, 1, 2], [1, 0, 3], [0, 1, 4], [0, 1, 5], [5, 6, 7]];
+        let mut mesh = TriangleMesh::new(
+            triangles.clone(),
+            positions.clone(),
+            Some(vec![Vec3::Y; positions.len()]),
+            None,
+        );
 
-        assert_eq!(0.5, triangle_a.area(&positions), "Triangle A");
-        assert_eq!(0.5, triangle_b.area(&positions), "Triangle B");
-        assert_eq!(2.0, triangle_c.area(&positions), "Triangle C");
+        let max_faces = |mesh: &TriangleMesh| -> usize {
+            mesh.edge_face_counts()
+                .into_values()
+                .max()
+                .unwrap_or_default()
+        };
+        assert_eq!(4, max_faces(&mesh), "book edge should start non-manifold");
 
-        let triangles: Vec<Triangle> = vec![triangle_a, triangle_b, triangle_c];
-        let mesh = TriangleMesh::new(triangles, positions, None, None);
+        mesh.make_manifold();
+        mesh.debug_validate();
+        assert!(
+            max_faces(&mesh) <= 2,
+            "no edge should be shared by more than two faces after repair"
+        );
+        assert_eq!(
+            triangles.len(),
+            mesh.triangles.len(),
+            "triangle count should be preserved"
+        );
+        assert_eq!(
+            mesh.positions.len(),
+            mesh.normals.len(),
+            "vertex attributes should be duplicated alongside positions"
+        );
+        for (tri, original) in mesh.triangles.iter().zip(triangles.iter()) {
+            for (index, original_index) in tri.iter().zip(original.iter()) {
+                assert_eq!(
+                    positions[*original_index], mesh.positions[*index],
+                    "split vertices should keep their original position"
+                );
+            }
+        }
 
-        assert_eq!(3.0, mesh.surface_area(), "Mesh Surface Area");
+        // Repairing a manifold mesh should change nothing
+        let mut cube = unit_cube();
+        let original = cube.clone();
+        cube.make_manifold();
+        assert!(
+            cube == original,
+            "manifold mesh should be unchanged by repair"
+        );
     }
 
     #[test]
-    fn test_remove_unused() {
+    fn test_split_by_group() {
+        // A quad split across its diagonal into two groups, and a third triangle sharing the first group
         let positions: Vec<Vec3> = vec![
-            Vec3::new(0.0, 0.0, 0.0), // 0
-            Vec3::new(1.0, 0.0, 0.0), // 1
-            Vec3::new(0.0, 1.0, 0.0), // 2
-            Vec3::new(0.0, 1.0, 0.0), // 3
-            Vec3::new(0.0, 0.0, 1.0), // 4
-            Vec3::new(0.0, 0.0, 1.0), // 5
+            vec3(0.0, 0.0, 0.0),
+            vec3(1.0, 0.0, 0.0),
+            vec3(1.0, 0.0, 1.0),
+            vec3(0.0, 0.0, 1.0),
+            vec3(-1.0, 0.0, 0.0),
         ];
-        let normals: Vec<Vec3> = positions.clone();
-        let tris: Vec<Triangle> = vec![[1, 4, 3], [3, 4, 1]];
-        let indices: Vec<usize> = vec![1, 4, 3, 3, 4, 1];
-
-        let mut mesh =
-            TriangleMesh::new(tris.clone(), positions.clone(), Some(normals.clone()), None);
-        let mut index_mesh =
-            TriangleMesh::from_indices(indices.clone(), positions.clone(), Some(normals.clone()));
+        let triangles = vec![[0, 2, 1], [0, 3, 2], [0, 4, 3]];
+        let mut mesh = TriangleMesh::new(
+            triangles.clone(),
+            positions.clone(),
+            Some(vec![Vec3::Y; positions.len()]),
+            None,
+        );
+        mesh.custom0 = Some((0..positions.len()).map(|i| i as f32).collect());
 
+        let sources = mesh.split_by_group(&[0, 1, 0]);
+        mesh.debug_validate();
         assert_eq!(
-            tris, mesh.triangles,
-            "created TriangleMesh has the same triangles"
+            vec![0, 1, 2, 3, 4, 0, 2, 3],
+            sources,
+            "only vertices shared between groups should be copied, once per group"
         );
         assert_eq!(
-            positions, mesh.positions,
-            "created TriangleMesh has the same vertex positions"
+            vec![[0, 2, 1], [5, 3, 6], [0, 4, 7]],
+            mesh.triangles,
+            "the first group to use a vertex should keep it, other groups use copies"
         );
         assert_eq!(
-            normals, mesh.normals,
-            "created TriangleMesh has the same vertex normals"
+            Some(sources.iter().map(|i| *i as f32).collect()),
+            mesh.custom0,
+            "vertex attributes should be copied from their source"
         );
-        assert_eq!(
-            indices,
-            mesh.indices(),
-            "created TriangleMesh has the same indices"
+        for (index, source) in sources.iter().enumerate() {
+            assert_eq!(
+                positions[*source], mesh.positions[index],
+                "copies should keep their original position"
+            );
+        }
+
+        // A single group should change nothing
+        let mut cube = unit_cube();
+        let original = cube.clone();
+        let sources = cube.split_by_group(&vec![0; cube.triangles.len()]);
+        assert!(cube == original, "single group should be unchanged");
+        assert_eq!((0..cube.positions.len()).collect::<Vec<usize>>(), sources);
+    }
+
+    #[test]
+    fn test_memory_footprint() {
+        let mut mesh = TriangleMesh::new(
+            vec![[0, 1, 2], [0, 2, 3]],
+            vec![Vec3::X, Vec3::Y, Vec3::Z, Vec3::ONE],
+            Some(vec![Vec3::Y; 4]),
+            None,
         );
+        mesh.uv1 = Some(vec![Vec2::ZERO; 4]);
+        mesh.shrink_to_fit();
 
+        // 2 triangles of 3 indices, 4 positions, 4 normals, 4 UVs
+        let expected = 2 * 3 * size_of::<usize>() + 4 * 12 + 4 * 12 + 4 * 8;
         assert_eq!(
-            tris, index_mesh.triangles,
-            "INDEXED TriangleMesh has the same triangles"
+            expected,
+            mesh.memory_footprint(),
+            "footprint should match the size of all buffers"
         );
+        assert!(mesh.uv1.is_some(), "shrinking should keep UVs");
+
+        mesh.bake_raycast_planes();
         assert_eq!(
-            positions, index_mesh.positions,
-            "INDEXED TriangleMesh has the same vertex positions"
+            expected + 2 * 16,
+            mesh.memory_footprint(),
+            "footprint should include baked planes"
         );
-        assert_eq!(
-            normals, index_mesh.normals,
-            "INDEXED TriangleMesh has the same vertex normals"
+    }
+
+    #[test]
+    fn test_normal_segments() {
+        let mut mesh = unit_cube();
+        assert!(
+            mesh.normal_segments(1.0).is_empty(),
+            "mesh without normals should have no normal segments"
         );
+
+        mesh.bake_normals_smooth();
+        let segments = mesh.normal_segments(0.5);
         assert_eq!(
-            indices,
-            mesh.indices(),
-            "created TriangleMesh has the same indices"
+            mesh.positions.len(),
+            segments.len(),
+            "should have one segment per vertex"
         );
+        for (idx, (start, end)) in segments.iter().enumerate() {
+            assert_eq!(
+                mesh.positions[idx], *start,
+                "segment should start at vertex"
+            );
+            assert!(
+                (start.distance(*end) - 0.5).abs() < 1e-6,
+                "segment should be the given length"
+            );
+        }
+    }
 
-        // Remove unused positions
-        mesh.remove_unused();
-        index_mesh.remove_unused();
-
-        // Mesh vertex buffers shrank
-        assert_eq!(3, mesh.positions.len(), "only 3 positions remain");
-        assert_eq!(3, mesh.normals.len(), "only 3 normals remain");
-        assert_eq!(3, index_mesh.positions.len(), "only 3 positions remain");
-        assert_eq!(3, index_mesh.normals.len(), "only 3 normals remain");
-
-        // Mesh should only contain our expected positions
-        let positions_expected: Vec<Vec3> = vec![
-            Vec3::new(1.0, 0.0, 0.0),
-            Vec3::new(0.0, 1.0, 0.0),
-            Vec3::new(0.0, 0.0, 1.0),
-        ];
-        assert_eq!(positions_expected, mesh.positions);
-        assert_eq!(positions_expected, index_mesh.positions);
+    #[test]
+    fn test_manifold_report() {
+        let cube = unit_cube();
+        assert_eq!(
+            ManifoldReport::default(),
+            cube.manifold_report(),
+            "closed cube should have no defects"
+        );
+        assert!(cube.is_manifold(), "closed cube should be manifold");
 
-        // Indices should have been adjusted for resized buffers
-        let indices_expected: Vec<usize> = vec![0, 2, 1, 1, 2, 0];
+        // Open plane with one stray vertex
+        let plane = TriangleMesh::new(
+            vec![[0, 1, 2], [0, 2, 3]],
+            vec![Vec3::ZERO, Vec3::X, vec3(1.0, 0.0, 1.0), Vec3::Z, Vec3::Y],
+            None,
+            None,
+        );
         assert_eq!(
-            indices_expected,
-            mesh.indices(),
-            "should return expected vertex indices"
+            ManifoldReport {
+                boundary_edges: 4,
+                non_manifold_edges: 0,
+                isolated_vertices: 1,
+            },
+            plane.manifold_report(),
+            "open plane should report its outline and the unused vertex"
+        );
+        assert!(!plane.is_manifold(), "open plane should not be manifold");
+
+        // Fan of three triangles sharing edge 0-1
+        let fan = TriangleMesh::new(
+            vec![[0, 1, 2], [1, 0, 3], [0, 1, 4]],
+            vec![
+                Vec3::ZERO,
+                Vec3::Y,
+                vec3(1.0, 0.5, 0.0),
+                vec3(-1.0, 0.5, 0.0),
+                vec3(0.0, 0.5, 1.0),
+            ],
+            None,
+            None,
         );
+        let report = fan.manifold_report();
         assert_eq!(
-            indices_expected,
-            index_mesh.indices(),
-            "should return expected vertex indices"
+            1, report.non_manifold_edges,
+            "shared fan edge should be non-manifold"
         );
+        assert_eq!(6, report.boundary_edges, "outer fan edges should be open");
+        assert!(!fan.is_manifold(), "fan should not be manifold");
     }
 
     #[test]
-    fn test_planar_decimation() {
-        let positions: Vec<Vec3> = vec![
-            vec3(1.0, 0.0, 0.0),
-            vec3(-1.0, 0.0, 0.0),
-            vec3(0.0, 0.0, 1.0),
-            vec3(0.0, 0.0, -1.0),
-        ];
-        let triangles: Vec<Triangle> = vec![[0, 1, 2], [0, 3, 1]];
-        let mut mesh = TriangleMesh::new(triangles.clone(), positions.clone(), None, None);
+    fn test_edges_with_faces() {
+        let mesh = unit_cube();
+        let edges: Vec<(Edge, usize, Option<usize>)> = mesh.edges_with_faces().collect();
 
+        // 12 cube edges, plus a diagonal across each of the 6 faces
+        assert_eq!(18, edges.len(), "should yield each undirected edge once");
         assert_eq!(
-            1.0,
-            triangles[0].orientation(&positions).signum(),
-            "first triangle should be facing up"
+            edges,
+            mesh.edges_with_faces().collect::<Vec<_>>(),
+            "edges should be yielded deterministically"
         );
-        assert_eq!(
+
+        let mut previous: Option<Edge> = None;
+        for (edge, left, right) in edges.iter() {
+            let key = [edge[0].min(edge[1]), edge[0].max(edge[1])];
+            if let Some(previous) = previous {
+                assert!(
+                    previous < key,
+                    "edges should be sorted by their vertex indices"
+                );
+            }
+            previous = Some(key);
+
+            assert!(
+                mesh.triangles[*left].edges().contains(edge),
+                "left face {left} should contain edge {edge:?} in its winding"
+            );
+            let right = right.expect("closed cube should have a right face on every edge");
+            assert!(left < &right, "left face should be the lowest-indexed face");
+            assert!(
+                mesh.triangles[right].edges().contains(&edge.flip()),
+                "right face {right} should contain the flipped edge {edge:?}"
+            );
+        }
+
+        // Should agree with the edge map
+        let map = mesh.edge_map();
+        for (edge, left, right) in edges {
+            let faces = map.get(&edge).expect("edge should be in the edge map");
+            assert_eq!(
+                (left, right),
+                (faces.0, faces.1.map(|face| face.get())),
+                "faces of edge {edge:?} should match the edge map"
+            );
+        }
+    }
+
+    #[test]
+    fn test_convex_decompose() {
+        // Extruded L-shape, made of a 2x1 and a 1x2 box overlapping at the corner
+        let outline = [
+            vec2(0.0, 0.0),
+            vec2(2.0, 0.0),
+            vec2(2.0, 1.0),
+            vec2(1.0, 1.0),
+            vec2(1.0, 2.0),
+            vec2(0.0, 2.0),
+        ];
+        let positions: Vec<Vec3> = [0.0, 1.0]
+            .into_iter()
+            .flat_map(|z| outline.map(|p| vec3(p.x, p.y, z)))
+            .collect();
+        let mut triangles: Vec<Triangle> = vec![];
+        for i in 1..5 {
+            triangles.push([6, i + 6, i + 7]); // Top cap
+            triangles.push([0, i + 1, i]); // Bottom cap
+        }
+        for i in 0..6 {
+            let j = (i + 1) % 6;
+            triangles.push([i, j, j + 6]);
+            triangles.push([i, j + 6, i + 6]);
+        }
+        let mesh = TriangleMesh::new(triangles, positions, None, None);
+        assert!(
+            mesh.signed_volume() > 0.0,
+            "L-shape should be wound outward"
+        );
+
+        let triangle_soup =
+            |mesh: &TriangleMesh| -> Vec<[Vec3; 3]> { mesh.iter_triangle_positions().collect() };
+        assert_in_delta(
             1.0,
-            triangles[1].orientation(&positions).signum(),
-            "second triangle should be facing up"
+            TriangleMesh::piece_concavity(&triangle_soup(&mesh)),
+            1e-5,
+            "L-shape should be concave by the length of its arms".to_string(),
         );
 
-        assert_eq!(
-            0.0,
-            mesh.face_angle(&triangles[0], &triangles[1]),
-            "faces sharing same plane should have an angle of zero"
+        let single = mesh.convex_decompose(1, 0.01);
+        assert_eq!(1, single.len(), "should not exceed the maximum piece count");
+
+        let pieces = mesh.convex_decompose(8, 0.01);
+        assert!(
+            pieces.len() >= 2,
+            "L-shape should be split into at least two pieces, got {}",
+            pieces.len()
         );
+        for (idx, piece) in pieces.iter().enumerate() {
+            assert!(
+                TriangleMesh::piece_concavity(&triangle_soup(piece)) <= 0.01,
+                "piece {idx} should be convex"
+            );
+        }
 
-        let edges = mesh.edge_map();
-        assert_eq!(5, edges.len());
+        // Pieces should cover the whole surface of the mesh
+        assert_in_delta(
+            mesh.surface_area(),
+            pieces.iter().map(|piece| piece.surface_area()).sum(),
+            1e-4,
+            "pieces should cover the surface of the mesh".to_string(),
+        );
 
-        mesh.decimate_planar(0.1, 10, 0);
-        assert_eq!(0, mesh.triangles.len());
+        // Pieces should cover the volume of the mesh, without any piece covering the whole mesh
+        let bounds: Vec<BoundingBox> = pieces.iter().map(|piece| piece.bounding_box()).collect();
+        for bound in bounds.iter() {
+            assert!(
+                bound.volume() < mesh.bounding_box().volume() - 0.5,
+                "each piece should only cover part of the mesh"
+            );
+        }
+        for x in 0..8 {
+            for y in 0..8 {
+                let point = vec3(x as f32 + 0.5, y as f32 + 0.5, 2.0) * 0.25;
+                if point.x > 1.0 && point.y > 1.0 {
+                    continue; // Outside of L-shape
+                }
+                assert!(
+                    bounds.iter().any(|bound| {
+                        point.cmpge(bound.minimum - 1e-5).all()
+                            && point.cmple(bound.maximum + 1e-5).all()
+                    }),
+                    "point {point} of the L-shape should be covered by a piece"
+                );
+            }
+        }
     }
 
     #[test]
-    fn test_join() {
-        let positions1: Vec<Vec3> = vec![Vec3::X, Vec3::Y, Vec3::Z];
-        let positions2: Vec<Vec3> = vec![Vec3::NEG_X, Vec3::NEG_Y, Vec3::NEG_Z];
-
-        let triangles = vec![[0, 1, 2]];
-        let mut mesh1 = TriangleMesh::new(triangles.clone(), positions1.clone(), None, None);
-        let mesh2 = TriangleMesh::new(triangles.clone(), positions2.clone(), None, None);
+    fn test_set_indices() {
+        let mut mesh = unit_cube();
+        mesh.bake_raycast_planes();
+        let original = mesh.triangles.clone();
 
-        assert_eq!(
-            1,
-            mesh1.triangles.len(),
-            "mesh 1 should only have one triangle"
+        assert!(
+            !mesh.set_indices(&[0, 1, 2, 3]),
+            "index count not divisible by 3 should be rejected"
+        );
+        assert!(
+            !mesh.set_indices(&[0, 1, 8]),
+            "out of range index should be rejected"
         );
         assert_eq!(
-            1,
-            mesh2.triangles.len(),
-            "mesh 2 should only have one triangle"
+            original, mesh.triangles,
+            "rejected indices should leave triangles unchanged"
+        );
+        assert!(
+            !mesh.planes.is_empty(),
+            "rejected indices should leave planes baked"
         );
-        assert_eq!(3, mesh1.positions.len(), "mesh 1 should have 3 vertices");
-        assert_eq!(3, mesh2.positions.len(), "mesh 2 should have 3 vertices");
 
-        mesh1.join(&mesh2);
-        assert_eq!(
-            6,
-            mesh1.positions.len(),
-            "joined mesh should have 6 vertices"
+        let indices = [0, 1, 2, 2, 1, 3];
+        assert!(
+            mesh.set_indices(&indices),
+            "valid indices should be accepted"
         );
         assert_eq!(
-            2,
-            mesh1.triangles.len(),
-            "joined mesh should have 2 triangles"
+            vec![[0, 1, 2], [2, 1, 3]],
+            mesh.triangles,
+            "triangles should match indices"
         );
-
         assert_eq!(
-            vec![
-                Vec3::X,
-                Vec3::Y,
-                Vec3::Z,
-                Vec3::NEG_X,
-                Vec3::NEG_Y,
-                Vec3::NEG_Z
-            ],
-            mesh1.positions
+            indices.to_vec(),
+            mesh.indices(),
+            "indices should round-trip"
         );
-        assert_eq!(vec![[0, 1, 2], [3, 4, 5]], mesh1.triangles);
+        assert!(mesh.planes.is_empty(), "stale planes should be cleared");
+        assert_eq!(8, mesh.positions.len(), "vertices should be kept");
     }
 
     #[test]
-    fn test_merge_by_distance() {
-        let positions: Vec<Vec3> = vec![
-            vec3(1.0, 0.0, -1.0),
-            vec3(-1.0, 0.0, -1.0),
-            vec3(0.0, 0.0, 1.0),
-            vec3(1.0, 1e-6, -1.0),
-            vec3(-1.0, 1e-6, -1.0),
-            vec3(0.0, 0.0, -1.0),
-        ];
-        let triangles = vec![[0, 1, 2], [3, 4, 5]];
-        let mut mesh = TriangleMesh::new(triangles.clone(), positions.clone(), None, None);
-
-        mesh.merge_by_distance(1e-5);
-
-        assert_eq!(2, mesh.triangles.len()); // Mesh still retains both triangles
-        assert_eq!(
-            vec![[0, 1, 2], [0, 1, 5]],
-            mesh.triangles,
-            "mesh uses only necessary points"
-        );
-        assert_eq!(6, mesh.positions.len()); // Mesh retained vertices but is not using them
-
-        mesh.remove_unused();
-        assert_eq!(4, mesh.positions.len(), "unused vertices should be removed");
-
-        let mut mesh = TriangleMesh::new(triangles.clone(), positions.clone(), None, None);
-        mesh.optimize(1e-5);
+    fn test_iter_triangle_positions() {
+        let mesh = unit_cube();
+        let triangles: Vec<[Vec3; 3]> = mesh.iter_triangle_positions().collect();
         assert_eq!(
-            vec![[0, 1, 2], [0, 1, 3]],
-            mesh.triangles,
-            "optimize only uses necessary points"
+            mesh.triangles.len(),
+            triangles.len(),
+            "should yield one item per triangle"
         );
-        assert_eq!(4, mesh.positions.len(), "optimize should do all cleanup");
+        for (tri, positions) in mesh.triangles.iter().zip(triangles.iter()) {
+            for corner in 0..3 {
+                assert_eq!(
+                    mesh.positions[tri[corner]], positions[corner],
+                    "triangle positions should match the indexed vertices"
+                );
+            }
+        }
     }
 
-    // TODO: edge map test using a manifold cube
-
     #[test]
-    fn test_raycast_backface_triangles() {
-        let positions: Vec<Vec3> = vec![
-            vec3(1.0, 0.0, -1.0),
-            vec3(-1.0, 0.0, -1.0),
-            vec3(0.0, 0.0, 1.0),
-        ];
-        let triangles: Vec<Triangle> = vec![[0, 1, 2]];
-        let mesh = TriangleMesh::new(triangles.clone(), positions.clone(), None, None);
-
-        let result = mesh
-            .raycast(RaycastParameters::new(
-                Vec3::Y,
-                Vec3::NEG_Y,
-                f32::INFINITY,
-                false,
-            ))
-            .expect("raycast should hit directly");
+    fn test_mean_curvature() {
+        let mut mesh = icosphere(3);
+        for position in mesh.positions.iter_mut() {
+            *position *= 2.0;
+        }
+        // Icosphere faces are wound inward, flip them to face outward
+        for tri in mesh.triangles.iter_mut() {
+            tri.swap(1, 2);
+        }
+        mesh.bake_normals_smooth();
 
+        let curvature = mesh.mean_curvature();
         assert_eq!(
-            Vec3::ZERO,
-            result.point,
-            "ray should should intersect at origin"
-        );
-        assert_eq!(result.normal, Vec3::Y, "normal should be facing the ray");
-        assert_eq!(result.depth, 1.0, "depth should be 1");
-        assert_eq!(
-            0,
-            result
-                .face_index
-                .expect("face index should be set for trimesh")
+            mesh.positions.len(),
+            curvature.len(),
+            "should have one curvature value per vertex"
         );
+        for (idx, value) in curvature.iter().enumerate() {
+            assert_in_delta(
+                0.5,
+                *value,
+                0.05,
+                format!("vertex {idx} on a sphere of radius 2 should have a curvature of 1/2"),
+            );
+        }
 
+        // Viewed from the inside, the sphere is concave
+        for normal in mesh.normals.iter_mut() {
+            *normal = -*normal;
+        }
         assert!(
-            mesh.raycast(RaycastParameters::new(
-                Vec3::NEG_Y,
-                Vec3::Y,
-                f32::INFINITY,
-                false
-            ))
-            .is_none(),
-            "raycast should miss backface"
+            mesh.mean_curvature().iter().all(|value| *value < 0.0),
+            "inverted sphere should be concave everywhere"
         );
+    }
+
+    #[test]
+    fn test_recenter() {
+        let mut mesh = unit_cube();
+        mesh.translate(vec3(1000.0, -250.0, 30.5));
+        mesh.bake_raycast_planes();
+        let original = mesh.positions.clone();
+
+        let offset = mesh.recenter();
         assert!(
-            mesh.raycast(RaycastParameters::new(
-                Vec3::NEG_Y,
-                Vec3::Y,
-                f32::INFINITY,
-                true
-            ))
-            .is_some(),
-            "raycast should hit backface"
+            mesh.bounding_box().center().length() < 1e-4,
+            "recentered mesh should be centered on the origin, got {}",
+            mesh.bounding_box().center()
         );
-
         assert!(
-            mesh.raycast(RaycastParameters::new(
-                Vec3::new(5.0, 5.0, 5.0),
-                Vec3::NEG_Y,
-                f32::INFINITY,
-                true
-            ))
-            .is_none(),
-            "raycast should miss triangle during barycentric projection"
+            (offset - vec3(1000.0, -250.0, 30.5)).length() < 1e-4,
+            "offset should be the original center, got {offset}"
         );
+        for (position, original) in mesh.positions.iter().zip(original.iter()) {
+            assert!(
+                (*position + offset - *original).length() < 1e-4,
+                "offset should restore original position {original}, got {}",
+                *position + offset
+            );
+        }
+        for (plane, tri) in mesh.planes.iter().zip(mesh.triangles.iter()) {
+            assert_eq!(
+                tri.plane(&mesh.positions),
+                *plane,
+                "baked planes should follow the mesh"
+            );
+        }
     }
 
     #[test]
-    fn test_raycast_offset() {
-        let positions: Vec<Vec3> = vec![
-            vec3(1.0, 1.0, -1.0),
-            vec3(-1.0, 1.0, -1.0),
-            vec3(0.0, 1.0, 1.0),
-        ];
-        let triangles: Vec<Triangle> = vec![[0, 1, 2]];
-        let mesh = TriangleMesh::new(triangles.clone(), positions.clone(), None, None);
+    fn test_feature_edges() {
+        let cube = unit_cube();
 
-        let result = mesh
-            .raycast(RaycastParameters::new(
-                Vec3::new(0.1, 2.0, -0.5),
-                Vec3::NEG_Y,
-                f32::INFINITY,
-                false,
-            ))
-            .expect("raycast should hit directly");
+        let edges = cube.feature_edges(45f32.to_radians());
+        assert_eq!(12, edges.len(), "all cube edges should be feature edges");
+        for edge in edges.iter() {
+            assert!(
+                (edge.length(&cube.positions) - 1.0).abs() < 1e-5,
+                "feature edge {edge:?} should not be a face diagonal"
+            );
+        }
 
-        assert_eq!(
-            Vec3::new(0.1, 1.0, -0.5),
-            result.point,
-            "ray should should intersect at expected point"
+        // UV sphere, with a single vertex at each pole
+        let (stacks, slices) = (16, 32);
+        let mut sphere_positions: Vec<Vec3> = vec![Vec3::Y, Vec3::NEG_Y];
+        for stack in 1..stacks {
+            let phi = std::f32::consts::PI * stack as f32 / stacks as f32;
+            for slice in 0..slices {
+                let theta = std::f32::consts::TAU * slice as f32 / slices as f32;
+                sphere_positions.push(vec3(
+                    phi.sin() * theta.cos(),
+                    phi.cos(),
+                    phi.sin() * theta.sin(),
+                ));
+            }
+        }
+        let ring = |stack: usize, slice: usize| 2 + (stack - 1) * slices + slice % slices;
+        let mut sphere_faces: Vec<Triangle> = vec![];
+        for slice in 0..slices {
+            sphere_faces.push([0, ring(1, slice), ring(1, slice + 1)]);
+            sphere_faces.push([1, ring(stacks - 1, slice), ring(stacks - 1, slice + 1)]);
+            for stack in 1..(stacks - 1) {
+                let (a, b) = (ring(stack, slice), ring(stack, slice + 1));
+                let (c, d) = (ring(stack + 1, slice), ring(stack + 1, slice + 1));
+                sphere_faces.push([a, b, d]);
+                sphere_faces.push([a, d, c]);
+            }
+        }
+        let sphere = TriangleMesh::new(
+            outward_triangles(&sphere_positions, &sphere_faces),
+            sphere_positions,
+            None,
+            None,
         );
-        assert_eq!(result.normal, Vec3::Y, "normal should be facing the ray");
-        assert_eq!(result.depth, 1.0, "depth should be 1");
+
         assert_eq!(
             0,
-            result
-                .face_index
-                .expect("face index should be set for trimesh")
+            sphere.feature_edges(30f32.to_radians()).len(),
+            "smooth sphere should have no feature edges"
         );
-
-        // raycast that should completely miss
-        let result = mesh.raycast(RaycastParameters::new(
-            Vec3::new(0.1, 10.0, -0.5),
-            Vec3::Y,
-            f32::INFINITY,
-            false,
-        ));
-
-        assert_eq!(None, result, "raycast should have missed");
     }
 
     #[test]
-    fn test_raycast_layered() {
-        // Should return nearest face index
-        let positions_layered: Vec<Vec3> = vec![
-            vec3(1.0, 0.0, -1.0),
-            vec3(-1.0, 0.0, -1.0),
+    fn test_raycast_interpolate_normals() {
+        let positions: Vec<Vec3> = vec![
+            vec3(0.0, 0.0, 0.0),
             vec3(0.0, 0.0, 1.0),
-            vec3(1.0, 1.0, -1.0),
-            vec3(-1.0, 1.0, -1.0),
-            vec3(0.0, 1.0, 1.0),
+            vec3(1.0, 0.0, 0.0),
         ];
-        let triangles_layered: Vec<Triangle> = vec![[0, 1, 2], [3, 4, 5]];
-        let mesh_layered = TriangleMesh::new(
-            triangles_layered.clone(),
-            positions_layered.clone(),
-            None,
+        let normals: Vec<Vec3> = vec![
+            vec3(-1.0, 1.0, 0.0).normalize(),
+            vec3(0.0, 1.0, 1.0).normalize(),
+            vec3(1.0, 1.0, 0.0).normalize(),
+        ];
+        let mut mesh = TriangleMesh::new(
+            vec![[0, 1, 2]],
+            positions.clone(),
+            Some(normals.clone()),
             None,
         );
+        let params = RaycastParameters::builder(vec3(0.25, 1.0, 0.5), Vec3::NEG_Y);
 
-        let result = mesh_layered
-            .raycast(RaycastParameters::new(
-                Vec3::Y * 3.0,
-                Vec3::NEG_Y,
-                f32::INFINITY,
-                false,
-            ))
-            .expect("raycast should hit directly");
+        let flat = mesh
+            .raycast(params.build())
+            .expect("raycast should hit triangle");
+        assert_eq!(Vec3::Y, flat.normal, "normal should be flat by default");
+        assert_eq!(
+            Some(vec3(0.25, 0.5, 0.25)),
+            flat.barycentric,
+            "barycentric coordinate should be ordered by vertex"
+        );
 
-        assert_eq!(2.0, result.depth, "raycast should be 2 units from surface");
-        assert_eq!(1, result.face_index.expect("face_index should exist"));
+        let smooth = mesh
+            .raycast(params.interpolate_normals(true).build())
+            .expect("raycast should hit triangle");
+        let expected = (normals[0] * 0.25 + normals[1] * 0.5 + normals[2] * 0.25).normalize();
+        assert!(
+            smooth.normal.distance(expected) < 1e-6,
+            "normal should be interpolated: expected {expected}, got {0}",
+            smooth.normal
+        );
+        assert!(
+            smooth.normal.distance(Vec3::Y) > 0.1,
+            "interpolated normal should differ from flat normal"
+        );
+
+        // Without vertex normals, fall back to the face normal
+        mesh.normals.clear();
+        let fallback = mesh
+            .raycast(params.interpolate_normals(true).build())
+            .expect("raycast should hit triangle");
         assert_eq!(
             Vec3::Y,
-            result.point,
-            "raycast should intersect at (0, 1, 0)"
+            fallback.normal,
+            "normal should fall back to face normal"
+        );
+    }
+
+    #[test]
+    fn test_closest_point() {
+        let positions: Vec<Vec3> = vec![
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 0.0, 1.0),
+            vec3(1.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+        ];
+        let mesh = TriangleMesh::new(vec![[0, 1, 2], [0, 3, 1]], positions, None, None);
+
+        let cases = [
+            // Above the face
+            (vec3(0.6, 0.5, 0.2), vec3(0.6, 0.0, 0.2), Some(0)),
+            // Beyond a vertex
+            (vec3(2.0, -1.0, -1.0), vec3(1.0, 0.0, 0.0), Some(0)),
+            // Beside the hypotenuse
+            (vec3(1.0, -0.5, 1.0), vec3(0.5, 0.0, 0.5), Some(0)),
+            // Beside the second face
+            (vec3(-1.0, 0.5, 0.5), vec3(0.0, 0.5, 0.5), Some(1)),
+        ];
+        for (point, expected, face_index) in cases {
+            let result = mesh.closest_point(point);
+            assert!(
+                result.point.distance(expected) < 1e-6,
+                "closest point to {point} should be {expected}, got {0}",
+                result.point
+            );
+            assert_eq!(face_index, result.face_index, "face index for {point}");
+            assert_eq!(
+                point.distance(result.point),
+                result.distance,
+                "distance for {point}"
+            );
+        }
+
+        let empty = TriangleMesh::default().closest_point(Vec3::ZERO);
+        assert_eq!(None, empty.face_index, "empty mesh should have no face");
+
+        // Batched queries should match individual queries
+        let mut state: u32 = 4321;
+        let mut random = || {
+            state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+            (state >> 8) as f32 / (1 << 24) as f32 * 4.0 - 2.0
+        };
+        let points: Vec<Vec3> = (0..200)
+            .map(|_| vec3(random(), random(), random()))
+            .collect();
+        let batched = mesh.closest_points(&points);
+        assert_eq!(
+            points.len(),
+            batched.len(),
+            "batched results should match length"
         );
+        for (point, result) in points.iter().zip(batched) {
+            assert_eq!(
+                mesh.closest_point(*point),
+                result,
+                "batched result should match individual query for {point}"
+            );
+        }
     }
 }
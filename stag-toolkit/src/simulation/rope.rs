@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
-use glam::{FloatExt, Vec3, Vec4, Vec4Swizzles, vec3};
+use glam::{FloatExt, Mat4, Vec3, Vec4, Vec4Swizzles, vec3};
+use noise::{NoiseFn, Perlin};
 
 /// Returns a tuple of values A and B, constrainted within the given distance from each other.
 /// Acts as a double-sided Jakobsen constraint, with added strain.
@@ -18,6 +19,209 @@ pub fn jakobsen_constraint_single(a: Vec3, b: Vec3, ideal_distance: f32) -> Vec3
     a + (b - a).normalize() * ideal_distance
 }
 
+/// Resolves a rope point colliding with a surface, returning the new local position of the point.
+/// The point's motion since the previous step slides along the surface plane, offset from the surface by the given margin.
+/// `transform` converts from rope local space into the space of the hit position and normal.
+pub fn resolve_collision(
+    previous: Vec3,
+    simulated: Vec3,
+    hit_position: Vec3,
+    hit_normal: Vec3,
+    offset: f32,
+    transform: Mat4,
+) -> Vec3 {
+    let motion = simulated - previous;
+
+    // Get our actual position, and slide it along the surface plane of our hit normal
+    let position =
+        hit_position + (motion - hit_normal * hit_normal.dot(motion)) + hit_normal * offset;
+
+    // Deproject the point back into local space
+    transform.inverse().project_point3(position)
+}
+
+/// Maximum number of passes over all analytic colliders when pushing a single rope point out of them.
+/// Overlapping colliders can push a point back into one another, so they are resolved iteratively.
+pub const ROPE_COLLIDER_PASSES: usize = 4;
+
+/// Analytic shape of a [RopeCollider], in the collider's local space.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RopeColliderShape {
+    /// A sphere centered on the origin.
+    Sphere {
+        /// Radius of the sphere.
+        radius: f32,
+    },
+    /// A capsule centered on the origin, aligned along the Y axis.
+    Capsule {
+        /// Radius of the capsule.
+        radius: f32,
+        /// Total height of the capsule, including both end caps.
+        height: f32,
+    },
+}
+
+/// An analytic shape that rope points are pushed out of during constraint passes.
+/// Far more stable than raycast-based collision when draping ropes over shapes.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RopeCollider {
+    /// Shape of the collider.
+    pub shape: RopeColliderShape,
+    /// Transform of the collider, in rope local space. Should be rigid, as scaling is not accounted for.
+    transform: Mat4,
+    /// Cached inverse of the collider transform.
+    inverse: Mat4,
+}
+
+impl RopeCollider {
+    /// Creates a new collider with the given shape and transform, in rope local space.
+    /// The transform should be rigid (rotation and translation only).
+    pub fn new(shape: RopeColliderShape, transform: Mat4) -> Self {
+        Self {
+            shape,
+            transform,
+            inverse: transform.inverse(),
+        }
+    }
+
+    /// Returns the transform of the collider, in rope local space.
+    pub fn transform(&self) -> Mat4 {
+        self.transform
+    }
+
+    /// Returns the given point pushed out to the surface of the collider along the shape gradient,
+    /// with the given margin added to the surface.
+    /// Returns [None] if the point is already outside the collider.
+    pub fn push_out(&self, point: Vec3, margin: f32) -> Option<Vec3> {
+        let local = self.inverse.transform_point3(point);
+
+        // Find the nearest point on the shape's core, and the direction pointing away from it
+        let (core, radius, fallback) = match self.shape {
+            RopeColliderShape::Sphere { radius } => (Vec3::ZERO, radius, Vec3::Y),
+            RopeColliderShape::Capsule { radius, height } => {
+                let half_height = (height * 0.5 - radius).max(0.0);
+                (
+                    Vec3::Y * local.y.clamp(-half_height, half_height),
+                    radius,
+                    Vec3::X,
+                )
+            }
+        };
+
+        let (direction, distance) = (local - core).normalize_and_length();
+        let surface = radius + margin;
+        if distance >= surface {
+            return None;
+        }
+
+        // Points on the core itself have no gradient, so push them out in an arbitrary direction
+        let direction = if distance > 0.0 && direction.is_finite() {
+            direction
+        } else {
+            fallback
+        };
+        Some(self.transform.transform_point3(core + direction * surface))
+    }
+
+    /// Pushes the given point out of all given colliders, repeating passes until no collider moves the point,
+    /// or [ROPE_COLLIDER_PASSES] is reached.
+    /// Returns true if the point was moved.
+    pub fn resolve_all(colliders: &[Self], point: &mut Vec3, margin: f32) -> bool {
+        let mut moved = false;
+        for _ in 0..ROPE_COLLIDER_PASSES {
+            let mut pass_moved = false;
+            for collider in colliders.iter() {
+                if let Some(pushed) = collider.push_out(*point, margin) {
+                    *point = pushed;
+                    pass_moved = true;
+                }
+            }
+
+            if !pass_moved {
+                break;
+            }
+            moved = true;
+        }
+        moved
+    }
+}
+
+/// Returns the positions of the given point bindings, ordered by rope point index, transformed by the given matrix.
+pub fn bindings_transformed(bindings: &HashMap<usize, Vec3>, transform: Mat4) -> Vec<Vec3> {
+    let mut indices: Vec<&usize> = bindings.keys().collect();
+    indices.sort_unstable();
+    indices
+        .into_iter()
+        .map(|idx| transform.transform_point3(bindings[idx]))
+        .collect()
+}
+
+/// Exponentially smooths a force towards the target force over the given time step.
+/// `smoothing` is the time constant in seconds, with zero or less disabling smoothing.
+/// Repeatedly applying a constant target converges to the target, so steady-state forces are unaffected.
+pub fn smooth_force(previous: Vec3, target: Vec3, smoothing: f32, delta: f32) -> Vec3 {
+    if smoothing <= 0.0 {
+        return target;
+    }
+    previous.lerp(target, 1.0 - (-delta / smoothing).exp())
+}
+
+/// Default downward acceleration applied to ropes, in meters per second squared.
+pub const ROPE_GRAVITY: f32 = 9.81;
+
+/// Maximum number of fixed steps a [FixedTimestep] takes in a single advance.
+/// Any additional accumulated time is discarded, so long frames do not snowball into longer ones.
+pub const FIXED_TIMESTEP_MAX_STEPS: u32 = 8;
+
+/// Accumulates variable frame time, and splits it into fixed-size steps for reproducible simulation.
+/// Leftover time is carried over to the next advance.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct FixedTimestep {
+    /// Size of each fixed step, in seconds. Zero or less disables fixed stepping.
+    pub step: f64,
+    /// Accumulated time that has not been stepped yet.
+    accumulated: f64,
+}
+
+impl FixedTimestep {
+    /// Creates a new accumulator with the given step size, in seconds.
+    pub fn new(step: f64) -> Self {
+        Self {
+            step,
+            accumulated: 0.0,
+        }
+    }
+
+    /// Returns true if fixed stepping is enabled.
+    pub fn is_fixed(&self) -> bool {
+        self.step > 0.0
+    }
+
+    /// Returns the accumulated time that has not been stepped yet.
+    pub fn remainder(&self) -> f64 {
+        self.accumulated
+    }
+
+    /// Accumulates the given frame time, and returns the number of fixed steps to take.
+    /// At most [FIXED_TIMESTEP_MAX_STEPS] are returned, discarding any time beyond them.
+    /// Always returns zero if fixed stepping is disabled.
+    pub fn advance(&mut self, delta: f64) -> u32 {
+        if !self.is_fixed() {
+            return 0;
+        }
+
+        self.accumulated += delta.max(0.0);
+        let steps = (self.accumulated / self.step).floor();
+        if steps >= FIXED_TIMESTEP_MAX_STEPS as f64 {
+            self.accumulated = 0.0;
+            return FIXED_TIMESTEP_MAX_STEPS;
+        }
+
+        self.accumulated -= steps * self.step;
+        steps as u32
+    }
+}
+
 /// Describes the current simulation state of a rope point.
 #[derive(Clone, Copy)]
 pub struct RopeTensionData {
@@ -48,6 +252,18 @@ impl Default for RopeTensionData {
     }
 }
 
+/// Overall tension state of a rope.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(i32)]
+pub enum RopeState {
+    /// No section of the rope is near its ideal length.
+    Slack = 0,
+    /// At least one section of the rope is pulled to its ideal length.
+    Taut = 1,
+    /// At least one section of the rope is stretched well past its ideal length.
+    Overstretched = 2,
+}
+
 /// Data for managing a simulated rope.
 ///
 /// I use techniques described in [Robert Badea's rope simulation article](https://owlree.blog/posts/simulating-a-rope.html).
@@ -59,10 +275,28 @@ pub struct RopeData {
     pub distance_between_points: f32,
     /// Spring constant of the rope.
     pub spring_constant: f32,
-    /// Constant acceleration applied to the rope.
-    pub acceleration: Vec3,
+    /// Gravitational acceleration applied to the rope.
+    pub gravity: Vec3,
+    /// Constant wind acceleration applied to the rope.
+    pub wind: Vec3,
+    /// Strength of turbulent wind acceleration, sampled from noise at each point.
+    /// Zero disables turbulence.
+    pub wind_turbulence: f32,
+    /// Spatial and temporal frequency of the wind turbulence.
+    pub wind_frequency: f32,
     /// Number of Jakobsen constraint steps to perform.
     pub constraint_iterations: u32,
+    /// Number of sub-ticks each [Self::tick] is split into.
+    /// More substeps keep stiff ropes stable under large time steps, at the cost of performance.
+    pub substeps: u32,
+    /// Stretch ratio at which the rope is considered taut.
+    pub taut_threshold: f32,
+    /// Stretch ratio at which the rope is considered overstretched.
+    pub overstretch_threshold: f32,
+    /// Analytic colliders that rope points are pushed out of during constraint passes.
+    pub colliders: Vec<RopeCollider>,
+    /// Distance rope points are kept from the surface of analytic colliders.
+    pub collider_margin: f32,
 
     /// All current simulated rope positions, with tension.
     pub points: Vec<Vec3>,
@@ -76,6 +310,11 @@ pub struct RopeData {
 
     /// Last computed tension data for each point on the rope.
     tension: Vec<RopeTensionData>,
+
+    /// Noise used for sampling wind turbulence.
+    wind_noise: Perlin,
+    /// Total simulated time, in seconds, for animating wind turbulence.
+    time: f64,
 }
 
 impl RopeData {
@@ -94,16 +333,100 @@ impl RopeData {
             point_count: count,
             distance_between_points: ideal_length / (count as f32),
             spring_constant: 5000.0,
-            acceleration: vec3(0.0, -9.81, 0.0),
+            gravity: vec3(0.0, -ROPE_GRAVITY, 0.0),
+            wind: Vec3::ZERO,
+            wind_turbulence: 0.0,
+            wind_frequency: 0.5,
             constraint_iterations: 50,
+            substeps: 1,
+            taut_threshold: 0.98,
+            overstretch_threshold: 1.05,
+            colliders: vec![],
+            collider_margin: 0.05,
 
             points: points.clone(),
             points_simulated_previous: points,
             pinned: vec![false; count],
             tension: vec![RopeTensionData::default(); count],
+
+            wind_noise: Perlin::new(0),
+            time: 0.0,
         }
     }
 
+    /// Generates a new RopeData struct from an existing polyline, starting at rest.
+    /// The ideal distance between points is the average distance between the given points,
+    /// so the rope keeps the polyline's length.
+    ///
+    /// Returns None if less than two points are given.
+    pub fn from_points(points: Vec<Vec3>) -> Option<Self> {
+        let count = points.len();
+        if count < 2 {
+            return None;
+        }
+
+        let length: f32 = points
+            .windows(2)
+            .map(|pair| pair[0].distance(pair[1]))
+            .sum();
+
+        Some(Self {
+            point_count: count,
+            distance_between_points: length / (count - 1) as f32,
+            points_simulated_previous: points.clone(),
+            points,
+            pinned: vec![false; count],
+            tension: vec![RopeTensionData::default(); count],
+            ..Self::default()
+        })
+    }
+
+    /// Returns all current simulated rope positions.
+    pub fn points(&self) -> &[Vec3] {
+        &self.points
+    }
+
+    /// Returns all current simulated rope positions, for modification.
+    /// Previous positions are left untouched, so moving points imparts velocity on the next step.
+    pub fn points_mut(&mut self) -> &mut [Vec3] {
+        &mut self.points
+    }
+
+    /// Changes the number of points in the rope without resetting the simulation, such as for paying out or reeling in rope.
+    /// The ideal distance between points is kept, so the ideal length of the rope changes with the point count.
+    ///
+    /// When growing, new points are interpolated between the last two points, keeping the end of the rope in place.
+    /// When shrinking, points are dropped from the end of the rope.
+    /// Previous positions are resized alongside, so rope velocity stays continuous.
+    /// The rope always keeps at least two points.
+    pub fn resize(&mut self, new_point_count: usize) {
+        let count = new_point_count.max(2);
+        let current = self.points.len();
+
+        if count > current {
+            let added = count - current;
+            let insert = |points: &mut Vec<Vec3>| {
+                let (a, b) = (points[current - 2], points[current - 1]);
+                let interpolated = (1..=added).map(|i| a.lerp(b, i as f32 / (added + 1) as f32));
+                points.splice(current - 1..current - 1, interpolated);
+            };
+            insert(&mut self.points);
+            insert(&mut self.points_simulated_previous);
+        } else {
+            self.points.truncate(count);
+            self.points_simulated_previous.truncate(count);
+        }
+
+        self.point_count = count;
+        self.pinned.resize(count, false);
+        self.tension.resize(count, RopeTensionData::default());
+    }
+
+    /// Returns an iterator over each segment of the rope, as pairs of start and end positions.
+    pub fn segments(&self) -> impl Iterator<Item = (Vec3, Vec3)> + '_ {
+        self.points.windows(2).map(|pair| (pair[0], pair[1]))
+    }
+
     /// Returns the point index for the given binding location (between 0 and 1).
     /// Assumes passed "param" value is between 0 and 1.
     pub fn bind_index(&self, param: f32) -> usize {
@@ -115,6 +438,56 @@ impl RopeData {
         index as f32 / (self.point_count - 1) as f32
     }
 
+    /// Returns the cumulative distance along the rope at each point, starting from zero.
+    fn arclengths(&self) -> Vec<f32> {
+        let mut total = 0.0;
+        let mut lengths = Vec::with_capacity(self.points.len());
+        lengths.push(0.0);
+        for pair in self.points.windows(2) {
+            total += pair[0].distance(pair[1]);
+            lengths.push(total);
+        }
+        lengths
+    }
+
+    /// Converts an index-based rope factor into the fraction of the actual rope length at that point,
+    /// based on the current point distances. Both are in the range \[0, 1\].
+    pub fn factor_to_arclength(&self, factor: f32) -> f32 {
+        let lengths = self.arclengths();
+        let total = lengths[lengths.len() - 1];
+        if total <= 0.0 {
+            return factor;
+        }
+
+        let position = factor.clamp(0.0, 1.0) * (self.points.len() - 1) as f32;
+        let idx = (position.floor() as usize).min(self.points.len() - 2);
+        lengths[idx].lerp(lengths[idx + 1], position - idx as f32) / total
+    }
+
+    /// Converts a fraction of the actual rope length into an index-based rope factor,
+    /// based on the current point distances. Inverse of [Self::factor_to_arclength].
+    pub fn arclength_to_factor(&self, arclength: f32) -> f32 {
+        let lengths = self.arclengths();
+        let total = lengths[lengths.len() - 1];
+        if total <= 0.0 {
+            return arclength;
+        }
+
+        let target = arclength.clamp(0.0, 1.0) * total;
+        // Find the first section ending at or past the target length
+        let idx = lengths
+            .partition_point(|length| *length < target)
+            .clamp(1, lengths.len() - 1);
+        let section = lengths[idx] - lengths[idx - 1];
+        let t = if section > 0.0 {
+            (target - lengths[idx - 1]) / section
+        } else {
+            0.0
+        };
+
+        ((idx - 1) as f32 + t) / (self.points.len() - 1) as f32
+    }
+
     /// Returns the calculated slack at the given index.
     /// 1 is fully slack, 0 is fully stretched.
     pub fn slack(&self, index: usize) -> f32 {
@@ -124,6 +497,71 @@ impl RopeData {
         // .powi(2)
     }
 
+    /// Returns the approximate horizontal tension, in Newtons, of a rope hanging at rest between two fixed points,
+    /// with the given length and mass per meter, under [ROPE_GRAVITY].
+    /// Models the rope as an ideal, inextensible catenary, for predicting rope setups without running the simulation.
+    ///
+    /// Returns zero for a vertical rope, which has no horizontal tension,
+    /// and infinity if the rope is too short to span the two points.
+    pub fn static_catenary_tension(a: Vec3, b: Vec3, length: f32, mass_per_length: f32) -> f32 {
+        let offset = b - a;
+        let span = (offset.x as f64).hypot(offset.z as f64);
+        let rise = offset.y as f64;
+        let length = length as f64;
+
+        let chord_sq = span * span + rise * rise;
+        if length * length <= chord_sq {
+            return f32::INFINITY;
+        }
+        if span <= f64::EPSILON {
+            return 0.0;
+        }
+
+        // With catenary parameter c and x = span / 2c, the rope length satisfies
+        // sqrt(length^2 - rise^2) = 2c * sinh(x), so solve sinh(x) / x = ratio
+        let ratio = (length * length - rise * rise).sqrt() / span;
+        let sinhc = |x: f64| if x > 0.0 { x.sinh() / x } else { 1.0 };
+
+        let mut upper: f64 = 1.0;
+        while sinhc(upper) < ratio {
+            upper *= 2.0;
+        }
+        let mut lower: f64 = 0.0;
+        for _ in 0..64 {
+            let mid = (lower + upper) * 0.5;
+            if sinhc(mid) < ratio {
+                lower = mid;
+            } else {
+                upper = mid;
+            }
+        }
+
+        let parameter = span / (lower + upper);
+        (parameter * (mass_per_length * ROPE_GRAVITY) as f64) as f32
+    }
+
+    /// Returns the largest ratio of actual distance to ideal distance of any section of rope between binds,
+    /// as of the last tension computation.
+    /// 1 is exactly at the ideal length, and greater than 1 is overstretched.
+    pub fn stretch_ratio(&self) -> f32 {
+        self.tension
+            .iter()
+            .map(|t| t.section_distance / t.max_section_distance)
+            .fold(0.0, f32::max)
+    }
+
+    /// Returns the overall state of the rope, based on the stretch ratio and configured thresholds.
+    pub fn state(&self) -> RopeState {
+        let ratio = self.stretch_ratio();
+        if ratio >= self.overstretch_threshold {
+            RopeState::Overstretched
+        } else if ratio >= self.taut_threshold {
+            RopeState::Taut
+        } else {
+            RopeState::Slack
+        }
+    }
+
     /// Fetches a linearized position based on the bounding binding locations, if possible.
     pub fn fetch_linear_point(
         &self,
@@ -138,28 +576,86 @@ impl RopeData {
         None
     }
 
+    /// Returns the turbulent wind acceleration at the given point, at the current simulation time.
+    /// Returns zero if `wind_turbulence` is zero.
+    pub fn wind_turbulence_at(&self, point: Vec3) -> Vec3 {
+        if self.wind_turbulence == 0.0 {
+            return Vec3::ZERO;
+        }
+
+        let frequency = self.wind_frequency as f64;
+        let sample = |offset: f64| {
+            self.wind_noise.get([
+                point.x as f64 * frequency,
+                point.y as f64 * frequency,
+                point.z as f64 * frequency,
+                self.time * frequency + offset,
+            ]) as f32
+        };
+
+        // Offset each axis in time, so they are uncorrelated
+        vec3(sample(0.0), sample(31.7), sample(63.4)) * self.wind_turbulence
+    }
+
     /// Steps the simulation forward by many X seconds using Verlet integration.
+    /// Gravity and wind are applied to every point.
     /// Does NOT apply constraints.
     pub fn step(&mut self, delta_time: f64) {
-        // let delta_time_squared: f32 = (delta_time * delta_time) as f32;
-        let accel = self.acceleration * ((delta_time * delta_time) as f32);
-        for (idx, point) in self.points.iter_mut().enumerate() {
+        let delta_time_squared = (delta_time * delta_time) as f32;
+        let accel = (self.gravity + self.wind) * delta_time_squared;
+        for idx in 0..self.points.len() {
             // Perform a Verlet integration of the given point
-            let p = *point;
-            *point = (p * 2.0) - self.points_simulated_previous[idx] + accel;
+            let p = self.points[idx];
+            let turbulence = self.wind_turbulence_at(p) * delta_time_squared;
+            self.points[idx] = (p * 2.0) - self.points_simulated_previous[idx] + accel + turbulence;
             self.points_simulated_previous[idx] = p;
         }
+        self.time += delta_time;
+    }
+
+    /// Ticks the simulation forward by X seconds, split evenly into [Self::substeps] sub-ticks.
+    /// Each sub-tick recomputes tension, steps the simulation, then applies constraints against the given bindings.
+    ///
+    /// Verlet integration scales acceleration by the squared sub-tick time,
+    /// so the total motion from gravity and wind over a tick is the same regardless of substep count.
+    pub fn tick(&mut self, delta_time: f64, binding_map: &HashMap<usize, Vec3>) {
+        let substeps = self.substeps.max(1);
+        let substep_delta = delta_time / substeps as f64;
+        for _ in 0..substeps {
+            self.tension(binding_map);
+            self.step(substep_delta);
+            self.constrain(binding_map);
+        }
     }
 
     /// Converts a keyed-by-ID bindings map to a keyed-by-index map of unique bindings.
+    /// Binds that resolve to the same point index have their positions averaged.
     pub fn unique_bind_map(&self, bindings: &HashMap<i64, Vec4>) -> HashMap<usize, Vec3> {
-        let mut unique: HashMap<usize, Vec3> = HashMap::with_capacity(bindings.len());
+        let mut unique: HashMap<usize, (Vec3, usize)> = HashMap::with_capacity(bindings.len());
 
         for b in bindings.values() {
-            unique.insert(self.bind_index(b.w), b.xyz());
+            let entry = unique
+                .entry(self.bind_index(b.w))
+                .or_insert((Vec3::ZERO, 0));
+            entry.0 += b.xyz();
+            entry.1 += 1;
         }
 
         unique
+            .into_iter()
+            .map(|(idx, (sum, count))| (idx, sum / count as f32))
+            .collect()
+    }
+
+    /// Returns a keyed-by-index map of how many binds share each bound point index.
+    pub fn bind_share_counts(&self, bindings: &HashMap<i64, Vec4>) -> HashMap<usize, usize> {
+        let mut counts: HashMap<usize, usize> = HashMap::with_capacity(bindings.len());
+
+        for b in bindings.values() {
+            *counts.entry(self.bind_index(b.w)).or_insert(0) += 1;
+        }
+
+        counts
     }
 
     /// Returns the immediate indices of the binds smaller and greater than the given index, if present.
@@ -281,6 +777,15 @@ impl RopeData {
                 );
             }
 
+            // Push unpinned points out of any analytic colliders
+            if !self.colliders.is_empty() {
+                for (point, pinned) in self.points.iter_mut().zip(self.pinned.iter()) {
+                    if !*pinned {
+                        RopeCollider::resolve_all(&self.colliders, point, self.collider_margin);
+                    }
+                }
+            }
+
             // Enforce binding positions, if any are present
             for (idx, b) in binding_map.iter() {
                 self.points[*idx] = *b;
@@ -288,6 +793,14 @@ impl RopeData {
         }
     }
 
+    /// Returns all current simulated rope positions, transformed by the given matrix.
+    pub fn points_transformed(&self, transform: Mat4) -> Vec<Vec3> {
+        self.points
+            .iter()
+            .map(|pt| transform.transform_point3(*pt))
+            .collect()
+    }
+
     /// Computes the force at the given point.
     pub fn force(&self, index: usize) -> Vec3 {
         let mut left_tension: Vec3 = Vec3::ZERO;
@@ -324,11 +837,124 @@ impl Default for RopeData {
 
 #[cfg(test)]
 mod tests {
-    use glam::Vec3;
+    use std::collections::HashMap;
+
+    use glam::{Mat4, Vec3, Vec4, vec4};
 
-    use crate::{math::delta::assert_in_delta, simulation::rope::jakobsen_constraint};
+    use crate::{
+        math::delta::{assert_in_delta, assert_in_delta_vector},
+        simulation::rope::{
+            bindings_transformed, jakobsen_constraint, resolve_collision, smooth_force,
+        },
+    };
 
-    use super::RopeData;
+    use super::{
+        FIXED_TIMESTEP_MAX_STEPS, FixedTimestep, ROPE_GRAVITY, RopeCollider, RopeColliderShape,
+        RopeData, RopeState,
+    };
+
+    #[test]
+    fn test_static_catenary_tension() {
+        // A catenary with parameter c spanning x has length 2c * sinh(x / 2c), and horizontal tension c * weight
+        let parameter: f32 = 10.0;
+        let span: f32 = 20.0;
+        let length = 2.0 * parameter * (span / (2.0 * parameter)).sinh();
+        let mass_per_length = 0.5;
+        let expected = parameter * mass_per_length * ROPE_GRAVITY;
+
+        let a = Vec3::new(3.0, 2.0, -1.0);
+        let b = a + Vec3::new(span * 0.6, 0.0, span * 0.8);
+        assert_in_delta(
+            expected,
+            RopeData::static_catenary_tension(a, b, length, mass_per_length),
+            expected * 1e-3,
+            "level catenary tension should match".to_string(),
+        );
+
+        // Raising one end keeps the same catenary if the length accounts for the rise
+        let rise: f32 = 5.0;
+        let raised_length = (length * length + rise * rise).sqrt();
+        assert_in_delta(
+            expected,
+            RopeData::static_catenary_tension(
+                a,
+                b + Vec3::Y * rise,
+                raised_length,
+                mass_per_length,
+            ),
+            expected * 1e-3,
+            "uneven catenary tension should match".to_string(),
+        );
+
+        // Longer ropes sag more, with less tension
+        assert!(
+            RopeData::static_catenary_tension(a, b, length * 1.5, mass_per_length) < expected,
+            "slacker rope should have less tension"
+        );
+        assert_eq!(
+            f32::INFINITY,
+            RopeData::static_catenary_tension(a, b, span * 0.9, mass_per_length),
+            "rope too short to span the points should have infinite tension"
+        );
+        assert_eq!(
+            0.0,
+            RopeData::static_catenary_tension(a, a + Vec3::NEG_Y * 3.0, 4.0, mass_per_length),
+            "vertical rope should have no horizontal tension"
+        );
+    }
+
+    #[test]
+    fn test_fixed_timestep() {
+        let step = 1.0 / 60.0;
+        let mut timestep = FixedTimestep::new(step);
+
+        // Irregular frame times, all well under the step cap
+        let frames = [0.007, 0.021, 0.016, 0.033, 0.002, 0.05, 0.0125, 0.019];
+        let mut steps: u32 = 0;
+        for delta in frames {
+            steps += timestep.advance(delta);
+        }
+
+        let total: f64 = frames.iter().sum();
+        assert_eq!(
+            (total / step).floor() as u32,
+            steps,
+            "steps taken should match accumulated time divided by the fixed step"
+        );
+        assert_in_delta(
+            (total - steps as f64 * step) as f32,
+            timestep.remainder() as f32,
+            1e-6,
+            "leftover time should be carried over".to_string(),
+        );
+        assert!(
+            timestep.remainder() < step,
+            "remainder should be less than a single step"
+        );
+
+        // Long frames are capped
+        assert_eq!(
+            FIXED_TIMESTEP_MAX_STEPS,
+            timestep.advance(step * 100.0),
+            "long frames should be capped"
+        );
+        assert_eq!(
+            0.0,
+            timestep.remainder(),
+            "time beyond the cap should be discarded"
+        );
+
+        let mut variable = FixedTimestep::new(0.0);
+        assert!(
+            !variable.is_fixed(),
+            "zero step should disable fixed stepping"
+        );
+        assert_eq!(
+            0,
+            variable.advance(1.0),
+            "variable stepping should never take fixed steps"
+        );
+    }
 
     #[test]
     fn binds_and_factor_conversion() {
@@ -360,6 +986,116 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_rope_substeps() {
+        // Pinned rope, initially stretched to ten times its length, and pulled on by heavy gravity
+        let simulate = |substeps: u32| -> f32 {
+            let mut rope = RopeData::new(5.0, 0.25);
+            rope.substeps = substeps;
+            rope.constraint_iterations = 4;
+            rope.gravity = Vec3::new(0.0, -50.0, 0.0);
+            for point in rope.points.iter_mut() {
+                *point *= 10.0;
+            }
+            rope.points_simulated_previous = rope.points.clone();
+            let bind_map = HashMap::from([(0, Vec3::ZERO)]);
+
+            for _ in 0..30 {
+                rope.tick(1.0 / 30.0, &bind_map);
+            }
+            assert!(
+                rope.points.iter().all(|point| point.is_finite()),
+                "rope with {substeps} substeps should not have NaNs"
+            );
+
+            let ideal = rope.distance_between_points * (rope.point_count - 1) as f32;
+            let length: f32 = rope.segments().map(|(a, b)| a.distance(b)).sum();
+            (length - ideal).abs() / ideal
+        };
+
+        let single = simulate(1);
+        let substepped = simulate(4);
+        assert!(
+            substepped < single,
+            "4 substeps should converge closer to rest length ({substepped}) than 1 substep ({single})"
+        );
+        assert!(
+            substepped < 0.05,
+            "substepped rope should be near its rest length, stretched by {substepped}"
+        );
+    }
+
+    #[test]
+    fn test_rope_resize() {
+        let polyline: Vec<Vec3> = (0..=4).map(|i| Vec3::new(i as f32, 0.0, 0.0)).collect();
+        let mut rope = RopeData::from_points(polyline.clone()).expect("rope should build");
+        // Give the rope a uniform velocity
+        rope.points_simulated_previous = polyline.iter().map(|p| *p - Vec3::Y).collect();
+
+        rope.resize(8);
+        assert_eq!(8, rope.point_count, "point count should grow");
+        assert_eq!(8, rope.points.len(), "points should grow");
+        assert_eq!(
+            8,
+            rope.points_simulated_previous.len(),
+            "previous points should grow"
+        );
+        assert_eq!(
+            &polyline[..4],
+            &rope.points()[..4],
+            "existing points should be kept"
+        );
+        assert_eq!(
+            polyline[4],
+            rope.points()[7],
+            "rope end should stay in place"
+        );
+        for idx in 4..7 {
+            let point = rope.points()[idx];
+            assert!(
+                point.x > 3.0 && point.x < 4.0 && point.y == 0.0,
+                "new point {point} should be between the last two points"
+            );
+        }
+        for (point, previous) in rope
+            .points
+            .iter()
+            .zip(rope.points_simulated_previous.iter())
+        {
+            assert_in_delta_vector(
+                Vec3::Y,
+                *point - *previous,
+                1e-5,
+                "velocity should stay continuous",
+            );
+        }
+        assert_eq!(
+            1.0, rope.distance_between_points,
+            "point spacing should be kept"
+        );
+
+        rope.resize(3);
+        assert_eq!(3, rope.point_count, "point count should shrink");
+        assert_eq!(
+            &polyline[..3],
+            rope.points(),
+            "points should drop from the end"
+        );
+        assert_eq!(
+            3,
+            rope.points_simulated_previous.len(),
+            "previous points should shrink"
+        );
+
+        rope.resize(0);
+        assert_eq!(2, rope.points.len(), "rope should keep at least two points");
+
+        // Simulation should carry on without issue
+        let bind_map = HashMap::from([(0, Vec3::ZERO)]);
+        rope.tick(1.0 / 60.0, &bind_map);
+        assert!(rope.points.iter().all(|point| point.is_finite()));
+    }
+
     #[test]
     fn test_jakobsen_constraint() {
         let test_cases = [
@@ -405,4 +1141,524 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_unique_bind_map_shared_point() {
+        let rope = RopeData::new(10.0, 0.1);
+
+        let mut bindings: HashMap<i64, Vec4> = HashMap::new();
+        bindings.insert(0, vec4(0.0, 0.0, 0.0, 0.0));
+        bindings.insert(1, vec4(2.0, 0.0, -4.0, 0.5));
+        bindings.insert(2, vec4(-2.0, 2.0, -6.0, 0.5));
+
+        let bind_map = rope.unique_bind_map(&bindings);
+        let idx = rope.bind_index(0.5);
+
+        assert_eq!(2, bind_map.len(), "overlapping binds should share a point");
+        assert_eq!(
+            Some(&Vec3::new(0.0, 1.0, -5.0)),
+            bind_map.get(&idx),
+            "shared point should be the midpoint of both binds"
+        );
+        assert_eq!(
+            Some(&Vec3::ZERO),
+            bind_map.get(&0),
+            "lone bind should be unaffected"
+        );
+
+        let counts = rope.bind_share_counts(&bindings);
+        assert_eq!(Some(&2), counts.get(&idx), "two binds should share a point");
+        assert_eq!(Some(&1), counts.get(&0), "one bind should be at the start");
+    }
+
+    #[test]
+    fn test_points_transformed() {
+        let mut rope = RopeData::new(10.0, 0.5);
+        for (idx, pt) in rope.points.iter_mut().enumerate() {
+            *pt = Vec3::new(idx as f32, 0.0, 0.0);
+        }
+
+        let local = rope.points_transformed(Mat4::IDENTITY);
+        assert_eq!(
+            rope.points, local,
+            "local points should match internal buffer"
+        );
+
+        let offset = Vec3::new(0.0, 2.0, -1.0);
+        let global = rope.points_transformed(Mat4::from_translation(offset));
+        assert_eq!(
+            rope.points.len(),
+            global.len(),
+            "transformed points should match internal buffer length"
+        );
+        for (idx, pt) in global.iter().enumerate() {
+            assert_eq!(
+                rope.points[idx] + offset,
+                *pt,
+                "transformed point {idx} should be in order"
+            );
+        }
+
+        assert_eq!(
+            rope.point_count,
+            rope.points_simulated_previous.len(),
+            "previous points should match point count"
+        );
+    }
+
+    #[test]
+    fn test_rope_state() {
+        let mut rope = RopeData::new(10.0, 0.1);
+        let end = rope.points.len() - 1;
+        let ideal = rope.distance_between_points * rope.points.len() as f32;
+        let (taut, overstretch) = (rope.taut_threshold, rope.overstretch_threshold);
+
+        let state_at = |rope: &mut RopeData, distance: f32| -> RopeState {
+            let mut bind_map: HashMap<usize, Vec3> = HashMap::new();
+            bind_map.insert(0, Vec3::ZERO);
+            bind_map.insert(end, Vec3::NEG_Z * distance);
+            rope.tension(&bind_map);
+            rope.state()
+        };
+
+        assert_eq!(
+            RopeState::Slack,
+            state_at(&mut rope, ideal * 0.5),
+            "rope should be slack with binds close together"
+        );
+        assert_eq!(
+            RopeState::Slack,
+            state_at(&mut rope, ideal * (taut - 0.01)),
+            "rope should be slack just below the taut threshold"
+        );
+        assert_eq!(
+            RopeState::Taut,
+            state_at(&mut rope, ideal * (taut + 0.01)),
+            "rope should be taut just above the taut threshold"
+        );
+        assert_eq!(
+            RopeState::Taut,
+            state_at(&mut rope, ideal * (overstretch - 0.01)),
+            "rope should be taut just below the overstretch threshold"
+        );
+        assert_eq!(
+            RopeState::Overstretched,
+            state_at(&mut rope, ideal * (overstretch + 0.01)),
+            "rope should be overstretched past the overstretch threshold"
+        );
+    }
+
+    #[test]
+    fn test_arclength_factors() {
+        let mut rope = RopeData::new(10.0, 0.5);
+        let count = rope.points.len();
+
+        // Stretch the second half of the rope three times as far as the first
+        let mut z = 0.0;
+        for (idx, point) in rope.points.iter_mut().enumerate() {
+            *point = Vec3::NEG_Z * z;
+            z += if idx < count / 2 { 0.1 } else { 0.3 };
+        }
+
+        assert_eq!(
+            0.0,
+            rope.factor_to_arclength(0.0),
+            "start should map to start"
+        );
+        assert_in_delta(
+            1.0,
+            rope.factor_to_arclength(1.0),
+            1e-6,
+            "end should map to end".to_string(),
+        );
+        assert!(
+            rope.factor_to_arclength(0.5) < 0.4,
+            "halfway by index should be less than halfway by length on an unevenly stretched rope"
+        );
+
+        let mut previous = -1.0;
+        for i in 0..=100 {
+            let factor = i as f32 / 100.0;
+            let arclength = rope.factor_to_arclength(factor);
+            assert!(
+                arclength > previous,
+                "arc-length mapping should be monotonic at factor {factor}"
+            );
+            previous = arclength;
+
+            assert_in_delta(
+                factor,
+                rope.arclength_to_factor(arclength),
+                1e-5,
+                format!("arc-length mapping should be invertible at factor {factor}"),
+            );
+        }
+    }
+
+    #[test]
+    fn test_collision_bindings() {
+        let mut rope = RopeData::new(4.0, 1.0);
+        for (idx, point) in rope.points.iter_mut().enumerate() {
+            *point = Vec3::new(idx as f32, 1.0, 0.0);
+        }
+        rope.points_simulated_previous = rope.points.clone();
+        let transform = Mat4::from_translation(Vec3::new(0.0, 10.0, 0.0));
+
+        // Points 3 and 1 fall through the floor at Y=10 in global space
+        let mut bindings: HashMap<usize, Vec3> = HashMap::new();
+        for idx in [3, 1] {
+            let previous = rope.points[idx];
+            let simulated = previous + Vec3::new(0.5, -2.0, 0.0);
+            let hit = transform.transform_point3(Vec3::new(previous.x, 0.0, 0.0));
+            let resolved = resolve_collision(previous, simulated, hit, Vec3::Y, 0.1, transform);
+            bindings.insert(idx, resolved);
+        }
+
+        let points = bindings_transformed(&bindings, transform);
+        let expected = [
+            Vec3::new(1.5, 10.1, 0.0), // Slid along the floor, offset by the collision margin
+            Vec3::new(3.5, 10.1, 0.0),
+        ];
+        assert_eq!(
+            expected.len(),
+            points.len(),
+            "should return a point per binding"
+        );
+        for (expected, actual) in expected.iter().zip(points.iter()) {
+            assert_in_delta_vector(
+                *expected,
+                *actual,
+                1e-5,
+                "collision points should be resolved in global space, ordered by index",
+            );
+        }
+
+        assert!(
+            bindings_transformed(&HashMap::new(), transform).is_empty(),
+            "no bindings should return no points"
+        );
+    }
+
+    #[test]
+    fn test_snap_position() {
+        let rope = RopeData::from_points(vec![
+            Vec3::ZERO,
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(1.0, 2.0, 0.0),
+        ])
+        .expect("polyline should be valid");
+
+        // Snaps happen at the point a binding is attached to
+        let cases = [
+            (0.0, Vec3::ZERO),
+            (0.2, Vec3::ZERO),
+            (0.5, Vec3::new(1.0, 0.0, 0.0)),
+            (0.8, Vec3::new(1.0, 2.0, 0.0)),
+            (1.0, Vec3::new(1.0, 2.0, 0.0)),
+        ];
+        for (bind_at, expected) in cases {
+            assert_eq!(
+                expected,
+                rope.points[rope.bind_index(bind_at)],
+                "snap position at factor {bind_at}"
+            );
+        }
+
+        // Arc length factors are converted before binding, so a third of the way lands on the corner
+        let bind_at = rope.arclength_to_factor(1.0 / 3.0);
+        assert_eq!(
+            Vec3::new(1.0, 0.0, 0.0),
+            rope.points[rope.bind_index(bind_at)],
+            "snap position at arc length factor"
+        );
+    }
+
+    #[test]
+    fn test_from_points() {
+        assert!(
+            RopeData::from_points(vec![Vec3::ZERO]).is_none(),
+            "a rope needs at least two points"
+        );
+
+        let polyline: Vec<Vec3> = (0..=10).map(|i| Vec3::new(i as f32, 0.0, 0.0)).collect();
+        let mut rope = RopeData::from_points(polyline.clone()).expect("rope should build");
+        assert_eq!(polyline.len(), rope.point_count, "point count");
+        assert_eq!(
+            polyline.as_slice(),
+            rope.points(),
+            "rope should start at the polyline"
+        );
+        assert_in_delta(
+            1.0,
+            rope.distance_between_points,
+            1e-5,
+            "rope should keep the polyline spacing".to_string(),
+        );
+
+        let segments: Vec<(Vec3, Vec3)> = rope.segments().collect();
+        assert_eq!(polyline.len() - 1, segments.len(), "segment count");
+        assert_eq!(
+            (polyline[3], polyline[4]),
+            segments[3],
+            "segments should join neighboring points"
+        );
+
+        // Pin both ends and let the rope hang
+        let ends: HashMap<usize, Vec3> =
+            HashMap::from([(0, polyline[0]), (polyline.len() - 1, polyline[10])]);
+        for _ in 0..10 {
+            rope.step(1.0 / 60.0);
+            rope.constrain(&ends);
+        }
+
+        let points = rope.points();
+        assert_eq!(polyline[0], points[0], "start should stay pinned");
+        assert_eq!(polyline[10], points[10], "end should stay pinned");
+        assert!(
+            points[5].y < 0.0,
+            "middle of the rope should sag under gravity, got {}",
+            points[5]
+        );
+
+        rope.points_mut()[5] = Vec3::ZERO;
+        assert_eq!(Vec3::ZERO, rope.points()[5], "points should be writable");
+    }
+
+    #[test]
+    fn force_smoothing() {
+        let mean = Vec3::new(0.0, -50.0, 10.0);
+        let delta = 1.0 / 60.0;
+
+        // Deterministic pseudo-random noise on top of a steady force
+        let mut state: u32 = 98765;
+        let mut random = || {
+            state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+            (state >> 8) as f32 / (1 << 24) as f32 * 40.0 - 20.0
+        };
+        let raw: Vec<Vec3> = (0..2000)
+            .map(|_| mean + Vec3::new(random(), random(), random()))
+            .collect();
+
+        let mut force = Vec3::ZERO;
+        let smoothed: Vec<Vec3> = raw
+            .iter()
+            .map(|target| {
+                force = smooth_force(force, *target, 0.1, delta);
+                force
+            })
+            .collect();
+
+        // Skip the initial ramp up from rest
+        let variance = |forces: &[Vec3]| -> (Vec3, f32) {
+            let avg = forces.iter().sum::<Vec3>() / forces.len() as f32;
+            let var =
+                forces.iter().map(|f| f.distance_squared(avg)).sum::<f32>() / forces.len() as f32;
+            (avg, var)
+        };
+        let (raw_mean, raw_variance) = variance(&raw[200..]);
+        let (smooth_mean, smooth_variance) = variance(&smoothed[200..]);
+
+        assert!(
+            smooth_variance < raw_variance * 0.25,
+            "smoothed variance {smooth_variance} should be much lower than raw variance {raw_variance}"
+        );
+        assert!(
+            smooth_mean.distance(raw_mean) < 1.0,
+            "smoothed mean {smooth_mean} should converge to raw mean {raw_mean}"
+        );
+
+        // Constant forces are unaffected once settled
+        let mut force = Vec3::ZERO;
+        for _ in 0..1000 {
+            force = smooth_force(force, mean, 0.1, delta);
+        }
+        assert!(
+            force.distance(mean) < 1e-3,
+            "steady-state force {force} should match target {mean}"
+        );
+        assert_eq!(
+            mean,
+            smooth_force(Vec3::ZERO, mean, 0.0, delta),
+            "zero smoothing should return the target immediately"
+        );
+    }
+
+    #[test]
+    fn test_rope_colliders() {
+        let sphere = RopeCollider::new(
+            RopeColliderShape::Sphere { radius: 1.0 },
+            Mat4::from_translation(Vec3::new(0.0, 0.0, 5.0)),
+        );
+        assert_eq!(
+            None,
+            sphere.push_out(Vec3::new(0.0, 1.5, 5.0), 0.1),
+            "points outside the sphere should not move"
+        );
+        assert_in_delta_vector(
+            Vec3::new(0.0, 1.1, 5.0),
+            sphere
+                .push_out(Vec3::new(0.0, 0.5, 5.0), 0.1)
+                .expect("point inside sphere"),
+            1e-6,
+            "points inside the sphere should be pushed out along the gradient",
+        );
+        assert_in_delta_vector(
+            Vec3::new(0.0, 1.1, 5.0),
+            sphere
+                .push_out(Vec3::new(0.0, 0.0, 5.0), 0.1)
+                .expect("point at center"),
+            1e-6,
+            "points at the sphere center should still be pushed out",
+        );
+
+        // Capsule lying along the X axis
+        let capsule = RopeCollider::new(
+            RopeColliderShape::Capsule {
+                radius: 0.5,
+                height: 4.0,
+            },
+            Mat4::from_rotation_z(std::f32::consts::FRAC_PI_2),
+        );
+        assert_in_delta_vector(
+            Vec3::new(1.0, 0.5, 0.0),
+            capsule
+                .push_out(Vec3::new(1.0, 0.2, 0.0), 0.0)
+                .expect("point inside capsule body"),
+            1e-5,
+            "points inside the capsule body should be pushed perpendicular to its axis",
+        );
+        assert_in_delta_vector(
+            Vec3::new(2.0, 0.0, 0.0),
+            capsule
+                .push_out(Vec3::new(1.9, 0.0, 0.0), 0.0)
+                .expect("point inside capsule cap"),
+            1e-5,
+            "points inside the capsule cap should be pushed out of the cap",
+        );
+        assert_eq!(
+            None,
+            capsule.push_out(Vec3::new(2.1, 0.0, 0.0), 0.0),
+            "points past the capsule cap should not move"
+        );
+
+        // Overlapping colliders are resolved iteratively
+        let colliders = [
+            RopeCollider::new(
+                RopeColliderShape::Sphere { radius: 1.0 },
+                Mat4::from_translation(Vec3::new(-0.5, 0.0, 0.0)),
+            ),
+            RopeCollider::new(
+                RopeColliderShape::Sphere { radius: 1.0 },
+                Mat4::from_translation(Vec3::new(0.5, 0.0, 0.0)),
+            ),
+        ];
+        let mut point = Vec3::new(0.1, 0.1, 0.0);
+        assert!(
+            RopeCollider::resolve_all(&colliders, &mut point, 0.0),
+            "point inside colliders should be moved"
+        );
+        for collider in colliders.iter() {
+            assert!(
+                collider.transform().w_axis.truncate().distance(point) > 0.9,
+                "point {point} should be pushed out of both overlapping colliders"
+            );
+        }
+
+        // A rope draped over a sphere should rest outside of it
+        let mut rope = RopeData::new(6.0, 0.25);
+        rope.colliders = vec![RopeCollider::new(
+            RopeColliderShape::Sphere { radius: 1.0 },
+            Mat4::IDENTITY,
+        )];
+        let last = rope.point_count - 1;
+        for (idx, point) in rope.points.iter_mut().enumerate() {
+            *point = Vec3::new(idx as f32 / last as f32 * 4.0 - 2.0, 1.5, 0.0);
+        }
+        rope.points_simulated_previous = rope.points.clone();
+        let mut bindings: HashMap<usize, Vec3> = HashMap::new();
+        bindings.insert(0, Vec3::new(-2.0, 1.5, 0.0));
+        bindings.insert(last, Vec3::new(2.0, 1.5, 0.0));
+
+        for _ in 0..120 {
+            rope.tension(&bindings);
+            rope.step(1.0 / 60.0);
+            rope.constrain(&bindings);
+        }
+        for (idx, point) in rope.points.iter().enumerate() {
+            assert!(
+                point.length() >= 1.0 + rope.collider_margin - 1e-4,
+                "rope point {idx} at {point} should rest outside the sphere"
+            );
+        }
+        assert!(
+            rope.points[last / 2].y < 1.5,
+            "rope should sag onto the sphere"
+        );
+    }
+
+    #[test]
+    fn test_rope_forces() {
+        // A free rope in zero gravity should hold its shape indefinitely
+        let points: Vec<Vec3> = (0..12)
+            .map(|idx| {
+                let x = idx as f32 * 0.5;
+                Vec3::new(x, if idx % 2 == 0 { 0.0 } else { 0.5 }, x * 0.25)
+            })
+            .collect();
+        let mut rope = RopeData::from_points(points.clone()).expect("valid polyline");
+        rope.gravity = Vec3::ZERO;
+        let bindings: HashMap<usize, Vec3> = HashMap::new();
+        for _ in 0..1000 {
+            rope.tension(&bindings);
+            rope.step(1.0 / 60.0);
+            rope.constrain(&bindings);
+        }
+        for (idx, (expected, actual)) in points.iter().zip(rope.points()).enumerate() {
+            assert_in_delta_vector(
+                *expected,
+                *actual,
+                1e-4,
+                &format!("point {idx} should not drift without gravity"),
+            );
+        }
+
+        // Gravity and wind accelerate a free rope uniformly
+        let delta: f64 = 0.1;
+        let mut rope = RopeData::from_points(points.clone()).expect("valid polyline");
+        rope.gravity = Vec3::new(1.0, 0.0, 0.0);
+        rope.wind = Vec3::new(0.0, 0.0, 2.0);
+        rope.step(delta);
+        for (idx, (start, actual)) in points.iter().zip(rope.points()).enumerate() {
+            assert_in_delta_vector(
+                *start + Vec3::new(1.0, 0.0, 2.0) * (delta * delta) as f32,
+                *actual,
+                1e-6,
+                &format!("point {idx} should be accelerated by gravity and wind"),
+            );
+        }
+
+        // Turbulence varies along the rope, and over time
+        assert_eq!(
+            Vec3::ZERO,
+            rope.wind_turbulence_at(points[3]),
+            "no turbulence should be applied by default"
+        );
+        rope.wind_turbulence = 2.0;
+        let first = rope.wind_turbulence_at(Vec3::new(0.3, 0.2, 0.1));
+        assert_ne!(
+            first,
+            rope.wind_turbulence_at(Vec3::new(2.3, 1.2, 0.7)),
+            "turbulence should vary across points"
+        );
+        rope.step(1.3);
+        assert_ne!(
+            first,
+            rope.wind_turbulence_at(Vec3::new(0.3, 0.2, 0.1)),
+            "turbulence should vary over time"
+        );
+        assert!(
+            first.length() <= 2.0 * 3f32.sqrt(),
+            "turbulence {first} should be bounded by its strength"
+        );
+    }
 }
@@ -1,3 +1,4 @@
+use glam::Vec3;
 use std::num::NonZero;
 use std::thread::available_parallelism;
 
@@ -20,3 +21,60 @@ pub fn worker_count(workload_size: usize, default_thread_count: usize) -> NonZer
     )
     .expect("zero-value workload provided")
 }
+
+/// Conversion of setting values to and from little-endian bytes, for serializing setting presets.
+pub trait SettingBytes: Sized {
+    /// Appends the bytes of this value to the given buffer.
+    fn write_bytes(&self, out: &mut Vec<u8>);
+    /// Reads a value from the front of the given buffer, advancing it past the read bytes.
+    /// Returns [None] if the buffer is too short.
+    fn read_bytes(bytes: &mut &[u8]) -> Option<Self>;
+}
+
+macro_rules! setting_bytes_numeric {
+    ($($t:ty),*) => {
+        $(
+            impl SettingBytes for $t {
+                fn write_bytes(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+                fn read_bytes(bytes: &mut &[u8]) -> Option<Self> {
+                    let (value, rest) = bytes.split_first_chunk()?;
+                    *bytes = rest;
+                    Some(Self::from_le_bytes(*value))
+                }
+            }
+        )*
+    };
+}
+setting_bytes_numeric!(u32, f32, f64);
+
+impl SettingBytes for bool {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+    fn read_bytes(bytes: &mut &[u8]) -> Option<Self> {
+        let (value, rest) = bytes.split_first()?;
+        *bytes = rest;
+        match value {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl SettingBytes for Vec3 {
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        self.x.write_bytes(out);
+        self.y.write_bytes(out);
+        self.z.write_bytes(out);
+    }
+    fn read_bytes(bytes: &mut &[u8]) -> Option<Self> {
+        Some(Self::new(
+            f32::read_bytes(bytes)?,
+            f32::read_bytes(bytes)?,
+            f32::read_bytes(bytes)?,
+        ))
+    }
+}